@@ -0,0 +1,39 @@
+/*
+ * C interface to the Cubatrice rules engine, built by enabling the `ffi`
+ * feature of cubatrice-core. See cubatrice-core/src/ffi.rs for details.
+ *
+ * Everything but games is passed as nul-terminated UTF-8 JSON. Strings
+ * returned by these functions belong to the caller and must be freed with
+ * cubatrice_string_free. Functions returning a pointer return NULL on
+ * failure, and cubatrice_last_error says why.
+ */
+
+#ifndef CUBATRICE_H
+#define CUBATRICE_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct CubatriceGame CubatriceGame;
+
+CubatriceGame *cubatrice_game_new(const char *config);
+CubatriceGame *cubatrice_game_load(const char *json);
+void cubatrice_game_free(CubatriceGame *game);
+
+char *cubatrice_game_save(CubatriceGame *game);
+char *cubatrice_game_view(CubatriceGame *game, const char *viewer);
+char *cubatrice_game_submit(CubatriceGame *game, size_t player, const char *submission);
+int cubatrice_game_apply(CubatriceGame *game, const char *group);
+char *cubatrice_game_undo(CubatriceGame *game);
+
+char *cubatrice_last_error(void);
+void cubatrice_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,431 @@
+//! Full screen terminal client for running a game at the table. The whole
+//! board is redrawn after every command: each player's cubes, converters
+//! and colonies, the bid tracks, and who the phase is waiting on. Commands
+//! are import rows (see `state::import`), plus a few of the client's own.
+
+use std::{
+    env,
+    io::{self, BufRead, Write},
+    process::ExitCode,
+};
+
+use anyhow::{anyhow, Error};
+use cubatrice_core::{
+    demo,
+    entity::{
+        colony::ColonyID,
+        converter::{ConverterID, ConverterKind},
+        cube::CubeRecord,
+        faction::FactionType,
+    },
+    state::{
+        access::Viewer,
+        awaiting::PendingAction,
+        config::GameConfig,
+        import::parse_command,
+        player::PlayerID,
+        record::{RecordGroup, RecordType},
+        view::{GameStateView, PlayerView},
+        GameData, GameState,
+    },
+};
+
+/// How many lines of recent messages are shown under the board.
+const RECENT: usize = 8;
+
+const USAGE: &str = "usage: cubatrice-tui <seed> <faction>...
+       cubatrice-tui load <save>
+       cubatrice-tui demo [save]";
+
+const HELP: [&str; 12] = [
+    "Records use the same format as CSV import rows, e.g.",
+    "  bid, p1, 2, 1                  trade, p1, p2, 2 food, 1 power",
+    "  take, p1, colony, 0            take, p2, research, pass",
+    "  invent, p1, <tech>, <cube>     mark, p1, all",
+    "Prefix a record with ! to apply it despite warnings.",
+    "",
+    "  next          move on to the next phase",
+    "  run           run every marked converter and colony (economy phase)",
+    "  as <player>   show the board as a player sees it, or 'as all'",
+    "  undo          take back the last record",
+    "  save <path>   save the game",
+    "  help, quit",
+];
+
+struct App {
+    state: GameState,
+    /// Who the board is drawn for. Spectators don't see sealed bids or
+    /// marked converters.
+    viewer: Viewer,
+    /// Messages from recent commands, oldest first.
+    recent: Vec<String>,
+    help: bool,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let state = match start(&args) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut app = App {
+        state,
+        viewer: Viewer::Spectator,
+        recent: Vec::new(),
+        help: false,
+    };
+    loop {
+        app.draw();
+        let Some(line) = prompt("> ") else {
+            break;
+        };
+        if !app.command(&line) {
+            break;
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Sets up or loads the game named on the command line.
+fn start(args: &[String]) -> Result<GameState, Error> {
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    match args.as_slice() {
+        ["demo"] => GameState::from_config(demo::data()?, demo::config()),
+        ["demo", save] => GameState::load(demo::data()?, save),
+        ["load", save] => GameState::load(GameData::preloaded()?, save),
+        [seed, factions @ ..] if !factions.is_empty() => {
+            let seed = seed
+                .parse()
+                .map_err(|_| anyhow!("'{}' isn't a seed", seed))?;
+            let mut config = GameConfig::new(seed);
+            for (i, name) in factions.iter().enumerate() {
+                let faction =
+                    parse_faction(name).ok_or_else(|| anyhow!("unknown faction '{}'", name))?;
+                config = config.with_player(PlayerID(i + 1), faction);
+            }
+            GameState::from_config(GameData::preloaded()?, config)
+        }
+        _ => Err(anyhow!("no game given")),
+    }
+}
+
+impl App {
+    /// Carries out one line of input. Returns false if the client should
+    /// exit.
+    fn command(&mut self, line: &str) -> bool {
+        self.help = false;
+        let line = line.trim();
+        let (word, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let rest = rest.trim();
+        match word {
+            "" => {}
+            "quit" | "exit" => return false,
+            "help" => self.help = true,
+            "next" => match self.state.next_phase() {
+                Some(to) => self.submit(RecordType::ChangePhase { to }, true),
+                None => self.say(String::from("the game is over")),
+            },
+            "run" => {
+                let ran = self.state.run_marked_converters();
+                let colonies = self.state.run_colonies();
+                self.say(format!(
+                    "ran {} converter(s) and {} colonies",
+                    ran.len(),
+                    colonies.len()
+                ));
+                self.take_events();
+            }
+            "as" => match rest {
+                "all" | "spectator" => self.viewer = Viewer::Spectator,
+                p => match self.find_player(p) {
+                    Some(p) => self.viewer = Viewer::Player(p),
+                    None => self.say(format!("no player matches '{}'", p)),
+                },
+            },
+            "undo" => match self.state.undo() {
+                Some(_) => self.say(String::from("undone")),
+                None => self.say(String::from("nothing to undo")),
+            },
+            "save" if !rest.is_empty() => match self.state.save(rest) {
+                Ok(()) => self.say(format!("saved to {}", rest)),
+                Err(e) => self.say(format!("{:#}", e)),
+            },
+            _ => {
+                let (force, row) = match line.strip_prefix('!') {
+                    Some(row) => (true, row),
+                    None => (false, line),
+                };
+                match parse_command(&self.state, row) {
+                    Ok(rec) => self.submit(rec, force),
+                    Err(e) => self.say(format!("{} (type 'help' for commands)", e)),
+                }
+            }
+        }
+        true
+    }
+
+    /// Validates and applies a record. Records with warnings are only
+    /// applied if `force` is set.
+    fn submit(&mut self, rec: RecordType, force: bool) {
+        match self.state.validate_group(std::slice::from_ref(&rec)) {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                self.say(format!("not allowed: {}", e));
+                return;
+            }
+            Err(e) => {
+                self.say(format!("{:#}", e));
+                return;
+            }
+        }
+        let warnings = self.state.check_warnings(&rec);
+        if !force && !warnings.is_empty() {
+            for w in warnings {
+                self.say(format!("warning: {}", w));
+            }
+            self.say(String::from(
+                "repeat the command with ! in front to apply it anyway",
+            ));
+            return;
+        }
+        self.state.apply(RecordGroup {
+            id: self.state.next_record_id(),
+            rec: vec![rec],
+        });
+        self.say(String::from("ok"));
+        self.take_events();
+    }
+
+    fn take_events(&mut self) {
+        for (_, e) in self.state.take_events() {
+            self.say(e.to_string());
+        }
+    }
+
+    fn say(&mut self, msg: String) {
+        self.recent.push(msg);
+        let excess = self.recent.len().saturating_sub(RECENT);
+        self.recent.drain(..excess);
+    }
+
+    fn find_player(&self, s: &str) -> Option<PlayerID> {
+        let view = self.state.spectator_view();
+        let num = s.trim_start_matches('p');
+        view.players
+            .iter()
+            .find(|p| {
+                num.parse() == Ok(p.player.0)
+                    || self.state.player_name(p.player).eq_ignore_ascii_case(s)
+                    || p.faction.short_name().eq_ignore_ascii_case(s)
+            })
+            .map(|p| p.player)
+    }
+
+    fn draw(&self) {
+        let view = self.state.view_as(self.viewer);
+        let mut out = String::new();
+        out.push_str("\x1b[2J\x1b[3J\x1b[H");
+        let viewing = match self.viewer {
+            Viewer::Player(p) => self.state.player_name(p),
+            Viewer::Spectator => String::from("everyone"),
+        };
+        out.push_str(&format!(
+            "\x1b[1mConfluence {}, {:?} phase\x1b[0m    (showing the board to {})\n",
+            view.confluence.0, view.phase, viewing
+        ));
+        for p in view.players.iter() {
+            out.push('\n');
+            out.push_str(&self.player_panel(p));
+        }
+        out.push('\n');
+        out.push_str(&self.tracks_panel(&view));
+        out.push('\n');
+        out.push_str(&self.waiting_panel());
+        out.push('\n');
+        let lines: Vec<&str> = if self.help {
+            HELP.to_vec()
+        } else {
+            self.recent.iter().map(|s| s.as_str()).collect()
+        };
+        for l in lines {
+            out.push_str(&format!("  {}\n", l));
+        }
+        print!("{}", out);
+        let _ = io::stdout().flush();
+    }
+
+    fn player_panel(&self, p: &PlayerView) -> String {
+        let marked = p.marked.clone().unwrap_or_default();
+        let converters: Vec<String> = p
+            .converters
+            .iter()
+            .map(|c| {
+                let star = if marked.contains(c) { "*" } else { "" };
+                format!("{}{}", self.converter_name(*c), star)
+            })
+            .collect();
+        let colonies: Vec<String> = p.colonies.iter().map(|c| self.colony_name(*c)).collect();
+        let research: Vec<String> = p
+            .research_teams
+            .iter()
+            .filter_map(|t| self.state.data().tech.get(t))
+            .map(|t| t.name.clone())
+            .collect();
+        let bid = match (p.has_bid, p.bid) {
+            (_, Some(b)) => format!("colony {}, research {}", b.colony.0, b.tech.0),
+            (true, None) => String::from("sealed"),
+            (false, None) => String::from("-"),
+        };
+        let mut out = format!(
+            "\x1b[1m{}\x1b[0m ({})\n",
+            self.state.player_name(p.player),
+            p.faction.name()
+        );
+        for (label, value) in [
+            ("cubes", format_cubes(&p.cubes)),
+            ("converters", list(converters)),
+            ("colonies", list(colonies)),
+            ("research", list(research)),
+            ("bid", bid),
+        ] {
+            out.push_str(&format!("  {:<11} {}\n", label, value));
+        }
+        out
+    }
+
+    fn tracks_panel(&self, view: &GameStateView) -> String {
+        let slots = |names: Vec<Option<String>>| {
+            let slots: Vec<String> = names
+                .into_iter()
+                .enumerate()
+                .map(|(i, n)| format!("[{}] {}", i, n.unwrap_or_else(|| String::from("-"))))
+                .collect();
+            list(slots)
+        };
+        let colonies = view
+            .colony_track
+            .iter()
+            .map(|c| c.map(|c| self.colony_name(c)))
+            .collect();
+        let research = view
+            .tech_track
+            .iter()
+            .map(|t| {
+                t.map(|t| {
+                    self.state
+                        .data()
+                        .tech
+                        .get(&t)
+                        .map_or_else(|| format!("technology {}", t.0), |t| t.name.clone())
+                })
+            })
+            .collect();
+        format!(
+            "\x1b[1mBid tracks\x1b[0m\n  {:<11} {}\n  {:<11} {}\n",
+            "colonies",
+            slots(colonies),
+            "research",
+            slots(research)
+        )
+    }
+
+    fn waiting_panel(&self) -> String {
+        let mut out = String::from("\x1b[1mWaiting on\x1b[0m\n");
+        let awaiting = self.state.awaiting();
+        if !awaiting.iter().any(|(_, a)| a.blocking()) {
+            out.push_str("  nobody, type 'next' to move on\n");
+        }
+        for (p, a) in awaiting {
+            let what = match a {
+                PendingAction::Bid => String::from("bid"),
+                PendingAction::TakeColony => String::from("take a colony, or pass"),
+                PendingAction::TakeResearch => String::from("take a research team, or pass"),
+                PendingAction::Invent { tech } => format!(
+                    "may invent {}",
+                    self.state
+                        .data()
+                        .tech
+                        .get(&tech)
+                        .map_or_else(|| format!("technology {}", tech.0), |t| t.name.clone())
+                ),
+                PendingAction::RunConverter { converter } => {
+                    format!("run {}", self.converter_name(converter))
+                }
+                PendingAction::RespondToOffer { offer } => {
+                    format!("may respond to offer {}", offer.0)
+                }
+            };
+            out.push_str(&format!("  {}: {}\n", self.state.player_name(p), what));
+        }
+        out
+    }
+
+    fn converter_name(&self, c: ConverterID) -> String {
+        match self.state.converter(c).map(|c| c.kind()) {
+            Some(ConverterKind::Prototype(p)) => p.name,
+            Some(ConverterKind::Colony(c)) => c.name,
+            _ => format!("converter {}", c.0),
+        }
+    }
+
+    fn colony_name(&self, c: ColonyID) -> String {
+        self.state
+            .data()
+            .colony
+            .get(&c)
+            .map_or_else(|| format!("colony {}", c.0), |c| c.name.clone())
+    }
+}
+
+fn list(items: Vec<String>) -> String {
+    if items.is_empty() {
+        String::from("none")
+    } else {
+        items.join(", ")
+    }
+}
+
+fn format_cubes(c: &CubeRecord) -> String {
+    let counts = [
+        (c.ships, "ships"),
+        (c.food, "food"),
+        (c.culture, "culture"),
+        (c.industry, "industry"),
+        (c.small_wild, "small wild"),
+        (c.power, "power"),
+        (c.biotech, "biotech"),
+        (c.information, "information"),
+        (c.large_wild, "large wild"),
+        (c.ultratech, "ultratech"),
+        (c.points, "VP"),
+    ];
+    let parts: Vec<String> = counts
+        .iter()
+        .filter(|(n, _)| *n != 0)
+        .map(|(n, name)| format!("{} {}", n, name))
+        .collect();
+    list(parts)
+}
+
+fn parse_faction(s: &str) -> Option<FactionType> {
+    FactionType::core()
+        .into_iter()
+        .chain(FactionType::bifurcation())
+        .find(|f| f.name().eq_ignore_ascii_case(s) || f.short_name().eq_ignore_ascii_case(s))
+}
+
+/// Prints a prompt and reads a trimmed line from stdin, or None at the end
+/// of input.
+fn prompt(msg: &str) -> Option<String> {
+    print!("{}", msg);
+    let _ = io::stdout().flush();
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim().to_string()),
+    }
+}
@@ -1,4 +0,0 @@
-use serde::{Deserialize, Serialize};
-
-#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct AltUnityState;
@@ -0,0 +1,239 @@
+//! A C ABI for embedding the rules engine in frontends written in other
+//! languages, such as Unity or Godot games. Declarations are in
+//! `include/cubatrice.h`.
+//!
+//! Games are passed around as opaque `CubatriceGame` pointers. Everything
+//! else crosses the boundary as nul-terminated UTF-8 JSON, in the same
+//! shapes serde gives the Rust types. Strings returned by this module belong
+//! to the caller, and must be given back to `cubatrice_string_free`.
+//!
+//! Nothing here panics across the boundary: on failure functions return
+//! null (or -1), and `cubatrice_last_error` says what went wrong.
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, c_int, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr,
+};
+
+use anyhow::{anyhow, Context, Error};
+use serde::{de::DeserializeOwned, Serialize};
+
+use cubatrice_engine::state::{
+    access::Viewer, config::GameConfig, player::PlayerID, record::RecordGroup,
+    submission::Submission, GameData, GameState,
+};
+
+/// A game owned by the caller, freed with `cubatrice_game_free`.
+pub struct CubatriceGame {
+    state: GameState,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, turning errors and panics into a stored error and `fail`.
+fn guard<T>(fail: T, f: impl FnOnce() -> Result<T, Error>) -> T {
+    let result = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(r) => r,
+        Err(e) => {
+            let msg = e
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| e.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| String::from("unknown panic"));
+            Err(anyhow!("panicked: {}", msg))
+        }
+    };
+    match result {
+        Ok(v) => {
+            LAST_ERROR.with(|e| *e.borrow_mut() = None);
+            v
+        }
+        Err(err) => {
+            LAST_ERROR.with(|e| *e.borrow_mut() = Some(format!("{:#}", err)));
+            fail
+        }
+    }
+}
+
+/// Reads a JSON argument.
+///
+/// # Safety
+/// `s` must be null or a valid nul-terminated string.
+unsafe fn from_json<T: DeserializeOwned>(s: *const c_char, what: &str) -> Result<T, Error> {
+    if s.is_null() {
+        return Err(anyhow!("{} is null", what));
+    }
+    let s = CStr::from_ptr(s)
+        .to_str()
+        .with_context(|| format!("{} isn't UTF-8", what))?;
+    serde_json::from_str(s).with_context(|| format!("couldn't read {}", what))
+}
+
+/// Hands a value back to the caller as a JSON string.
+fn to_json<T: Serialize>(value: &T) -> Result<*mut c_char, Error> {
+    let json = serde_json::to_string(value)?;
+    Ok(CString::new(json)?.into_raw())
+}
+
+/// Borrows a game from its handle.
+///
+/// # Safety
+/// `game` must be null or a handle from this module which hasn't been freed.
+unsafe fn game<'a>(game: *mut CubatriceGame) -> Result<&'a mut GameState, Error> {
+    game.as_mut()
+        .map(|g| &mut g.state)
+        .ok_or_else(|| anyhow!("game is null"))
+}
+
+/// Sets up a new game from a `GameConfig`, using the game data in the data
+/// directories. Returns null on failure.
+///
+/// # Safety
+/// `config` must be a valid nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cubatrice_game_new(config: *const c_char) -> *mut CubatriceGame {
+    guard(ptr::null_mut(), || {
+        let config: GameConfig = from_json(config, "config")?;
+        let state = GameState::from_config(GameData::preloaded()?, config)?;
+        Ok(Box::into_raw(Box::new(CubatriceGame { state })))
+    })
+}
+
+/// Restores a game saved with `cubatrice_game_save`. Returns null on
+/// failure.
+///
+/// # Safety
+/// `json` must be a valid nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cubatrice_game_load(json: *const c_char) -> *mut CubatriceGame {
+    guard(ptr::null_mut(), || {
+        if json.is_null() {
+            return Err(anyhow!("saved game is null"));
+        }
+        let json = CStr::from_ptr(json).to_str()?;
+        let state = GameState::from_json(GameData::preloaded()?, json)?;
+        Ok(Box::into_raw(Box::new(CubatriceGame { state })))
+    })
+}
+
+/// Frees a game. Does nothing if `game` is null.
+///
+/// # Safety
+/// `game` must be null or a handle from this module which hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn cubatrice_game_free(game: *mut CubatriceGame) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// Serializes the whole game, including its log. Returns null on failure.
+///
+/// # Safety
+/// `game` must be a handle from this module which hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn cubatrice_game_save(game: *mut CubatriceGame) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let json = self::game(game)?.to_json()?;
+        Ok(CString::new(json)?.into_raw())
+    })
+}
+
+/// The game as a `Viewer` sees it, as a `GameStateView`. Returns null on
+/// failure.
+///
+/// # Safety
+/// `game` must be a handle from this module which hasn't been freed, and
+/// `viewer` a valid nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cubatrice_game_view(
+    game: *mut CubatriceGame,
+    viewer: *const c_char,
+) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let viewer: Viewer = from_json(viewer, "viewer")?;
+        to_json(&self::game(game)?.view_as(viewer))
+    })
+}
+
+/// Submits a `Submission` from `player`'s client, returning its
+/// `SubmitOutcome`. Returns null on failure.
+///
+/// # Safety
+/// `game` must be a handle from this module which hasn't been freed, and
+/// `submission` a valid nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cubatrice_game_submit(
+    game: *mut CubatriceGame,
+    player: usize,
+    submission: *const c_char,
+) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let sub: Submission = from_json(submission, "submission")?;
+        to_json(&self::game(game)?.submit(PlayerID(player), sub)?)
+    })
+}
+
+/// Validates and applies a `RecordGroup`, its records together as
+/// `GameState::validate_group` does. The group is given the game's next
+/// record ID, whatever ID it was sent with. Returns 0 if it was applied, or
+/// -1 if it wasn't, in which case the game is unchanged.
+///
+/// # Safety
+/// `game` must be a handle from this module which hasn't been freed, and
+/// `group` a valid nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cubatrice_game_apply(
+    game: *mut CubatriceGame,
+    group: *const c_char,
+) -> c_int {
+    guard(-1, || {
+        let group: RecordGroup = from_json(group, "record group")?;
+        let state = self::game(game)?;
+        state
+            .validate_group(&group.rec)?
+            .map_err(|e| anyhow!("{}", e))?;
+        state.apply(RecordGroup {
+            id: state.next_record_id(),
+            rec: group.rec,
+        });
+        Ok(0)
+    })
+}
+
+/// Undoes the last record group, returning it, or "null" if there was
+/// nothing to undo. Returns null on failure.
+///
+/// # Safety
+/// `game` must be a handle from this module which hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn cubatrice_game_undo(game: *mut CubatriceGame) -> *mut c_char {
+    guard(ptr::null_mut(), || to_json(&self::game(game)?.undo()))
+}
+
+/// What went wrong in the last call on this thread which failed, or null if
+/// it succeeded.
+#[no_mangle]
+pub extern "C" fn cubatrice_last_error() -> *mut c_char {
+    LAST_ERROR.with(|e| {
+        e.borrow()
+            .as_ref()
+            .and_then(|msg| CString::new(msg.replace('\0', " ")).ok())
+            .map_or(ptr::null_mut(), CString::into_raw)
+    })
+}
+
+/// Frees a string returned by this module. Does nothing if `s` is null.
+///
+/// # Safety
+/// `s` must be null or a string from this module which hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn cubatrice_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
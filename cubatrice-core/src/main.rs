@@ -1 +1,876 @@
-fn main() {}
+use std::{
+    env, fs,
+    io::{self, BufRead, Write},
+    path::Path,
+    process::{self, ExitCode},
+};
+
+use cubatrice_core::{
+    analysis::rank_techs,
+    demo,
+    entity::{
+        colony::ColonyID,
+        cube::{parse_cube_type, CubeRecord},
+        faction::FactionType,
+    },
+    print::{render, SheetFormat},
+    state::{
+        config::GameConfig,
+        corpus::{add_to_corpus, bless_corpus, check_corpus},
+        import::parse_command,
+        lazy::LazyGameData,
+        notes::NoteTarget,
+        player::{PlayerID, Profile},
+        record::{RecordGroup, RecordType},
+        view::{GameStateView, PlayerView},
+        Confluence, GameData, GameState,
+    },
+    sweep::{Parameter, Sweep},
+    data_dirs, data_file_exists, Fraction,
+};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(|s| s.as_str()) {
+        Some("doctor") => doctor(),
+        Some("demo") => demo(),
+        Some("play") => play(&args[2..]),
+        Some("corpus") => corpus(&args[2..]),
+        Some("print") => print_sheets(&args[2..]),
+        Some("rank") => rank(&args[2..]),
+        Some("sweep") => sweep(&args[2..]),
+        #[cfg(feature = "typescript")]
+        Some("typescript") => {
+            print!("{}", cubatrice_core::typescript::definitions());
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("usage: {} <command>", args[0]);
+            eprintln!();
+            eprintln!("commands:");
+            eprintln!("  doctor    check that game data is present and loads correctly");
+            eprintln!("  demo      watch bots play a short built-in game, no data needed");
+            eprintln!("  play      play a pass-and-play game: play <seed> <faction>...,");
+            eprintln!("            play <seed> --preset <name>");
+            eprintln!("  corpus    replay golden games: corpus check|bless <dir>,");
+            eprintln!("            corpus add <save> <dir>, with --demo for the");
+            eprintln!("            demo's data");
+            eprintln!("  print     print play aids from the game data: print [text|html]");
+            eprintln!("  rank      rank technologies by value: rank [interest rate] [confluence]");
+            eprintln!("  sweep     compare bot win rates as a parameter changes, see");
+            eprintln!("            sweep --help");
+            #[cfg(feature = "typescript")]
+            eprintln!("  typescript  print TypeScript definitions for web frontends");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Checks the data directory layout, loads every table and reports what it
+/// found. Exits with failure if anything is wrong.
+fn doctor() -> ExitCode {
+    let dirs = data_dirs();
+    let bundled = cfg!(feature = "bundled-data");
+    println!("Data directories, lowest priority first:");
+    if bundled {
+        println!("  (bundled)");
+    }
+    for d in dirs.iter() {
+        if Path::new(d).is_dir() {
+            println!("  {}", d);
+        } else {
+            println!("  {} (does not exist)", d);
+        }
+    }
+    if !bundled && !dirs.iter().any(|d| Path::new(d).is_dir()) {
+        println!("  none of them exist.");
+        println!("  fix: create one, or set CUBE_DIR to the directory containing your data.");
+        return ExitCode::FAILURE;
+    }
+    let mut problems = 0;
+
+    println!();
+    println!("Layout:");
+    let expected = GameData::data_files();
+    for f in expected.iter() {
+        let found = dirs.iter().any(|d| Path::new(d).join(f).is_file());
+        if !found && bundled {
+            println!("  missing {} (the bundled copy will be used)", f);
+        } else if !found {
+            println!("  missing {}", f);
+            problems += 1;
+        }
+    }
+    for (i, d) in dirs.iter().enumerate() {
+        let dir = Path::new(d);
+        let mut found = Vec::new();
+        find_json(dir, dir, &mut found);
+        found.sort();
+        for f in found.iter() {
+            if !expected.contains(f) {
+                println!(
+                    "  unrecognized {}/{} (stale or misnamed? it will not be loaded)",
+                    d, f
+                );
+            } else if dirs[..i].iter().any(|l| Path::new(l).join(f).is_file()) {
+                println!("  {}/{} overrides rows of a lower directory", d, f);
+            }
+        }
+    }
+    if problems == 0 {
+        println!("  ok");
+    }
+
+    println!();
+    println!("Tables:");
+    let data = LazyGameData::new();
+    let tables = [
+        ("colonies", data.colony().map(|t| t.len())),
+        ("technologies", data.tech().map(|t| t.len())),
+        ("prototypes", data.tech_prototype().map(|t| t.len())),
+        (
+            "faction converters",
+            data.tech_converter()
+                .map(|t| t.values().map(|v| v.len()).sum()),
+        ),
+        (
+            "starting resources",
+            data.start_resources().map(|t| t.len()),
+        ),
+    ];
+    for (name, res) in tables {
+        match res {
+            Ok(n) => println!("  {:<20} {}", name, n),
+            Err(e) => {
+                println!("  {:<20} failed to load: {:#}", name, e);
+                problems += 1;
+            }
+        }
+    }
+
+    // cross references only make sense once every table loads
+    if problems == 0 {
+        println!();
+        println!("Cross references:");
+        match GameData::preloaded().and_then(|d| d.validate()) {
+            Ok(()) => println!("  ok"),
+            Err(e) => {
+                let msg = format!("{:#}", e);
+                for line in msg.lines() {
+                    println!("  {}", line);
+                }
+                problems += 1;
+            }
+        }
+    }
+
+    println!();
+    if problems == 0 {
+        println!("No problems found.");
+        ExitCode::SUCCESS
+    } else {
+        println!("{} problem(s) found.", problems);
+        println!("fix: restore missing files from a fresh copy of the data, or check");
+        println!("     the reported files for JSON syntax errors and broken references.");
+        ExitCode::FAILURE
+    }
+}
+
+/// Plays the built-in demo game with bots and prints what happened, then
+/// the final scores. Needs no data directory, so it's the quickest way to
+/// check that the engine works at all.
+fn demo() -> ExitCode {
+    let demo = match demo::play() {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("the demo failed: {:#}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    for entry in demo.digest.iter() {
+        println!("{}", entry);
+    }
+    println!();
+    println!("Final scores:");
+    for s in demo.state.final_scores().ranking {
+        let faction = demo
+            .state
+            .faction(s.player)
+            .map(|f| f.name())
+            .unwrap_or_default();
+        println!(
+            "  {}. {} ({}): {}",
+            s.place,
+            demo.state.player_name(s.player),
+            faction,
+            s.total
+        );
+    }
+    ExitCode::SUCCESS
+}
+
+/// Writes printable sheets of every card in the game data to stdout.
+fn print_sheets(args: &[String]) -> ExitCode {
+    let format = match args.first().map(|s| s.as_str()) {
+        None | Some("text") => SheetFormat::Text,
+        Some("html") => SheetFormat::Html,
+        Some(f) => {
+            eprintln!("unknown format '{}', expected text or html", f);
+            return ExitCode::FAILURE;
+        }
+    };
+    let data = match GameData::preloaded() {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("couldn't load game data: {:#}", e);
+            eprintln!("run `doctor` to find out what's wrong.");
+            return ExitCode::FAILURE;
+        }
+    };
+    print!("{}", render(&data, format));
+    ExitCode::SUCCESS
+}
+
+/// Lists every technology by what inventing it is worth, given an interest
+/// rate like `5/4` (1 by default) and the confluence it's invented in (1 by
+/// default).
+fn rank(args: &[String]) -> ExitCode {
+    let rate = match args.first().map(|s| s.parse::<Fraction>()) {
+        None => Fraction::new(1, 1),
+        Some(Ok(r)) => r,
+        Some(Err(_)) => {
+            eprintln!("'{}' isn't an interest rate, try 1 or 5/4", args[0]);
+            return ExitCode::FAILURE;
+        }
+    };
+    let confluence = match args.get(1).map(|s| s.parse()) {
+        None => Confluence::default(),
+        Some(Ok(c)) => Confluence(c),
+        Some(Err(_)) => {
+            eprintln!("'{}' isn't a confluence", args[1]);
+            return ExitCode::FAILURE;
+        }
+    };
+    let data = match GameData::preloaded() {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("couldn't load game data: {:#}", e);
+            eprintln!("run `doctor` to find out what's wrong.");
+            return ExitCode::FAILURE;
+        }
+    };
+    for (i, t) in rank_techs(&data, rate, confluence).iter().enumerate() {
+        println!(
+            "{:>3}. {:<32} tier {}  cost {} {:<12} {:>8} per run  {:>8} total",
+            i + 1,
+            t.name,
+            t.tier,
+            t.cost.qty,
+            t.cost.typ.name(),
+            t.profit.to_string(),
+            t.total.to_string()
+        );
+    }
+    ExitCode::SUCCESS
+}
+
+/// Collects every `.json` file under `dir`, relative to `root`.
+fn find_json(root: &Path, dir: &Path, out: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for e in entries.flatten() {
+        let path = e.path();
+        if path.is_dir() {
+            find_json(root, &path, out);
+        } else if path.extension().is_some_and(|x| x == "json") {
+            if let Ok(rel) = path.strip_prefix(root) {
+                out.push(rel.to_string_lossy().into_owned());
+            }
+        }
+    }
+}
+
+/// Plays a game on one screen, passed between players. Each seat is gated
+/// behind a PIN (or just a keypress if the player didn't set one) and the
+/// screen is cleared between seats, so players only ever see their own
+/// `GameStateView`.
+fn play(args: &[String]) -> ExitCode {
+    let Some(seed) = args.first().and_then(|s| s.parse().ok()) else {
+        eprintln!("usage: play <seed> <faction>...");
+        return ExitCode::FAILURE;
+    };
+    let mut data = match GameData::preloaded() {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("failed to load game data: {:#}", e);
+            eprintln!("run `doctor` for details.");
+            return ExitCode::FAILURE;
+        }
+    };
+    let config = if args.get(1).map(|s| s.as_str()) == Some("--preset") {
+        let Some(name) = args.get(2) else {
+            eprintln!("usage: play <seed> --preset <name>");
+            return ExitCode::FAILURE;
+        };
+        if data_file_exists("presets.json") {
+            if let Err(e) = data.load_presets() {
+                eprintln!("failed to load presets: {:#}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        match GameConfig::preset(&data, name, seed) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("{:#}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        let mut config = GameConfig::new(seed);
+        for (i, name) in args[1..].iter().enumerate() {
+            let Some(faction) = parse_faction(name) else {
+                eprintln!("unknown faction '{}'", name);
+                return ExitCode::FAILURE;
+            };
+            config = config.with_player(PlayerID(i + 1), faction);
+        }
+        config
+    };
+    if config.players.is_empty() {
+        eprintln!("usage: play <seed> <faction>...");
+        return ExitCode::FAILURE;
+    }
+    let seats = config.players.clone();
+    let mut state = match GameState::from_config(data, config) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut pins = Vec::new();
+    for (player, faction) in seats.iter() {
+        clear_screen();
+        println!("Player {} ({})", player.0, faction.name());
+        let Some(name) = prompt("Your name, or leave blank to skip: ") else {
+            return ExitCode::SUCCESS;
+        };
+        if !name.is_empty() {
+            let rec = RecordType::SetProfile {
+                player: *player,
+                profile: Profile {
+                    name: Some(name),
+                    ..Profile::default()
+                },
+            };
+            if let Err(e) = state.validate(&rec) {
+                eprintln!("{}", e);
+                return ExitCode::FAILURE;
+            }
+            state.apply(RecordGroup {
+                id: state.next_record_id(),
+                rec: vec![rec],
+            });
+        }
+        let Some(pin) = prompt_hidden("Choose a PIN, or leave blank to skip: ") else {
+            return ExitCode::SUCCESS;
+        };
+        pins.push((*player, pin));
+    }
+
+    loop {
+        for ((player, faction), (_, pin)) in seats.iter().zip(pins.iter()) {
+            clear_screen();
+            println!(
+                "Pass to {} ({}).",
+                state.player_name(*player),
+                faction.name()
+            );
+            loop {
+                if pin.is_empty() {
+                    if prompt("Press Enter when ready. ").is_none() {
+                        return ExitCode::SUCCESS;
+                    }
+                    break;
+                }
+                match prompt_hidden("PIN: ") {
+                    Some(p) if p == *pin => break,
+                    Some(_) => println!("Wrong PIN."),
+                    None => return ExitCode::SUCCESS,
+                }
+            }
+            clear_screen();
+            print_view(&state, &state.view(*player));
+            if !take_turn(&mut state, *player, &pins) {
+                clear_screen();
+                return ExitCode::SUCCESS;
+            }
+        }
+    }
+}
+
+/// Replays golden games to check that rules changes haven't altered their
+/// outcomes, re-records them after an intended change, or adds a saved game
+/// to the corpus.
+fn corpus(args: &[String]) -> ExitCode {
+    let mut args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    // the corpus checked in with the engine is played with the demo's data
+    let use_demo = args.last() == Some(&"--demo");
+    if use_demo {
+        args.pop();
+    }
+    if !matches!(args.as_slice(), ["check" | "bless", _] | ["add", _, _]) {
+        eprintln!("usage: corpus check <dir> [--demo]");
+        eprintln!("       corpus bless <dir> [--demo]");
+        eprintln!("       corpus add <save> <dir> [--demo]");
+        return ExitCode::FAILURE;
+    }
+    let data = if use_demo {
+        demo::data()
+    } else {
+        GameData::preloaded()
+    };
+    let data = match data {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("failed to load game data: {:#}", e);
+            eprintln!("run `doctor` for details.");
+            return ExitCode::FAILURE;
+        }
+    };
+    match args.as_slice() {
+        ["check", dir] => {
+            let checks = match check_corpus(dir, &data) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("{:#}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let mut failed = 0;
+            for c in checks.iter() {
+                match &c.result {
+                    Ok(diffs) if diffs.is_empty() => println!("ok    {}", c.path.display()),
+                    Ok(diffs) => {
+                        println!("FAIL  {}", c.path.display());
+                        for d in diffs {
+                            println!("        {}", d);
+                        }
+                    }
+                    Err(e) => println!("FAIL  {}: {:#}", c.path.display(), e),
+                }
+                if !c.passed() {
+                    failed += 1;
+                }
+            }
+            println!();
+            println!("{} games, {} failed", checks.len(), failed);
+            if failed == 0 {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        ["bless", dir] => match bless_corpus(dir, &data) {
+            Ok(n) => {
+                println!("re-recorded {} games", n);
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{:#}", e);
+                ExitCode::FAILURE
+            }
+        },
+        ["add", save, dir] => match add_to_corpus(save, dir, data) {
+            Ok(path) => {
+                println!("added {}", path.display());
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{:#}", e);
+                ExitCode::FAILURE
+            }
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// How many games `sweep` plays at each value, unless told otherwise.
+const SWEEP_GAMES: usize = 20;
+
+/// Plays batches of bot games with a parameter set to each of a list of
+/// values, and prints every seat's win rate at each.
+fn sweep(args: &[String]) -> ExitCode {
+    let usage = || {
+        eprintln!("usage: sweep <parameter> <values> [--games <n>] (--demo | <faction>...)");
+        eprintln!();
+        eprintln!("parameters:");
+        eprintln!("  colony <id> <cube>       cubes of a type a colony makes each run");
+        eprintln!("  start <faction> <cube>   cubes of a type a faction starts with");
+        eprintln!("  bid <player>             share of their ships a seat's bot bids");
+        eprintln!();
+        eprintln!("values are separated by commas, like 1,2,3 or 1/4,1/2,3/4. Each");
+        eprintln!(
+            "is played {} times unless --games says otherwise, with the same seeds.",
+            SWEEP_GAMES
+        );
+        ExitCode::FAILURE
+    };
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let (parameter, rest) = match args.as_slice() {
+        ["colony", id, cube, rest @ ..] => {
+            let (Ok(id), Ok(cube)) = (id.parse(), parse_cube_type(cube)) else {
+                return usage();
+            };
+            (
+                Parameter::ColonyOutput {
+                    colony: ColonyID(id),
+                    cube,
+                },
+                rest,
+            )
+        }
+        ["start", faction, cube, rest @ ..] => {
+            let (Some(faction), Ok(cube)) = (parse_faction(faction), parse_cube_type(cube)) else {
+                return usage();
+            };
+            (Parameter::StartingCubes { faction, cube }, rest)
+        }
+        ["bid", player, rest @ ..] => {
+            let Ok(player) = player.trim_start_matches('p').parse() else {
+                return usage();
+            };
+            (
+                Parameter::BidShare {
+                    player: PlayerID(player),
+                },
+                rest,
+            )
+        }
+        _ => return usage(),
+    };
+    let Some((values, mut rest)) = rest.split_first() else {
+        return usage();
+    };
+    let values: Result<Vec<Fraction>, _> = values.split(',').map(|v| v.parse()).collect();
+    let values = match values {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut games = SWEEP_GAMES;
+    if let ["--games", n, tail @ ..] = rest {
+        let Ok(n) = n.parse() else {
+            return usage();
+        };
+        games = n;
+        rest = tail;
+    }
+    let (data, config) = if rest == ["--demo"] {
+        (demo::data(), demo::config())
+    } else {
+        let mut config = GameConfig::new(0);
+        for (i, name) in rest.iter().enumerate() {
+            let Some(faction) = parse_faction(name) else {
+                eprintln!("unknown faction '{}'", name);
+                return ExitCode::FAILURE;
+            };
+            config = config.with_player(PlayerID(i + 1), faction);
+        }
+        (GameData::preloaded(), config)
+    };
+    if config.players.is_empty() {
+        return usage();
+    }
+    let data = match data {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("failed to load game data: {:#}", e);
+            eprintln!("run `doctor` for details.");
+            return ExitCode::FAILURE;
+        }
+    };
+    let sweep = Sweep {
+        parameter,
+        values,
+        games,
+    };
+    let results = match sweep.run(&data, &config) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    print!("{:>12}", "value");
+    for (player, faction) in config.players.iter() {
+        print!("  {:>16}", format!("p{} {}", player.0, faction.short_name()));
+    }
+    println!();
+    for r in results.iter() {
+        print!("{:>12}", r.value.to_string());
+        for (player, _) in config.players.iter() {
+            let rate = r.win_rate(*player) * 100;
+            print!("  {:>15.1}%", rate.value());
+        }
+        println!();
+    }
+    ExitCode::SUCCESS
+}
+
+/// Reads and applies commands for one seat until they're done. Commands may
+/// only act for the seat, or for other seats who agree with their PIN, such
+/// as the other side of a trade. Returns false if the game should stop.
+fn take_turn(state: &mut GameState, player: PlayerID, pins: &[(PlayerID, String)]) -> bool {
+    loop {
+        let Some(line) = prompt("> ") else {
+            return false;
+        };
+        match line.as_str() {
+            "" => continue,
+            "done" => return true,
+            "quit" => return false,
+            "view" => {
+                clear_screen();
+                print_view(state, &state.view(player));
+                continue;
+            }
+            "help" => {
+                println!("Commands use the same format as CSV import rows, e.g.");
+                println!("  bid, p1, 2, 1");
+                println!("  trade, p1, p2, 2 food, 1 power");
+                println!("  invent, p1, <technology>, <cube type>");
+                println!("  take, p1, colony, 0");
+                println!("  mark, p1, all");
+                println!("  relic, p1");
+                println!("  phase, economy");
+                println!("or: note <text>, save <path>, view, done (pass to the next");
+                println!("player), quit");
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(path) = line.strip_prefix("save ") {
+            match state.save(path.trim()) {
+                Ok(()) => println!("saved"),
+                Err(e) => println!("{:#}", e),
+            }
+            continue;
+        }
+        let rec = match line.strip_prefix("note ") {
+            Some(text) => Ok(RecordType::AddNote {
+                player,
+                target: NoteTarget::Game,
+                text: text.trim().to_string(),
+            }),
+            None => parse_command(state, &line),
+        };
+        let rec = match rec {
+            Ok(r) => r,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+        match state.validate_group(std::slice::from_ref(&rec)) {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                println!("not allowed: {}", e);
+                continue;
+            }
+            Err(e) => {
+                println!("{:#}", e);
+                continue;
+            }
+        }
+        // records left to the host need the whole table to agree
+        let others: Vec<_> = if state.may_host(&rec) {
+            pins.iter().map(|(p, _)| *p).collect()
+        } else {
+            state.acting_players(&rec).into_iter().collect()
+        };
+        let others: Vec<_> = others.into_iter().filter(|p| *p != player).collect();
+        if !others.iter().all(|p| agrees(state, *p, pins)) {
+            println!("not allowed: other players must agree");
+            continue;
+        }
+        let warnings = state.check_warnings(&rec);
+        for w in warnings.iter() {
+            println!("warning: {}", w);
+        }
+        if !warnings.is_empty() {
+            if let Ok(summary) = state.describe(&rec) {
+                print!("{}", summary);
+            }
+            if prompt("apply anyway? [y/N] ").as_deref() != Some("y") {
+                continue;
+            }
+        }
+        let group = RecordGroup {
+            id: state.next_record_id(),
+            rec: vec![rec],
+        };
+        state.apply(group);
+        let events = state.take_events();
+        if events.is_empty() {
+            println!("ok");
+        }
+        for (_, e) in events {
+            println!("{}", e);
+        }
+    }
+}
+
+fn print_view(state: &GameState, view: &GameStateView) {
+    println!("Confluence {}, {:?} phase", view.confluence.0, view.phase);
+    for p in view.players.iter() {
+        println!();
+        print_player(state, p, view.viewer.player() == Some(p.player));
+    }
+    if !view.notes.is_empty() {
+        println!();
+        println!("Your notes:");
+        for n in view.notes.iter() {
+            println!("  {}", n.text);
+        }
+    }
+    let reminders = view
+        .viewer
+        .player()
+        .map(|p| state.reminders_for(p))
+        .unwrap_or_default();
+    if !reminders.is_empty() {
+        println!();
+        println!("Reminders:");
+        for r in reminders {
+            println!("  [ ] {}", r.text);
+        }
+    }
+    println!();
+    println!("Type 'help' for commands.");
+}
+
+fn print_player(state: &GameState, p: &PlayerView, own: bool) {
+    println!(
+        "{}: {}{}",
+        state.player_name(p.player),
+        p.faction.name(),
+        if own { " (you)" } else { "" }
+    );
+    println!("  cubes:      {}", format_cubes(&p.cubes));
+    let techs: Vec<&str> = p
+        .research_teams
+        .iter()
+        .filter_map(|t| state.data().tech.get(t))
+        .map(|t| t.name.as_str())
+        .collect();
+    println!("  research:   {}", techs.join(", "));
+    println!("  converters: {}", p.converters.len());
+    if !p.loaned_out.is_empty() {
+        println!("  loaned out: {} converter(s)", p.loaned_out.len());
+    }
+    println!("  colonies:   {}", p.colonies.len());
+    match (p.has_bid, p.bid) {
+        (_, Some(b)) => println!(
+            "  bid:        colony {}{}, tech {}{}",
+            b.colony.0,
+            b.colony.1.map(|x| format!(" + {}", x)).unwrap_or_default(),
+            b.tech.0,
+            b.tech.1.map(|x| format!(" + {}", x)).unwrap_or_default()
+        ),
+        (true, None) => println!("  bid:        (sealed)"),
+        (false, None) => {}
+    }
+    if let Some(marked) = p.marked.as_ref().filter(|m| !m.is_empty()) {
+        println!("  marked:     {} converter(s)", marked.len());
+    }
+}
+
+fn format_cubes(c: &CubeRecord) -> String {
+    let counts = [
+        (c.ships, "ships"),
+        (c.food, "food"),
+        (c.culture, "culture"),
+        (c.industry, "industry"),
+        (c.small_wild, "small wild"),
+        (c.power, "power"),
+        (c.biotech, "biotech"),
+        (c.information, "information"),
+        (c.large_wild, "large wild"),
+        (c.ultratech, "ultratech"),
+        (c.points, "VP"),
+    ];
+    let parts: Vec<String> = counts
+        .iter()
+        .filter(|(n, _)| *n != 0)
+        .map(|(n, name)| format!("{} {}", n, name))
+        .collect();
+    if parts.is_empty() {
+        String::from("none")
+    } else {
+        parts.join(", ")
+    }
+}
+
+fn parse_faction(s: &str) -> Option<FactionType> {
+    FactionType::core()
+        .into_iter()
+        .chain(FactionType::bifurcation())
+        .find(|f| f.name().eq_ignore_ascii_case(s) || f.short_name().eq_ignore_ascii_case(s))
+}
+
+/// Clears the terminal, so the previous seat's view isn't left on screen.
+fn clear_screen() {
+    print!("\x1b[2J\x1b[3J\x1b[H");
+    let _ = io::stdout().flush();
+}
+
+/// Asks another seat to agree to a command acting for them, by entering
+/// their PIN (or just confirming, if they didn't set one).
+fn agrees(state: &GameState, player: PlayerID, pins: &[(PlayerID, String)]) -> bool {
+    let name = state.player_name(player);
+    match pins.iter().find(|(p, _)| *p == player) {
+        Some((_, pin)) if !pin.is_empty() => {
+            prompt_hidden(&format!("{}, enter your PIN to agree: ", name)).as_deref()
+                == Some(pin.as_str())
+        }
+        Some(_) => prompt(&format!("{}, do you agree? [y/N] ", name)).as_deref() == Some("y"),
+        None => false,
+    }
+}
+
+/// Like `prompt`, but doesn't echo what's typed, for PINs. Echo is turned
+/// off with `stty`, so where that isn't available input is shown as usual.
+fn prompt_hidden(msg: &str) -> Option<String> {
+    let stty = |arg| {
+        process::Command::new("stty")
+            .arg(arg)
+            .stdin(process::Stdio::inherit())
+            .stderr(process::Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success())
+    };
+    let hidden = stty("-echo");
+    let line = prompt(msg);
+    if hidden {
+        stty("echo");
+        println!();
+    }
+    line
+}
+
+/// Prints a prompt and reads a trimmed line from stdin, or None at the end
+/// of input.
+fn prompt(msg: &str) -> Option<String> {
+    print!("{}", msg);
+    let _ = io::stdout().flush();
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim().to_string()),
+    }
+}
@@ -0,0 +1,35 @@
+//! The canonical data files, built into the binary so `GameData::preloaded`
+//! works without a data directory. The files aren't part of this repository,
+//! so the directory holding them is given by `CUBE_BUNDLE_DIR` at build
+//! time, laid out the same as `DATA_DIR`.
+
+macro_rules! bundle {
+    ($($file:literal),* $(,)?) => {
+        const FILES: &[(&str, &str)] = &[
+            $(($file, include_str!(concat!(env!("CUBE_BUNDLE_DIR"), "/", $file)))),*
+        ];
+    };
+}
+
+// Everything listed by `GameData::data_files`, the optional tables aren't
+// bundled.
+bundle!(
+    "colony.json",
+    "technology.json",
+    "prototypes.json",
+    "startResources.json",
+    "techConverters/Caylion.json",
+    "techConverters/Eni Et.json",
+    "techConverters/Faderan.json",
+    "techConverters/Imdril.json",
+    "techConverters/Kit.json",
+    "techConverters/Kjas.json",
+    "techConverters/Yengii.json",
+    "techConverters/Zeth.json",
+);
+
+/// The bundled copy of a data file, by its path relative to the data
+/// directory.
+pub fn file(name: &str) -> Option<&'static str> {
+    FILES.iter().find(|(f, _)| *f == name).map(|(_, src)| *src)
+}
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::game_data::GameData;
+
 use super::{
-    converter::{Convert, Converter},
+    converter::{Convert, Converter, ConverterKind},
     cube::CubeType,
     faction::alt_kit::UpgradeToken,
     Item, Upgrade,
@@ -57,6 +59,19 @@ pub struct OldColony {
     pub up_cost: Option<Vec<Item>>,
 }
 
+impl Colony {
+    /// The card this colony becomes when upgraded, without upgrading it.
+    /// None if the colony is already upgraded, or the upgraded card isn't in
+    /// the game data.
+    pub fn upgraded_view<'a>(&self, data: &'a GameData) -> Option<&'a Colony> {
+        if self.id.0 >= 100 {
+            // already upgraded
+            return None;
+        }
+        data.colony.get(&ColonyID(self.id.0 + 100))
+    }
+}
+
 impl Convert for Colony {
     fn input(&self) -> &[Item] {
         self.conv.input.as_slice()
@@ -66,27 +81,11 @@ impl Convert for Colony {
         self.conv.output.as_slice()
     }
 
-    fn upgrade(&mut self, data: &crate::state::GameData, opt: usize) {
-        if self.id.0 >= 100 {
-            // already upgraded
-            return;
-        }
-        let new_data = data.colony.get(&ColonyID(self.id.0 + 100));
-        if new_data.is_none() {
+    fn upgrade(&mut self, data: &GameData, _opt: usize) {
+        let Some(new_data) = self.upgraded_view(data) else {
             return;
-        }
-        let Colony {
-            name,
-            id,
-            typ,
-            conv,
-            up_cost,
-        } = new_data.unwrap().clone();
-        self.name = name;
-        self.id = id;
-        self.typ = typ;
-        self.conv = conv;
-        self.up_cost = up_cost;
+        };
+        *self = new_data.clone();
     }
 
     fn upgradable(&self) -> bool {
@@ -120,4 +119,8 @@ impl Convert for Colony {
     fn color(&self) -> super::converter::Arrow {
         self.conv.color
     }
+
+    fn kind(&self) -> ConverterKind {
+        ConverterKind::Colony(self.clone())
+    }
 }
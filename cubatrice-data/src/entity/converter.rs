@@ -2,9 +2,20 @@ use std::{fmt::Debug, hash::Hash};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{state::GameData, Fraction};
-
-use super::{cube::CubeType, faction::alt_kit::UpgradeToken, Item, Upgrade};
+use crate::{game_data::GameData, Fraction};
+
+use super::{
+    colony::Colony,
+    cube::CubeType,
+    faction::{
+        alt_caylion::ProjectConverter,
+        alt_kit::{KitConverter, UpgradeToken},
+        base_faderan::RelicWorld,
+        GenericStartingConverter,
+    },
+    technology::ConverterPrototype,
+    Item, Upgrade,
+};
 
 /// Transparent type for referring to a specific converter.
 #[derive(
@@ -13,7 +24,7 @@ use super::{cube::CubeType, faction::alt_kit::UpgradeToken, Item, Upgrade};
 pub struct ConverterID(pub usize);
 
 /// Used for determining when a converter can be run
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Arrow {
     /// Runs during economy phase (e.g. converters)
     White,
@@ -30,6 +41,10 @@ pub struct Converter {
     pub color: Arrow,
     pub input: Vec<Item>,
     pub output: Vec<Item>,
+    /// Fleet support needed to run the converter. Only Imdril converters
+    /// need any.
+    #[serde(default)]
+    pub fleet: usize,
 }
 
 /// Used as a generic converter. Specific types might be a planet, a converter
@@ -46,7 +61,7 @@ pub trait Convert: Debug {
         for i in self.input() {
             match i {
                 Item::Cubes(typ, qty) | Item::DonationCubes(typ, qty) => {
-                    sum = sum + (typ.value() * (*qty) as isize)
+                    sum += typ.value() * (*qty) as isize
                 }
                 _ => continue,
             }
@@ -61,20 +76,18 @@ pub trait Convert: Debug {
     fn input_value_adjusted(&self, interest_rate: Fraction, turns_left: usize) -> Fraction {
         let mut rate = Fraction::new(1, 1);
         for _ in 0..(turns_left - 1) {
-            rate = rate * interest_rate;
+            rate *= interest_rate;
         }
         let mut sum = Fraction::new(0, 1);
         for i in self.input() {
             match i {
                 Item::Cubes(CubeType::Ship, qty) | Item::DonationCubes(CubeType::Ship, qty) => {
-                    sum = sum + (*qty) as isize
+                    sum += (*qty) as isize
                 }
                 Item::Cubes(CubeType::VictoryPoint, qty)
-                | Item::DonationCubes(CubeType::VictoryPoint, qty) => {
-                    sum = sum + (6 * qty) as isize
-                }
+                | Item::DonationCubes(CubeType::VictoryPoint, qty) => sum += (6 * qty) as isize,
                 Item::Cubes(typ, qty) | Item::DonationCubes(typ, qty) => {
-                    sum = sum + rate * (typ.value() * (*qty) as isize)
+                    sum += rate * (typ.value() * (*qty) as isize)
                 }
                 _ => continue,
             }
@@ -94,7 +107,7 @@ pub trait Convert: Debug {
         for i in self.output() {
             match i {
                 Item::Cubes(typ, qty) | Item::DonationCubes(typ, qty) => {
-                    sum = sum + (typ.value() * (*qty) as isize)
+                    sum += typ.value() * (*qty) as isize
                 }
                 _ => continue,
             }
@@ -109,20 +122,18 @@ pub trait Convert: Debug {
     fn output_value_adjusted(&self, interest_rate: Fraction, turns_left: usize) -> Fraction {
         let mut rate = Fraction::new(1, 1);
         for _ in 0..(turns_left - 1) {
-            rate = rate * interest_rate;
+            rate *= interest_rate;
         }
         let mut sum = Fraction::new(0, 1);
         for i in self.output() {
             match i {
                 Item::Cubes(CubeType::Ship, qty) | Item::DonationCubes(CubeType::Ship, qty) => {
-                    sum = sum + (*qty) as isize;
+                    sum += (*qty) as isize;
                 }
                 Item::Cubes(CubeType::VictoryPoint, qty)
-                | Item::DonationCubes(CubeType::VictoryPoint, qty) => {
-                    sum = sum + (6 * qty) as isize
-                }
+                | Item::DonationCubes(CubeType::VictoryPoint, qty) => sum += (6 * qty) as isize,
                 Item::Cubes(typ, qty) | Item::DonationCubes(typ, qty) => {
-                    sum = sum + rate * (typ.value() * (*qty) as isize)
+                    sum += rate * (typ.value() * (*qty) as isize)
                 }
                 _ => continue,
             }
@@ -133,7 +144,7 @@ pub trait Convert: Debug {
     /// Checks whether the converter can be run for free. This is only the case
     /// when the the converter has no input cost.
     fn free(&self) -> bool {
-        self.input().len() == 0
+        self.input().is_empty()
     }
 
     /// Modifies the converter, upgrading it. This should be done after removing
@@ -160,7 +171,84 @@ pub trait Convert: Debug {
 
     fn upgrade_token(&self) -> Option<UpgradeToken>;
 
+    /// How much fleet support whoever runs this converter must commit to it
+    /// for the economy phase.
+    fn fleet_support(&self) -> usize {
+        0
+    }
+
+    /// Whether the converter can change hands permanently in a trade.
+    fn tradable(&self) -> bool {
+        true
+    }
+
+    /// Whether the converter can be lent to another player for an economy
+    /// phase.
+    fn loanable(&self) -> bool {
+        true
+    }
+
     /// The color of the converter's arrow, used to determine when the
     /// converter can be run.
     fn color(&self) -> Arrow;
+
+    /// A copy of this converter as its concrete type, for serialization.
+    fn kind(&self) -> ConverterKind;
+}
+
+/// Every concrete type of converter. Converters are stored as trait objects,
+/// which can't be serialized directly, so they're saved as one of these and
+/// turned back into trait objects when loaded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConverterKind {
+    Prototype(ConverterPrototype),
+    Colony(Colony),
+    Kit(KitConverter),
+    Starting(GenericStartingConverter),
+    Relic(RelicWorld),
+    Project(ProjectConverter),
+}
+
+impl ConverterKind {
+    pub fn into_convert(self) -> Box<dyn Convert> {
+        match self {
+            Self::Prototype(c) => Box::new(c),
+            Self::Colony(c) => Box::new(c),
+            Self::Kit(c) => Box::new(c),
+            Self::Starting(c) => Box::new(c),
+            Self::Relic(c) => Box::new(c),
+            Self::Project(c) => Box::new(c),
+        }
+    }
+}
+
+/// Serializes a map of converter trait objects as their concrete kinds. Use
+/// with `#[serde(with = "...")]`.
+pub mod boxed_map {
+    use std::{collections::HashMap, hash::Hash};
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Convert, ConverterKind};
+
+    pub fn serialize<K, S>(map: &HashMap<K, Box<dyn Convert>>, s: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize + Eq + Hash,
+        S: Serializer,
+    {
+        let kinds: HashMap<&K, ConverterKind> = map.iter().map(|(k, c)| (k, c.kind())).collect();
+        kinds.serialize(s)
+    }
+
+    pub fn deserialize<'de, K, D>(d: D) -> Result<HashMap<K, Box<dyn Convert>>, D::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        D: Deserializer<'de>,
+    {
+        let kinds: HashMap<K, ConverterKind> = HashMap::deserialize(d)?;
+        Ok(kinds
+            .into_iter()
+            .map(|(k, c)| (k, c.into_convert()))
+            .collect())
+    }
 }
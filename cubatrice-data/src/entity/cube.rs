@@ -2,7 +2,10 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{state::player::PlayerID, Fraction};
+use crate::{
+    entity::{player::PlayerID, Item},
+    Fraction,
+};
 
 /// Transparent type for cube IDs
 #[derive(
@@ -13,7 +16,7 @@ pub struct CubeID(pub usize);
 /// Different types of cube. Some cubes exist only virtually, as inputs or
 /// outputs on cards. Physical cubes that players can own can only be of
 /// certain types.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum CubeType {
     /// Ships
     Ship,
@@ -48,6 +51,27 @@ pub enum CubeType {
 }
 
 impl CubeType {
+    /// The cube's name, without any terminal colouring.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Self::Ship => "Ship",
+            Self::Culture => "Culture",
+            Self::Food => "Food",
+            Self::Industry => "Industry",
+            Self::UnitySmall => "Small Wild",
+            Self::AnySmall => "Any Small",
+            Self::AnySmallNonUnity => "Any Small (Zeth)",
+            Self::Power => "Power",
+            Self::Biotech => "Biotech",
+            Self::Information => "Information",
+            Self::UnityLarge => "Large Wild",
+            Self::AnyLarge => "Any Large",
+            Self::AnyLargeNonUnity => "Any Large (Zeth)",
+            Self::Ultratech => "Ultratech",
+            Self::VictoryPoint => "Victory Point",
+        }
+    }
+
     /// Gets the suggested raw value of this cube type. Perceived value of
     /// cubes may change based on supply and demand.
     pub fn value(&self) -> Fraction {
@@ -170,7 +194,7 @@ pub struct CubeRecord {
 
 impl CubeRecord {
     pub fn split(self) -> (Self, Self) {
-        (self.clone().gt_zero(), (-self).gt_zero())
+        (self.gt_zero(), (-self).gt_zero())
     }
 
     fn gt_zero(self) -> Self {
@@ -189,16 +213,19 @@ impl CubeRecord {
         }
     }
 
-    fn value(&self) -> Fraction {
+    /// Gets the suggested raw value of all cubes in this record.
+    pub fn value(&self) -> Fraction {
         Fraction::new(1, 1)
             * (self.food + self.culture + self.industry + self.small_wild + self.ships)
             + Fraction::new(3, 2) * (self.biotech + self.power + self.information + self.large_wild)
             + Fraction::new(3, 1) * (self.ultratech + self.points)
     }
 
-    fn vp_value(&self) -> Fraction {
-        Fraction::new(1, 6)
-            * (self.food + self.culture + self.industry + self.small_wild + self.ships)
+    /// Gets the victory points these cubes are worth at the end of the
+    /// game. Victory point cubes count in full. Ships aren't cubes, and
+    /// aren't counted: what they're worth depends on how the game is scored.
+    pub fn vp_value(&self) -> Fraction {
+        Fraction::new(1, 6) * (self.food + self.culture + self.industry + self.small_wild)
             + Fraction::new(1, 4) * (self.biotech + self.power + self.information + self.large_wild)
             + Fraction::new(1, 2) * (self.ultratech)
             + Fraction::new(1, 1) * (self.points)
@@ -223,6 +250,86 @@ impl CubeRecord {
             CubeType::VictoryPoint => self.points,
         }
     }
+
+    /// Each physical cube type in the record along with its count. Wild
+    /// cubes come first, since only wild cubes can pay for them.
+    pub fn entries(&self) -> [(CubeType, isize); 11] {
+        [
+            (CubeType::UnitySmall, self.small_wild),
+            (CubeType::UnityLarge, self.large_wild),
+            (CubeType::Food, self.food),
+            (CubeType::Culture, self.culture),
+            (CubeType::Industry, self.industry),
+            (CubeType::Biotech, self.biotech),
+            (CubeType::Power, self.power),
+            (CubeType::Information, self.information),
+            (CubeType::Ultratech, self.ultratech),
+            (CubeType::Ship, self.ships),
+            (CubeType::VictoryPoint, self.points),
+        ]
+    }
+
+    /// Iterates over each physical cube type in the record along with its
+    /// count, in the same order as `entries`.
+    pub fn iter(&self) -> impl Iterator<Item = (CubeType, isize)> {
+        self.entries().into_iter()
+    }
+
+    /// Whether the cubes in this record can pay for `cost`. In a cost, the
+    /// wild counts stand for 'any' cube of that size, as `add_type` records
+    /// them; in the cubes paying, they're wild cubes, which can stand in for
+    /// any cube of their size once the exact cubes run out.
+    pub fn satisfies(&self, cost: &CubeRecord) -> bool {
+        let mut have = *self;
+        // exact cubes first, with wilds of the same size covering shortfalls
+        for (typ, qty) in cost.iter() {
+            let wild = match typ {
+                CubeType::Food | CubeType::Culture | CubeType::Industry => {
+                    Some(CubeType::UnitySmall)
+                }
+                CubeType::Power | CubeType::Biotech | CubeType::Information => {
+                    Some(CubeType::UnityLarge)
+                }
+                CubeType::UnitySmall | CubeType::UnityLarge => continue,
+                _ => None,
+            };
+            let exact = have.count_type(typ).clamp(0, qty.max(0));
+            have.add_type(typ, -exact);
+            let short = qty - exact;
+            if short <= 0 {
+                continue;
+            }
+            match wild {
+                Some(w) if have.count_type(w) >= short => have.add_type(w, -short),
+                _ => return false,
+            }
+        }
+        // then 'any' cubes from whatever's left of their size
+        cost.small_wild <= have.count_type(CubeType::AnySmall)
+            && cost.large_wild <= have.count_type(CubeType::AnyLarge)
+    }
+
+    /// Adds some quantity of cubes of a given type to the record. Virtual
+    /// 'any' cubes are counted towards the wild cube of the same size.
+    pub fn add_type(&mut self, typ: CubeType, qty: isize) {
+        match typ {
+            CubeType::Ship => self.ships += qty,
+            CubeType::Food => self.food += qty,
+            CubeType::Culture => self.culture += qty,
+            CubeType::Industry => self.industry += qty,
+            CubeType::UnitySmall | CubeType::AnySmall | CubeType::AnySmallNonUnity => {
+                self.small_wild += qty
+            }
+            CubeType::Power => self.power += qty,
+            CubeType::Information => self.information += qty,
+            CubeType::Biotech => self.biotech += qty,
+            CubeType::UnityLarge | CubeType::AnyLarge | CubeType::AnyLargeNonUnity => {
+                self.large_wild += qty
+            }
+            CubeType::Ultratech => self.ultratech += qty,
+            CubeType::VictoryPoint => self.points += qty,
+        }
+    }
 }
 
 impl PartialOrd for CubeRecord {
@@ -251,6 +358,72 @@ impl std::ops::Neg for CubeRecord {
     }
 }
 
+impl std::ops::Add for CubeRecord {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            food: self.food + rhs.food,
+            culture: self.culture + rhs.culture,
+            industry: self.industry + rhs.industry,
+            small_wild: self.small_wild + rhs.small_wild,
+            biotech: self.biotech + rhs.biotech,
+            power: self.power + rhs.power,
+            information: self.information + rhs.information,
+            large_wild: self.large_wild + rhs.large_wild,
+            ultratech: self.ultratech + rhs.ultratech,
+            ships: self.ships + rhs.ships,
+            points: self.points + rhs.points,
+        }
+    }
+}
+
+impl std::ops::Sub for CubeRecord {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl std::ops::AddAssign for CubeRecord {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::SubAssign for CubeRecord {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::iter::Sum for CubeRecord {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |a, b| a + b)
+    }
+}
+
+impl<'a> std::iter::Sum<&'a CubeRecord> for CubeRecord {
+    fn sum<I: Iterator<Item = &'a CubeRecord>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+/// The cubes in a list of items, such as a converter's inputs. Anything
+/// that isn't cubes is left out.
+impl From<&[Item]> for CubeRecord {
+    fn from(value: &[Item]) -> Self {
+        let mut s = Self::default();
+        for i in value {
+            if let Item::Cubes(typ, qty) | Item::DonationCubes(typ, qty) = i {
+                s.add_type(*typ, *qty as isize);
+            }
+        }
+        s
+    }
+}
+
 impl From<&[Cube]> for CubeRecord {
     fn from(value: &[Cube]) -> Self {
         value.iter().collect()
@@ -261,19 +434,7 @@ impl<'a> FromIterator<&'a Cube> for CubeRecord {
     fn from_iter<T: IntoIterator<Item = &'a Cube>>(iter: T) -> Self {
         let mut s = Self::default();
         for v in iter {
-            match v.typ {
-                CubeType::Ship => s.ships += 1,
-                CubeType::Culture => s.culture += 1,
-                CubeType::Food => s.food += 1,
-                CubeType::Industry => s.industry += 1,
-                CubeType::UnitySmall => s.small_wild += 1,
-                CubeType::Biotech => s.biotech += 1,
-                CubeType::Power => s.power += 1,
-                CubeType::Information => s.information += 1,
-                CubeType::UnityLarge => s.large_wild += 1,
-                CubeType::VictoryPoint => s.points += 1,
-                _ => {}
-            }
+            s.add_type(v.typ, 1);
         }
         s
     }
@@ -281,23 +442,42 @@ impl<'a> FromIterator<&'a Cube> for CubeRecord {
 
 impl Display for CubeType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let (color, text) = match *self {
-            Self::Ship => ("31", "Ship"),
-            Self::Culture => ("37", "Culture"),
-            Self::Food => ("32", "Food"),
-            Self::Industry => ("33", "Industry"),
-            Self::UnitySmall => ("90", "Small Wild"),
-            Self::AnySmall => ("35", "Any Small"),
-            Self::AnySmallNonUnity => ("35", "Any Small (Zeth)"),
-            Self::Power => ("93", "Power"),
-            Self::Biotech => ("94", "Biotech"),
-            Self::Information => ("97", "Information"),
-            Self::UnityLarge => ("90", "Large Wild"),
-            Self::AnyLarge => ("35", "Any Large"),
-            Self::AnyLargeNonUnity => ("35", "Any Large (Zeth)"),
-            Self::Ultratech => ("33", "Ultratech"),
-            Self::VictoryPoint => ("35", "Victory Point"),
+        let color = match *self {
+            Self::Ship => "31",
+            Self::Culture => "37",
+            Self::Food => "32",
+            Self::Industry | Self::Ultratech => "33",
+            Self::UnitySmall | Self::UnityLarge => "90",
+            Self::Power => "93",
+            Self::Biotech => "94",
+            Self::Information => "97",
+            Self::AnySmall
+            | Self::AnySmallNonUnity
+            | Self::AnyLarge
+            | Self::AnyLargeNonUnity
+            | Self::VictoryPoint => "35",
         };
-        write!(f, "\x1b[{}m{}\x1b[0m", color, text)
+        write!(f, "\x1b[{}m{}\x1b[0m", color, self.name())
     }
 }
+
+/// Parses a cube type from its name or colour, as written in spreadsheets
+/// and data files, ignoring case, plurals and separators.
+pub fn parse_cube_type(s: &str) -> Result<CubeType, String> {
+    let s = s.to_lowercase();
+    let s = s.trim_end_matches('s').replace([' ', '_', '-'], "");
+    Ok(match s.as_str() {
+        "ship" | "red" => CubeType::Ship,
+        "culture" | "white" => CubeType::Culture,
+        "food" | "green" => CubeType::Food,
+        "industry" | "brown" => CubeType::Industry,
+        "smallwild" | "smallgray" | "smallgrey" => CubeType::UnitySmall,
+        "power" | "yellow" => CubeType::Power,
+        "biotech" | "blue" => CubeType::Biotech,
+        "information" | "black" => CubeType::Information,
+        "largewild" | "largegray" | "largegrey" => CubeType::UnityLarge,
+        "ultratech" | "honey" => CubeType::Ultratech,
+        "vp" | "victorypoint" | "point" => CubeType::VictoryPoint,
+        _ => return Err(format!("unknown cube type '{}'", s)),
+    })
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{
+    converter::{Arrow, Convert, Converter, ConverterKind},
+    Item, Upgrade,
+};
+
+use super::alt_kit::UpgradeToken;
+
+#[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProjectState {
+    /// Project has been activated by the Caylion, and cards have been
+    /// distributed
+    Active,
+    /// Project has all the necessary votes, and is awaiting approval
+    /// from the Caylion.
+    Pending,
+    /// Project does not have all of the requisite votes, and needs more votes
+    /// before it can be activated.
+    Idle,
+    /// Project is on the reverse of a currently available project, and cannot
+    /// be voted on this turn.
+    Unavailable,
+}
+
+#[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ProjectID(pub usize);
+
+/// An Alt Caylion project card. Cards are double sided, and only one side
+/// can be voted on at a time.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Project {
+    pub id: ProjectID,
+    pub name: String,
+    /// How many players must vote for the project before the Caylion can
+    /// activate it.
+    pub votes_needed: usize,
+    /// The other side of the card, if it has one.
+    pub reverse: Option<ProjectID>,
+    /// Converters every voter receives when the project is activated.
+    pub converters: Vec<Converter>,
+}
+
+/// A converter handed out by an active project.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectConverter {
+    pub project: ProjectID,
+    pub name: String,
+    #[serde(flatten)]
+    pub conv: Converter,
+}
+
+impl Convert for ProjectConverter {
+    fn input(&self) -> &[Item] {
+        self.conv.input.as_slice()
+    }
+
+    fn output(&self) -> &[Item] {
+        self.conv.output.as_slice()
+    }
+
+    fn upgrade(&mut self, _data: &crate::game_data::GameData, _opt: usize) {}
+
+    fn upgradable(&self) -> bool {
+        false
+    }
+
+    fn upgrade_opts(&self) -> Option<usize> {
+        None
+    }
+
+    fn upgrade_cost(&self, _alt: usize) -> Option<Upgrade> {
+        None
+    }
+
+    fn upgrade_token(&self) -> Option<UpgradeToken> {
+        None
+    }
+
+    fn color(&self) -> Arrow {
+        self.conv.color
+    }
+
+    fn fleet_support(&self) -> usize {
+        self.conv.fleet
+    }
+
+    fn kind(&self) -> ConverterKind {
+        ConverterKind::Project(self.clone())
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::entity::{
-    converter::{Arrow, Convert},
+    converter::{Arrow, Convert, ConverterKind},
     Item, Upgrade,
 };
 
@@ -43,7 +43,7 @@ impl Convert for KitConverter {
         self.output_cache.as_slice()
     }
 
-    fn upgrade(&mut self, data: &crate::state::GameData, opt: usize) {
+    fn upgrade(&mut self, data: &crate::game_data::GameData, opt: usize) {
         if opt < 2 && self.l_conv.upgradable() {
             self.l_conv.upgrade(data, opt)
         } else {
@@ -56,6 +56,7 @@ impl Convert for KitConverter {
                 },
             )
         }
+        self.update_cache();
     }
 
     fn upgradable(&self) -> bool {
@@ -91,4 +92,8 @@ impl Convert for KitConverter {
     fn color(&self) -> Arrow {
         Arrow::White
     }
+
+    fn kind(&self) -> ConverterKind {
+        ConverterKind::Kit(self.clone())
+    }
 }
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// Deep Unity's dice and dice change cards. Each Deep Unity starting
+/// converter shows a die face, and can only be run while one of the dice
+/// shows that face.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AltUnityState {
+    /// The face each die currently shows. Empty until the dice are first
+    /// rolled.
+    pub faces: Vec<u8>,
+    /// Dice change cards held. Each one lets a die be set to any face.
+    pub change_cards: usize,
+    /// Whether the dice have been rolled this confluence. They're only
+    /// rolled once.
+    #[serde(default)]
+    pub rolled: bool,
+}
+
+impl AltUnityState {
+    /// How many dice are rolled each confluence.
+    pub const DICE: usize = 3;
+    /// How many faces each die has, numbered from 1.
+    pub const SIDES: u8 = 6;
+    /// How many dice change cards Deep Unity gain at the start of every
+    /// confluence.
+    pub const CHANGE_CARDS_PER_CONFLUENCE: usize = 1;
+
+    /// Whether any die currently shows a face.
+    pub fn shows(&self, face: u8) -> bool {
+        self.faces.contains(&face)
+    }
+}
@@ -1,6 +1,6 @@
 use crate::entity::{
     colony::ColonyType,
-    converter::{Arrow, Convert},
+    converter::{Arrow, Convert, ConverterKind},
     cube::CubeType,
     Item, Upgrade,
 };
@@ -23,6 +23,24 @@ pub enum RelicWorld {
     TheGrandArmilla,
 }
 
+impl RelicWorld {
+    /// Every relic world, in the order they're listed in the rulebook.
+    pub const ALL: [RelicWorld; 12] = [
+        Self::GiftOfTheDuruntai,
+        Self::ContextualIntegratorCache,
+        Self::AutomatedTransportNetwork,
+        Self::RelicDetector,
+        Self::LibraryOfEntelechy,
+        Self::TransmutiveDecomposer,
+        Self::NalgorianGrindstone,
+        Self::StarsRuin,
+        Self::ParadiseConverter,
+        Self::BarianTradeArmada,
+        Self::ThilsDemiring,
+        Self::TheGrandArmilla,
+    ];
+}
+
 const ATN_OUT: [Item; 1] = [Item::Cubes(CubeType::Food, 1)];
 
 const LIBRARY_OUT: [Item; 4] = [
@@ -102,9 +120,13 @@ impl Convert for RelicWorld {
         None
     }
 
-    fn upgrade(&mut self, _data: &crate::state::GameData, _opt: usize) {}
+    fn upgrade(&mut self, _data: &crate::game_data::GameData, _opt: usize) {}
 
     fn upgrade_token(&self) -> Option<super::alt_kit::UpgradeToken> {
         None
     }
+
+    fn kind(&self) -> ConverterKind {
+        ConverterKind::Relic(*self)
+    }
 }
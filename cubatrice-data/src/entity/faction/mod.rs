@@ -3,10 +3,10 @@ use serde::{Deserialize, Serialize};
 use self::alt_kit::UpgradeToken;
 
 use super::{
-    converter::{Arrow, Convert, Converter},
+    converter::{Arrow, Convert, ConverterKind},
     Item, Upgrade,
 };
-use crate::{state::GameData, Fraction};
+use crate::{game_data::GameData, Fraction};
 
 pub mod alt_caylion;
 pub mod alt_kit;
@@ -268,6 +268,26 @@ pub struct GenericStartingConverter {
     pub upg_opts: Vec<Upgrade>,
     pub tier: Option<UpgradeToken>,
     pub upgraded: bool,
+    /// The die face Deep Unity needs showing to run this converter. None for
+    /// every other faction.
+    #[serde(default)]
+    pub die: Option<u8>,
+    /// Fleet support needed to run this converter. Only Imdril converters
+    /// need any.
+    #[serde(default)]
+    pub fleet: usize,
+    /// Whether the converter can change hands permanently. Converters are
+    /// tradable unless their data says otherwise.
+    #[serde(default = "default_true")]
+    pub tradable: bool,
+    /// Whether the converter can be lent out. Converters are loanable
+    /// unless their data says otherwise.
+    #[serde(default = "default_true")]
+    pub loanable: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl Convert for GenericStartingConverter {
@@ -288,7 +308,7 @@ impl Convert for GenericStartingConverter {
     }
 
     fn upgradable(&self) -> bool {
-        self.upgraded == false
+        !self.upgraded
     }
 
     fn upgrade_opts(&self) -> Option<usize> {
@@ -307,7 +327,23 @@ impl Convert for GenericStartingConverter {
         self.tier
     }
 
+    fn fleet_support(&self) -> usize {
+        self.fleet
+    }
+
+    fn tradable(&self) -> bool {
+        self.tradable
+    }
+
+    fn loanable(&self) -> bool {
+        self.loanable
+    }
+
     fn color(&self) -> Arrow {
         Arrow::White
     }
+
+    fn kind(&self) -> ConverterKind {
+        ConverterKind::Starting(self.clone())
+    }
 }
@@ -6,13 +6,18 @@ use self::{
     colony::{ColonyID, ColonyType},
     cube::{CubeRecord, CubeType},
     technology::TechID,
+    token::{TokenDef, TokenID},
 };
 
 pub mod colony;
 pub mod converter;
 pub mod cube;
 pub mod faction;
+pub mod player;
+pub mod preset;
+pub mod reminder;
 pub mod technology;
+pub mod token;
 
 /// Item is used in a lot of places where we need a generic item. For example,
 /// the inputs / outputs of converters, upgrades for converters, or trades.
@@ -34,6 +39,10 @@ pub enum Item {
     /// Some kind of token. Only ever seen as output. Used for the envoys
     /// converter, Imdril factories, and Eni Et service tokens.
     Token(Token),
+
+    /// A scripted effect, for homebrew content. Only ever seen as output.
+    #[cfg(feature = "scripting")]
+    Script(crate::script::Script),
 }
 
 pub enum OldItem {
@@ -88,21 +97,16 @@ pub enum Token {
     CrossColonization,
     /// Factory token produced by the grand fleet, which produces small cubes
     Factory(CubeType),
+    /// A token defined in data, for expansions and homebrew factions.
+    Custom(TokenID),
 }
 
 impl Token {
-    /// Whether the token is intended to be limited to a certain
-    /// quantity and, if so, how many are allowed to exist at once.
+    /// Whether a base game token is intended to be limited to a certain
+    /// quantity and, if so, how many are allowed to exist at once. Custom
+    /// tokens are defined in `GameData::tokens`.
     pub fn quantity_limited(&self) -> Option<usize> {
-        match self {
-            Self::Envoy => Some(7),
-            Self::CrossColonization => Some(3),
-            Self::Acknowledgement => None,
-            Self::Regret => None,
-            Self::Service => Some(17),
-            // There can only exist 3 of *each color* factory, up to 18 total.
-            Self::Factory(_) => Some(3),
-        }
+        TokenDef::builtin(self.id()).and_then(|d| d.supply)
     }
 }
 
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use super::faction::FactionType;
+
+/// A recommended set of factions, so a game can be set up in one step
+/// instead of picking each faction by hand.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Preset {
+    /// Short name used to look the preset up (e.g. "first-game").
+    pub id: String,
+    /// Name shown to players.
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// The factions in the game, in seating order.
+    pub factions: Vec<FactionType>,
+}
+
+impl Preset {
+    fn new(id: &str, name: &str, description: &str, factions: Vec<FactionType>) -> Self {
+        Self {
+            id: String::from(id),
+            name: String::from(name),
+            description: String::from(description),
+            factions,
+        }
+    }
+
+    /// The presets recommended by the rulebook.
+    pub fn builtins() -> Vec<Preset> {
+        vec![
+            Preset::new(
+                "first-game",
+                "First game: Kit/Kjas/Caylion/Faderan",
+                "The factions recommended for a group's first game.",
+                vec![
+                    FactionType::KitCore,
+                    FactionType::KjasCore,
+                    FactionType::CaylionCore,
+                    FactionType::FaderanCore,
+                ],
+            ),
+            Preset::new(
+                "all-alt",
+                "All alt factions",
+                "Every alternate faction from the Bifurcation expansion.",
+                FactionType::bifurcation(),
+            ),
+            Preset::new(
+                "no-zeth",
+                "No Zeth",
+                "Every core faction except the Zeth, for groups who'd rather not deal with theft.",
+                FactionType::core()
+                    .into_iter()
+                    .filter(|f| *f != FactionType::ZethCore)
+                    .collect(),
+            ),
+        ]
+    }
+
+    /// A built in preset, by ID.
+    pub fn builtin(id: &str) -> Option<Preset> {
+        Self::builtins().into_iter().find(|p| p.id == id)
+    }
+}
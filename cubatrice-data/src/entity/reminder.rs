@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+use super::faction::FactionType;
+
+/// Something a faction's player tends to forget, shown to them at the right
+/// point of each confluence.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Reminder {
+    pub faction: FactionType,
+    /// The phase the reminder applies to, named as in record imports (e.g.
+    /// "trade", "colony bid").
+    pub phase: String,
+    pub text: String,
+}
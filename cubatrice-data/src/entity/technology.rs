@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::{
-    converter::{Arrow, Convert, Converter},
+    converter::{Arrow, Convert, Converter, ConverterKind},
     cube::CubeType,
     faction::alt_kit::UpgradeToken,
     Item, Upgrade,
@@ -76,6 +76,16 @@ pub struct Technology {
     pub invent_reward: usize,
 }
 
+/// Points the inventor of a technology scores when it's shared, by the
+/// confluence it's shared in. The Yengii don't share their inventions, and
+/// score from their own, smaller table as they license them instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SharingBonus {
+    pub confluence: usize,
+    pub standard: usize,
+    pub yengii: usize,
+}
+
 /// A converter without additional information, such as who owns it or
 /// additional faction data (such as imdril fleet cost).
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -95,7 +105,7 @@ impl Convert for ConverterPrototype {
         self.conv.output.as_slice()
     }
 
-    fn upgrade(&mut self, _data: &crate::state::GameData, _opt: usize) {
+    fn upgrade(&mut self, _data: &crate::game_data::GameData, _opt: usize) {
         // converter prototypes should not be upgraded, they aren't real
         // converters, and can't know (for example) which faction's set
         // of converters to actually pull from.
@@ -127,6 +137,10 @@ impl Convert for ConverterPrototype {
         }
     }
 
+    fn fleet_support(&self) -> usize {
+        self.conv.fleet
+    }
+
     fn upgrade_token(&self) -> Option<UpgradeToken> {
         if self.id.0 <= 7 {
             Some(UpgradeToken::TierOne)
@@ -140,4 +154,8 @@ impl Convert for ConverterPrototype {
     fn color(&self) -> Arrow {
         self.conv.color
     }
+
+    fn kind(&self) -> ConverterKind {
+        ConverterKind::Prototype(self.clone())
+    }
 }
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+use super::{cube::CubeType, Token};
+
+/// Transparent type for token IDs. Tokens in the base game have fixed IDs;
+/// homebrew tokens should use IDs from `TokenID::FIRST_CUSTOM` onwards.
+#[derive(
+    Clone, Copy, Default, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub struct TokenID(pub usize);
+
+/// Colours of factory the Grand Fleet can build, in the order of their
+/// token IDs.
+pub const FACTORY_TYPES: [CubeType; 6] = [
+    CubeType::Culture,
+    CubeType::Food,
+    CubeType::Industry,
+    CubeType::Power,
+    CubeType::Biotech,
+    CubeType::Information,
+];
+
+impl TokenID {
+    pub const ACKNOWLEDGEMENT: TokenID = TokenID(0);
+    pub const ENVOY: TokenID = TokenID(1);
+    pub const REGRET: TokenID = TokenID(2);
+    pub const SERVICE: TokenID = TokenID(3);
+    pub const CROSS_COLONIZATION: TokenID = TokenID(4);
+    /// Factories take one ID per colour, following `FACTORY_TYPES`.
+    pub const FIRST_FACTORY: TokenID = TokenID(5);
+    /// The first ID which isn't reserved for the base game.
+    pub const FIRST_CUSTOM: TokenID = TokenID(100);
+
+    /// The ID of a colour of factory, if factories come in that colour.
+    pub fn factory(typ: CubeType) -> Option<TokenID> {
+        FACTORY_TYPES
+            .iter()
+            .position(|t| *t == typ)
+            .map(|i| TokenID(Self::FIRST_FACTORY.0 + i))
+    }
+}
+
+/// The rules for a type of token: how many exist and what they're worth.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenDef {
+    pub id: TokenID,
+    pub name: String,
+    /// How many of the token exist. None if the supply is unlimited.
+    pub supply: Option<usize>,
+    /// Whether players may trade the token.
+    pub tradable: bool,
+    /// Victory points each token is worth to whoever holds it at the end of
+    /// the game.
+    #[serde(default)]
+    pub vp: isize,
+    /// Whether the token is placed on a card or colony, rather than held by
+    /// a player.
+    #[serde(default)]
+    pub placed: bool,
+}
+
+impl TokenDef {
+    fn new(id: TokenID, name: &str, supply: Option<usize>, tradable: bool) -> Self {
+        Self {
+            id,
+            name: String::from(name),
+            supply,
+            tradable,
+            vp: 0,
+            placed: false,
+        }
+    }
+
+    /// Definitions of every token in the base game.
+    pub fn builtins() -> Vec<TokenDef> {
+        let mut defs = vec![
+            TokenDef::new(TokenID::ACKNOWLEDGEMENT, "Acknowledgement", None, true),
+            TokenDef::new(TokenID::ENVOY, "Envoy", Some(7), true),
+            TokenDef {
+                vp: -1,
+                ..TokenDef::new(TokenID::REGRET, "Regret", None, false)
+            },
+            TokenDef {
+                placed: true,
+                ..TokenDef::new(TokenID::SERVICE, "Service", Some(17), true)
+            },
+            TokenDef {
+                placed: true,
+                ..TokenDef::new(
+                    TokenID::CROSS_COLONIZATION,
+                    "Cross Colonization",
+                    Some(3),
+                    true,
+                )
+            },
+        ];
+        // There can only exist 3 of *each color* factory, up to 18 total.
+        for t in FACTORY_TYPES {
+            defs.push(TokenDef {
+                placed: true,
+                ..TokenDef::new(
+                    TokenID::factory(t).unwrap_or_default(),
+                    &format!("{:?} Factory", t),
+                    Some(3),
+                    false,
+                )
+            });
+        }
+        defs
+    }
+
+    /// The definition of a base game token.
+    pub fn builtin(id: TokenID) -> Option<TokenDef> {
+        Self::builtins().into_iter().find(|d| d.id == id)
+    }
+}
+
+impl Token {
+    /// The ID of this token's definition.
+    pub fn id(&self) -> TokenID {
+        match *self {
+            Self::Acknowledgement => TokenID::ACKNOWLEDGEMENT,
+            Self::Envoy => TokenID::ENVOY,
+            Self::Regret => TokenID::REGRET,
+            Self::Service => TokenID::SERVICE,
+            Self::CrossColonization => TokenID::CROSS_COLONIZATION,
+            Self::Factory(t) => TokenID::factory(t).unwrap_or_default(),
+            Self::Custom(id) => id,
+        }
+    }
+
+    /// The token with a given ID. IDs which aren't built in are custom
+    /// tokens.
+    pub fn from_id(id: TokenID) -> Token {
+        match id {
+            TokenID::ACKNOWLEDGEMENT => Self::Acknowledgement,
+            TokenID::ENVOY => Self::Envoy,
+            TokenID::REGRET => Self::Regret,
+            TokenID::SERVICE => Self::Service,
+            TokenID::CROSS_COLONIZATION => Self::CrossColonization,
+            _ => match FACTORY_TYPES.get(id.0.wrapping_sub(TokenID::FIRST_FACTORY.0)) {
+                Some(t) => Self::Factory(*t),
+                None => Self::Custom(id),
+            },
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use std::{collections::HashMap, fmt::Debug, fs, hash::Hash, path::Path};
+
+use anyhow::{anyhow, Context, Error};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    data_dirs,
+    entity::{
+        colony::{Colony, ColonyID},
+        faction::{
+            alt_caylion::{Project, ProjectID},
+            FactionType, StartingResources,
+        },
+        preset::Preset,
+        reminder::Reminder,
+        technology::{ConverterPrototype, SharingBonus, TechID, Technology},
+        token::{TokenDef, TokenID},
+        Item,
+    },
+};
+
+/// Used as the source of truth for game data. This is not static to allow for
+/// custom data from Unity buffs to completely custom factions.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct GameData {
+    pub colony: HashMap<ColonyID, Colony>,
+    pub tech: HashMap<TechID, Technology>,
+    pub tech_prototype: HashMap<TechID, ConverterPrototype>,
+    pub tech_converter: HashMap<&'static str, Vec<ConverterPrototype>>,
+    pub start_resources: HashMap<FactionType, Vec<Item>>,
+    pub projects: HashMap<ProjectID, Project>,
+    /// Token definitions from data. Base game tokens are built in, and only
+    /// appear here if they're overridden.
+    pub tokens: HashMap<TokenID, TokenDef>,
+    /// Sharing bonuses by confluence, in confluence order.
+    pub sharing_bonus: Vec<SharingBonus>,
+    /// Faction reminders, in the order they're listed in data.
+    pub reminders: Vec<Reminder>,
+    /// Faction presets from data, by ID. Recommended presets are built in,
+    /// and only appear here if they're overridden.
+    pub presets: HashMap<String, Preset>,
+}
+
+/// Factions whose converters are documented in the data files. Eventually
+/// this will be `FactionType::core()` or all factions.
+pub(crate) const DOCUMENTED_FACTIONS: [FactionType; 8] = [
+    FactionType::CaylionCore,
+    FactionType::EniEtCore,
+    FactionType::FaderanCore,
+    FactionType::ImdrilCore,
+    FactionType::KitCore,
+    FactionType::KjasCore,
+    FactionType::YengiiCore,
+    FactionType::ZethCore,
+];
+
+/// Every copy of a data file, lowest priority first, as where it came from
+/// and its contents. `file` is the path relative to the data directories.
+fn layers(dirs: &[String], file: &str) -> Result<Vec<(String, String)>, Error> {
+    let mut out = Vec::new();
+    #[cfg(feature = "bundled-data")]
+    if let Some(src) = crate::bundled::file(file) {
+        out.push((format!("bundled {}", file), String::from(src)));
+    }
+    for dir in dirs {
+        let path = format!("{}/{}", dir, file);
+        if Path::new(&path).exists() {
+            let ser = fs::read_to_string(&path).with_context(|| format!("reading {}", path))?;
+            out.push((path, ser));
+        }
+    }
+    if out.is_empty() {
+        return Err(anyhow!("{} not found in {}", file, dirs.join(", ")));
+    }
+    Ok(out)
+}
+
+/// Reads and parses a data file as a whole, from the highest priority
+/// directory that has it. For tables without IDs to merge rows by.
+pub(crate) fn read_table<T: DeserializeOwned>(
+    dirs: &[String],
+    file: &str,
+) -> Result<Vec<T>, Error> {
+    let (from, ser) = layers(dirs, file)?.pop().unwrap();
+    serde_json::from_str(ser.as_str()).with_context(|| format!("parsing {}", from))
+}
+
+/// Reads and parses a data file from every directory that has it, with rows
+/// in higher priority copies replacing rows with the same ID in lower ones.
+pub(crate) fn read_merged<K, T>(
+    dirs: &[String],
+    file: &str,
+    id: impl Fn(&T) -> K,
+) -> Result<HashMap<K, T>, Error>
+where
+    K: Debug + Eq + Hash,
+    T: DeserializeOwned,
+{
+    let mut map = HashMap::new();
+    for (from, ser) in layers(dirs, file)? {
+        let rows: Vec<T> =
+            serde_json::from_str(ser.as_str()).with_context(|| format!("parsing {}", from))?;
+        map.extend(index(&from, rows, &id)?);
+    }
+    Ok(map)
+}
+
+/// Indexes a table's rows by their IDs. IDs must be unique within a table,
+/// since a later row would otherwise silently replace an earlier one.
+pub(crate) fn index<K, V>(
+    file: &str,
+    rows: Vec<V>,
+    id: impl Fn(&V) -> K,
+) -> Result<HashMap<K, V>, Error>
+where
+    K: Debug + Eq + Hash,
+{
+    let mut map = HashMap::new();
+    for row in rows {
+        let k = id(&row);
+        if map.contains_key(&k) {
+            return Err(anyhow!("{}: {:?} is listed more than once", file, k));
+        }
+        map.insert(k, row);
+    }
+    Ok(map)
+}
+
+impl GameData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn preloaded() -> Result<Self, Error> {
+        let mut gd = Self::default();
+        gd.load_all()?;
+        Ok(gd)
+    }
+
+    /// Paths of every file read by `load_all`, relative to the data
+    /// directory.
+    pub fn data_files() -> Vec<String> {
+        let mut files = vec![
+            String::from("colony.json"),
+            String::from("technology.json"),
+            String::from("prototypes.json"),
+            String::from("startResources.json"),
+        ];
+        for f in DOCUMENTED_FACTIONS {
+            files.push(format!("techConverters/{}.json", f.short_name()));
+        }
+        files
+    }
+
+    /// Loads all data into this gameData object. not all data may be necessary
+    /// so if size becomes an issue, use individual loads for data you need,
+    /// or `LazyGameData`. Each table is merged from every data directory,
+    /// see `data_dirs`.
+    pub fn load_all(&mut self) -> Result<(), Error> {
+        self.load_colonies()?;
+        self.load_tech()?;
+        self.load_resources()?;
+        for f in DOCUMENTED_FACTIONS {
+            self.load_faction(f)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the tables refer to each other correctly, for data that
+    /// has been loaded with `load_all`. Every colony with an upgrade cost
+    /// must have its upgraded side, every prototype (including faction tech
+    /// converters) must belong to a technology, every technology's upgrade
+    /// pair must exist, and every documented faction must have starting
+    /// resources. All the problems found are listed in the error, one per
+    /// line.
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut problems = Vec::new();
+
+        let mut colonies: Vec<&Colony> = self.colony.values().collect();
+        colonies.sort_by_key(|c| c.id);
+        for c in colonies {
+            if c.up_cost.is_some() && c.id.0 < 100 && c.upgraded_view(self).is_none() {
+                problems.push(format!(
+                    "colony {} ({}) has an upgrade cost, but its upgraded side, colony {}, is missing",
+                    c.id.0,
+                    c.name,
+                    c.id.0 + 100
+                ));
+            }
+        }
+
+        let mut prototypes: Vec<(&str, &ConverterPrototype)> = self
+            .tech_prototype
+            .values()
+            .map(|p| ("prototypes.json", p))
+            .collect();
+        prototypes.sort_by_key(|(_, p)| p.id);
+        for f in DOCUMENTED_FACTIONS {
+            if let Some(v) = self.tech_converter.get(f.short_name()) {
+                prototypes.extend(v.iter().map(|p| (f.short_name(), p)));
+            }
+        }
+        for (from, p) in prototypes {
+            if !self.tech.contains_key(&p.id) {
+                problems.push(format!(
+                    "{} ({}) is for technology {}, which is missing",
+                    p.name, from, p.id.0
+                ));
+            }
+        }
+
+        let mut techs: Vec<&Technology> = self.tech.values().collect();
+        techs.sort_by_key(|t| t.id);
+        for t in techs {
+            let Some((a, b)) = t.id.upgrades_with() else {
+                continue;
+            };
+            for u in [a, b].into_iter().filter(|u| !self.tech.contains_key(u)) {
+                problems.push(format!(
+                    "technology {} ({}) upgrades with technology {}, which is missing",
+                    t.id.0, t.name, u.0
+                ));
+            }
+        }
+
+        for f in DOCUMENTED_FACTIONS {
+            if !self.start_resources.contains_key(&f) {
+                problems.push(format!("{} has no starting resources", f.name()));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "{} problem(s) in game data:\n{}",
+                problems.len(),
+                problems.join("\n")
+            ))
+        }
+    }
+
+    /// Loads all colony data from `colony.json`
+    pub fn load_colonies(&mut self) -> Result<(), Error> {
+        let obj = read_merged(&data_dirs(), "colony.json", |c: &Colony| c.id)?;
+        self.colony.extend(obj);
+        Ok(())
+    }
+
+    /// Loads all tech and prototype data from `technology.json` and
+    /// `prototypes.json`
+    pub fn load_tech(&mut self) -> Result<(), Error> {
+        let dirs = data_dirs();
+        let obj = read_merged(&dirs, "technology.json", |t: &Technology| t.id)?;
+        let obj2 = read_merged(&dirs, "prototypes.json", |p: &ConverterPrototype| p.id)?;
+        self.tech.extend(obj);
+        self.tech_prototype.extend(obj2);
+        Ok(())
+    }
+
+    /// Loads all starting resources from `startResources.json`
+    pub fn load_resources(&mut self) -> Result<(), Error> {
+        let obj = read_merged(
+            &data_dirs(),
+            "startResources.json",
+            |s: &StartingResources| s.0,
+        )?;
+        self.start_resources
+            .extend(obj.into_iter().map(|(f, s)| (f, s.1)));
+        Ok(())
+    }
+
+    /// Loads a specific faction's starting converters and tech converters
+    /// from `techConverters/{faction}.json` and
+    /// `startConverters/{faction}.json`. This also loads faction
+    /// specific data such as relic worlds, jii constraints, nullspace
+    /// colonies, or other things not represented by starting converters.
+    pub fn load_faction(&mut self, f: FactionType) -> Result<(), Error> {
+        let obj = read_merged(
+            &data_dirs(),
+            &format!("techConverters/{}.json", f.short_name()),
+            |p: &ConverterPrototype| p.id,
+        )?;
+        let mut obj: Vec<ConverterPrototype> = obj.into_values().collect();
+        obj.sort_by_key(|p| p.id);
+        self.tech_converter.insert(f.short_name(), obj);
+        Ok(())
+    }
+
+    /// Loads custom token definitions from `tokens.json`. Not part
+    /// of `load_all`, since the base game doesn't need any.
+    pub fn load_tokens(&mut self) -> Result<(), Error> {
+        let obj = read_merged(&data_dirs(), "tokens.json", |t: &TokenDef| t.id)?;
+        self.tokens.extend(obj);
+        Ok(())
+    }
+
+    /// Loads the sharing bonus table from `sharingBonus.json`
+    pub fn load_sharing_bonus(&mut self) -> Result<(), Error> {
+        let obj = read_merged(&data_dirs(), "sharingBonus.json", |b: &SharingBonus| {
+            b.confluence
+        })?;
+        let mut obj: Vec<SharingBonus> = obj.into_values().collect();
+        obj.sort_by_key(|b| b.confluence);
+        self.sharing_bonus = obj;
+        Ok(())
+    }
+
+    /// Loads faction reminders from `reminders.json`. Reminders have no IDs,
+    /// so the highest priority copy of the file is used as a whole.
+    pub fn load_reminders(&mut self) -> Result<(), Error> {
+        self.reminders = read_table(&data_dirs(), "reminders.json")?;
+        Ok(())
+    }
+
+    /// Loads faction presets from `presets.json`. Not part of
+    /// `load_all`, since the recommended presets are built in.
+    pub fn load_presets(&mut self) -> Result<(), Error> {
+        let obj = read_merged(&data_dirs(), "presets.json", |p: &Preset| p.id.clone())?;
+        self.presets.extend(obj);
+        Ok(())
+    }
+
+    /// The sharing bonus for a confluence, if the table has been loaded and
+    /// covers it.
+    pub fn sharing_bonus(&self, confluence: usize) -> Option<SharingBonus> {
+        self.sharing_bonus
+            .iter()
+            .find(|b| b.confluence == confluence)
+            .copied()
+    }
+
+    /// The definition of a token, from data if it's there, or built in
+    /// otherwise.
+    pub fn token(&self, id: TokenID) -> Option<TokenDef> {
+        self.tokens
+            .get(&id)
+            .cloned()
+            .or_else(|| TokenDef::builtin(id))
+    }
+
+    /// Every token definition, built in and from data, in ID order.
+    pub fn all_tokens(&self) -> Vec<TokenDef> {
+        let mut defs: Vec<TokenDef> = TokenDef::builtins()
+            .into_iter()
+            .filter(|d| !self.tokens.contains_key(&d.id))
+            .chain(self.tokens.values().cloned())
+            .collect();
+        defs.sort_by_key(|d| d.id);
+        defs
+    }
+
+    /// A faction preset, from data if it's there, or built in otherwise.
+    pub fn preset(&self, id: &str) -> Option<Preset> {
+        self.presets
+            .get(id)
+            .cloned()
+            .or_else(|| Preset::builtin(id))
+    }
+
+    /// Every faction preset, built in and from data. Built in presets come
+    /// first in their usual order, followed by the rest in ID order.
+    pub fn all_presets(&self) -> Vec<Preset> {
+        let builtins = Preset::builtins();
+        let mut custom: Vec<Preset> = self
+            .presets
+            .values()
+            .filter(|p| !builtins.iter().any(|b| b.id == p.id))
+            .cloned()
+            .collect();
+        custom.sort_by(|a, b| a.id.cmp(&b.id));
+        builtins
+            .into_iter()
+            .map(|b| self.presets.get(&b.id).cloned().unwrap_or(b))
+            .chain(custom)
+            .collect()
+    }
+
+    /// Loads Alt Caylion project cards from `projects.json`. Not
+    /// part of `load_all` until the alt factions are documented.
+    pub fn load_projects(&mut self) -> Result<(), Error> {
+        let obj = read_merged(&data_dirs(), "projects.json", |p: &Project| p.id)?;
+        self.projects.extend(obj);
+        Ok(())
+    }
+}
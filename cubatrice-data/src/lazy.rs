@@ -0,0 +1,199 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use anyhow::Error;
+
+use crate::{
+    data_dirs,
+    entity::{
+        colony::{Colony, ColonyID},
+        faction::{
+            alt_caylion::{Project, ProjectID},
+            FactionType, StartingResources,
+        },
+        preset::Preset,
+        reminder::Reminder,
+        technology::{ConverterPrototype, SharingBonus, TechID, Technology},
+        token::{TokenDef, TokenID},
+        Item,
+    },
+};
+
+use crate::game_data::{read_merged, read_table, GameData, DOCUMENTED_FACTIONS};
+
+/// Game data where each table is read and parsed the first time it's asked
+/// for, rather than all at once. Useful for tools that only need one table
+/// (e.g. tech analysis only needs techs and prototypes).
+///
+/// Tables are never reloaded once parsed. If a table fails to load, the
+/// error is returned and the next access will try again.
+#[derive(Debug, Default)]
+pub struct LazyGameData {
+    dirs: Vec<String>,
+    colony: OnceLock<HashMap<ColonyID, Colony>>,
+    tech: OnceLock<HashMap<TechID, Technology>>,
+    tech_prototype: OnceLock<HashMap<TechID, ConverterPrototype>>,
+    tech_converter: OnceLock<HashMap<&'static str, Vec<ConverterPrototype>>>,
+    start_resources: OnceLock<HashMap<FactionType, Vec<Item>>>,
+    projects: OnceLock<HashMap<ProjectID, Project>>,
+    tokens: OnceLock<HashMap<TokenID, TokenDef>>,
+    sharing_bonus: OnceLock<Vec<SharingBonus>>,
+    reminders: OnceLock<Vec<Reminder>>,
+    presets: OnceLock<HashMap<String, Preset>>,
+}
+
+impl LazyGameData {
+    /// Lazily loads data from the data directories, see `data_dirs`.
+    pub fn new() -> Self {
+        Self::with_dirs(data_dirs())
+    }
+
+    /// Lazily loads data from a specific directory.
+    pub fn with_dir(dir: &str) -> Self {
+        Self::with_dirs(vec![String::from(dir)])
+    }
+
+    /// Lazily loads data from several directories, lowest priority first,
+    /// merged the same way as `data_dirs`.
+    pub fn with_dirs(dirs: Vec<String>) -> Self {
+        Self {
+            dirs,
+            ..Self::default()
+        }
+    }
+
+    /// Colony data from `colony.json`
+    pub fn colony(&self) -> Result<&HashMap<ColonyID, Colony>, Error> {
+        if let Some(v) = self.colony.get() {
+            return Ok(v);
+        }
+        let map = read_merged(&self.dirs, "colony.json", |c: &Colony| c.id)?;
+        Ok(self.colony.get_or_init(|| map))
+    }
+
+    /// Tech data from `technology.json`
+    pub fn tech(&self) -> Result<&HashMap<TechID, Technology>, Error> {
+        if let Some(v) = self.tech.get() {
+            return Ok(v);
+        }
+        let map = read_merged(&self.dirs, "technology.json", |t: &Technology| t.id)?;
+        Ok(self.tech.get_or_init(|| map))
+    }
+
+    /// Tech converter prototypes from `prototypes.json`
+    pub fn tech_prototype(&self) -> Result<&HashMap<TechID, ConverterPrototype>, Error> {
+        if let Some(v) = self.tech_prototype.get() {
+            return Ok(v);
+        }
+        let map = read_merged(&self.dirs, "prototypes.json", |p: &ConverterPrototype| p.id)?;
+        Ok(self.tech_prototype.get_or_init(|| map))
+    }
+
+    /// Faction specific tech converters from `techConverters/{faction}.json`,
+    /// for every faction with documented converters.
+    pub fn tech_converter(&self) -> Result<&HashMap<&'static str, Vec<ConverterPrototype>>, Error> {
+        if let Some(v) = self.tech_converter.get() {
+            return Ok(v);
+        }
+        let mut map = HashMap::new();
+        for f in DOCUMENTED_FACTIONS {
+            let obj = read_merged(
+                &self.dirs,
+                &format!("techConverters/{}.json", f.short_name()),
+                |p: &ConverterPrototype| p.id,
+            )?;
+            let mut obj: Vec<ConverterPrototype> = obj.into_values().collect();
+            obj.sort_by_key(|p| p.id);
+            map.insert(f.short_name(), obj);
+        }
+        Ok(self.tech_converter.get_or_init(|| map))
+    }
+
+    /// Starting resources from `startResources.json`
+    pub fn start_resources(&self) -> Result<&HashMap<FactionType, Vec<Item>>, Error> {
+        if let Some(v) = self.start_resources.get() {
+            return Ok(v);
+        }
+        let map = read_merged(
+            &self.dirs,
+            "startResources.json",
+            |s: &StartingResources| s.0,
+        )?;
+        Ok(self
+            .start_resources
+            .get_or_init(|| map.into_iter().map(|(k, s)| (k, s.1)).collect()))
+    }
+
+    /// Alt Caylion projects from `projects.json`
+    pub fn projects(&self) -> Result<&HashMap<ProjectID, Project>, Error> {
+        if let Some(v) = self.projects.get() {
+            return Ok(v);
+        }
+        let map = read_merged(&self.dirs, "projects.json", |p: &Project| p.id)?;
+        Ok(self.projects.get_or_init(|| map))
+    }
+
+    /// Custom token definitions from `tokens.json`
+    pub fn tokens(&self) -> Result<&HashMap<TokenID, TokenDef>, Error> {
+        if let Some(v) = self.tokens.get() {
+            return Ok(v);
+        }
+        let map = read_merged(&self.dirs, "tokens.json", |t: &TokenDef| t.id)?;
+        Ok(self.tokens.get_or_init(|| map))
+    }
+
+    /// The sharing bonus table from `sharingBonus.json`, in confluence order
+    pub fn sharing_bonus(&self) -> Result<&[SharingBonus], Error> {
+        if let Some(v) = self.sharing_bonus.get() {
+            return Ok(v);
+        }
+        let obj = read_merged(&self.dirs, "sharingBonus.json", |b: &SharingBonus| {
+            b.confluence
+        })?;
+        let mut obj: Vec<SharingBonus> = obj.into_values().collect();
+        obj.sort_by_key(|b| b.confluence);
+        Ok(self.sharing_bonus.get_or_init(|| obj))
+    }
+
+    /// Faction reminders from `reminders.json`, the highest priority copy
+    /// used as a whole
+    pub fn reminders(&self) -> Result<&[Reminder], Error> {
+        if let Some(v) = self.reminders.get() {
+            return Ok(v);
+        }
+        let obj: Vec<Reminder> = read_table(&self.dirs, "reminders.json")?;
+        Ok(self.reminders.get_or_init(|| obj))
+    }
+
+    /// Custom faction presets from `presets.json`
+    pub fn presets(&self) -> Result<&HashMap<String, Preset>, Error> {
+        if let Some(v) = self.presets.get() {
+            return Ok(v);
+        }
+        let map = read_merged(&self.dirs, "presets.json", |p: &Preset| p.id.clone())?;
+        Ok(self.presets.get_or_init(|| map))
+    }
+
+    /// Converts into fully loaded game data, loading any tables that haven't
+    /// been loaded yet. Projects, tokens, sharing bonuses, reminders and
+    /// presets are only included if they were already loaded, as with
+    /// `GameData::load_all`.
+    pub fn into_game_data(self) -> Result<GameData, Error> {
+        self.colony()?;
+        self.tech()?;
+        self.tech_prototype()?;
+        self.tech_converter()?;
+        self.start_resources()?;
+        Ok(GameData {
+            colony: self.colony.into_inner().unwrap_or_default(),
+            tech: self.tech.into_inner().unwrap_or_default(),
+            tech_prototype: self.tech_prototype.into_inner().unwrap_or_default(),
+            tech_converter: self.tech_converter.into_inner().unwrap_or_default(),
+            start_resources: self.start_resources.into_inner().unwrap_or_default(),
+            projects: self.projects.into_inner().unwrap_or_default(),
+            tokens: self.tokens.into_inner().unwrap_or_default(),
+            sharing_bonus: self.sharing_bonus.into_inner().unwrap_or_default(),
+            reminders: self.reminders.into_inner().unwrap_or_default(),
+            presets: self.presets.into_inner().unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,621 @@
+#![allow(dead_code)]
+
+//! Game entities (cubes, converters, colonies, technologies, factions) and
+//! the game data tables they're loaded from.
+
+use std::collections::VecDeque;
+use std::env;
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Error};
+use lazy_static::lazy_static;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+lazy_static! {
+    pub static ref DATA_DIR: String = match env::var("CUBE_DIR") {
+        Ok(d) => d,
+        Err(_) => match env::var("HOME") {
+            Ok(h) => format!("{}/.local/share/Cubatrice/data", h),
+            Err(_) => String::from("./data"),
+        },
+    };
+    /// Data shared by everyone on the system, which `DATA_DIR` overrides.
+    pub static ref SYSTEM_DATA_DIR: String = match env::var("CUBE_SYSTEM_DIR") {
+        Ok(d) => d,
+        Err(_) => String::from("/usr/share/cubatrice/data"),
+    };
+}
+
+/// Data directories in priority order, lowest first: `SYSTEM_DATA_DIR`,
+/// then `DATA_DIR`. Rows in a higher directory's files replace rows with the
+/// same ID in lower ones, so an override directory only needs the rows it
+/// changes. With the `bundled-data` feature, the bundled files sit
+/// underneath all of these.
+pub fn data_dirs() -> Vec<String> {
+    let mut dirs = vec![SYSTEM_DATA_DIR.clone()];
+    if *DATA_DIR != *SYSTEM_DATA_DIR {
+        dirs.push(DATA_DIR.clone());
+    }
+    dirs
+}
+
+/// Whether any data directory has a file, by its path relative to the data
+/// directory.
+pub fn data_file_exists(file: &str) -> bool {
+    data_dirs()
+        .iter()
+        .any(|d| Path::new(&format!("{}/{}", d, file)).exists())
+}
+
+/// Canonical data files built in, underneath every data directory
+#[cfg(feature = "bundled-data")]
+mod bundled;
+/// Game Entity representation
+pub mod entity;
+/// Game data tables, and reading them from the data directories
+pub mod game_data;
+/// Game data which is only read from disk when it's first needed.
+pub mod lazy;
+/// Printable play aids rendered from game data
+pub mod print;
+/// Scripted converter effects for homebrew content
+#[cfg(feature = "scripting")]
+pub mod script;
+
+/// Common number type to represent fractions, when floating point isn't
+/// necessary, and fractions make more sense.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Fraction {
+    n: isize,
+    d: isize,
+}
+
+impl Fraction {
+    /// Creates a fraction with a given numerator and denominator. The sign
+    /// is kept on the numerator, so `Fraction::new(1, -2)` is `-1/2`.
+    ///
+    /// Panics if the denominator is zero.
+    pub fn new(n: isize, d: isize) -> Self {
+        Self::checked_new(n, d).expect("fraction with a zero denominator")
+    }
+
+    /// Creates a fraction with a given numerator and denominator, or None if
+    /// the denominator is zero.
+    pub fn checked_new(n: isize, d: isize) -> Option<Self> {
+        Self::from_wide(n as i128, d as i128)
+    }
+
+    /// Gets the floating point value of the fraction
+    pub fn value(&self) -> f64 {
+        (self.n as f64) / (self.d as f64)
+    }
+
+    /// Creates a new fraction with numerator and denominator swapped
+    ///
+    /// Panics if the fraction is zero.
+    pub fn reciprocal(&self) -> Self {
+        Fraction::new(self.d, self.n)
+    }
+
+    /// Gets the integer component of the fraction
+    pub fn integer(&self) -> isize {
+        self.n / self.d
+    }
+
+    /// Gets the remainder of the fraction after the integer component is
+    /// removed
+    pub fn remainder(&self) -> isize {
+        self.n % self.d
+    }
+
+    /// Numerator of the fraction
+    pub fn numerator(&self) -> isize {
+        self.n
+    }
+
+    /// Denominator of the fraction
+    pub fn denominator(&self) -> isize {
+        self.d
+    }
+
+    /// Adds two fractions, or None if the result doesn't fit.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let (a, b) = (self.wide(), rhs.wide());
+        Self::from_wide(a.0 * b.1 + b.0 * a.1, a.1 * b.1)
+    }
+
+    /// Subtracts one fraction from another, or None if the result doesn't
+    /// fit.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        let (a, b) = (self.wide(), rhs.wide());
+        Self::from_wide(a.0 * b.1 - b.0 * a.1, a.1 * b.1)
+    }
+
+    /// Multiplies two fractions, or None if the result doesn't fit.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let (a, b) = (self.wide(), rhs.wide());
+        Self::from_wide(a.0 * b.0, a.1 * b.1)
+    }
+
+    /// Divides one fraction by another, or None if `rhs` is zero or the
+    /// result doesn't fit.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        let (a, b) = (self.wide(), rhs.wide());
+        Self::from_wide(a.0 * b.1, a.1 * b.0)
+    }
+
+    fn wide(self) -> (i128, i128) {
+        (self.n as i128, self.d as i128)
+    }
+
+    /// Builds a fraction in simplest form, with a positive denominator, from
+    /// a widened numerator and denominator. Every operation goes through
+    /// here, so products of two `isize`s can't overflow before they're
+    /// reduced.
+    fn from_wide(n: i128, d: i128) -> Option<Self> {
+        if d == 0 {
+            return None;
+        }
+        let sign = d.signum();
+        let gcd = gcd(n, d);
+        Some(Fraction {
+            n: isize::try_from(sign * n / gcd).ok()?,
+            d: isize::try_from(sign * d / gcd).ok()?,
+        })
+    }
+}
+
+impl PartialOrd for Fraction {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Fraction {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let (a, b) = (self.wide(), other.wide());
+        (a.0 * b.1).cmp(&(a.1 * b.0))
+    }
+}
+
+impl Display for Fraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            self.integer(),
+            match self.remainder() {
+                0 => String::new(),
+                r if r > 0 => format!(" + {}/{}", r, self.denominator()),
+                r => format!(" - {}/{}", -r, self.denominator()),
+            }
+        )
+    }
+}
+
+impl Add for Fraction {
+    type Output = Fraction;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs).expect("fraction addition overflowed")
+    }
+}
+
+impl Add<isize> for Fraction {
+    type Output = Fraction;
+
+    fn add(self, rhs: isize) -> Self::Output {
+        self + Fraction::from(rhs)
+    }
+}
+
+impl Neg for Fraction {
+    type Output = Fraction;
+
+    fn neg(self) -> Self::Output {
+        Fraction::from_wide(-(self.n as i128), self.d as i128)
+            .expect("fraction negation overflowed")
+    }
+}
+
+impl Sub for Fraction {
+    type Output = Fraction;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("fraction subtraction overflowed")
+    }
+}
+
+impl Sub<isize> for Fraction {
+    type Output = Fraction;
+
+    fn sub(self, rhs: isize) -> Self::Output {
+        self - Fraction::from(rhs)
+    }
+}
+
+impl Mul for Fraction {
+    type Output = Fraction;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs)
+            .expect("fraction multiplication overflowed")
+    }
+}
+
+impl Mul<isize> for Fraction {
+    type Output = Fraction;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        self * Fraction::from(rhs)
+    }
+}
+
+impl Div for Fraction {
+    type Output = Fraction;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(rhs.n != 0, "fraction division by zero");
+        self.checked_div(rhs).expect("fraction division overflowed")
+    }
+}
+
+impl Div<isize> for Fraction {
+    type Output = Fraction;
+
+    fn div(self, rhs: isize) -> Self::Output {
+        self / Fraction::from(rhs)
+    }
+}
+
+impl AddAssign for Fraction {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl AddAssign<isize> for Fraction {
+    fn add_assign(&mut self, rhs: isize) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Fraction {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl SubAssign<isize> for Fraction {
+    fn sub_assign(&mut self, rhs: isize) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Fraction {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl MulAssign<isize> for Fraction {
+    fn mul_assign(&mut self, rhs: isize) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Fraction {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl DivAssign<isize> for Fraction {
+    fn div_assign(&mut self, rhs: isize) {
+        *self = *self / rhs;
+    }
+}
+
+impl From<isize> for Fraction {
+    fn from(n: isize) -> Self {
+        Fraction { n, d: 1 }
+    }
+}
+
+/// The largest denominator `Fraction::try_from` will use to approximate a
+/// float.
+const MAX_APPROXIMATION_DENOMINATOR: i128 = 1_000_000;
+
+impl TryFrom<f64> for Fraction {
+    type Error = Error;
+
+    /// The closest fraction to a float with a denominator of at most a
+    /// million, found from the float's continued fraction. Fails for NaN,
+    /// infinities and anything too large to fit.
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if !value.is_finite() || value.abs() >= isize::MAX as f64 {
+            bail!("{} can't be made into a fraction", value);
+        }
+        // successive convergents h/k, starting from 1/0 and 0/1
+        let (mut h, mut h_prev) = (1i128, 0i128);
+        let (mut k, mut k_prev) = (0i128, 1i128);
+        let mut rest = value;
+        loop {
+            let a = rest.floor();
+            let (next_h, next_k) = (a as i128 * h + h_prev, a as i128 * k + k_prev);
+            if next_k > MAX_APPROXIMATION_DENOMINATOR {
+                break;
+            }
+            (h_prev, h, k_prev, k) = (h, next_h, k, next_k);
+            let frac = rest - a;
+            if frac < 1e-12 || (h as f64 / k as f64 - value).abs() <= f64::EPSILON * value.abs() {
+                break;
+            }
+            rest = 1.0 / frac;
+        }
+        Fraction::from_wide(h, k).ok_or_else(|| anyhow!("{} can't be made into a fraction", value))
+    }
+}
+
+impl FromStr for Fraction {
+    type Err = Error;
+
+    /// Parses a whole number (`3`), a fraction (`7/5`), a whole number and
+    /// a fraction the way they're displayed (`1 + 2/5`, `-1 - 2/5`), or a
+    /// decimal (`1.4`). Any of them may have a leading sign. Decimals are
+    /// read exactly, not through a float.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || anyhow!("'{}' isn't a fraction, try 7/5, 1 + 2/5 or 1.4", s);
+        let s = s.trim();
+        // The leading sign belongs to the first number, so it comes off
+        // before looking for the sign between the whole and the part.
+        let (negative, rest) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let sign = |f: Fraction| if negative { -f } else { f };
+        if let Some(at) = rest.find(['+', '-']) {
+            let whole: Fraction = rest[..at].parse().map_err(|_| bad())?;
+            let part: Fraction = rest[at + 1..].parse().map_err(|_| bad())?;
+            let whole = sign(whole);
+            return match &rest[at..at + 1] {
+                "+" => whole.checked_add(part),
+                _ => whole.checked_sub(part),
+            }
+            .ok_or_else(bad);
+        }
+        if let Some((n, d)) = rest.split_once('/') {
+            let n: isize = n.trim().parse().map_err(|_| bad())?;
+            let d: isize = d.trim().parse().map_err(|_| bad())?;
+            return Fraction::checked_new(n, d).map(sign).ok_or_else(bad);
+        }
+        if let Some((whole, digits)) = rest.split_once('.') {
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(bad());
+            }
+            let whole: isize = match whole {
+                "" => 0,
+                w => w.parse().map_err(|_| bad())?,
+            };
+            let scale = 10isize.checked_pow(digits.len() as u32).ok_or_else(bad)?;
+            let part: isize = digits.parse().map_err(|_| bad())?;
+            let n = whole
+                .checked_mul(scale)
+                .and_then(|w| w.checked_add(part))
+                .ok_or_else(bad)?;
+            return Fraction::checked_new(n, scale).map(sign).ok_or_else(bad);
+        }
+        rest.parse::<isize>()
+            .map(Fraction::from)
+            .map(sign)
+            .map_err(|_| bad())
+    }
+}
+
+/// Does what it says on the tin. You've seen this algorithm before. Always
+/// positive, unless both are zero.
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// What a deck does with its discard pile when a draw finds it empty.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Recycle {
+    /// Discards stay discarded, and the deck is simply empty.
+    #[default]
+    Never,
+    /// Discards go back into the deck in the order they were discarded.
+    InOrder,
+    /// Discards are shuffled back into the deck. The shuffle is seeded from
+    /// this and how many times the deck has been reshuffled, so replays
+    /// reshuffle the same way.
+    Shuffled(u64),
+}
+
+/// A deck of cards, drawn from the top, with a discard pile for cards that
+/// are passed on or consumed. Serializes as its cards in draw order, so
+/// saves and replays keep the deck exactly as it was.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+pub struct Deck<T> {
+    items: VecDeque<T>,
+    /// Discarded cards, oldest first.
+    #[serde(default)]
+    discards: Vec<T>,
+    #[serde(default)]
+    recycle: Recycle,
+    /// How many times the discards have been shuffled back in.
+    #[serde(default)]
+    reshuffles: u64,
+}
+
+impl<T> Default for Deck<T> {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl<T> Deck<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        Self {
+            items: items.into(),
+            discards: Vec::new(),
+            recycle: Recycle::Never,
+            reshuffles: 0,
+        }
+    }
+
+    pub fn new_shuffled(mut items: Vec<T>) -> Self {
+        items.shuffle(&mut rand::thread_rng());
+        Self::new(items)
+    }
+
+    pub fn new_shuffled_with_rng<R>(mut items: Vec<T>, rng: &mut R) -> Self
+    where
+        R: RngCore,
+    {
+        items.shuffle(rng);
+        Self::new(items)
+    }
+
+    /// Sets what happens to the discards when the deck runs out.
+    pub fn with_recycle(mut self, recycle: Recycle) -> Self {
+        self.recycle = recycle;
+        self
+    }
+
+    pub fn draw_next(&mut self) -> Option<T> {
+        self.draw_next_matches(|_| true)
+    }
+
+    pub fn draw_next_matches<P>(&mut self, pred: P) -> Option<T>
+    where
+        P: Fn(&T) -> bool,
+    {
+        if self.items.is_empty() {
+            self.recycle_discards();
+        }
+        if self.items.is_empty() {
+            return None;
+        }
+        let mut item = self.items.pop_front().unwrap();
+        let mut count = 0;
+        while !pred(&item) {
+            count += 1;
+            self.items.push_back(item);
+            if count > self.items.len() {
+                return None;
+            }
+            item = self.items.pop_front().unwrap();
+        }
+        Some(item)
+    }
+
+    pub fn add_to_bottom(&mut self, item: T) {
+        self.items.push_back(item);
+    }
+
+    pub fn add_to_top(&mut self, item: T) {
+        self.items.push_front(item);
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.items.front()
+    }
+
+    /// The top `n` cards, top first, without drawing them. Fewer are
+    /// returned if the deck is smaller.
+    pub fn peek_n(&self, n: usize) -> impl Iterator<Item = &T> {
+        self.items.iter().take(n)
+    }
+
+    /// Draws the top `n` cards, top first. Fewer are returned if the deck
+    /// runs out, after recycling the discards if the deck does that.
+    pub fn draw_n(&mut self, n: usize) -> Vec<T> {
+        std::iter::from_fn(|| self.draw_next()).take(n).collect()
+    }
+
+    /// Takes every card matching `pred` out of the deck, top first, leaving
+    /// the rest in order.
+    pub fn remove_where<P>(&mut self, pred: P) -> Vec<T>
+    where
+        P: Fn(&T) -> bool,
+    {
+        let mut removed = Vec::new();
+        let mut kept = VecDeque::with_capacity(self.items.len());
+        for item in self.items.drain(..) {
+            if pred(&item) {
+                removed.push(item);
+            } else {
+                kept.push_back(item);
+            }
+        }
+        self.items = kept;
+        removed
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Iterates over the deck from the top, without drawing.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+
+    /// Puts a card on the discard pile.
+    pub fn discard(&mut self, item: T) {
+        self.discards.push(item);
+    }
+
+    /// The discard pile, oldest first.
+    pub fn discards(&self) -> &[T] {
+        &self.discards
+    }
+
+    /// Shuffles the discard pile and puts it under what's left of the deck.
+    pub fn reshuffle_discards<R>(&mut self, rng: &mut R)
+    where
+        R: RngCore,
+    {
+        self.discards.shuffle(rng);
+        self.items.extend(self.discards.drain(..));
+        self.reshuffles += 1;
+    }
+
+    /// Shuffles the cards currently in the deck.
+    pub fn shuffle_with_rng<R>(&mut self, rng: &mut R)
+    where
+        R: RngCore,
+    {
+        self.items.make_contiguous().shuffle(rng);
+    }
+
+    /// Brings the discards back into an empty deck, if the deck recycles
+    /// them.
+    fn recycle_discards(&mut self) {
+        match self.recycle {
+            Recycle::Never => {}
+            Recycle::InOrder => self.items.extend(self.discards.drain(..)),
+            Recycle::Shuffled(seed) => {
+                let mut rng = StdRng::seed_from_u64(seed.wrapping_add(self.reshuffles));
+                self.reshuffle_discards(&mut rng);
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::fmt::Write;
+
+use crate::{
+    entity::{
+        colony::ColonyType,
+        converter::{Arrow, Converter},
+        faction::FactionType,
+        Item,
+    },
+    game_data::GameData,
+};
+
+/// How printed sheets are written out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SheetFormat {
+    /// Plain text, one card per block.
+    Text,
+    /// A single HTML page, styled so cards don't split across printed
+    /// pages.
+    Html,
+}
+
+/// A titled group of cards, printed together.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Sheet {
+    pub title: String,
+    pub cards: Vec<Card>,
+}
+
+/// One card as it appears on a printed sheet.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Card {
+    pub name: String,
+    pub lines: Vec<String>,
+}
+
+/// Every card in the data, grouped into sheets: generic technologies by
+/// tier, each faction's technologies by tier, starting resources, then
+/// colonies by type. Cards are in ID order within a sheet, and empty sheets
+/// are left out.
+pub fn sheets(data: &GameData) -> Vec<Sheet> {
+    let mut out = Vec::new();
+    let tier = |id| data.tech.get(&id).map(|t| t.tier).unwrap_or_default();
+
+    let mut techs: Vec<_> = data.tech.values().collect();
+    techs.sort_by_key(|t| (t.tier, t.id));
+    for t in 1..=4 {
+        let cards = techs
+            .iter()
+            .filter(|tech| tech.tier == t)
+            .map(|tech| {
+                let costs: Vec<String> = tech
+                    .cost
+                    .iter()
+                    .map(|c| format!("{} {}", c.qty, c.typ.name()))
+                    .collect();
+                let mut lines = vec![format!("Cost: {}", costs.join(" or "))];
+                if let Some(invents) = tech.invents.as_ref() {
+                    lines.push(format!("Invents: {}", invents));
+                }
+                lines.push(format!("Reward: {} VP", tech.invent_reward));
+                if let Some(p) = data.tech_prototype.get(&tech.id) {
+                    lines.push(converter_line(&p.conv));
+                }
+                Card {
+                    name: tech.name.clone(),
+                    lines,
+                }
+            })
+            .collect();
+        push_sheet(&mut out, format!("Technologies, tier {}", t), cards);
+    }
+
+    let mut factions: Vec<&&str> = data.tech_converter.keys().collect();
+    factions.sort();
+    for f in factions {
+        let mut convs: Vec<_> = data.tech_converter[*f].iter().collect();
+        convs.sort_by_key(|p| (tier(p.id), p.id));
+        let mut tiers: Vec<usize> = convs.iter().map(|p| tier(p.id)).collect();
+        tiers.dedup();
+        for t in tiers {
+            let cards = convs
+                .iter()
+                .filter(|p| tier(p.id) == t)
+                .map(|p| Card {
+                    name: p.name.clone(),
+                    lines: vec![converter_line(&p.conv)],
+                })
+                .collect();
+            push_sheet(&mut out, format!("{} technologies, tier {}", f, t), cards);
+        }
+    }
+
+    let cards = FactionType::core()
+        .into_iter()
+        .chain(FactionType::bifurcation())
+        .filter_map(|f| {
+            data.start_resources.get(&f).map(|items| Card {
+                name: f.name().to_string(),
+                lines: vec![items_line(items)],
+            })
+        })
+        .collect();
+    push_sheet(&mut out, String::from("Starting resources"), cards);
+
+    for typ in [
+        ColonyType::Desert,
+        ColonyType::Ice,
+        ColonyType::Jungle,
+        ColonyType::Ocean,
+        ColonyType::Any,
+    ] {
+        let mut colonies: Vec<_> = data.colony.values().filter(|c| c.typ == typ).collect();
+        colonies.sort_by_key(|c| c.id);
+        let cards = colonies
+            .into_iter()
+            .map(|c| {
+                let mut lines = vec![converter_line(&c.conv)];
+                if let Some((typ, qty)) = c.up_cost {
+                    lines.push(format!("Upgrade: {} {}", qty, typ.name()));
+                }
+                Card {
+                    name: c.name.clone(),
+                    lines,
+                }
+            })
+            .collect();
+        push_sheet(&mut out, format!("{:?} colonies", typ), cards);
+    }
+    out
+}
+
+/// Renders every sheet in the data in the given format.
+pub fn render(data: &GameData, format: SheetFormat) -> String {
+    let sheets = sheets(data);
+    let mut out = String::new();
+    match format {
+        SheetFormat::Text => {
+            for s in sheets.iter() {
+                let _ = writeln!(out, "== {} ==", s.title);
+                for c in s.cards.iter() {
+                    let _ = writeln!(out);
+                    let _ = writeln!(out, "{}", c.name);
+                    for l in c.lines.iter() {
+                        let _ = writeln!(out, "  {}", l);
+                    }
+                }
+                let _ = writeln!(out);
+            }
+        }
+        SheetFormat::Html => {
+            out.push_str(HTML_HEAD);
+            for s in sheets.iter() {
+                let _ = writeln!(out, "<section>\n<h2>{}</h2>", escape(&s.title));
+                for c in s.cards.iter() {
+                    let _ = writeln!(out, "<div class=\"card\">\n<h3>{}</h3>", escape(&c.name));
+                    for l in c.lines.iter() {
+                        let _ = writeln!(out, "<p>{}</p>", escape(l));
+                    }
+                    out.push_str("</div>\n");
+                }
+                out.push_str("</section>\n");
+            }
+            out.push_str("</body>\n</html>\n");
+        }
+    }
+    out
+}
+
+const HTML_HEAD: &str = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Cubatrice play aids</title>
+<style>
+body { font-family: sans-serif; }
+section { page-break-before: always; }
+.card { display: inline-block; vertical-align: top; width: 6cm; margin: 0.2cm;
+  padding: 0.3cm; border: 1px solid black; page-break-inside: avoid; }
+.card h3 { margin: 0 0 0.2cm 0; font-size: 1em; }
+.card p { margin: 0; font-size: 0.9em; }
+</style>
+</head>
+<body>
+";
+
+fn push_sheet(out: &mut Vec<Sheet>, title: String, cards: Vec<Card>) {
+    if !cards.is_empty() {
+        out.push(Sheet { title, cards });
+    }
+}
+
+/// A converter as `inputs -> outputs`, noting when it runs if it isn't an
+/// economy phase converter.
+fn converter_line(conv: &Converter) -> String {
+    let input = if conv.input.is_empty() {
+        String::from("nothing")
+    } else {
+        items_line(&conv.input)
+    };
+    let when = match conv.color {
+        Arrow::White => "",
+        Arrow::Purple => " (trade phase)",
+        Arrow::Red => " (steal)",
+    };
+    let fleet = match conv.fleet {
+        0 => String::new(),
+        n => format!(", needs {} fleet support", n),
+    };
+    format!("{} -> {}{}{}", input, items_line(&conv.output), when, fleet)
+}
+
+fn items_line(items: &[Item]) -> String {
+    items.iter().map(item).collect::<Vec<String>>().join(" + ")
+}
+
+fn item(i: &Item) -> String {
+    match i {
+        Item::Cubes(typ, qty) => format!("{} {}", qty, typ.name()),
+        Item::DonationCubes(typ, qty) => format!("{} {} (donation)", qty, typ.name()),
+        Item::Colony(typ) => format!("a {:?} colony", typ),
+        Item::SpecificColony(id) => format!("colony {}", id.0),
+        Item::Token(t) => format!("a {:?} token", t),
+        #[cfg(feature = "scripting")]
+        Item::Script(_) => String::from("a scripted effect"),
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,146 @@
+use std::fmt::Display;
+
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::cube::{parse_cube_type, CubeType};
+
+/// Scripts longer than this are rejected, so a data file can't make a
+/// converter do an unbounded amount of work.
+pub const MAX_EFFECTS: usize = 32;
+
+/// The most cubes one `grant` statement can give.
+pub const MAX_GRANT: usize = 10;
+
+/// The most cubes a whole script can grant, across all its statements.
+pub const MAX_GRANTED: usize = 20;
+
+/// A scripted converter effect, for homebrew content that can't be expressed
+/// with the other `Item`s. Scripts are parsed when data is loaded, and can
+/// only do the things listed in `Effect`; there are no loops, variables or
+/// access to anything else in the game.
+///
+/// Statements are separated by newlines or `;`, and `#` starts a comment:
+///
+/// ```text
+/// grant 2 food; grant 1 ultratech
+/// draw colony     # deck is one of tech, colony or relic
+/// support +1      # change colony support
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Script {
+    src: String,
+    effects: Vec<Effect>,
+}
+
+/// One thing a script can do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Effect {
+    /// Give the converter's owner cubes from the supply.
+    Grant(CubeType, usize),
+    /// Draw the top card of a deck for the converter's owner.
+    Draw(ScriptDeck),
+    /// Increase (or decrease) the owner's colony support.
+    Support(isize),
+}
+
+/// Decks a script is allowed to draw from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ScriptDeck {
+    Tech,
+    Colony,
+    Relic,
+}
+
+impl Script {
+    /// Parses a script, checking every statement.
+    pub fn parse(src: &str) -> Result<Self, Error> {
+        let mut effects = Vec::new();
+        for (i, stmt) in src
+            .lines()
+            .flat_map(|l| l.split('#').next().unwrap_or("").split(';'))
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .enumerate()
+        {
+            if i >= MAX_EFFECTS {
+                return Err(anyhow!(
+                    "scripts may have at most {} statements",
+                    MAX_EFFECTS
+                ));
+            }
+            effects.push(parse_statement(stmt).map_err(|e| anyhow!("'{}': {}", stmt, e))?);
+        }
+        let granted: usize = effects
+            .iter()
+            .map(|e| match e {
+                Effect::Grant(_, qty) => *qty,
+                _ => 0,
+            })
+            .sum();
+        if granted > MAX_GRANTED {
+            return Err(anyhow!(
+                "scripts may grant at most {} cubes, not {}",
+                MAX_GRANTED,
+                granted
+            ));
+        }
+        Ok(Self {
+            src: src.to_string(),
+            effects,
+        })
+    }
+
+    /// The effects of the script, in the order they happen.
+    pub fn effects(&self) -> &[Effect] {
+        &self.effects
+    }
+}
+
+fn parse_statement(stmt: &str) -> Result<Effect, String> {
+    let words: Vec<&str> = stmt.split_whitespace().collect();
+    match words.as_slice() {
+        ["grant", qty, typ @ ..] if !typ.is_empty() => {
+            let qty: usize = qty
+                .parse()
+                .map_err(|_| format!("'{}' is not a number", qty))?;
+            if qty > MAX_GRANT {
+                return Err(format!("can grant at most {} cubes at once", MAX_GRANT));
+            }
+            Ok(Effect::Grant(parse_cube_type(&typ.join(" "))?, qty))
+        }
+        ["draw", deck] => match deck.to_lowercase().as_str() {
+            "tech" | "technology" => Ok(Effect::Draw(ScriptDeck::Tech)),
+            "colony" => Ok(Effect::Draw(ScriptDeck::Colony)),
+            "relic" => Ok(Effect::Draw(ScriptDeck::Relic)),
+            _ => Err(format!("unknown deck '{}'", deck)),
+        },
+        ["support", delta] => delta
+            .trim_start_matches('+')
+            .parse()
+            .map(Effect::Support)
+            .map_err(|_| format!("'{}' is not a number", delta)),
+        _ => Err(String::from("expected grant, draw or support")),
+    }
+}
+
+impl TryFrom<String> for Script {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<Script> for String {
+    fn from(value: Script) -> Self {
+        value.src
+    }
+}
+
+impl Display for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.src)
+    }
+}
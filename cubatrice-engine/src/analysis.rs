@@ -0,0 +1,536 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt::Display,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::{
+        converter::{Arrow, Convert, Converter, ConverterID},
+        cube::{CubeRecord, CubeType},
+        faction::FactionType,
+        technology::{ConverterPrototype, TechCost, TechID},
+        Item, Token,
+    },
+    game_data::GameData,
+    state::{player::PlayerID, Confluence, GameState},
+    Fraction,
+};
+
+/// One way a player could invent one of their research teams.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InventionOption {
+    /// The research team being invented
+    pub tech: TechID,
+    /// Which of the technology's alternate costs this option pays
+    pub cost: TechCost,
+    /// How many confluences until the player can afford this cost, based on
+    /// their forecast income. 0 if they can afford it now, None if their
+    /// income never covers it.
+    pub turns_until_affordable: Option<usize>,
+    /// The confluence the technology would be shared in if invented as soon
+    /// as it's affordable. None if it would never be shared, either because
+    /// the game ends first or because the player's faction doesn't share.
+    pub shared_at: Option<Confluence>,
+    /// Victory points awarded for inventing the technology
+    pub invent_reward: usize,
+}
+
+/// Lists every cost alternative for every research team a player owns,
+/// along with when each becomes affordable and when the technology would be
+/// shared.
+pub fn invention_plan(state: &GameState, player: PlayerID) -> Vec<InventionOption> {
+    let cubes = state.get_player_cubes(player);
+    let income = state.income(player);
+    let shares = !matches!(
+        state.faction(player),
+        Some(FactionType::YengiiCore) | Some(FactionType::FaderanAlt)
+    );
+    let mut out = Vec::new();
+    for id in state.research_teams(player) {
+        let Some(tech) = state.data().tech.get(&id) else {
+            continue;
+        };
+        for cost in tech.cost.iter() {
+            let missing = cost.qty as isize - cubes.count_type(cost.typ);
+            let per_turn = income.count_type(cost.typ);
+            let turns_until_affordable = if missing <= 0 {
+                Some(0)
+            } else if per_turn <= 0 {
+                None
+            } else {
+                Some(((missing + per_turn - 1) / per_turn) as usize)
+            };
+            // technologies are shared the confluence after they're invented
+            let shared_at = turns_until_affordable
+                .map(|t| Confluence(state.confluence().0 + t + 1))
+                .filter(|c| shares && *c <= state.config().end.last_confluence());
+            out.push(InventionOption {
+                tech: id,
+                cost: *cost,
+                turns_until_affordable,
+                shared_at,
+                invent_reward: tech.invent_reward,
+            });
+        }
+    }
+    out
+}
+
+/// How attractive a player is as a target for Zeth stealing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StealTarget {
+    pub player: PlayerID,
+    /// Whether the player is safe from stealing, either because they traded
+    /// with the Zeth or have already been stolen from.
+    pub safe: bool,
+    /// How many envoy tokens the player holds.
+    pub envoys: usize,
+    /// The suggested value of every cube the player holds.
+    pub wealth: Fraction,
+    /// Higher is a better target. Safe players score 0; otherwise this is
+    /// the player's wealth.
+    pub score: Fraction,
+}
+
+/// Scores every other player as a target for a Zeth player's stealing, in
+/// the order they must be stolen from: players that aren't safe first,
+/// then those holding more envoys, then by score. Ties go to the lower
+/// player ID.
+pub fn steal_targets(state: &GameState, zeth: PlayerID) -> Vec<StealTarget> {
+    let mut out: Vec<StealTarget> = state
+        .seats()
+        .iter()
+        .filter(|p| **p != zeth)
+        .map(|p| {
+            let safe = state.is_zeth_safe(*p);
+            let envoys = state.envoys(*p);
+            let wealth = state.get_player_cubes(*p).value();
+            let score = if safe { Fraction::new(0, 1) } else { wealth };
+            StealTarget {
+                player: *p,
+                safe,
+                envoys,
+                wealth,
+                score,
+            }
+        })
+        .collect();
+    out.sort_by(|a, b| {
+        a.safe
+            .cmp(&b.safe)
+            .then(b.envoys.cmp(&a.envoys))
+            .then(b.score.cmp(&a.score))
+            .then(a.player.cmp(&b.player))
+    });
+    out
+}
+
+/// Suggests a target for each of a Zeth player's steal (red) converters.
+/// Players become safe once they've been stolen from, so each target is
+/// used at most once: converters are assigned in ID order to the best
+/// remaining targets. Safe players are never suggested, so some converters
+/// may be left without a target.
+pub fn steal_assignments(state: &GameState, zeth: PlayerID) -> Vec<(ConverterID, PlayerID)> {
+    let steals = state
+        .owned_converters(zeth)
+        .into_iter()
+        .filter(|c| state.converter(*c).is_some_and(|c| c.color() == Arrow::Red));
+    let targets = steal_targets(state, zeth).into_iter().filter(|t| !t.safe);
+    steals.zip(targets.map(|t| t.player)).collect()
+}
+
+/// What another acknowledgement in a player's hands is worth to the
+/// Faderan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AcknowledgementTarget {
+    pub player: PlayerID,
+    /// How many acknowledgements the player already holds.
+    pub held: usize,
+    /// How many of the player's research teams they can afford to invent
+    /// before the game ends, going by their forecast income.
+    pub inventions: usize,
+    /// The victory points the Faderan can expect from giving the player
+    /// another acknowledgement. Each invention returns one acknowledgement,
+    /// so this is 1 if the player will invent more technologies than they
+    /// hold acknowledgements, and 0 otherwise.
+    pub expected_points: Fraction,
+}
+
+/// Scores a player as a holder of the Faderan's acknowledgements.
+pub fn acknowledgement_target(state: &GameState, player: PlayerID) -> AcknowledgementTarget {
+    let last = state.config().end.last_confluence();
+    let inventions: HashSet<TechID> = invention_plan(state, player)
+        .into_iter()
+        .filter(|o| {
+            o.turns_until_affordable
+                .is_some_and(|t| state.confluence().0 + t <= last.0)
+        })
+        .map(|o| o.tech)
+        .collect();
+    let held = state.acknowledgements(player);
+    let expected_points = if inventions.len() > held {
+        Fraction::new(1, 1)
+    } else {
+        Fraction::new(0, 1)
+    };
+    AcknowledgementTarget {
+        player,
+        held,
+        inventions: inventions.len(),
+        expected_points,
+    }
+}
+
+/// Scores every other player as a holder of a Faderan player's
+/// acknowledgements, best first: those expected to pay off, then those with
+/// the most inventions to spare. Ties go to the lower player ID.
+pub fn acknowledgement_targets(state: &GameState, faderan: PlayerID) -> Vec<AcknowledgementTarget> {
+    let mut out: Vec<AcknowledgementTarget> = state
+        .seats()
+        .iter()
+        .filter(|p| **p != faderan)
+        .map(|p| acknowledgement_target(state, *p))
+        .collect();
+    out.sort_by(|a, b| {
+        b.expected_points
+            .cmp(&a.expected_points)
+            .then(
+                (b.inventions as isize - b.held as isize)
+                    .cmp(&(a.inventions as isize - a.held as isize)),
+            )
+            .then(a.player.cmp(&b.player))
+    });
+    out
+}
+
+/// How a bot handles the Faderan's acknowledgements. The provided methods
+/// follow `acknowledgement_targets`; bots with a strategy of their own
+/// override them.
+pub trait AcknowledgementPolicy {
+    /// Who a Faderan bot should give an acknowledgement to now, if anyone.
+    /// By default, the best target who's owed one, if another
+    /// acknowledgement would pay off and the supply has one left.
+    fn give(&self, state: &GameState, faderan: PlayerID) -> Option<PlayerID> {
+        if !state.supply_of(Token::Acknowledgement).can_supply(1) {
+            return None;
+        }
+        acknowledgement_targets(state, faderan)
+            .into_iter()
+            .find(|t| {
+                t.expected_points > Fraction::new(0, 1) && state.acknowledgements_owed(t.player) > 0
+            })
+            .map(|t| t.player)
+    }
+
+    /// What receiving an acknowledgement is worth to a bot, in victory
+    /// points, for weighing trades which include one. Holding one gains
+    /// nothing, but hands the Faderan a point when the holder next invents,
+    /// so by default it costs the bot its share of a point to one of its
+    /// opponents.
+    fn receive(&self, state: &GameState, player: PlayerID) -> Fraction {
+        let opponents = state.seats().len().saturating_sub(1).max(1) as isize;
+        -acknowledgement_target(state, player).expected_points / opponents
+    }
+}
+
+/// The acknowledgement policy which uses every provided method.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DefaultAcknowledgements;
+
+impl AcknowledgementPolicy for DefaultAcknowledgements {}
+
+/// How many partial plans `convert_path` will look at before giving up.
+const MAX_CONVERT_SEARCH: usize = 100_000;
+
+/// A sequence of converters which turns one set of cubes into another.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversionPath {
+    /// The converters to run, in order.
+    pub converters: Vec<ConverterID>,
+    /// Every cube fed into the converters.
+    pub spent: CubeRecord,
+    /// Every cube the converters produce.
+    pub produced: CubeRecord,
+    /// The cubes left over once every converter has run.
+    pub result: CubeRecord,
+}
+
+/// Searches for the shortest sequence of converters, each run at most once,
+/// which turns the cubes in `from` into at least the cubes in `to`. Only
+/// white and purple converters whose inputs are all cubes are considered.
+/// Wild cubes pay for a specific type only when none of that type is left,
+/// and inputs of 'any' cube are paid from whichever type is most plentiful.
+/// Returns None if no sequence works, or if there are too many converters to
+/// search.
+pub fn convert_path(
+    from: CubeRecord,
+    to: CubeRecord,
+    available: &[(ConverterID, &dyn Convert)],
+) -> Option<ConversionPath> {
+    let usable: Vec<&(ConverterID, &dyn Convert)> = available
+        .iter()
+        .filter(|(_, c)| c.color() != Arrow::Red)
+        .filter(|(_, c)| {
+            c.input()
+                .iter()
+                .all(|i| matches!(i, Item::Cubes(..) | Item::DonationCubes(..)))
+        })
+        .collect();
+    if usable.len() > 64 {
+        return None;
+    }
+
+    let start = ConversionPath {
+        converters: Vec::new(),
+        spent: CubeRecord::default(),
+        produced: CubeRecord::default(),
+        result: from,
+    };
+    let mut queue = VecDeque::from([(0u64, start)]);
+    let mut seen = HashSet::from([0u64]);
+    while let Some((used, path)) = queue.pop_front() {
+        if covers(path.result, to) {
+            return Some(path);
+        }
+        for (i, (id, conv)) in usable.iter().enumerate() {
+            let mask = used | (1 << i);
+            if mask == used || seen.contains(&mask) {
+                continue;
+            }
+            let mut next = path.clone();
+            let mut ok = true;
+            for (typ, qty) in cubes_of(conv.input()) {
+                ok &= pay(&mut next.result, &mut next.spent, typ, qty);
+            }
+            if !ok {
+                continue;
+            }
+            for (typ, qty) in cubes_of(conv.output()) {
+                next.result.add_type(typ, qty);
+                next.produced.add_type(typ, qty);
+            }
+            next.converters.push(*id);
+            seen.insert(mask);
+            if seen.len() > MAX_CONVERT_SEARCH {
+                return None;
+            }
+            queue.push_back((mask, next));
+        }
+    }
+    None
+}
+
+/// Searches for a way for a player to turn their current cubes into at
+/// least `to` using the converters they own. See `convert_path`.
+pub fn player_convert_path(
+    state: &GameState,
+    player: PlayerID,
+    to: CubeRecord,
+) -> Option<ConversionPath> {
+    let owned: Vec<(ConverterID, &dyn Convert)> = state
+        .owned_converters(player)
+        .into_iter()
+        .filter_map(|id| state.converter(id).map(|c| (id, c)))
+        .collect();
+    convert_path(state.get_player_cubes(player), to, &owned)
+}
+
+/// The cubes in a list of converter items, ignoring anything else.
+fn cubes_of(items: &[Item]) -> impl Iterator<Item = (CubeType, isize)> + '_ {
+    items.iter().filter_map(|i| match i {
+        Item::Cubes(t, q) | Item::DonationCubes(t, q) => Some((*t, *q as isize)),
+        _ => None,
+    })
+}
+
+/// Whether `have` holds at least the cubes in `need`.
+fn covers(mut have: CubeRecord, need: CubeRecord) -> bool {
+    let mut spent = CubeRecord::default();
+    need.entries()
+        .into_iter()
+        .all(|(typ, qty)| pay(&mut have, &mut spent, typ, qty))
+}
+
+/// Takes `qty` cubes matching `typ` out of `have`, adding them to `spent`.
+/// Returns false if there aren't enough.
+fn pay(have: &mut CubeRecord, spent: &mut CubeRecord, typ: CubeType, qty: isize) -> bool {
+    let mut candidates: Vec<CubeType> = [
+        CubeType::Food,
+        CubeType::Culture,
+        CubeType::Industry,
+        CubeType::Power,
+        CubeType::Biotech,
+        CubeType::Information,
+        CubeType::Ship,
+        CubeType::Ultratech,
+        CubeType::VictoryPoint,
+    ]
+    .into_iter()
+    .filter(|c| typ.matches(*c))
+    .collect();
+    candidates.sort_by_key(|c| -have.count_type(*c));
+    candidates.extend(
+        [CubeType::UnitySmall, CubeType::UnityLarge]
+            .into_iter()
+            .filter(|c| typ.matches(*c)),
+    );
+
+    let mut left = qty;
+    for c in candidates {
+        let take = left.min(have.count_type(c)).max(0);
+        have.add_type(c, -take);
+        spent.add_type(c, take);
+        left -= take;
+    }
+    left <= 0
+}
+
+/// What a victory point is worth in cubes, as `Convert::output_value_adjusted`
+/// values them.
+const POINT_VALUE: isize = 6;
+
+/// What inventing a technology is worth over the rest of the game.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TechValue {
+    pub tech: TechID,
+    pub name: String,
+    pub tier: usize,
+    /// The cheapest of the technology's costs, and what it's worth.
+    pub cost: TechCost,
+    pub cost_value: Fraction,
+    /// What one run of the invented converter gains, adjusted for
+    /// inflation: its outputs less its inputs. Zero for technologies which
+    /// don't invent a converter.
+    pub profit: Fraction,
+    /// Every remaining run of the converter, plus the invention reward, less
+    /// the cost.
+    pub total: Fraction,
+}
+
+/// Ranks every technology in the game data by what inventing it in
+/// `confluence` is worth, best first. Cube values grow by `interest_rate`
+/// each confluence, so a rate above 1 favours converters which pay off
+/// sooner. Converters are valued from their prototypes, ignoring any faction
+/// specific versions. Empty once the game has no confluences left.
+pub fn rank_techs(
+    data: &GameData,
+    interest_rate: Fraction,
+    confluence: Confluence,
+) -> Vec<TechValue> {
+    let Some(turns_left) = (Confluence::LAST.0 + 1)
+        .checked_sub(confluence.0)
+        .filter(|t| *t > 0)
+    else {
+        return Vec::new();
+    };
+    let mut ranked: Vec<TechValue> = data
+        .tech
+        .values()
+        .filter_map(|t| {
+            let cost = *t
+                .cost
+                .iter()
+                .min_by_key(|c| c.typ.value() * c.qty as isize)?;
+            let cost_value = cost.typ.value() * cost.qty as isize;
+            let profit = data
+                .tech_prototype
+                .get(&t.id)
+                .filter(|_| t.invents.is_some())
+                .map_or(Fraction::new(0, 1), |p| {
+                    p.output_value_adjusted(interest_rate, turns_left)
+                        - p.input_value_adjusted(interest_rate, turns_left)
+                });
+            let total =
+                profit * turns_left as isize + POINT_VALUE * t.invent_reward as isize - cost_value;
+            Some(TechValue {
+                tech: t.id,
+                name: t.name.clone(),
+                tier: t.tier,
+                cost,
+                cost_value,
+                profit,
+                total,
+            })
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.total.cmp(&a.total).then(a.tech.cmp(&b.tech)));
+    ranked
+}
+
+/// What each side of a proposed cube trade is worth, in the same units as
+/// `CubeRecord::value`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeValue {
+    /// What the cubes being given away are worth.
+    pub giving: Fraction,
+    /// What the cubes being received are worth.
+    pub receiving: Fraction,
+}
+
+impl TradeValue {
+    /// What the trade gains, or loses if negative.
+    pub fn net(&self) -> Fraction {
+        self.receiving - self.giving
+    }
+}
+
+impl Display for TradeValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "giving {:.1}, receiving {:.1}",
+            self.giving.value(),
+            self.receiving.value()
+        )
+    }
+}
+
+/// Values both sides of a cube trade made in `confluence`, for showing
+/// hints while players negotiate: `offer` is what the player gives, and
+/// `ask` what they get back. See `trade_value_at`; cube values aren't
+/// inflated here.
+pub fn trade_value(offer: &CubeRecord, ask: &CubeRecord, confluence: Confluence) -> TradeValue {
+    trade_value_at(offer, ask, Fraction::new(1, 1), confluence)
+}
+
+/// Values both sides of a cube trade made in `confluence`, with cube values
+/// growing by `interest_rate` each confluence. Cubes are valued as
+/// converter inputs are by `Convert::input_value_adjusted`, so ships and
+/// victory points lose value against cubes the more confluences are left.
+/// Once the game is over, only what the cubes score is counted, using
+/// `CubeRecord::vp_value`.
+pub fn trade_value_at(
+    offer: &CubeRecord,
+    ask: &CubeRecord,
+    interest_rate: Fraction,
+    confluence: Confluence,
+) -> TradeValue {
+    let turns_left = (Confluence::LAST.0 + 1).saturating_sub(confluence.0);
+    let value = |cubes: &CubeRecord| {
+        if turns_left == 0 {
+            return cubes.vp_value() * POINT_VALUE;
+        }
+        let input = cubes
+            .iter()
+            .filter(|(_, qty)| *qty > 0)
+            .map(|(typ, qty)| Item::Cubes(typ, qty as usize))
+            .collect();
+        let bundle = ConverterPrototype {
+            id: TechID::default(),
+            name: String::new(),
+            conv: Converter {
+                color: Arrow::White,
+                input,
+                output: Vec::new(),
+                fleet: 0,
+            },
+        };
+        bundle.input_value_adjusted(interest_rate, turns_left)
+    };
+    TradeValue {
+        giving: value(offer),
+        receiving: value(ask),
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Error};
+
+use crate::{
+    analysis::trade_value,
+    state::{
+        awaiting::PendingAction,
+        offers::TradeTerms,
+        player::PlayerID,
+        record::{RecordGroup, RecordID, RecordType},
+        view::{OfferView, PlayerView, TurnView},
+        GameState, Phase,
+    },
+    Fraction,
+};
+
+/// A computer player. Bots are asked what to do whenever their seat might
+/// act, and again once what they chose has been applied, until they choose
+/// nothing. They only see what their seat's player would.
+pub trait Bot {
+    /// The records the bot submits next for `view.player`, each in its own
+    /// group, numbered on from the view's `turn.next_record`. Records are
+    /// applied in order.
+    fn choose_records(&mut self, view: &PlayerView) -> Vec<RecordGroup>;
+}
+
+/// Asks a bot what a seat does next, validating and applying every group it
+/// chooses. Returns how many groups were applied. Seats whose profile is
+/// `Controller::Ai`, or that nobody has joined, can be played like this.
+pub fn play_bot(
+    state: &mut GameState,
+    bot: &mut dyn Bot,
+    player: PlayerID,
+) -> Result<usize, Error> {
+    let view = state
+        .view(player)
+        .players
+        .into_iter()
+        .find(|p| p.player == player)
+        .ok_or_else(|| anyhow!("player {} isn't in the game", player.0))?;
+    let groups = bot.choose_records(&view);
+    let applied = groups.len();
+    for group in groups {
+        for rec in group.rec.iter() {
+            state
+                .validate(rec)
+                .map_err(|e| anyhow!("bot record {:?} is invalid: {}", rec, e))?;
+        }
+        state.apply(group);
+    }
+    Ok(applied)
+}
+
+/// Plays a game to the end with a bot in every seat, moving on to the next
+/// phase whenever no bot has anything left to do. Fails if a bot's record
+/// is invalid, or if the bots leave a phase that can't end.
+pub fn play_to_end(
+    state: &mut GameState,
+    bots: &mut BTreeMap<PlayerID, Box<dyn Bot>>,
+) -> Result<(), Error> {
+    loop {
+        let mut applied = 0;
+        for (player, bot) in bots.iter_mut() {
+            applied += play_bot(state, bot.as_mut(), *player)?;
+        }
+        if applied > 0 {
+            continue;
+        }
+        if let Some((player, action)) = state.awaiting().into_iter().find(|(_, a)| a.blocking()) {
+            return Err(anyhow!("bots left player {} to {:?}", player.0, action));
+        }
+        let Some(to) = state.next_phase() else {
+            return Ok(());
+        };
+        let rec = RecordType::ChangePhase { to };
+        state
+            .validate(&rec)
+            .map_err(|e| anyhow!("can't move on to {:?}: {}", to, e))?;
+        state.apply(RecordGroup {
+            id: state.next_record_id(),
+            rec: vec![rec],
+        });
+    }
+}
+
+/// A baseline bot which plays greedily, with no plan beyond the current
+/// phase. It marks every converter it can pay for and runs them along with
+/// its colonies, bids a share of its ships split evenly between the tracks,
+/// takes the first slot it can afford, and accepts cube trades worth more
+/// to it than they cost.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GreedyBot {
+    /// The share of its ships the bot bids each confluence.
+    pub bid_share: Fraction,
+    /// How much a trade must gain, going by `analysis::trade_value`, for the
+    /// bot to accept it.
+    pub threshold: Fraction,
+}
+
+impl Default for GreedyBot {
+    fn default() -> Self {
+        Self {
+            bid_share: Fraction::new(1, 2),
+            threshold: Fraction::new(0, 1),
+        }
+    }
+}
+
+impl Bot for GreedyBot {
+    fn choose_records(&mut self, view: &PlayerView) -> Vec<RecordGroup> {
+        let Some(turn) = view.turn.as_ref() else {
+            return Vec::new();
+        };
+        let mut out = Vec::new();
+        let mut accepted = false;
+        for action in turn.awaiting.iter() {
+            if let Some(rec) = self.respond(view, turn, *action, &mut accepted) {
+                out.push(rec);
+            }
+        }
+        match turn.phase {
+            Phase::Trade if !turn.markable.is_empty() => {
+                out.push(RecordType::MarkAll {
+                    player: view.player,
+                    except: Vec::new(),
+                });
+            }
+            // colonies aren't marked, so run whichever can be paid for once
+            // the converters are done, with what they made
+            Phase::Economy if out.is_empty() => {
+                for (colony, inputs) in turn.colony_inputs.iter() {
+                    out.push(RecordType::RunColony {
+                        colony: *colony,
+                        inputs: inputs.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+        out.into_iter()
+            .enumerate()
+            .map(|(i, rec)| RecordGroup {
+                id: RecordID(turn.next_record.0 + i),
+                rec: vec![rec],
+            })
+            .collect()
+    }
+}
+
+impl GreedyBot {
+    /// What the bot does about one thing its seat is waiting on, if
+    /// anything. Only one offer is accepted a turn, since the cubes it
+    /// gives away may be wanted by another.
+    fn respond(
+        &self,
+        view: &PlayerView,
+        turn: &TurnView,
+        action: PendingAction,
+        accepted: &mut bool,
+    ) -> Option<RecordType> {
+        let player = view.player;
+        let rec = match action {
+            PendingAction::Bid => {
+                let ships = view.cubes.ships.max(0);
+                let bid = (self.bid_share * ships).integer().clamp(0, ships) as usize;
+                let colony = bid / 2;
+                RecordType::Bid {
+                    player,
+                    for_colony: colony,
+                    for_colony_kjas: None,
+                    for_tech: bid - colony,
+                    for_tech_faderan: None,
+                }
+            }
+            PendingAction::TakeColony => RecordType::TakeColony {
+                player,
+                colony: turn.colony_slots.first().copied(),
+            },
+            PendingAction::TakeResearch => RecordType::TakeResearch {
+                player,
+                tech: turn.tech_slots.first().copied(),
+            },
+            PendingAction::RunConverter { converter } => {
+                let inputs = turn
+                    .converter_inputs
+                    .iter()
+                    .find(|(c, _)| *c == converter)
+                    .and_then(|(_, inputs)| inputs.clone());
+                match inputs {
+                    Some(inputs) => RecordType::RunConverter {
+                        converter,
+                        inputs,
+                        target: None,
+                    },
+                    None => RecordType::UnmarkConverter { player, converter },
+                }
+            }
+            PendingAction::RespondToOffer { offer } => {
+                let o = turn.offers.iter().find(|o| o.offer.id == offer)?;
+                if !self.worth_accepting(turn, o) {
+                    RecordType::RejectTrade { offer }
+                } else if *accepted {
+                    return None;
+                } else if o.acceptable {
+                    *accepted = true;
+                    RecordType::AcceptTrade { offer }
+                } else {
+                    RecordType::RejectTrade { offer }
+                }
+            }
+            // inventing isn't part of the greedy plan
+            PendingAction::Invent { .. } => return None,
+        };
+        Some(rec)
+    }
+
+    /// Whether an offer gains the bot more than its threshold. Only cubes
+    /// can be valued, so offers including anything else are turned down.
+    fn worth_accepting(&self, turn: &TurnView, o: &OfferView) -> bool {
+        let cubes_only = |t: &TradeTerms| {
+            t.colonies.is_empty() && t.converters.is_empty() && t.retro_tokens.is_empty()
+        };
+        if !cubes_only(&o.offer.give) || !cubes_only(&o.offer.take) {
+            return false;
+        }
+        let value = trade_value(&o.take_cubes, &o.give_cubes, turn.confluence);
+        value.net() > self.threshold
+    }
+}
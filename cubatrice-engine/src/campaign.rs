@@ -0,0 +1,232 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{anyhow, Context, Error};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::{faction::FactionType, player::PlayerID},
+    state::{config::GameConfig, GameState, Phase},
+    Fraction,
+};
+
+/// How a campaign scores its games, and what carries over between them.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CampaignRules {
+    /// Standing points for each finishing place, first place first. Places
+    /// past the end of the list score nothing.
+    pub place_points: Vec<usize>,
+    /// Victory points everyone except the last game's winners starts the
+    /// next game with, so that winners carry a handicap.
+    pub winner_handicap: usize,
+}
+
+impl Default for CampaignRules {
+    fn default() -> Self {
+        Self {
+            place_points: vec![3, 2, 1],
+            winner_handicap: 1,
+        }
+    }
+}
+
+/// Milestones a member keeps for the rest of a campaign once they've
+/// reached them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    /// Won a game.
+    FirstWin,
+    /// Won three games in a row.
+    HatTrick,
+    /// Won games as three different factions.
+    Versatile,
+    /// Played ten games.
+    Veteran,
+}
+
+/// How one member did in one game of a campaign.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CampaignResult {
+    pub member: String,
+    pub faction: FactionType,
+    /// 1 for the winner, as in `FinalScore::place`.
+    pub place: usize,
+    pub total: Fraction,
+}
+
+/// A member's position in a campaign.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Standing {
+    pub member: String,
+    /// Standing points from every game, see `CampaignRules::place_points`.
+    pub points: usize,
+    pub games: usize,
+    pub wins: usize,
+    pub achievements: BTreeSet<Achievement>,
+}
+
+/// A series of games between the same group, such as a weekly league.
+/// Members are known by their profile names, so a player must have set a
+/// name for their games to count towards a campaign.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Campaign {
+    pub name: String,
+    pub rules: CampaignRules,
+    /// Every game recorded so far, in the order they were played, each
+    /// listed best first.
+    games: Vec<Vec<CampaignResult>>,
+    achievements: BTreeMap<String, BTreeSet<Achievement>>,
+}
+
+impl Campaign {
+    /// Starts a campaign with no games.
+    pub fn new(name: &str, rules: CampaignRules) -> Self {
+        Self {
+            name: String::from(name),
+            rules,
+            games: Vec::new(),
+            achievements: BTreeMap::new(),
+        }
+    }
+
+    /// Every game recorded so far, in the order they were played.
+    pub fn games(&self) -> &[Vec<CampaignResult>] {
+        &self.games
+    }
+
+    /// The achievements a member has earned.
+    pub fn achievements(&self, member: &str) -> BTreeSet<Achievement> {
+        self.achievements.get(member).cloned().unwrap_or_default()
+    }
+
+    /// Adds a finished game to the campaign, returning the achievements it
+    /// earned.
+    pub fn record(&mut self, state: &GameState) -> Result<Vec<(String, Achievement)>, Error> {
+        if state.phase() != Phase::Finish {
+            return Err(anyhow!("the game hasn't finished yet"));
+        }
+        let mut results = Vec::new();
+        for score in state.final_scores().ranking {
+            let profile = state.profile(score.player);
+            let member = profile.name.ok_or_else(|| {
+                anyhow!("player {} needs a name to join a campaign", score.player.0)
+            })?;
+            let faction = state
+                .faction(score.player)
+                .ok_or_else(|| anyhow!("player {} has no faction", score.player.0))?;
+            results.push(CampaignResult {
+                member,
+                faction,
+                place: score.place,
+                total: score.total,
+            });
+        }
+        let members: Vec<String> = results.iter().map(|r| r.member.clone()).collect();
+        self.games.push(results);
+
+        let mut earned = Vec::new();
+        for member in members {
+            for a in self.reached(&member) {
+                if self
+                    .achievements
+                    .entry(member.clone())
+                    .or_default()
+                    .insert(a)
+                {
+                    earned.push((member.clone(), a));
+                }
+            }
+        }
+        Ok(earned)
+    }
+
+    /// Every achievement a member's record so far qualifies for.
+    fn reached(&self, member: &str) -> Vec<Achievement> {
+        let played: Vec<&CampaignResult> = self
+            .games
+            .iter()
+            .filter_map(|g| g.iter().find(|r| r.member == member))
+            .collect();
+        let won: Vec<&CampaignResult> = played.iter().copied().filter(|r| r.place == 1).collect();
+        let factions: BTreeSet<FactionType> = won.iter().map(|r| r.faction).collect();
+        let streak = played.iter().rev().take_while(|r| r.place == 1).count();
+        let mut out = Vec::new();
+        if !won.is_empty() {
+            out.push(Achievement::FirstWin);
+        }
+        if streak >= 3 {
+            out.push(Achievement::HatTrick);
+        }
+        if factions.len() >= 3 {
+            out.push(Achievement::Versatile);
+        }
+        if played.len() >= 10 {
+            out.push(Achievement::Veteran);
+        }
+        out
+    }
+
+    /// Everyone who has played in the campaign, best first: by standing
+    /// points, then wins, then name.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: BTreeMap<&str, Standing> = BTreeMap::new();
+        for r in self.games.iter().flatten() {
+            let s = standings.entry(&r.member).or_insert_with(|| Standing {
+                member: r.member.clone(),
+                points: 0,
+                games: 0,
+                wins: 0,
+                achievements: self.achievements(&r.member),
+            });
+            s.points += self
+                .rules
+                .place_points
+                .get(r.place - 1)
+                .copied()
+                .unwrap_or(0);
+            s.games += 1;
+            if r.place == 1 {
+                s.wins += 1;
+            }
+        }
+        let mut out: Vec<Standing> = standings.into_values().collect();
+        out.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then(b.wins.cmp(&a.wins))
+                .then(a.member.cmp(&b.member))
+        });
+        out
+    }
+
+    /// Sets up the campaign's handicaps for the next game. `members` says
+    /// who is playing as which player.
+    pub fn prepare(&self, config: GameConfig, members: &[(PlayerID, &str)]) -> GameConfig {
+        let winners: BTreeSet<&str> = self
+            .games
+            .last()
+            .into_iter()
+            .flatten()
+            .filter(|r| r.place == 1)
+            .map(|r| r.member.as_str())
+            .collect();
+        if winners.is_empty() || self.rules.winner_handicap == 0 {
+            return config;
+        }
+        members
+            .iter()
+            .filter(|(_, m)| !winners.contains(m))
+            .fold(config, |cfg, (p, _)| {
+                cfg.with_start_points(*p, self.rules.winner_handicap)
+            })
+    }
+
+    /// Serializes the campaign to JSON.
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string(self).context("Failed to serialize campaign")
+    }
+
+    /// Restores a campaign serialized with `to_json`.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).context("Failed to deserialize campaign")
+    }
+}
@@ -0,0 +1,392 @@
+use std::fmt::Display;
+
+use anyhow::{anyhow, Context, Error};
+use serde::de::DeserializeOwned;
+
+use crate::entity::{
+    colony::{Colony, ColonyID},
+    converter::{ConverterID, ConverterKind},
+    faction::{FactionType, StartingResources},
+    player::PlayerID,
+    technology::{ConverterPrototype, Technology},
+};
+use crate::game_data::GameData;
+use crate::state::{
+    awaiting::PendingAction,
+    config::GameConfig,
+    endgame::EndCondition,
+    events::GameEvent,
+    import::parse_command,
+    player::Profile,
+    record::{RecordGroup, RecordType},
+    Confluence, GameState, Phase,
+};
+
+// The demo's game data, laid out like the data directory, but built in so
+// the demo runs without one.
+const COLONIES: &str = include_str!("../demo/colony.json");
+const TECHNOLOGIES: &str = include_str!("../demo/technology.json");
+const PROTOTYPES: &str = include_str!("../demo/prototypes.json");
+const START_RESOURCES: &str = include_str!("../demo/startResources.json");
+
+/// Seed for the demo game, so it plays out the same every time.
+const SEED: u64 = 2024;
+
+/// Who plays the demo, and the names their bots go by.
+const PLAYERS: [(PlayerID, FactionType, &str); 2] = [
+    (PlayerID(1), FactionType::CaylionCore, "Ada"),
+    (PlayerID(2), FactionType::EniEtCore, "Brin"),
+];
+
+/// Commands played during the trade phase of a confluence, before the bots
+/// take over, in the same format as `import::parse_command`.
+const SCRIPT: [(usize, &str); 2] = [
+    (1, "trade, p1, p2, 1 culture, 1 industry"),
+    (2, "trade, p1, p2, 1 industry, 1 culture"),
+];
+
+/// Something that happened in the demo, with when it happened.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DigestEntry {
+    pub confluence: Confluence,
+    pub phase: Phase,
+    pub text: String,
+}
+
+impl Display for DigestEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[confluence {}, {:?}] {}",
+            self.confluence.0, self.phase, self.text
+        )
+    }
+}
+
+/// A finished demo game, and what happened in it.
+#[derive(Debug)]
+pub struct Demo {
+    pub state: GameState,
+    pub digest: Vec<DigestEntry>,
+}
+
+/// The demo's game data.
+pub fn data() -> Result<GameData, Error> {
+    let mut data = GameData::new();
+    for c in parse::<Colony>(COLONIES, "colony.json")? {
+        data.colony.insert(c.id, c);
+    }
+    for t in parse::<Technology>(TECHNOLOGIES, "technology.json")? {
+        data.tech.insert(t.id, t);
+    }
+    for p in parse::<ConverterPrototype>(PROTOTYPES, "prototypes.json")? {
+        data.tech_prototype.insert(p.id, p);
+    }
+    for s in parse::<StartingResources>(START_RESOURCES, "startResources.json")? {
+        data.start_resources.insert(s.0, s.1);
+    }
+    Ok(data)
+}
+
+fn parse<T: DeserializeOwned>(src: &str, name: &str) -> Result<Vec<T>, Error> {
+    serde_json::from_str(src).with_context(|| format!("parsing built in demo/{}", name))
+}
+
+/// The demo game's config: two players, over two confluences.
+pub fn config() -> GameConfig {
+    PLAYERS
+        .iter()
+        .fold(GameConfig::new(SEED), |cfg, (p, f, _)| {
+            cfg.with_player(*p, *f)
+        })
+        .with_end(EndCondition::AfterConfluence(Confluence(2)))
+}
+
+/// Sets up the demo game and plays it to the end, with a bot in every seat.
+/// Every record is validated before it's applied, so a rules change which
+/// breaks the demo is reported as an error rather than played through.
+pub fn play() -> Result<Demo, Error> {
+    let mut demo = Demo {
+        state: GameState::from_config(data()?, config())?,
+        digest: Vec::new(),
+    };
+    for (player, _, name) in PLAYERS {
+        let profile = Profile {
+            name: Some(String::from(name)),
+            ..Profile::default()
+        };
+        demo.submit(RecordType::SetProfile { player, profile }, None)?;
+    }
+    demo.state.take_events();
+
+    while let Some(next) = demo.state.next_phase() {
+        match demo.state.phase() {
+            Phase::Trade => demo.trade()?,
+            Phase::Economy => demo.economy(),
+            _ => {}
+        }
+        while let Some((player, action)) = demo
+            .state
+            .awaiting()
+            .into_iter()
+            .find(|(_, a)| a.blocking())
+        {
+            let (rec, why) = demo.decide(player, action)?;
+            demo.submit(rec, Some(why))?;
+        }
+        demo.submit(RecordType::ChangePhase { to: next }, None)?;
+    }
+    Ok(demo)
+}
+
+impl Demo {
+    /// Validates and applies a record, adding it and the events it caused
+    /// to the digest.
+    fn submit(&mut self, rec: RecordType, why: Option<String>) -> Result<(), Error> {
+        self.state
+            .validate(&rec)
+            .map_err(|e| anyhow!("demo record {:?} is invalid: {}", rec, e))?;
+        self.state.apply(RecordGroup {
+            id: self.state.next_record_id(),
+            rec: vec![rec],
+        });
+        if let Some(why) = why {
+            self.note(why);
+        }
+        self.take_events();
+        Ok(())
+    }
+
+    fn note(&mut self, text: String) {
+        self.digest.push(DigestEntry {
+            confluence: self.state.confluence(),
+            phase: self.state.phase(),
+            text,
+        });
+    }
+
+    fn take_events(&mut self) {
+        for (_, e) in self.state.take_events() {
+            if let Some(text) = self.annotate(&e) {
+                self.note(text);
+            }
+        }
+    }
+
+    /// Describes an event using names rather than IDs. Phases starting
+    /// aren't worth a line of their own, since every entry says when it
+    /// happened.
+    fn annotate(&self, event: &GameEvent) -> Option<String> {
+        let s = &self.state;
+        let tech = |t| {
+            s.data()
+                .tech
+                .get(&t)
+                .map_or_else(|| format!("technology {}", t.0), |t| t.name.clone())
+        };
+        let text = match event {
+            GameEvent::ConfluenceStarted { .. } | GameEvent::PhaseStarted { .. } => return None,
+            GameEvent::ColonyWon {
+                player,
+                colony,
+                ships,
+            } => format!(
+                "{} took the colony {} for {} ships",
+                s.player_name(*player),
+                s.data()
+                    .colony
+                    .get(colony)
+                    .map_or_else(|| format!("{}", colony.0), |c| c.name.clone()),
+                ships
+            ),
+            GameEvent::ResearchTeamWon {
+                player,
+                tech: t,
+                ships,
+            } => format!(
+                "{} took the {} research team for {} ships",
+                s.player_name(*player),
+                tech(*t),
+                ships
+            ),
+            GameEvent::TechInvented {
+                player,
+                tech: t,
+                points,
+            } => format!(
+                "{} invented {}, scoring {} points",
+                s.player_name(*player),
+                tech(*t),
+                points
+            ),
+            GameEvent::TechShared { tech: t } => {
+                format!("{} was shared with everyone", tech(*t))
+            }
+            GameEvent::Traded { a, b } => {
+                format!("{} and {} traded", s.player_name(*a), s.player_name(*b))
+            }
+            e => e.to_string(),
+        };
+        Some(text)
+    }
+
+    /// Plays this confluence's scripted trades, then has every bot invent
+    /// whatever it can afford and mark all its converters to run.
+    fn trade(&mut self) -> Result<(), Error> {
+        let confluence = self.state.confluence().0;
+        for (_, line) in SCRIPT.iter().filter(|(c, _)| *c == confluence) {
+            let rec = parse_command(&self.state, line)
+                .map_err(|e| anyhow!("demo script line '{}': {}", line, e))?;
+            self.submit(rec, Some(format!("scripted: {}", line)))?;
+        }
+        for (player, action) in self.state.awaiting() {
+            let PendingAction::Invent { tech } = action else {
+                continue;
+            };
+            let cubes = self.state.get_player_cubes(player);
+            let cost = self.state.data().tech.get(&tech).and_then(|t| {
+                t.cost
+                    .iter()
+                    .find(|c| cubes.count_type(c.typ) >= c.qty as isize)
+                    .map(|c| c.typ)
+            });
+            // an earlier invention may have used the cubes
+            if let Some(cost) = cost {
+                self.submit(
+                    RecordType::InventTech { player, tech, cost },
+                    Some(format!(
+                        "{}'s bot pays {} to invent",
+                        self.state.player_name(player),
+                        cost.name()
+                    )),
+                )?;
+            }
+        }
+        for (player, _, _) in PLAYERS {
+            if !self.state.expand_mark_all(player, &[]).is_empty() {
+                let why = format!(
+                    "{}'s bot marks every converter it can pay for",
+                    self.state.player_name(player)
+                );
+                self.submit(
+                    RecordType::MarkAll {
+                        player,
+                        except: Vec::new(),
+                    },
+                    Some(why),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs every marked converter, then every colony.
+    fn economy(&mut self) {
+        for group in self.state.run_marked_converters() {
+            for r in group.rec {
+                if let RecordType::RunConverter { converter, .. } = r {
+                    self.ran(converter);
+                }
+            }
+        }
+        for group in self.state.run_colonies() {
+            for r in group.rec {
+                if let RecordType::RunColony { colony, .. } = r {
+                    self.ran_colony(colony);
+                }
+            }
+        }
+        self.take_events();
+    }
+
+    fn ran_colony(&mut self, colony: ColonyID) {
+        let name = self
+            .state
+            .data()
+            .colony
+            .get(&colony)
+            .map_or_else(|| format!("colony {}", colony.0), |c| c.name.clone());
+        let owner = PLAYERS
+            .iter()
+            .map(|(p, _, _)| *p)
+            .find(|p| self.state.colonies_of(*p).contains(&colony));
+        let who = owner.map_or_else(|| String::from("someone"), |p| self.state.player_name(p));
+        self.note(format!("{} ran {}", who, name));
+    }
+
+    fn ran(&mut self, converter: ConverterID) {
+        let name = match self.state.converter(converter).map(|c| c.kind()) {
+            Some(ConverterKind::Prototype(p)) => p.name,
+            Some(ConverterKind::Colony(c)) => c.name,
+            _ => format!("converter {}", converter.0),
+        };
+        let owner = PLAYERS
+            .iter()
+            .map(|(p, _, _)| *p)
+            .find(|p| self.state.owned_converters(*p).contains(&converter));
+        let who = owner.map_or_else(|| String::from("someone"), |p| self.state.player_name(p));
+        self.note(format!("{} ran {}", who, name));
+    }
+
+    /// What a bot does about something blocking the phase, and why.
+    fn decide(
+        &self,
+        player: PlayerID,
+        action: PendingAction,
+    ) -> Result<(RecordType, String), Error> {
+        let name = self.state.player_name(player);
+        let decision = match action {
+            // split the fleet between the two tracks, favouring research
+            PendingAction::Bid => {
+                let ships = self.state.ships_of(player);
+                let colony = ships / 2;
+                let tech = ships - colony;
+                (
+                    RecordType::Bid {
+                        player,
+                        for_colony: colony,
+                        for_colony_kjas: None,
+                        for_tech: tech,
+                        for_tech_faderan: None,
+                    },
+                    format!(
+                        "{}'s bot bids {} ships for a colony and {} for research",
+                        name, colony, tech
+                    ),
+                )
+            }
+            // take the first slot on the track that's affordable
+            PendingAction::TakeColony => {
+                let slots = self.state.colony_track_size();
+                let take = |colony| RecordType::TakeColony { player, colony };
+                let choice = (0..slots).find(|i| self.state.validate(&take(Some(*i))).is_ok());
+                (take(choice), self.take_reason(&name, choice, "colony"))
+            }
+            PendingAction::TakeResearch => {
+                let slots = self.state.tech_track_size();
+                let take = |tech| RecordType::TakeResearch { player, tech };
+                let choice = (0..slots).find(|i| self.state.validate(&take(Some(*i))).is_ok());
+                (
+                    take(choice),
+                    self.take_reason(&name, choice, "research team"),
+                )
+            }
+            // anything still marked couldn't be paid for
+            PendingAction::RunConverter { converter } => (
+                RecordType::UnmarkConverter { player, converter },
+                format!("{}'s bot can't pay for a marked converter", name),
+            ),
+            PendingAction::Invent { .. } | PendingAction::RespondToOffer { .. } => {
+                return Err(anyhow!("{:?} doesn't block the phase", action));
+            }
+        };
+        Ok(decision)
+    }
+
+    fn take_reason(&self, name: &str, choice: Option<usize>, what: &str) -> String {
+        match choice {
+            Some(i) => format!("{}'s bot picks the {} in slot {}", name, what, i),
+            None => format!("{}'s bot can't afford a {}, and passes", name, what),
+        }
+    }
+}
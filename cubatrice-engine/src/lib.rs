@@ -0,0 +1,30 @@
+#![allow(dead_code)]
+
+//! The rules engine: game state, the records which change it, and analysis
+//! of games in progress or finished. Game data and entities live in
+//! `cubatrice-data`, and are re-exported here.
+
+pub use cubatrice_data::*;
+
+/// Analysis of game state, for suggestions and planning
+pub mod analysis;
+/// Computer players, for solo testing and empty seats
+pub mod bot;
+/// Series of games between the same group, and what carries between them
+pub mod campaign;
+/// A small built-in game played by bots, needing no data files
+pub mod demo;
+/// Gathering players for games and starting them
+pub mod lobby;
+/// Health counters for hosts running many games
+#[cfg(feature = "metrics")]
+pub mod metrics;
+/// Game state representation
+pub mod state;
+/// Post-game statistics
+pub mod stats;
+/// Parameter sweeps over bot games, for balancing homebrew content
+pub mod sweep;
+/// TypeScript definitions for frontends
+#[cfg(feature = "typescript")]
+pub mod typescript;
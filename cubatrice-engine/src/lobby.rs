@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::{faction::FactionType, player::PlayerID},
+    game_data::GameData,
+    state::{config::GameConfig, record::RecordGroup, wizard::PLAYER_COUNTS, GameState},
+};
+
+/// Identifies a table within a `Lobby`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct TableID(pub usize);
+
+/// Someone sitting at a table, before the game starts.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Seat {
+    /// Whoever is sitting here, named however the host identifies people.
+    pub member: String,
+    pub faction: Option<FactionType>,
+    /// Set once the member is happy to start. Only possible with a faction,
+    /// and cleared if they change it.
+    pub ready: bool,
+}
+
+/// One game, from gathering players to playing it.
+struct Table {
+    /// The config the game will be set up with, minus its players.
+    template: GameConfig,
+    seats: Vec<Seat>,
+    /// Set once the game starts, after which seats can't change.
+    game: Option<GameState>,
+}
+
+/// Any number of games, each gathering players at a table until everyone
+/// is ready to start. Members are seated in the order they join, and play
+/// as `PlayerID(1)` onwards in that order.
+///
+/// Nothing here knows about connections, so the same lobby can sit behind
+/// any kind of host.
+pub struct Lobby {
+    data: GameData,
+    tables: BTreeMap<TableID, Table>,
+    next: usize,
+}
+
+impl Lobby {
+    /// An empty lobby whose games will all be played with `data`.
+    pub fn new(data: GameData) -> Self {
+        Self {
+            data,
+            tables: BTreeMap::new(),
+            next: 0,
+        }
+    }
+
+    /// Opens a table for a game to be set up like `template`, which mustn't
+    /// have any players yet.
+    pub fn create(&mut self, template: GameConfig) -> Result<TableID, Error> {
+        if !template.players.is_empty() {
+            return Err(anyhow!(
+                "players join tables, they can't be set up in advance"
+            ));
+        }
+        let id = TableID(self.next);
+        self.next += 1;
+        self.tables.insert(
+            id,
+            Table {
+                template,
+                seats: Vec::new(),
+                game: None,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Closes a table, handing back its game if it had started.
+    pub fn close(&mut self, table: TableID) -> Option<GameState> {
+        self.tables.remove(&table).and_then(|t| t.game)
+    }
+
+    /// Every open table, in the order they were created.
+    pub fn tables(&self) -> impl Iterator<Item = TableID> + '_ {
+        self.tables.keys().copied()
+    }
+
+    /// Who's sitting at a table, in seating order.
+    pub fn seats(&self, table: TableID) -> Result<&[Seat], Error> {
+        Ok(&self.table(table)?.seats)
+    }
+
+    /// Sits a member down at a table, returning their seat.
+    pub fn join(&mut self, table: TableID, member: &str) -> Result<usize, Error> {
+        let t = self.waiting(table)?;
+        if t.seats.iter().any(|s| s.member == member) {
+            return Err(anyhow!("{} is already at table {}", member, table.0));
+        }
+        if t.seats.len() >= *PLAYER_COUNTS.end() {
+            return Err(anyhow!("table {} is full", table.0));
+        }
+        t.seats.push(Seat {
+            member: String::from(member),
+            faction: None,
+            ready: false,
+        });
+        Ok(t.seats.len() - 1)
+    }
+
+    /// Gets a member up from a table. Everyone after them moves up a seat.
+    pub fn leave(&mut self, table: TableID, member: &str) -> Result<(), Error> {
+        let t = self.waiting(table)?;
+        let seat = Self::seat_of(t, table, member)?;
+        t.seats.remove(seat);
+        Ok(())
+    }
+
+    /// Chooses a member's faction. Nobody else at the table may be playing
+    /// it, or the other side of it.
+    pub fn pick(
+        &mut self,
+        table: TableID,
+        member: &str,
+        faction: FactionType,
+    ) -> Result<(), Error> {
+        let t = self.waiting(table)?;
+        let seat = Self::seat_of(t, table, member)?;
+        let taken = t
+            .seats
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != seat)
+            .find_map(|(_, s)| {
+                s.faction
+                    .filter(|f| *f == faction || *f == faction.bifurcate())
+            });
+        if let Some(taken) = taken {
+            return Err(anyhow!(
+                "{} can't be played alongside {}",
+                faction.name(),
+                taken.name()
+            ));
+        }
+        t.seats[seat].faction = Some(faction);
+        t.seats[seat].ready = false;
+        Ok(())
+    }
+
+    /// Marks a member as ready to start, or not.
+    pub fn ready(&mut self, table: TableID, member: &str, ready: bool) -> Result<(), Error> {
+        let t = self.waiting(table)?;
+        let seat = Self::seat_of(t, table, member)?;
+        if ready && t.seats[seat].faction.is_none() {
+            return Err(anyhow!("{} hasn't picked a faction", member));
+        }
+        t.seats[seat].ready = ready;
+        Ok(())
+    }
+
+    /// Starts a table's game once everyone is ready, returning the records
+    /// it was set up with.
+    pub fn start(&mut self, table: TableID) -> Result<Vec<RecordGroup>, Error> {
+        let data = self.data.clone();
+        let t = self.waiting(table)?;
+        if !PLAYER_COUNTS.contains(&t.seats.len()) {
+            return Err(anyhow!(
+                "games are for {} to {} players",
+                PLAYER_COUNTS.start(),
+                PLAYER_COUNTS.end()
+            ));
+        }
+        if let Some(seat) = t.seats.iter().find(|s| !s.ready) {
+            return Err(anyhow!("{} isn't ready", seat.member));
+        }
+        let mut config = t.template.clone();
+        for (i, s) in t.seats.iter().enumerate() {
+            let faction = s
+                .faction
+                .ok_or_else(|| anyhow!("{} hasn't picked a faction", s.member))?;
+            config = config.with_player(PlayerID(i + 1), faction);
+        }
+        t.game = Some(GameState::from_config(data, config.clone())?);
+        Ok(config.setup_records())
+    }
+
+    /// A table's game, once it's started.
+    pub fn game(&self, table: TableID) -> Option<&GameState> {
+        self.tables.get(&table)?.game.as_ref()
+    }
+
+    /// A table's game, once it's started, for applying records to.
+    pub fn game_mut(&mut self, table: TableID) -> Option<&mut GameState> {
+        self.tables.get_mut(&table)?.game.as_mut()
+    }
+
+    /// Who a member is playing as at a table whose game has started.
+    pub fn player(&self, table: TableID, member: &str) -> Option<PlayerID> {
+        let t = self.tables.get(&table)?;
+        t.game.as_ref()?;
+        t.seats
+            .iter()
+            .position(|s| s.member == member)
+            .map(|i| PlayerID(i + 1))
+    }
+
+    fn table(&self, table: TableID) -> Result<&Table, Error> {
+        self.tables
+            .get(&table)
+            .ok_or_else(|| anyhow!("no table {}", table.0))
+    }
+
+    /// A table whose game hasn't started yet.
+    fn waiting(&mut self, table: TableID) -> Result<&mut Table, Error> {
+        let t = self
+            .tables
+            .get_mut(&table)
+            .ok_or_else(|| anyhow!("no table {}", table.0))?;
+        if t.game.is_some() {
+            return Err(anyhow!("table {} has already started", table.0));
+        }
+        Ok(t)
+    }
+
+    fn seat_of(t: &Table, table: TableID, member: &str) -> Result<usize, Error> {
+        t.seats
+            .iter()
+            .position(|s| s.member == member)
+            .ok_or_else(|| anyhow!("{} isn't at table {}", member, table.0))
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use crate::state::{validation::ValidationError, Phase};
+
+/// Counters describing the health of an engine hosting many games at once.
+/// A host shares one of these between all its games, updates it as records
+/// come in, and serves `render` to whatever scrapes it.
+///
+/// Records per second isn't tracked directly: `records_applied` is a
+/// counter, and the scraper works out the rate.
+#[derive(Debug, Default)]
+pub struct EngineMetrics {
+    games_active: AtomicU64,
+    records_applied: AtomicU64,
+    validation_failures: Mutex<BTreeMap<String, u64>>,
+    phase_durations: Mutex<BTreeMap<String, PhaseTiming>>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct PhaseTiming {
+    count: u64,
+    seconds: f64,
+}
+
+impl EngineMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A game was started or loaded.
+    pub fn game_started(&self) {
+        self.games_active.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A game finished or was unloaded.
+    pub fn game_stopped(&self) {
+        let _ = self
+            .games_active
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                Some(n.saturating_sub(1))
+            });
+    }
+
+    /// A record group was applied to some game.
+    pub fn record_applied(&self) {
+        self.records_applied.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A record was rejected by validation.
+    pub fn validation_failed(&self, err: &ValidationError) {
+        let mut failures = self.validation_failures.lock().unwrap();
+        *failures.entry(kind(err)).or_default() += 1;
+    }
+
+    /// A game spent `took` in `phase` before moving on.
+    pub fn phase_finished(&self, phase: Phase, took: Duration) {
+        let mut durations = self.phase_durations.lock().unwrap();
+        let timing = durations.entry(format!("{:?}", phase)).or_default();
+        timing.count += 1;
+        timing.seconds += took.as_secs_f64();
+    }
+
+    /// The metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# HELP cubatrice_games_active Games currently hosted.");
+        let _ = writeln!(out, "# TYPE cubatrice_games_active gauge");
+        let _ = writeln!(
+            out,
+            "cubatrice_games_active {}",
+            self.games_active.load(Ordering::Relaxed)
+        );
+
+        let _ = writeln!(
+            out,
+            "# HELP cubatrice_records_applied_total Record groups applied."
+        );
+        let _ = writeln!(out, "# TYPE cubatrice_records_applied_total counter");
+        let _ = writeln!(
+            out,
+            "cubatrice_records_applied_total {}",
+            self.records_applied.load(Ordering::Relaxed)
+        );
+
+        let _ = writeln!(
+            out,
+            "# HELP cubatrice_validation_failures_total Records rejected, by reason."
+        );
+        let _ = writeln!(out, "# TYPE cubatrice_validation_failures_total counter");
+        for (kind, n) in self.validation_failures.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "cubatrice_validation_failures_total{{kind=\"{}\"}} {}",
+                kind, n
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP cubatrice_phase_duration_seconds Time games spent in each phase."
+        );
+        let _ = writeln!(out, "# TYPE cubatrice_phase_duration_seconds summary");
+        for (phase, t) in self.phase_durations.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "cubatrice_phase_duration_seconds_sum{{phase=\"{}\"}} {}",
+                phase, t.seconds
+            );
+            let _ = writeln!(
+                out,
+                "cubatrice_phase_duration_seconds_count{{phase=\"{}\"}} {}",
+                phase, t.count
+            );
+        }
+        out
+    }
+}
+
+/// The name of an error's variant, without its fields.
+fn kind(err: &ValidationError) -> String {
+    format!("{:?}", err)
+        .chars()
+        .take_while(|c| c.is_alphanumeric())
+        .collect()
+}
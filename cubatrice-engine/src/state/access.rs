@@ -0,0 +1,446 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::faction::FactionType;
+
+use super::{player::PlayerID, record::RecordType, GameState};
+
+/// Parts of the game which not everyone can see.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Zone {
+    /// Each player's colony and research team bids.
+    SealedBids,
+    /// Converters a player has marked to run this economy phase.
+    MarkedConverters,
+    /// Projects, tiles and custom cards a player holds.
+    HeldCards,
+    /// The order of cards in a named deck. How many cards are left is
+    /// always public.
+    DeckContents,
+    /// Colonies and research teams on the bid tracks, which anyone may
+    /// examine.
+    BidTracks,
+}
+
+/// Who can see a zone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Visibility {
+    Everyone,
+    /// Only the player the zone belongs to.
+    Owner,
+    Nobody,
+}
+
+/// Who a view of the game is for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Viewer {
+    Player(PlayerID),
+    /// Someone watching who isn't playing. Spectators see what's visible to
+    /// everyone, and nothing that belongs to a particular player.
+    Spectator,
+}
+
+impl Viewer {
+    /// The player viewing, unless it's a spectator.
+    pub fn player(&self) -> Option<PlayerID> {
+        match self {
+            Viewer::Player(p) => Some(*p),
+            Viewer::Spectator => None,
+        }
+    }
+}
+
+impl GameState {
+    /// Who can see a zone right now. Sealed bids are only visible to the
+    /// bidder until every player has bid.
+    pub fn visibility(&self, zone: Zone) -> Visibility {
+        match zone {
+            Zone::SealedBids if self.bids_revealed() => Visibility::Everyone,
+            Zone::SealedBids | Zone::MarkedConverters | Zone::HeldCards => Visibility::Owner,
+            Zone::DeckContents => Visibility::Nobody,
+            Zone::BidTracks => Visibility::Everyone,
+        }
+    }
+
+    /// Whether `viewer` can see the part of a zone belonging to `owner`.
+    /// Zones which don't belong to anyone have no owner.
+    pub fn can_see(&self, viewer: Viewer, zone: Zone, owner: Option<PlayerID>) -> bool {
+        match self.visibility(zone) {
+            Visibility::Everyone => true,
+            Visibility::Owner => owner.is_some() && owner == viewer.player(),
+            Visibility::Nobody => false,
+        }
+    }
+
+    /// Whether `viewer` may be told about a record, once it's been applied.
+    /// Records which reveal a sealed bid, a player's marks or their notes
+    /// are only shown to that player (bids to everyone once they're
+    /// revealed), and trade offers and their rejection only to the two
+    /// players involved. Accepted trades are public, since what changes
+    /// hands is.
+    pub fn can_see_record(&self, viewer: Viewer, rec: &RecordType) -> bool {
+        match rec {
+            RecordType::Bid { player, .. } => self.can_see(viewer, Zone::SealedBids, Some(*player)),
+            RecordType::MarkConverter { player, .. }
+            | RecordType::MarkAll { player, .. }
+            | RecordType::UnmarkConverter { player, .. } => {
+                self.can_see(viewer, Zone::MarkedConverters, Some(*player))
+            }
+            RecordType::AddNote { player, .. } | RecordType::RemoveNote { player, .. } => {
+                viewer.player() == Some(*player)
+            }
+            RecordType::ProposeTrade { from, to, .. } => {
+                viewer.player() == Some(*from) || viewer.player() == Some(*to)
+            }
+            RecordType::RejectTrade { offer } => self
+                .log
+                .iter()
+                .filter(|g| g.id == *offer)
+                .flat_map(|g| g.rec.iter())
+                .find(|r| matches!(r, RecordType::ProposeTrade { .. }))
+                .is_none_or(|r| self.can_see_record(viewer, r)),
+            _ => true,
+        }
+    }
+
+    /// The players a record acts for, who have to agree to it. Records
+    /// which move two players' belongings, such as direct trades, act for
+    /// both of them. Records which set the game up or move it along, such
+    /// as phase changes, act for nobody, and are left to the host.
+    pub fn acting_players(&self, rec: &RecordType) -> BTreeSet<PlayerID> {
+        let converter = |c| self.converter_owners.get(c).copied();
+        let colony = |c| self.colony_owners.get(c).copied();
+        let players = match rec {
+            RecordType::TradeCubes { a, b, .. }
+            | RecordType::TradeColony { a, b, .. }
+            | RecordType::TradeConverter { a, b, .. }
+            | RecordType::TradeConverterPermanently { a, b, .. }
+            | RecordType::TradeRetroTokens { a, b, .. }
+            | RecordType::TradeItems { a, b, .. }
+            | RecordType::SwapSeats { a, b } => vec![*a, *b],
+            RecordType::Bid { player, .. }
+            | RecordType::TakeColony { player, .. }
+            | RecordType::TakeResearch { player, .. }
+            | RecordType::InventTech { player, .. }
+            | RecordType::MarkConverter { player, .. }
+            | RecordType::MarkAll { player, .. }
+            | RecordType::UnmarkConverter { player, .. }
+            | RecordType::DoubleColony { player, .. }
+            | RecordType::UndoubleColony { player, .. }
+            | RecordType::PlaceConstraint { player, .. }
+            | RecordType::RemoveConstraint { player, .. }
+            | RecordType::GiveEnvoy { player, .. }
+            | RecordType::ReturnEnvoy { player }
+            | RecordType::PlaceCrossToken { player, .. }
+            | RecordType::SpawnCubes { player, .. }
+            | RecordType::DrawFromDeck { player, .. }
+            | RecordType::VoteForProject { player, .. }
+            | RecordType::ActivateProject { player, .. }
+            | RecordType::CompleteProject { player, .. }
+            | RecordType::DrawRelic { player }
+            | RecordType::RollUnityDice { player }
+            | RecordType::ChangeUnityDie { player, .. }
+            | RecordType::GainDiceChangeCards { player, .. }
+            | RecordType::GainFleetSupport { player, .. }
+            | RecordType::PlaceServiceToken { player, .. }
+            | RecordType::PeekDeck { player, .. }
+            | RecordType::SettlePromise { player, .. }
+            | RecordType::AddNote { player, .. }
+            | RecordType::RemoveNote { player, .. }
+            | RecordType::SetProfile { player, .. } => vec![*player],
+            RecordType::RecurringTransfer { from, .. }
+            | RecordType::Promise { from, .. }
+            | RecordType::ProposeTrade { from, .. } => vec![*from],
+            // both players have to agree that a contract is binding
+            RecordType::Contract {
+                debtor,
+                creditor,
+                binding,
+                ..
+            } => {
+                if *binding {
+                    vec![*debtor, *creditor]
+                } else {
+                    vec![*debtor]
+                }
+            }
+            // the Yengii grant licenses, to whoever asks
+            RecordType::License { .. } => self.players_of(FactionType::YengiiCore),
+            // and the Faderan give acknowledgements
+            RecordType::GiveAcknowledgement { .. } => self.players_of(FactionType::FaderanCore),
+            RecordType::UpgradeConverter { conv: c, .. }
+            | RecordType::Retrocontinuity { converter: c, .. }
+            | RecordType::RunConverter { converter: c, .. }
+            | RecordType::RemoveServiceToken { converter: c } => converter(c).into_iter().collect(),
+            RecordType::ColonyRetrocontinuity { colony: c, .. }
+            | RecordType::RunColony { colony: c, .. } => colony(c).into_iter().collect(),
+            RecordType::DestroyCubes { cubes, .. } => cubes
+                .iter()
+                .filter_map(|c| self.cube_owners.get(c).copied())
+                .collect(),
+            RecordType::FulfillContract { contract, .. } => self
+                .contract(*contract)
+                .map(|c| c.debtor)
+                .into_iter()
+                .collect(),
+            // offers are answered by who they were made to
+            RecordType::AcceptTrade { offer } | RecordType::RejectTrade { offer } => {
+                self.offer(*offer).map(|o| o.to).into_iter().collect()
+            }
+            RecordType::ChangePhase { .. }
+            | RecordType::CreatePlayer { .. }
+            | RecordType::AssignSeats { .. }
+            | RecordType::CreateDeck { .. }
+            | RecordType::ShuffleDeck { .. }
+            | RecordType::CreateTechDeck
+            | RecordType::CreateColonyDeck => vec![],
+        };
+        players.into_iter().collect()
+    }
+
+    /// Whether only the engine may make a record, as part of applying
+    /// another. Cubes are only created and destroyed by the records which
+    /// produce and consume them, such as running a converter, and dice
+    /// change cards are only dealt by the rules, so these can't be
+    /// submitted or applied from outside the engine.
+    pub fn engine_only(&self, rec: &RecordType) -> bool {
+        matches!(
+            rec,
+            RecordType::SpawnCubes { .. }
+                | RecordType::DestroyCubes { .. }
+                | RecordType::GainDiceChangeCards { .. }
+        )
+    }
+
+    /// Whether `viewer` may submit a record on their own: it has to act
+    /// for them and nobody else, so records left to the host and records
+    /// only the engine makes can't be submitted. Spectators can't submit
+    /// anything.
+    pub fn may_submit(&self, viewer: Viewer, rec: &RecordType) -> bool {
+        let Some(player) = viewer.player() else {
+            return false;
+        };
+        !self.engine_only(rec) && self.acting_players(rec) == BTreeSet::from([player])
+    }
+
+    /// Whether the host, rather than a player, may apply a record: it acts
+    /// for nobody, like phase changes and setup, and isn't one only the
+    /// engine makes.
+    pub fn may_host(&self, rec: &RecordType) -> bool {
+        !self.engine_only(rec) && self.acting_players(rec).is_empty()
+    }
+
+    /// The players playing a faction. There's at most one, but trades can
+    /// hand a faction's belongings around, so this doesn't assume so.
+    fn players_of(&self, faction: FactionType) -> Vec<PlayerID> {
+        self.factions
+            .iter()
+            .filter(|(_, f)| **f == faction)
+            .map(|(p, _)| *p)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        demo,
+        state::{
+            config::GameConfig,
+            deck::{DeckID, EntityRef},
+            notes::NoteTarget,
+            record::RecordGroup,
+            view::PlayerView,
+            Phase,
+        },
+    };
+
+    const P1: PlayerID = PlayerID(1);
+    const P2: PlayerID = PlayerID(2);
+
+    /// The demo game, replayed up to (but not including) the first group
+    /// with a record matching `stop`. Returns the state and that group.
+    fn demo_until(stop: impl Fn(&RecordType) -> bool) -> (GameState, RecordGroup) {
+        let played = demo::play().unwrap().state;
+        let mut state = GameState::from_config(demo::data().unwrap(), demo::config()).unwrap();
+        for g in played.log()[state.log().len()..].iter() {
+            if g.rec.iter().any(&stop) {
+                return (state, g.clone());
+            }
+            state.apply(g.clone());
+        }
+        panic!("the demo has no matching record");
+    }
+
+    fn apply(state: &mut GameState, rec: RecordType) {
+        state.validate(&rec).unwrap();
+        let id = state.next_record_id();
+        state.apply(RecordGroup { id, rec: vec![rec] });
+    }
+
+    /// What `viewer` can see of `player`.
+    fn seen(state: &GameState, viewer: Viewer, player: PlayerID) -> PlayerView {
+        let view = state.view_as(viewer);
+        view.players
+            .into_iter()
+            .find(|p| p.player == player)
+            .unwrap()
+    }
+
+    #[test]
+    fn sealed_bids_are_hidden_until_everyone_bids() {
+        let (mut state, _) = demo_until(|r| matches!(r, RecordType::Bid { .. }));
+        let bid = |player| RecordType::Bid {
+            player,
+            for_colony: 1,
+            for_colony_kjas: None,
+            for_tech: 0,
+            for_tech_faderan: None,
+        };
+        apply(&mut state, bid(P1));
+        assert_eq!(state.visibility(Zone::SealedBids), Visibility::Owner);
+        assert!(seen(&state, Viewer::Player(P1), P1).bid.is_some());
+        assert!(seen(&state, Viewer::Player(P2), P1).bid.is_none());
+        assert!(seen(&state, Viewer::Spectator, P1).bid.is_none());
+        assert!(seen(&state, Viewer::Player(P2), P1).has_bid);
+        assert!(!state.can_see_record(Viewer::Player(P2), &bid(P1)));
+
+        apply(&mut state, bid(P2));
+        assert_eq!(state.visibility(Zone::SealedBids), Visibility::Everyone);
+        for viewer in [Viewer::Player(P1), Viewer::Player(P2), Viewer::Spectator] {
+            assert!(seen(&state, viewer, P1).bid.is_some());
+            assert!(seen(&state, viewer, P2).bid.is_some());
+            assert!(state.can_see_record(viewer, &bid(P1)));
+        }
+    }
+
+    #[test]
+    fn marked_converters_are_only_seen_by_their_owner() {
+        let (mut state, group) = demo_until(|r| matches!(r, RecordType::MarkAll { .. }));
+        state.apply(group.clone());
+        let mark = &group.rec[0];
+        let marked = seen(&state, Viewer::Player(P1), P1).marked.unwrap();
+        assert!(!marked.is_empty());
+        assert!(seen(&state, Viewer::Player(P2), P1).marked.is_none());
+        assert!(seen(&state, Viewer::Spectator, P1).marked.is_none());
+        assert!(state.can_see_record(Viewer::Player(P1), mark));
+        assert!(!state.can_see_record(Viewer::Player(P2), mark));
+        assert!(!state.can_see_record(Viewer::Spectator, mark));
+    }
+
+    #[test]
+    fn turns_are_only_seen_by_their_player() {
+        let (state, _) = demo_until(|r| matches!(r, RecordType::MarkAll { .. }));
+        let turn = seen(&state, Viewer::Player(P1), P1).turn.unwrap();
+        assert_eq!(turn.phase, Phase::Trade);
+        assert!(!turn.markable.is_empty());
+        assert!(seen(&state, Viewer::Player(P2), P1).turn.is_none());
+        assert!(seen(&state, Viewer::Spectator, P1).turn.is_none());
+    }
+
+    /// A state with a custom deck of three cards, the top one drawn by P1.
+    fn with_drawn_card() -> GameState {
+        let (mut state, _) = demo_until(|r| matches!(r, RecordType::Bid { .. }));
+        let deck = DeckID::FIRST_CUSTOM;
+        let cards = (0..3).map(EntityRef::Custom).collect();
+        apply(
+            &mut state,
+            RecordType::CreateDeck {
+                deck,
+                name: String::from("Omens"),
+                cards,
+            },
+        );
+        apply(&mut state, RecordType::DrawFromDeck { deck, player: P1 });
+        state
+    }
+
+    #[test]
+    fn held_cards_are_only_seen_by_their_holder() {
+        let state = with_drawn_card();
+        let own = seen(&state, Viewer::Player(P1), P1);
+        assert_eq!(own.held_cards.map(|c| c.len()), Some(1));
+        for viewer in [Viewer::Player(P2), Viewer::Spectator] {
+            let other = seen(&state, viewer, P1);
+            assert_eq!(other.held_count, 1);
+            assert!(other.held_cards.is_none());
+        }
+    }
+
+    #[test]
+    fn deck_contents_are_seen_by_nobody() {
+        let state = with_drawn_card();
+        assert_eq!(state.visibility(Zone::DeckContents), Visibility::Nobody);
+        for viewer in [Viewer::Player(P1), Viewer::Player(P2), Viewer::Spectator] {
+            let view = state.view_as(viewer);
+            let deck = view
+                .decks
+                .iter()
+                .find(|d| d.id == DeckID::FIRST_CUSTOM)
+                .unwrap();
+            assert_eq!(deck.size, 2);
+            assert!(deck.cards.is_none());
+        }
+    }
+
+    #[test]
+    fn spectators_see_nothing_belonging_to_a_player() {
+        let (mut state, _) = demo_until(|r| matches!(r, RecordType::Bid { .. }));
+        apply(
+            &mut state,
+            RecordType::AddNote {
+                player: P1,
+                target: NoteTarget::Game,
+                text: String::from("bid low"),
+            },
+        );
+        apply(
+            &mut state,
+            RecordType::Bid {
+                player: P1,
+                for_colony: 0,
+                for_colony_kjas: None,
+                for_tech: 1,
+                for_tech_faderan: None,
+            },
+        );
+        assert_eq!(state.view(P1).notes.len(), 1);
+        let view = state.spectator_view();
+        assert_eq!(view.viewer, Viewer::Spectator);
+        assert!(view.notes.is_empty());
+        assert!(view.offers.is_empty());
+        for p in view.players.iter() {
+            assert!(p.bid.is_none());
+            assert!(p.marked.is_none());
+            assert!(p.held_cards.is_none());
+        }
+        // the bid tracks are public, so spectators see them like players do
+        assert_eq!(view.colony_track, state.view(P1).colony_track);
+        assert!(view.colony_track.iter().any(|c| c.is_some()));
+    }
+
+    #[test]
+    fn players_only_submit_records_acting_for_them() {
+        let (state, _) = demo_until(|r| matches!(r, RecordType::Bid { .. }));
+        let next = RecordType::ChangePhase {
+            to: state.next_phase().unwrap(),
+        };
+        assert!(!state.may_submit(Viewer::Player(P1), &next));
+        assert!(state.may_host(&next));
+        let destroy = RecordType::DestroyCubes {
+            cubes: state.cube_owners.keys().take(1).copied().collect(),
+            to: Default::default(),
+        };
+        assert!(!state.may_submit(Viewer::Player(P1), &destroy));
+        assert!(!state.may_host(&destroy));
+        let config = GameConfig::new(1)
+            .with_player(P1, FactionType::FaderanCore)
+            .with_player(P2, FactionType::YengiiCore);
+        let state = GameState::from_config(demo::data().unwrap(), config).unwrap();
+        let ack = RecordType::GiveAcknowledgement { player: P2 };
+        assert!(state.may_submit(Viewer::Player(P1), &ack));
+        assert!(!state.may_submit(Viewer::Player(P2), &ack));
+    }
+}
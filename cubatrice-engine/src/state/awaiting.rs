@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{converter::ConverterID, technology::TechID};
+
+use super::{player::PlayerID, record::RecordID, GameState, Phase};
+
+/// Something a player still has to do (or may do) before the current phase
+/// should end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PendingAction {
+    /// The player hasn't submitted their bid.
+    Bid,
+    /// The player is next to take a colony from the bid track.
+    TakeColony,
+    /// The player is next to take a research team from the bid track.
+    TakeResearch,
+    /// The player can afford to invent one of their research teams. Inventing
+    /// is optional, so this doesn't stop the phase from ending.
+    Invent { tech: TechID },
+    /// A converter the player marked hasn't been run yet.
+    RunConverter { converter: ConverterID },
+    /// Someone has offered the player a trade.
+    RespondToOffer { offer: RecordID },
+}
+
+impl PendingAction {
+    /// Whether the phase can't end until this is done.
+    pub fn blocking(&self) -> bool {
+        !matches!(
+            self,
+            PendingAction::Invent { .. } | PendingAction::RespondToOffer { .. }
+        )
+    }
+}
+
+impl GameState {
+    /// Which players still need to act in the current phase, and what they
+    /// need to do, in player order. The phase is ready to advance once
+    /// nothing blocking is left.
+    pub fn awaiting(&self) -> Vec<(PlayerID, PendingAction)> {
+        let mut players: Vec<PlayerID> = self.factions.keys().copied().collect();
+        players.sort();
+        let mut out = Vec::new();
+        for p in players {
+            match self.phase {
+                Phase::Trade => {
+                    let cubes = self.get_player_cubes(p);
+                    for tech in self.research_teams(p) {
+                        let affordable = self.data.tech.get(&tech).is_some_and(|t| {
+                            t.cost
+                                .iter()
+                                .any(|c| cubes.count_type(c.typ) >= c.qty as isize)
+                        });
+                        if affordable {
+                            out.push((p, PendingAction::Invent { tech }));
+                        }
+                    }
+                }
+                Phase::Economy => {
+                    let mut marked: Vec<ConverterID> = self
+                        .marked_converters
+                        .iter()
+                        .filter(|c| self.converter_owners.get(c) == Some(&p))
+                        .filter(|c| !self.ran_converters.contains(c))
+                        .copied()
+                        .collect();
+                    marked.sort();
+                    for converter in marked {
+                        out.push((p, PendingAction::RunConverter { converter }));
+                    }
+                }
+                Phase::ColonyBid => {
+                    if !self.player_colony_bid.contains_key(&p) {
+                        out.push((p, PendingAction::Bid));
+                    } else if self.bids_revealed() && self.colony_bid_order.first() == Some(&p) {
+                        out.push((p, PendingAction::TakeColony));
+                    }
+                }
+                Phase::TechBid if self.tech_bid_order.first() == Some(&p) => {
+                    out.push((p, PendingAction::TakeResearch));
+                }
+                _ => {}
+            }
+            for o in self.pending_offers.iter().filter(|o| o.to == p) {
+                out.push((p, PendingAction::RespondToOffer { offer: o.id }));
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,281 @@
+use crate::entity::{cube::CubeType, faction::FactionType};
+
+use super::{
+    events::GameEvent,
+    fleet::GRAND_FLEET_SUPPORT,
+    player::PlayerID,
+    record::{RecordID, RecordType},
+    validation::{ensure, ValidationError},
+    GameState, Phase,
+};
+
+/// Rules particular to a faction, hooked in at fixed points in the game
+/// rather than special cased wherever records are handled. Every hook does
+/// nothing by default, so a faction only implements the ones it needs.
+///
+/// Behaviors hold no state of their own. Whatever a faction needs to
+/// remember is kept on the `GameState`, so it's saved and undone along with
+/// everything else.
+pub trait FactionBehavior: Sync {
+    /// Called once, when a player of this faction joins the game, before
+    /// they're given their starting resources.
+    fn on_setup(&self, _state: &mut GameState, _player: PlayerID) {}
+
+    /// Called for the player at the start of every phase, after the phase
+    /// has changed.
+    fn on_phase_start(&self, _state: &mut GameState, _player: PlayerID, _phase: Phase) {}
+
+    /// Extra checks on any record, made for the player once the checks
+    /// every record gets have passed.
+    fn validate_record(
+        &self,
+        _state: &GameState,
+        _player: PlayerID,
+        _rec: &RecordType,
+    ) -> Result<(), ValidationError> {
+        Ok(())
+    }
+
+    /// Called for the player after any record has been applied.
+    fn on_record_applied(
+        &self,
+        _state: &mut GameState,
+        _player: PlayerID,
+        _id: RecordID,
+        _rec: &RecordType,
+    ) {
+    }
+}
+
+/// The behavior for a faction. Factions without any rules hooked in share
+/// one which does nothing.
+pub fn behavior(faction: FactionType) -> &'static dyn FactionBehavior {
+    match faction {
+        FactionType::CaylionAlt => &CaylionAlt,
+        FactionType::FaderanCore => &FaderanCore,
+        FactionType::FaderanAlt => &FaderanAlt,
+        FactionType::ImdrilAlt => &ImdrilAlt,
+        FactionType::UnityAlt => &UnityAlt,
+        FactionType::YengiiCore => &YengiiCore,
+        _ => &NoBehavior,
+    }
+}
+
+struct NoBehavior;
+
+impl FactionBehavior for NoBehavior {}
+
+/// The Caylion Collaborative's projects are dealt out when they join.
+struct CaylionAlt;
+
+impl FactionBehavior for CaylionAlt {
+    fn on_setup(&self, state: &mut GameState, _player: PlayerID) {
+        state.setup_projects();
+    }
+}
+
+/// Each trade in which another player gives the Faderan something earns
+/// them an acknowledgement, which the Faderan may give whenever they like.
+/// Whenever a player holding an acknowledgement invents a technology, one
+/// of their acknowledgements goes back to the Faderan, who score a point.
+struct FaderanCore;
+
+impl FactionBehavior for FaderanCore {
+    fn on_record_applied(
+        &self,
+        state: &mut GameState,
+        player: PlayerID,
+        id: RecordID,
+        rec: &RecordType,
+    ) {
+        if let Some(helper) = helped_by(rec, player) {
+            *state.owed_acknowledgements.entry(helper).or_default() += 1;
+            return;
+        }
+        let RecordType::InventTech {
+            player: inventor, ..
+        } = rec
+        else {
+            return;
+        };
+        let Some(held) = state.acknowledgements.get_mut(inventor).filter(|n| **n > 0) else {
+            return;
+        };
+        *held -= 1;
+        state.spawn_cubes(player, CubeType::VictoryPoint, 1, None);
+        state.emit(
+            id,
+            GameEvent::AcknowledgementReturned {
+                player: *inventor,
+                faderan: player,
+            },
+        );
+    }
+}
+
+/// The other side of a trade, if they gave `player` anything in it.
+fn helped_by(rec: &RecordType, player: PlayerID) -> Option<PlayerID> {
+    let (a, b, a_gave, b_gave) = match rec {
+        RecordType::TradeCubes {
+            a,
+            b,
+            a_cubes,
+            b_cubes,
+        } => (a, b, !a_cubes.is_empty(), !b_cubes.is_empty()),
+        RecordType::TradeColony {
+            a,
+            b,
+            a_colony,
+            b_colony,
+        } => (a, b, !a_colony.is_empty(), !b_colony.is_empty()),
+        RecordType::TradeConverter {
+            a,
+            b,
+            a_converter,
+            b_converter,
+            ..
+        }
+        | RecordType::TradeConverterPermanently {
+            a,
+            b,
+            a_converter,
+            b_converter,
+        } => (a, b, !a_converter.is_empty(), !b_converter.is_empty()),
+        RecordType::TradeRetroTokens {
+            a,
+            b,
+            a_tokens,
+            b_tokens,
+        } => (a, b, !a_tokens.is_empty(), !b_tokens.is_empty()),
+        RecordType::TradeItems {
+            a,
+            b,
+            a_items,
+            b_items,
+        } => (a, b, !a_items.is_empty(), !b_items.is_empty()),
+        _ => return None,
+    };
+    if *a == player && b_gave {
+        Some(*b)
+    } else if *b == player && a_gave {
+        Some(*a)
+    } else {
+        None
+    }
+}
+
+/// The Society of Falling Light sells their inventions with regret, rather
+/// than sharing them.
+struct FaderanAlt;
+
+impl FactionBehavior for FaderanAlt {
+    fn on_record_applied(
+        &self,
+        state: &mut GameState,
+        player: PlayerID,
+        _id: RecordID,
+        rec: &RecordType,
+    ) {
+        if let RecordType::InventTech {
+            player: p, tech, ..
+        } = rec
+        {
+            if *p == player && state.to_share.remove(tech) {
+                state.regret_techs.insert(*tech);
+            }
+        }
+    }
+}
+
+/// The Grand Fleet starts with all the fleet support it will ever have.
+struct ImdrilAlt;
+
+impl FactionBehavior for ImdrilAlt {
+    fn on_setup(&self, state: &mut GameState, player: PlayerID) {
+        state.fleet_support.insert(player, GRAND_FLEET_SUPPORT);
+    }
+}
+
+/// Deep Unity roll their dice once every confluence, and are dealt dice
+/// change cards as it starts.
+struct UnityAlt;
+
+impl FactionBehavior for UnityAlt {
+    fn on_phase_start(&self, state: &mut GameState, _player: PlayerID, phase: Phase) {
+        if phase == Phase::Trade {
+            state.start_unity_confluence();
+        }
+    }
+}
+
+/// The Yengii keep their inventions to license, rather than sharing them,
+/// and only license inventions they hold to other players.
+struct YengiiCore;
+
+impl FactionBehavior for YengiiCore {
+    fn validate_record(
+        &self,
+        state: &GameState,
+        player: PlayerID,
+        rec: &RecordType,
+    ) -> Result<(), ValidationError> {
+        let RecordType::License { player: to, tech } = rec else {
+            return Ok(());
+        };
+        ensure(player != *to, ValidationError::SelfTrade { player: *to })?;
+        ensure(
+            state.yengii_techs.contains(tech),
+            ValidationError::NotLicensable { tech: *tech },
+        )
+    }
+
+    fn on_record_applied(
+        &self,
+        state: &mut GameState,
+        player: PlayerID,
+        _id: RecordID,
+        rec: &RecordType,
+    ) {
+        if let RecordType::InventTech {
+            player: p, tech, ..
+        } = rec
+        {
+            if *p == player && state.to_share.remove(tech) {
+                state.yengii_techs.insert(*tech);
+            }
+        }
+    }
+}
+
+impl GameState {
+    /// Players and their factions' behaviors, in seating order.
+    fn behaviors(&self) -> Vec<(PlayerID, &'static dyn FactionBehavior)> {
+        self.seats
+            .iter()
+            .filter_map(|p| self.factions.get(p).map(|f| (*p, behavior(*f))))
+            .collect()
+    }
+
+    /// Runs every player's `FactionBehavior::validate_record`.
+    pub(crate) fn validate_behaviors(&self, rec: &RecordType) -> Result<(), ValidationError> {
+        for (player, b) in self.behaviors() {
+            b.validate_record(self, player, rec)?;
+        }
+        Ok(())
+    }
+
+    /// Runs every player's `FactionBehavior::on_phase_start`.
+    pub(crate) fn phase_started_behaviors(&mut self) {
+        let phase = self.phase;
+        for (player, b) in self.behaviors() {
+            b.on_phase_start(self, player, phase);
+        }
+    }
+
+    /// Runs every player's `FactionBehavior::on_record_applied`.
+    pub(crate) fn record_applied_behaviors(&mut self, id: RecordID, rec: &RecordType) {
+        for (player, b) in self.behaviors() {
+            b.on_record_applied(self, player, id, rec);
+        }
+    }
+}
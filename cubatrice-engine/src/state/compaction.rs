@@ -0,0 +1,232 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use anyhow::{anyhow, Context, Error};
+use serde::{Deserialize, Serialize};
+
+use super::{record::RecordGroup, GameState};
+
+/// When and where a game moves old record groups out of memory.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Compaction {
+    /// Once more than this many record groups are in memory, the oldest are
+    /// moved to disk.
+    pub limit: usize,
+    /// How many of the most recent groups stay in memory after compacting.
+    /// Undoing further back than this reads history back from disk.
+    pub keep: usize,
+    /// The file older groups are written to, one JSON record group per
+    /// line.
+    pub path: PathBuf,
+}
+
+/// What a compacted game needs to get back to the start of its in-memory
+/// log.
+#[derive(Clone, Debug)]
+pub(crate) struct History {
+    settings: Compaction,
+    /// The game just after the last group moved to disk, from `to_json`,
+    /// with an empty log.
+    checkpoint: String,
+    /// How many groups have been moved to disk.
+    spilled: usize,
+    /// Compaction isn't tried again until the in-memory log is this long,
+    /// after it has failed.
+    retry_at: usize,
+    /// Why compaction last failed, unless it has worked since.
+    error: Option<String>,
+}
+
+impl History {
+    /// Every group moved to disk, oldest first.
+    fn read(&self) -> Result<Vec<RecordGroup>, Error> {
+        let path = &self.settings.path;
+        let s = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        s.lines()
+            .map(|l| serde_json::from_str(l).context("parsing spilled record group"))
+            .collect()
+    }
+
+    /// Replaces the groups on disk.
+    fn write(&self, groups: &[RecordGroup]) -> Result<(), Error> {
+        let path = &self.settings.path;
+        fs::write(path, lines(groups)?).with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// Adds groups to the end of those on disk. If that fails partway, the
+    /// file is cut back to how it was, so no group is on disk twice after
+    /// it's retried.
+    fn append(&self, groups: &[RecordGroup]) -> Result<(), Error> {
+        let path = &self.settings.path;
+        let lines = lines(groups)?;
+        let mut f = OpenOptions::new()
+            .append(true)
+            .open(path)
+            .with_context(|| format!("opening {}", path.display()))?;
+        let len = f.metadata()?.len();
+        f.write_all(lines.as_bytes())
+            .or_else(|e| f.set_len(len).and(Err(e)))
+            .with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+fn lines(groups: &[RecordGroup]) -> Result<String, Error> {
+    let mut out = String::new();
+    for g in groups {
+        out.push_str(&serde_json::to_string(g)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+impl GameState {
+    /// Keeps at most `compaction.limit` record groups in memory from now on,
+    /// moving older ones to `compaction.path`, which is overwritten. The log
+    /// then only holds recent groups, but undo works as before, and
+    /// `full_log` and saves still include everything.
+    ///
+    /// Compaction isn't saved, and isn't carried over to forks. `to_json`
+    /// only includes the groups in memory, so use `save` to keep the whole
+    /// game.
+    pub fn enable_compaction(&mut self, compaction: Compaction) -> Result<(), Error> {
+        if self.history.is_some() {
+            return Err(anyhow!("compaction is already enabled"));
+        }
+        if compaction.keep > compaction.limit {
+            return Err(anyhow!(
+                "can't keep {} groups when the limit is {}",
+                compaction.keep,
+                compaction.limit
+            ));
+        }
+        let history = History {
+            checkpoint: self.fresh().to_json()?,
+            settings: compaction,
+            spilled: 0,
+            retry_at: 0,
+            error: None,
+        };
+        history.write(&[])?;
+        self.history = Some(history);
+        self.compact()
+    }
+
+    /// Every record group applied to this game, in order, including any
+    /// moved out of memory.
+    pub fn full_log(&self) -> Result<Vec<RecordGroup>, Error> {
+        let mut log = match &self.history {
+            Some(h) => h.read()?,
+            None => Vec::new(),
+        };
+        log.extend(self.log.iter().cloned());
+        Ok(log)
+    }
+
+    /// Why moving history to disk last failed, unless it has worked since.
+    /// Until it works, groups past the limit stay in memory, so nothing is
+    /// lost, but memory isn't bounded either.
+    pub fn compaction_error(&self) -> Option<&str> {
+        self.history.as_ref().and_then(|h| h.error.as_deref())
+    }
+
+    /// Compacts after a group is applied. A failure is kept for
+    /// `compaction_error`, and compaction waits for another `limit - keep`
+    /// groups before trying again, rather than retrying after every group.
+    pub(crate) fn compact_if_due(&mut self) {
+        if self
+            .history
+            .as_ref()
+            .is_none_or(|h| self.log.len() < h.retry_at)
+        {
+            return;
+        }
+        let result = self.compact();
+        if let Some(h) = &mut self.history {
+            let backoff = (h.settings.limit - h.settings.keep).max(1);
+            match result {
+                Ok(()) => {
+                    h.retry_at = 0;
+                    h.error = None;
+                }
+                Err(e) => {
+                    h.retry_at = self.log.len() + backoff;
+                    h.error = Some(format!("{:#}", e));
+                }
+            }
+        }
+    }
+
+    /// Moves the oldest record groups to disk if there are too many in
+    /// memory. Nothing changes if writing them fails.
+    pub(crate) fn compact(&mut self) -> Result<(), Error> {
+        let Some(h) = &self.history else {
+            return Ok(());
+        };
+        if self.log.len() <= h.settings.limit {
+            return Ok(());
+        }
+        let n = self.log.len() - h.settings.keep;
+        let mut checkpoint = Self::from_json(self.data.clone(), &h.checkpoint)?;
+        for g in self.log[..n].iter() {
+            checkpoint.apply(g.clone());
+        }
+        checkpoint.log.clear();
+        checkpoint.events.clear();
+        let json = checkpoint.to_json()?;
+        h.append(&self.log[..n])?;
+
+        self.log.drain(..n);
+        if let Some(h) = &mut self.history {
+            h.checkpoint = json;
+            h.spilled += n;
+        }
+        Ok(())
+    }
+
+    /// The game as it was before the first record group in memory was
+    /// applied.
+    pub(crate) fn base(&self) -> Result<Self, Error> {
+        match &self.history {
+            Some(h) => Self::from_json(self.data.clone(), &h.checkpoint),
+            None => Ok(self.fresh()),
+        }
+    }
+
+    /// The game before any records were applied.
+    fn fresh(&self) -> Self {
+        let mut state = Self::new(self.data.clone());
+        state.config = self.config.clone();
+        state
+    }
+
+    /// Brings the most recent groups back from disk when the in-memory log
+    /// has run out, so they can be undone.
+    pub(crate) fn unspill(&mut self) -> Result<(), Error> {
+        let Some(h) = &self.history else {
+            return Ok(());
+        };
+        if !self.log.is_empty() || h.spilled == 0 {
+            return Ok(());
+        }
+        let mut groups = h.read()?;
+        let split = groups.len().saturating_sub(h.settings.keep.max(1));
+        let mut checkpoint = self.fresh();
+        for g in groups[..split].iter() {
+            checkpoint.apply(g.clone());
+        }
+        checkpoint.log.clear();
+        checkpoint.events.clear();
+        let json = checkpoint.to_json()?;
+        h.write(&groups[..split])?;
+
+        self.log = groups.split_off(split);
+        if let Some(h) = &mut self.history {
+            h.checkpoint = json;
+            h.spilled = split;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::faction::{base_faderan::RelicWorld, FactionType},
+    game_data::GameData,
+};
+
+use super::{
+    deck::{DeckID, EntityRef},
+    endgame::EndCondition,
+    player::PlayerID,
+    record::{RecordGroup, RecordID, RecordType},
+    rules::RulesVersion,
+    scoring::EndgameValues,
+    takes::Untaken,
+};
+
+/// Everything needed to set up a game. Two games set up from equal configs
+/// (and equal game data) will be identical, down to the IDs of every cube.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GameConfig {
+    /// Seed for all randomness in the game (deck shuffles, dice, etc.)
+    pub seed: u64,
+    /// Which players are in the game, and which faction each is playing,
+    /// in seating order (clockwise).
+    pub players: Vec<(PlayerID, FactionType)>,
+    /// Which rules interpretation the game is played under. Configs saved
+    /// without a version are V1.
+    #[serde(default)]
+    pub rules: RulesVersion,
+    /// When the game ends. Configs saved without one play every confluence.
+    #[serde(default)]
+    pub end: EndCondition,
+    /// What leftover ships and tokens score. Configs saved without values
+    /// score them as nothing.
+    #[serde(default)]
+    pub scoring: EndgameValues,
+    /// What happens to colonies and research teams nobody takes. Configs
+    /// saved without a choice leave them on the track.
+    #[serde(default)]
+    pub untaken: Untaken,
+    /// House rule letting cubes be traded during the economy phase, to
+    /// deliver deals struck during the trade phase. Off unless set.
+    #[serde(default)]
+    pub economy_deliveries: bool,
+    /// Victory points players start with, on top of their faction's
+    /// starting resources, such as a campaign's handicaps. Players not
+    /// listed start with none.
+    #[serde(default)]
+    pub start_points: Vec<(PlayerID, usize)>,
+}
+
+impl GameConfig {
+    /// Creates a config for a given seed, with no players, using the
+    /// current rules.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            players: Vec::new(),
+            rules: RulesVersion::CURRENT,
+            end: EndCondition::default(),
+            scoring: EndgameValues::default(),
+            untaken: Untaken::default(),
+            economy_deliveries: false,
+            start_points: Vec::new(),
+        }
+    }
+
+    /// Creates a config for a named faction preset, with one player per
+    /// faction, numbered from `PlayerID(1)` and seated in the preset's
+    /// order.
+    pub fn preset(data: &GameData, name: &str, seed: u64) -> Result<Self, Error> {
+        let preset = data.preset(name).ok_or_else(|| {
+            let known: Vec<String> = data.all_presets().into_iter().map(|p| p.id).collect();
+            anyhow!("no preset named {} (try {})", name, known.join(", "))
+        })?;
+        Ok(preset
+            .factions
+            .into_iter()
+            .enumerate()
+            .fold(Self::new(seed), |cfg, (i, f)| {
+                cfg.with_player(PlayerID(i + 1), f)
+            }))
+    }
+
+    /// Adds a player to the config.
+    pub fn with_player(mut self, player: PlayerID, faction: FactionType) -> Self {
+        self.players.push((player, faction));
+        self
+    }
+
+    /// Sets when the game ends.
+    pub fn with_end(mut self, end: EndCondition) -> Self {
+        self.end = end;
+        self
+    }
+
+    /// Sets what leftover ships and tokens score.
+    pub fn with_scoring(mut self, scoring: EndgameValues) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    /// Sets what happens to colonies and research teams nobody takes.
+    pub fn with_untaken(mut self, untaken: Untaken) -> Self {
+        self.untaken = untaken;
+        self
+    }
+
+    /// Sets whether cubes may be traded during the economy phase.
+    pub fn with_economy_deliveries(mut self, allowed: bool) -> Self {
+        self.economy_deliveries = allowed;
+        self
+    }
+
+    /// Gives a player victory points to start with.
+    pub fn with_start_points(mut self, player: PlayerID, points: usize) -> Self {
+        self.start_points.push((player, points));
+        self
+    }
+
+    /// The victory points a player starts with, on top of their faction's
+    /// starting resources.
+    pub fn start_points(&self, player: PlayerID) -> usize {
+        self.start_points
+            .iter()
+            .filter(|(p, _)| *p == player)
+            .map(|(_, n)| n)
+            .sum()
+    }
+
+    /// The records which set up a game with this config.
+    ///
+    /// IDs are never taken from hash map iteration order, so that setup is
+    /// reproducible:
+    /// - Players are created in ascending `PlayerID` order, regardless of the
+    ///   order they were added to the config, one record group each.
+    /// - Record groups are numbered from `RecordID(0)` in that same order.
+    /// - Each player's starting resources are created in the order they are
+    ///   listed in the game data, followed by any starting victory points,
+    ///   and cubes are numbered sequentially from `CubeID(0)` across all
+    ///   players.
+    /// - Players are then seated in the order they were added to the config,
+    ///   in one more group.
+    /// - The research team deck and colony deck are built in the next two
+    ///   groups, shuffled from the config seed, and the bid tracks are
+    ///   filled from them.
+    /// - If Base Faderan is playing, two more groups create the relic deck
+    ///   and shuffle it, seeded from the config seed.
+    pub fn setup_records(&self) -> Vec<RecordGroup> {
+        let mut players = self.players.clone();
+        players.sort();
+        let mut groups: Vec<RecordGroup> = players
+            .iter()
+            .enumerate()
+            .map(|(i, (player, faction))| RecordGroup {
+                id: RecordID(i),
+                rec: vec![RecordType::CreatePlayer {
+                    player: *player,
+                    faction: *faction,
+                }],
+            })
+            .collect();
+        groups.push(RecordGroup {
+            id: RecordID(groups.len()),
+            rec: vec![RecordType::AssignSeats {
+                seats: self.players.iter().map(|(p, _)| *p).collect(),
+            }],
+        });
+        for rec in [RecordType::CreateTechDeck, RecordType::CreateColonyDeck] {
+            groups.push(RecordGroup {
+                id: RecordID(groups.len()),
+                rec: vec![rec],
+            });
+        }
+        if players.iter().any(|(_, f)| *f == FactionType::FaderanCore) {
+            let relics = RelicWorld::ALL.into_iter().map(EntityRef::Relic).collect();
+            for rec in [
+                RecordType::CreateDeck {
+                    deck: DeckID::RELICS,
+                    name: String::from("Relic Worlds"),
+                    cards: relics,
+                },
+                RecordType::ShuffleDeck {
+                    deck: DeckID::RELICS,
+                },
+            ] {
+                groups.push(RecordGroup {
+                    id: RecordID(groups.len()),
+                    rec: vec![rec],
+                });
+            }
+        }
+        groups
+    }
+}
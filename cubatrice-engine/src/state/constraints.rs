@@ -0,0 +1,106 @@
+use crate::entity::{cube::CubeType, faction::FactionType};
+
+use super::{
+    player::PlayerID,
+    validation::{ensure, ValidationError},
+    GameState,
+};
+
+/// Colours of cube the Jii can constrain a player from using.
+pub const CONSTRAINT_TYPES: [CubeType; 6] = [
+    CubeType::Culture,
+    CubeType::Food,
+    CubeType::Industry,
+    CubeType::Power,
+    CubeType::Biotech,
+    CubeType::Information,
+];
+
+impl GameState {
+    /// The colours of cube a player is constrained from using, in type
+    /// order.
+    pub fn constraints_of(&self, player: PlayerID) -> Vec<CubeType> {
+        let mut out: Vec<CubeType> = self
+            .constraints
+            .get(&player)
+            .map(|s| s.iter().copied().collect())
+            .unwrap_or_default();
+        out.sort();
+        out
+    }
+
+    /// Whether a player is constrained from using a type of cube.
+    pub fn is_constrained(&self, player: PlayerID, typ: CubeType) -> bool {
+        self.constraints
+            .get(&player)
+            .is_some_and(|s| s.contains(&typ))
+    }
+
+    /// Checks that the Jii can place a constraint on another player. Like
+    /// anything else sold in a deal, constraints can only change hands when
+    /// colonies can be traded.
+    pub(crate) fn can_place_constraint(
+        &self,
+        jii: PlayerID,
+        on: PlayerID,
+        typ: CubeType,
+    ) -> Result<(), ValidationError> {
+        self.can_change_constraint(jii, on)?;
+        ensure(
+            CONSTRAINT_TYPES.contains(&typ),
+            ValidationError::NotConstrainable { typ },
+        )?;
+        ensure(
+            !self.is_constrained(on, typ),
+            ValidationError::AlreadyConstrained { player: on, typ },
+        )
+    }
+
+    /// Checks that the Jii can lift a constraint they placed on a player.
+    pub(crate) fn can_remove_constraint(
+        &self,
+        jii: PlayerID,
+        from: PlayerID,
+        typ: CubeType,
+    ) -> Result<(), ValidationError> {
+        self.can_change_constraint(jii, from)?;
+        ensure(
+            self.is_constrained(from, typ),
+            ValidationError::NotConstrained { player: from, typ },
+        )
+    }
+
+    fn can_change_constraint(&self, jii: PlayerID, on: PlayerID) -> Result<(), ValidationError> {
+        let actual = self.factions.get(&jii).copied();
+        ensure(
+            actual == Some(FactionType::YengiiAlt),
+            ValidationError::FactionMismatch {
+                player: jii,
+                expected: FactionType::YengiiAlt,
+                actual,
+            },
+        )?;
+        ensure(
+            self.factions.contains_key(&on),
+            ValidationError::NoSuchPlayer { player: on },
+        )?;
+        ensure(jii != on, ValidationError::SelfTrade { player: jii })?;
+        ensure(
+            self.phase_rules().trade_colonies,
+            ValidationError::TradingClosed { phase: self.phase },
+        )
+    }
+
+    pub(crate) fn place_constraint(&mut self, on: PlayerID, typ: CubeType) {
+        self.constraints.entry(on).or_default().insert(typ);
+    }
+
+    pub(crate) fn remove_constraint(&mut self, from: PlayerID, typ: CubeType) {
+        if let Some(s) = self.constraints.get_mut(&from) {
+            s.remove(&typ);
+            if s.is_empty() {
+                self.constraints.remove(&from);
+            }
+        }
+    }
+}
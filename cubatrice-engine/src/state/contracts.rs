@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::cube::CubeType;
+
+use super::{player::PlayerID, record::RecordID, Confluence, GameState};
+
+/// An obligation one player has agreed to fulfil for another in a later
+/// confluence, such as the "2 blue next confluence" half of "2 blue next
+/// confluence for 1 black now". The other half is an ordinary trade.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Contract {
+    /// The record which registered the contract, used to refer to it.
+    pub id: RecordID,
+    /// The player who owes the cubes.
+    pub debtor: PlayerID,
+    /// The player the cubes are owed to.
+    pub creditor: PlayerID,
+    pub typ: CubeType,
+    pub qty: usize,
+    /// The confluence the cubes are due by.
+    pub due: Confluence,
+    /// Whether both players agreed the contract is binding. Binding contracts
+    /// are enforced in the resolution phase; others are only reported.
+    pub binding: bool,
+    /// How many cubes have been paid so far.
+    pub paid: usize,
+}
+
+impl Contract {
+    /// How many cubes are still owed.
+    pub fn remaining(&self) -> usize {
+        self.qty.saturating_sub(self.paid)
+    }
+
+    pub fn fulfilled(&self) -> bool {
+        self.remaining() == 0
+    }
+}
+
+impl GameState {
+    /// Every contract registered this game, in the order they were agreed.
+    pub fn contracts(&self) -> &[Contract] {
+        &self.contracts
+    }
+
+    /// A contract, by the ID of the record which registered it.
+    pub fn contract(&self, id: RecordID) -> Option<&Contract> {
+        self.contracts.iter().find(|c| c.id == id)
+    }
+
+    /// Contracts which were due by the current confluence and haven't been
+    /// fully paid.
+    pub fn unfulfilled_contracts(&self) -> Vec<&Contract> {
+        self.contracts
+            .iter()
+            .filter(|c| !c.fulfilled() && c.due <= self.confluence)
+            .collect()
+    }
+
+    /// Collects as much as the debtor can pay of every unfulfilled binding
+    /// contract, in the order they were agreed.
+    pub(crate) fn enforce_contracts(&mut self) {
+        for i in 0..self.contracts.len() {
+            let c = self.contracts[i];
+            if !c.binding || c.fulfilled() || c.due > self.confluence {
+                continue;
+            }
+            let cubes = self.owned_cubes(c.debtor, c.typ);
+            let paid = cubes.len().min(c.remaining());
+            for cube in cubes.into_iter().take(paid) {
+                self.move_cube(cube, c.creditor);
+            }
+            self.contracts[i].paid += paid;
+        }
+    }
+}
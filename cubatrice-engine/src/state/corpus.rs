@@ -0,0 +1,202 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Error};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{player::PlayerID, savegame::SaveFile, GameData, GameState};
+
+/// File extension of golden games in a corpus directory.
+pub const GOLDEN_EXT: &str = "golden.json";
+
+/// How many differences to report for one game before giving up.
+const MAX_DIFFS: usize = 20;
+
+/// A recorded game along with the outcome it produced when it was recorded.
+/// Replaying the game must produce exactly the same final state and scores,
+/// so a rules change that alters the outcome of an old game is caught rather
+/// than silently changing it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GoldenGame {
+    pub save: SaveFile,
+    /// Victory points each player finished with.
+    pub scores: BTreeMap<PlayerID, isize>,
+    /// The final game state, as produced by `GameState::to_json`.
+    pub snapshot: Value,
+}
+
+impl GoldenGame {
+    /// Records a game's current outcome as golden.
+    pub fn record(state: &GameState) -> Result<Self, Error> {
+        Ok(Self {
+            save: state.save_file()?,
+            scores: scores(state),
+            snapshot: serde_json::from_str(&state.to_json()?)?,
+        })
+    }
+
+    /// Replays the game and lists every way the outcome differs from the
+    /// recorded one. An empty list means the game still plays out the same.
+    pub fn check(&self, data: GameData) -> Result<Vec<String>, Error> {
+        let state = self.save.clone().restore(data)?;
+        let mut diffs = Vec::new();
+        let now = scores(&state);
+        if now != self.scores {
+            diffs.push(format!(
+                "scores changed from {:?} to {:?}",
+                self.scores, now
+            ));
+        }
+        let snapshot: Value = serde_json::from_str(&state.to_json()?)?;
+        diff_values("", &self.snapshot, &snapshot, &mut diffs);
+        Ok(diffs)
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let s = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&s).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        let s = serde_json::to_string_pretty(self).context("serializing golden game")?;
+        fs::write(path, s).with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+/// Every golden game in a corpus directory, sorted by path.
+pub fn corpus_files<P: AsRef<Path>>(dir: P) -> Result<Vec<PathBuf>, Error> {
+    let dir = dir.as_ref();
+    let mut files = Vec::new();
+    for e in fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+        let path = e?.path();
+        if path.to_string_lossy().ends_with(GOLDEN_EXT) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The result of replaying one golden game.
+#[derive(Debug)]
+pub struct CorpusCheck {
+    pub path: PathBuf,
+    /// How the outcome differs from the recorded one, or why the game
+    /// couldn't be replayed.
+    pub result: Result<Vec<String>, Error>,
+}
+
+impl CorpusCheck {
+    /// Whether the game replayed with the recorded outcome.
+    pub fn passed(&self) -> bool {
+        matches!(&self.result, Ok(d) if d.is_empty())
+    }
+}
+
+/// Replays every game in a corpus directory. A game which fails to load or
+/// replay is reported as failed rather than stopping the check.
+pub fn check_corpus<P: AsRef<Path>>(dir: P, data: &GameData) -> Result<Vec<CorpusCheck>, Error> {
+    Ok(corpus_files(dir)?
+        .into_iter()
+        .map(|path| {
+            let result = GoldenGame::read(&path).and_then(|g| g.check(data.clone()));
+            CorpusCheck { path, result }
+        })
+        .collect())
+}
+
+/// Re-records the outcome of every game in a corpus directory, after a
+/// change which is meant to alter outcomes.
+pub fn bless_corpus<P: AsRef<Path>>(dir: P, data: &GameData) -> Result<usize, Error> {
+    let files = corpus_files(dir)?;
+    for f in files.iter() {
+        let game = GoldenGame::read(f)?;
+        let state = game
+            .save
+            .restore(data.clone())
+            .with_context(|| format!("replaying {}", f.display()))?;
+        GoldenGame::record(&state)?.write(f)?;
+    }
+    Ok(files.len())
+}
+
+/// Adds a saved game to a corpus directory, recording its current outcome.
+/// Returns the path of the new golden file.
+pub fn add_to_corpus<P: AsRef<Path>, Q: AsRef<Path>>(
+    save: P,
+    dir: Q,
+    data: GameData,
+) -> Result<PathBuf, Error> {
+    let save = save.as_ref();
+    let state = GameState::load(data, save)?;
+    let stem = save
+        .file_stem()
+        .ok_or_else(|| anyhow!("{} has no file name", save.display()))?;
+    let path = dir
+        .as_ref()
+        .join(format!("{}.{}", stem.to_string_lossy(), GOLDEN_EXT));
+    GoldenGame::record(&state)?.write(&path)?;
+    Ok(path)
+}
+
+/// Each player's victory points.
+fn scores(state: &GameState) -> BTreeMap<PlayerID, isize> {
+    state
+        .factions
+        .keys()
+        .map(|p| (*p, state.score(*p)))
+        .collect()
+}
+
+/// Lists the paths at which two JSON values differ, up to `MAX_DIFFS`.
+fn diff_values(path: &str, old: &Value, new: &Value, out: &mut Vec<String>) {
+    if out.len() >= MAX_DIFFS || old == new {
+        return;
+    }
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (k, v) in a.iter() {
+                let p = format!("{}/{}", path, k);
+                match b.get(k) {
+                    Some(w) => diff_values(&p, v, w, out),
+                    None => out.push(format!("{} was removed", p)),
+                }
+            }
+            for k in b.keys().filter(|k| !a.contains_key(*k)) {
+                out.push(format!("{}/{} was added", path, k));
+            }
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+            for (i, (v, w)) in a.iter().zip(b.iter()).enumerate() {
+                diff_values(&format!("{}/{}", path, i), v, w, out);
+            }
+        }
+        _ => out.push(format!("{} changed from {} to {}", path, old, new)),
+    }
+    out.truncate(MAX_DIFFS);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo;
+
+    /// The corpus checked in alongside the crate, played with the demo's
+    /// data.
+    const CORPUS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/corpus");
+
+    #[test]
+    fn corpus_replays_unchanged() {
+        let checks = check_corpus(CORPUS, &demo::data().unwrap()).unwrap();
+        assert!(!checks.is_empty(), "no golden games in {}", CORPUS);
+        for c in checks {
+            assert!(c.passed(), "{}: {:?}", c.path.display(), c.result);
+        }
+    }
+}
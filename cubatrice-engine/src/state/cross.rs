@@ -0,0 +1,104 @@
+use crate::entity::{colony::ColonyID, cube::CubeType, faction::FactionType, Token};
+
+use super::{
+    events::GameEvent,
+    player::PlayerID,
+    record::RecordID,
+    validation::{ensure, ValidationError},
+    GameState, Phase,
+};
+
+impl GameState {
+    /// The colonies carrying the Charity Syndicate's cross colonization
+    /// tokens, in ID order.
+    pub fn cross_colonized(&self) -> Vec<ColonyID> {
+        let mut out: Vec<ColonyID> = self.cross_tokens.iter().copied().collect();
+        out.sort();
+        out
+    }
+
+    /// Whether a colony carries a cross colonization token.
+    pub fn is_cross_colonized(&self, colony: ColonyID) -> bool {
+        self.cross_tokens.contains(&colony)
+    }
+
+    /// Whether another player has bought a colony carrying one of the
+    /// Syndicate's tokens, which lets them upgrade some of their converters.
+    pub fn cross_colonized_planet_bought(&self) -> bool {
+        let syndicate = self.charity_syndicate();
+        self.cross_tokens.iter().any(|c| {
+            self.colony_owners
+                .get(c)
+                .is_some_and(|p| Some(*p) != syndicate)
+        })
+    }
+
+    /// Checks that the Charity Syndicate can put a cross colonization token
+    /// on a colony up for bids. Tokens go out during the trade phase, before
+    /// anyone bids for the colony.
+    pub(crate) fn can_place_cross_token(
+        &self,
+        player: PlayerID,
+        colony: ColonyID,
+    ) -> Result<(), ValidationError> {
+        let actual = self.factions.get(&player).copied();
+        ensure(
+            actual == Some(FactionType::ZethAlt),
+            ValidationError::FactionMismatch {
+                player,
+                expected: FactionType::ZethAlt,
+                actual,
+            },
+        )?;
+        ensure(
+            self.phase == Phase::Trade,
+            ValidationError::WrongPhase { phase: self.phase },
+        )?;
+        ensure(
+            self.colony_bid_track.contains(&Some(colony)),
+            ValidationError::ColonyNotOnTrack { colony },
+        )?;
+        ensure(
+            !self.is_cross_colonized(colony),
+            ValidationError::AlreadyCrossColonized { colony },
+        )?;
+        ensure(
+            self.supply_of(Token::CrossColonization).can_supply(1),
+            ValidationError::TokenExhausted {
+                token: Token::CrossColonization,
+            },
+        )
+    }
+
+    pub(crate) fn place_cross_token(&mut self, colony: ColonyID) {
+        self.cross_tokens.insert(colony);
+    }
+
+    /// Returns the token on a colony that's been consumed to the supply,
+    /// scoring the Syndicate a point.
+    pub(crate) fn return_cross_token(&mut self, id: RecordID, colony: ColonyID) {
+        if !self.cross_tokens.remove(&colony) {
+            return;
+        }
+        if let Some(zeth) = self.charity_syndicate() {
+            self.spawn_cubes(zeth, CubeType::VictoryPoint, 1, None);
+            self.emit(id, GameEvent::CrossTokenReturned { zeth, colony });
+        }
+    }
+
+    /// Tokens on colonies which left the bid track without being bought go
+    /// back to the supply, without scoring.
+    pub(crate) fn drop_stray_cross_tokens(&mut self) {
+        let track = &self.colony_bid_track;
+        let owners = &self.colony_owners;
+        self.cross_tokens
+            .retain(|c| owners.contains_key(c) || track.contains(&Some(*c)));
+    }
+
+    fn charity_syndicate(&self) -> Option<PlayerID> {
+        self.factions
+            .iter()
+            .find(|(_, f)| **f == FactionType::ZethAlt)
+            .map(|(p, _)| *p)
+    }
+}
@@ -0,0 +1,171 @@
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::{
+        colony::ColonyID,
+        converter::ConverterID,
+        faction::{alt_caylion::ProjectID, base_faderan::RelicWorld},
+        technology::TechID,
+    },
+    Deck,
+};
+
+use super::{player::PlayerID, record::RecordID, Confluence, GameState};
+
+/// The number of research team tiers.
+pub const TECH_TIERS: usize = 4;
+
+/// How many research teams of each tier go into the deck. The research
+/// track gets a slot per player, refilled every confluence, and the deck
+/// only needs enough teams to keep it filled over a full game, with each
+/// tier lasting about a quarter of it. Fewer players means fewer teams, so
+/// the later tiers still come out on time.
+pub fn teams_per_tier(players: usize) -> usize {
+    (players * Confluence::LAST.0).div_ceil(TECH_TIERS)
+}
+
+/// Transparent type for referring to named decks. Decks used by the base
+/// game have fixed IDs; homebrew decks should use IDs from
+/// `DeckID::FIRST_CUSTOM` onwards.
+#[derive(
+    Clone, Copy, Default, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub struct DeckID(pub usize);
+
+impl DeckID {
+    /// The Faderan relic deck.
+    pub const RELICS: DeckID = DeckID(0);
+    /// The Alt Caylion project deck.
+    pub const PROJECTS: DeckID = DeckID(1);
+    /// Alt Kjas tiles.
+    pub const KJAS_TILES: DeckID = DeckID(2);
+    /// The first ID which isn't reserved for the base game.
+    pub const FIRST_CUSTOM: DeckID = DeckID(100);
+}
+
+/// A card in a named deck, referring to an entity somewhere else in the
+/// game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EntityRef {
+    /// A research team
+    Tech(TechID),
+    Colony(ColonyID),
+    Converter(ConverterID),
+    Project(ProjectID),
+    /// A Faderan relic world, which becomes a converter when drawn.
+    Relic(RelicWorld),
+    /// Anything else. What the number means is up to whoever created the
+    /// deck.
+    Custom(usize),
+}
+
+/// A named deck, along with its display name.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NamedDeck {
+    pub name: String,
+    pub cards: Deck<EntityRef>,
+}
+
+impl GameState {
+    /// A named deck, if it has been created.
+    pub fn deck(&self, id: DeckID) -> Option<&NamedDeck> {
+        self.decks.get(&id)
+    }
+
+    /// The top `count` cards of a named deck, top first. Fewer are returned
+    /// if the deck is smaller.
+    pub fn peek_deck(&self, id: DeckID, count: usize) -> Vec<EntityRef> {
+        self.decks
+            .get(&id)
+            .map(|d| d.cards.peek_n(count).copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Cards a player has drawn which don't belong anywhere else (projects,
+    /// tiles and custom cards).
+    pub fn held_cards(&self, player: PlayerID) -> &[EntityRef] {
+        self.held_cards
+            .get(&player)
+            .map(|c| c.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// How many research teams are left in the deck.
+    pub fn tech_deck_size(&self) -> usize {
+        self.tech_deck.len()
+    }
+
+    /// Research teams discarded from the track, oldest first.
+    pub fn tech_discards(&self) -> &[TechID] {
+        self.tech_deck.discards()
+    }
+
+    /// Builds the research team deck: each tier is shuffled and cut down to
+    /// `teams_per_tier` teams, then the tiers are stacked with tier 1 on
+    /// top.
+    pub(crate) fn build_tech_deck<R: RngCore>(&self, rng: &mut R) -> Deck<TechID> {
+        let keep = teams_per_tier(self.factions.len());
+        let mut techs: Vec<TechID> = Vec::new();
+        for tier in 1..=TECH_TIERS {
+            let mut ids: Vec<TechID> = self
+                .data
+                .tech
+                .values()
+                .filter(|t| t.tier == tier)
+                .map(|t| t.id)
+                .collect();
+            ids.sort();
+            let mut shuffled = Deck::new_shuffled_with_rng(ids, rng);
+            techs.extend(shuffled.draw_n(keep));
+        }
+        Deck::new(techs)
+    }
+
+    /// How many colonies are left in the deck.
+    pub fn colony_deck_size(&self) -> usize {
+        self.colony_deck.len()
+    }
+
+    /// Colonies discarded from the track or consumed by upgrades, oldest
+    /// first.
+    pub fn colony_discards(&self) -> &[ColonyID] {
+        self.colony_deck.discards()
+    }
+
+    /// Builds the colony deck from every colony in the game data that
+    /// nobody started with, shuffled. Upgraded sides of colonies aren't
+    /// cards of their own, so they're left out.
+    pub(crate) fn build_colony_deck<R: RngCore>(&self, rng: &mut R) -> Deck<ColonyID> {
+        let mut ids: Vec<ColonyID> = self
+            .data
+            .colony
+            .keys()
+            .filter(|c| c.0 < 100 && !self.colony_owners.contains_key(c))
+            .copied()
+            .collect();
+        ids.sort();
+        Deck::new_shuffled_with_rng(ids, rng)
+    }
+
+    /// Gives a drawn card to a player. Research teams, colonies and
+    /// converters become owned by the player, relic worlds are put into play
+    /// as converters, and everything else is held.
+    pub(crate) fn give_card(&mut self, rec: RecordID, player: PlayerID, card: EntityRef) {
+        match card {
+            EntityRef::Tech(t) => {
+                self.tech_team_owners.insert(t, player);
+            }
+            EntityRef::Colony(c) => {
+                self.colony_owners.insert(c, player);
+            }
+            EntityRef::Converter(c) => self.transfer_converter(rec, c, player),
+            EntityRef::Relic(r) => {
+                self.add_converter(rec, player, Box::new(r));
+            }
+            EntityRef::Project(_) | EntityRef::Custom(_) => {
+                self.held_cards.entry(player).or_default().push(card)
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Display,
+    hash::Hash,
+};
+
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{
+    colony::ColonyID,
+    converter::ConverterID,
+    cube::{CubeID, CubeRecord, CubeType},
+    technology::TechID,
+};
+
+use super::{
+    events::GameEvent,
+    player::PlayerID,
+    record::{RecordGroup, RecordType},
+    GameState,
+};
+
+/// Something which can be owned by a player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Owned {
+    Converter(ConverterID),
+    Colony(ColonyID),
+    /// A research team which hasn't been invented yet.
+    Tech(TechID),
+}
+
+/// Something changing hands. None means the thing was created or left the
+/// game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OwnershipChange {
+    pub what: Owned,
+    pub from: Option<PlayerID>,
+    pub to: Option<PlayerID>,
+}
+
+/// A single cube changing hands. None means the cube came from, or went
+/// back to, the supply.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CubeMove {
+    pub cube: CubeID,
+    pub typ: CubeType,
+    pub from: Option<PlayerID>,
+    pub to: Option<PlayerID>,
+}
+
+/// Everything a record would change, worked out without changing anything.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EffectSummary {
+    /// Every cube which would change hands, in cube ID order.
+    pub cubes: Vec<CubeMove>,
+    /// How each player's cubes would change, for players whose cubes change
+    /// at all. Victory points are included.
+    pub net: BTreeMap<PlayerID, CubeRecord>,
+    /// Converters, colonies and research teams which would change hands.
+    pub ownership: Vec<OwnershipChange>,
+    /// Events the record would emit.
+    pub events: Vec<GameEvent>,
+}
+
+impl EffectSummary {
+    /// Victory points each player would gain or lose.
+    pub fn vp(&self) -> BTreeMap<PlayerID, isize> {
+        self.net
+            .iter()
+            .filter(|(_, r)| r.points != 0)
+            .map(|(p, r)| (*p, r.points))
+            .collect()
+    }
+
+    /// Whether the record would change nothing anyone can see.
+    pub fn is_empty(&self) -> bool {
+        self.cubes.is_empty() && self.ownership.is_empty() && self.events.is_empty()
+    }
+}
+
+impl GameState {
+    /// Works out what a record would change if it were applied now, without
+    /// applying it. Fails if the record isn't valid.
+    pub fn describe(&self, record: &RecordType) -> Result<EffectSummary, Error> {
+        self.validate(record).map_err(|e| anyhow!("{}", e))?;
+        let mut after = self.fork()?;
+        after.apply(RecordGroup {
+            id: self.next_record_id(),
+            rec: vec![record.clone()],
+        });
+
+        let mut cubes = Vec::new();
+        let ids: BTreeSet<CubeID> = self
+            .cubes
+            .keys()
+            .chain(after.cubes.keys())
+            .copied()
+            .collect();
+        for cube in ids {
+            let from = self.cube_owners.get(&cube).copied();
+            let to = after.cube_owners.get(&cube).copied();
+            let typ = after.cubes.get(&cube).or_else(|| self.cubes.get(&cube));
+            if let (Some(c), true) = (typ, from != to) {
+                cubes.push(CubeMove {
+                    cube,
+                    typ: c.typ,
+                    from,
+                    to,
+                });
+            }
+        }
+
+        let mut net = BTreeMap::new();
+        let players: BTreeSet<PlayerID> = self.factions.keys().copied().collect();
+        for p in players {
+            let delta = after.get_player_cubes(p) - self.get_player_cubes(p);
+            if delta != CubeRecord::default() {
+                net.insert(p, delta);
+            }
+        }
+
+        let mut ownership = Vec::new();
+        owner_changes(
+            &self.converter_owners,
+            &after.converter_owners,
+            Owned::Converter,
+            &mut ownership,
+        );
+        owner_changes(
+            &self.colony_owners,
+            &after.colony_owners,
+            Owned::Colony,
+            &mut ownership,
+        );
+        owner_changes(
+            &self.tech_team_owners,
+            &after.tech_team_owners,
+            Owned::Tech,
+            &mut ownership,
+        );
+        ownership.sort_by_key(|c| c.what);
+
+        Ok(EffectSummary {
+            cubes,
+            net,
+            ownership,
+            events: after.take_events().into_iter().map(|(_, e)| e).collect(),
+        })
+    }
+}
+
+/// Lists everything whose owner differs between two ownership maps.
+fn owner_changes<K: Copy + Eq + Hash>(
+    before: &HashMap<K, PlayerID>,
+    after: &HashMap<K, PlayerID>,
+    what: fn(K) -> Owned,
+    out: &mut Vec<OwnershipChange>,
+) {
+    for k in before
+        .keys()
+        .chain(after.keys().filter(|k| !before.contains_key(k)))
+    {
+        let from = before.get(k).copied();
+        let to = after.get(k).copied();
+        if from != to {
+            out.push(OwnershipChange {
+                what: what(*k),
+                from,
+                to,
+            });
+        }
+    }
+}
+
+impl Display for EffectSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let who = |p: Option<PlayerID>| match p {
+            Some(p) => format!("player {}", p.0),
+            None => String::from("the supply"),
+        };
+        for (p, r) in self.net.iter() {
+            let changes: Vec<String> = r
+                .entries()
+                .into_iter()
+                .filter(|(_, n)| *n != 0)
+                .map(|(t, n)| format!("{:+} {}", n, t))
+                .collect();
+            writeln!(f, "player {}: {}", p.0, changes.join(", "))?;
+        }
+        for c in self.ownership.iter() {
+            writeln!(f, "{:?}: {} -> {}", c.what, who(c.from), who(c.to))?;
+        }
+        for e in self.events.iter() {
+            writeln!(f, "{}", e)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::entity::{colony::ColonyID, faction::FactionType};
+
+use super::{
+    player::PlayerID,
+    validation::{ensure, ValidationError},
+    GameState,
+};
+
+/// How many of their colonies the Caylion can have doubled at once.
+pub const DOUBLED_COLONY_LIMIT: usize = 2;
+
+impl GameState {
+    /// The colonies a player owns which are doubled, in ID order.
+    pub fn doubled_colonies_of(&self, player: PlayerID) -> Vec<ColonyID> {
+        let mut out: Vec<ColonyID> = self
+            .doubled_colonies
+            .iter()
+            .filter(|c| self.colony_owners.get(c) == Some(&player))
+            .copied()
+            .collect();
+        out.sort();
+        out
+    }
+
+    /// How many times over a colony produces its outputs. Doubling only
+    /// counts while the colony belongs to the Caylion.
+    pub fn colony_multiplier(&self, colony: ColonyID) -> usize {
+        let caylion = self
+            .colony_owners
+            .get(&colony)
+            .and_then(|p| self.factions.get(p))
+            == Some(&FactionType::CaylionCore);
+        if caylion && self.doubled_colonies.contains(&colony) {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Checks that the Caylion can double one of their colonies.
+    pub(crate) fn can_double_colony(
+        &self,
+        player: PlayerID,
+        colony: ColonyID,
+    ) -> Result<(), ValidationError> {
+        ensure(
+            self.colony_owners.get(&colony) == Some(&player),
+            ValidationError::ColonyNotOwned { player, colony },
+        )?;
+        ensure(
+            !self.doubled_colonies.contains(&colony),
+            ValidationError::AlreadyDoubled { colony },
+        )?;
+        ensure(
+            self.doubled_colonies_of(player).len() < DOUBLED_COLONY_LIMIT,
+            ValidationError::DoublingLimit {
+                player,
+                limit: DOUBLED_COLONY_LIMIT,
+            },
+        )
+    }
+
+    pub(crate) fn double_colony(&mut self, colony: ColonyID) {
+        self.doubled_colonies.insert(colony);
+    }
+
+    pub(crate) fn undouble_colony(&mut self, colony: ColonyID) {
+        self.doubled_colonies.remove(&colony);
+    }
+}
@@ -0,0 +1,425 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    entity::{
+        colony::ColonyID,
+        converter::{Arrow, ConverterID},
+        cube::{CubeID, CubeType},
+        Item, Token,
+    },
+    stats::{provenance::FlowNode, ProductionSource, RunAttribution},
+};
+
+use super::{
+    player::PlayerID,
+    pool::spawn_record,
+    record::{RecordGroup, RecordID, RecordType},
+    GameState,
+};
+
+impl GameState {
+    /// The cubes needed to run a converter once, one entry per cube. Halved
+    /// converters use their reduced cost, where a wild cube stands for any
+    /// cube of that size.
+    pub fn converter_inputs(&self, conv: ConverterID) -> Vec<CubeType> {
+        let mut out = Vec::new();
+        if let Some((_, halved)) = self.halved_converters.get(&conv) {
+            let costs = [
+                (CubeType::Ship, halved.ships),
+                (CubeType::Food, halved.food),
+                (CubeType::Culture, halved.culture),
+                (CubeType::Industry, halved.industry),
+                (CubeType::AnySmall, halved.small_wild),
+                (CubeType::Power, halved.power),
+                (CubeType::Biotech, halved.biotech),
+                (CubeType::Information, halved.information),
+                (CubeType::AnyLarge, halved.large_wild),
+                (CubeType::Ultratech, halved.ultratech),
+                (CubeType::VictoryPoint, halved.points),
+            ];
+            for (typ, qty) in costs {
+                out.extend(std::iter::repeat_n(typ, qty.max(0) as usize));
+            }
+        } else if let Some(c) = self.converters.get(&conv) {
+            for i in c.input() {
+                if let Item::Cubes(typ, qty) | Item::DonationCubes(typ, qty) = i {
+                    out.extend(std::iter::repeat_n(*typ, *qty));
+                }
+            }
+        }
+        out
+    }
+
+    /// The cubes needed to run a colony, one entry per cube. A doubled
+    /// colony takes its inputs twice over, as it produces twice over.
+    pub fn colony_inputs(&self, colony: ColonyID) -> Vec<CubeType> {
+        let mut out = Vec::new();
+        let times = self.colony_multiplier(colony);
+        if let Some(c) = self.data.colony.get(&colony) {
+            for i in c.conv.input.iter() {
+                if let Item::Cubes(typ, qty) | Item::DonationCubes(typ, qty) = i {
+                    out.extend(std::iter::repeat_n(*typ, *qty * times));
+                }
+            }
+        }
+        out
+    }
+
+    /// Whether a set of cubes pays exactly for one run of a converter, with
+    /// each cube matching a different input (see `CubeType::matches`).
+    pub(crate) fn inputs_match(&self, conv: ConverterID, inputs: &BTreeSet<CubeID>) -> bool {
+        self.cubes_match(&self.converter_inputs(conv), inputs)
+    }
+
+    /// Whether a set of cubes pays exactly for a list of inputs.
+    pub(crate) fn cubes_match(&self, needed: &[CubeType], inputs: &BTreeSet<CubeID>) -> bool {
+        let cubes: Vec<CubeID> = inputs.iter().copied().collect();
+        needed.len() == cubes.len() && self.assign_inputs(needed, &cubes).len() == needed.len()
+    }
+
+    /// Picks cubes from the owner's supply to run a converter, preferring
+    /// exact cubes over wilds and lower IDs over higher. None if the owner
+    /// can't pay.
+    pub fn pick_inputs(&self, conv: ConverterID) -> Option<BTreeSet<CubeID>> {
+        self.pick_inputs_excluding(conv, &BTreeSet::new())
+    }
+
+    /// Like `pick_inputs`, without using any of the `reserved` cubes.
+    pub fn pick_inputs_excluding(
+        &self,
+        conv: ConverterID,
+        reserved: &BTreeSet<CubeID>,
+    ) -> Option<BTreeSet<CubeID>> {
+        let owner = *self.converter_owners.get(&conv)?;
+        self.pick_cubes(owner, &self.converter_inputs(conv), reserved)
+    }
+
+    /// Picks cubes from the owner's supply to run a colony, like
+    /// `pick_inputs`.
+    pub fn pick_colony_inputs(&self, colony: ColonyID) -> Option<BTreeSet<CubeID>> {
+        self.pick_colony_inputs_excluding(colony, &BTreeSet::new())
+    }
+
+    /// Like `pick_colony_inputs`, without using any of the `reserved` cubes.
+    pub fn pick_colony_inputs_excluding(
+        &self,
+        colony: ColonyID,
+        reserved: &BTreeSet<CubeID>,
+    ) -> Option<BTreeSet<CubeID>> {
+        let owner = *self.colony_owners.get(&colony)?;
+        self.pick_cubes(owner, &self.colony_inputs(colony), reserved)
+    }
+
+    /// Picks cubes from a player's supply to pay for a list of inputs,
+    /// without using any of the `reserved` cubes.
+    fn pick_cubes(
+        &self,
+        owner: PlayerID,
+        needed: &[CubeType],
+        reserved: &BTreeSet<CubeID>,
+    ) -> Option<BTreeSet<CubeID>> {
+        let constrained = self.constraints.get(&owner);
+        let mut cubes: Vec<CubeID> = self
+            .owned
+            .cubes(owner)
+            .filter(|c| !reserved.contains(c))
+            .filter(|c| {
+                self.cubes
+                    .get(c)
+                    .is_some_and(|c| constrained.is_none_or(|s| !s.contains(&c.typ)))
+            })
+            .collect();
+        cubes.sort_by_key(|c| {
+            let typ = self.cubes[c].typ;
+            (
+                matches!(typ, CubeType::UnitySmall | CubeType::UnityLarge),
+                *c,
+            )
+        });
+        let assigned = self.assign_inputs(needed, &cubes);
+        (assigned.len() == needed.len()).then(|| assigned.into_iter().collect())
+    }
+
+    /// Matches cubes to converter inputs, returning the cubes used. Inputs
+    /// are matched one at a time, moving earlier cubes to other inputs they
+    /// also match when that frees up a cube for the current one, so a full
+    /// match is found whenever one exists.
+    fn assign_inputs(&self, needed: &[CubeType], cubes: &[CubeID]) -> Vec<CubeID> {
+        let types: Vec<CubeType> = cubes
+            .iter()
+            .map(|c| self.cubes.get(c).map(|c| c.typ))
+            .map(|t| t.unwrap_or(CubeType::VictoryPoint))
+            .collect();
+        // which input each cube is paying for, if any
+        let mut paying: Vec<Option<usize>> = vec![None; cubes.len()];
+        for input in 0..needed.len() {
+            let mut seen = vec![false; cubes.len()];
+            if !augment(input, needed, &types, &mut paying, &mut seen) {
+                break;
+            }
+        }
+        cubes
+            .iter()
+            .zip(paying)
+            .filter(|(_, p)| p.is_some())
+            .map(|(c, _)| *c)
+            .collect()
+    }
+
+    /// The marks a `MarkAll` record expands to: every white converter the
+    /// player owns and hasn't excluded, in ID order, as long as their cubes
+    /// and fleet support stretch to pay for it on top of everything marked
+    /// before it.
+    /// Converters which are already marked keep first claim on cubes.
+    pub fn expand_mark_all(&self, player: PlayerID, except: &[ConverterID]) -> Vec<RecordType> {
+        let mut owned: Vec<ConverterID> = self
+            .owned
+            .converters(player)
+            .filter(|c| {
+                self.converters
+                    .get(c)
+                    .is_some_and(|c| c.color() == Arrow::White)
+            })
+            .filter(|c| !self.retro_used_conveter.contains(c))
+            .collect();
+        owned.sort();
+        let (marked, unmarked): (Vec<ConverterID>, Vec<ConverterID>) = owned
+            .into_iter()
+            .partition(|c| self.marked_converters.contains(c));
+        let mut reserved = BTreeSet::new();
+        for c in marked {
+            if let Some(inputs) = self.pick_inputs_excluding(c, &reserved) {
+                reserved.extend(inputs);
+            }
+        }
+        let mut fleet = self.fleet_free(player);
+        let mut out = Vec::new();
+        for converter in unmarked.into_iter().filter(|c| !except.contains(c)) {
+            let need = self
+                .converter(converter)
+                .map(|c| c.fleet_support())
+                .unwrap_or_default();
+            if need > fleet {
+                continue;
+            }
+            if let Some(inputs) = self.pick_inputs_excluding(converter, &reserved) {
+                reserved.extend(inputs);
+                fleet -= need;
+                out.push(RecordType::MarkConverter { player, converter });
+            }
+        }
+        out
+    }
+
+    /// Runs every marked converter the owner can pay for, in ID order. Each
+    /// run is applied as its own record group, and the applied groups are
+    /// returned. Converters which couldn't be paid for stay marked.
+    pub fn run_marked_converters(&mut self) -> Vec<RecordGroup> {
+        let mut marked: Vec<ConverterID> = self.marked_converters.iter().copied().collect();
+        marked.sort();
+        let mut applied = Vec::new();
+        for converter in marked {
+            let Some(inputs) = self.pick_inputs(converter) else {
+                continue;
+            };
+            let rec = RecordType::RunConverter {
+                converter,
+                inputs,
+                target: None,
+            };
+            if self.validate(&rec).is_err() {
+                continue;
+            }
+            let group = RecordGroup {
+                id: self.next_record_id(),
+                rec: vec![rec],
+            };
+            applied.push(group.clone());
+            self.apply(group);
+        }
+        applied
+    }
+
+    /// Runs every white colony its owner can pay for, in ID order. Like
+    /// `run_marked_converters`, each run is applied as its own record group,
+    /// and the applied groups are returned.
+    pub fn run_colonies(&mut self) -> Vec<RecordGroup> {
+        let mut colonies: Vec<ColonyID> = self.colony_owners.keys().copied().collect();
+        colonies.sort();
+        let mut applied = Vec::new();
+        for colony in colonies {
+            let Some(inputs) = self.pick_colony_inputs(colony) else {
+                continue;
+            };
+            let rec = RecordType::RunColony { colony, inputs };
+            if self.validate(&rec).is_err() {
+                continue;
+            }
+            let group = RecordGroup {
+                id: self.next_record_id(),
+                rec: vec![rec],
+            };
+            applied.push(group.clone());
+            self.apply(group);
+        }
+        applied
+    }
+
+    /// Who a run of a converter counts for: the player it belongs to, and
+    /// the player holding it, who gets the outputs.
+    pub fn run_attribution(&self, conv: ConverterID) -> Option<RunAttribution> {
+        let operator = *self.converter_owners.get(&conv)?;
+        Some(RunAttribution {
+            owner: self.original_owners.get(&conv).copied().unwrap_or(operator),
+            operator,
+        })
+    }
+
+    /// Converters a player owns which are lent to someone else, in ID order.
+    pub fn loaned_out(&self, player: PlayerID) -> Vec<ConverterID> {
+        let mut out: Vec<ConverterID> = self
+            .original_owners
+            .iter()
+            .filter(|(_, o)| **o == player)
+            .map(|(c, _)| *c)
+            .collect();
+        out.sort();
+        out
+    }
+
+    /// Consumes a converter's inputs and produces its outputs for the owner.
+    #[cfg_attr(not(feature = "scripting"), allow(unused_variables))]
+    pub(crate) fn run_converter(
+        &mut self,
+        id: RecordID,
+        conv: ConverterID,
+        inputs: &BTreeSet<CubeID>,
+    ) {
+        let Some(by) = self.run_attribution(conv) else {
+            return;
+        };
+        let owner = by.operator;
+        let Some(outputs) = self.converters.get(&conv).map(|c| c.output().to_vec()) else {
+            return;
+        };
+        let from = FlowNode::Converter(conv);
+        let consumed = RecordType::DestroyCubes {
+            cubes: inputs.clone(),
+            to: from,
+        };
+        self.apply_record(id, &consumed);
+        for o in outputs.iter() {
+            match o {
+                Item::Cubes(typ, qty) => {
+                    self.apply_record(id, &spawn_record(owner, *typ, *qty, None, from))
+                }
+                Item::DonationCubes(typ, qty) => {
+                    self.apply_record(id, &spawn_record(owner, *typ, *qty, Some(owner), from))
+                }
+                Item::SpecificColony(c) => {
+                    self.colony_owners.insert(*c, owner);
+                }
+                Item::Token(Token::Envoy) => *self.envoys.entry(owner).or_default() += 1,
+                Item::Token(Token::Acknowledgement) => {
+                    *self.acknowledgements.entry(owner).or_default() += 1
+                }
+                Item::Token(Token::Regret) => *self.regret.entry(owner).or_default() += 1,
+                Item::Token(Token::Service) => self.gain_service_token(owner),
+                Item::Token(Token::Custom(t)) => {
+                    *self
+                        .custom_tokens
+                        .entry(owner)
+                        .or_default()
+                        .entry(*t)
+                        .or_default() += 1
+                }
+                #[cfg(feature = "scripting")]
+                Item::Script(s) => self.run_script(id, owner, from, s),
+                // TODO: factories and cross colonization tokens need a
+                // colony to be placed on, and colony outputs need a choice.
+                _ => {}
+            }
+        }
+        if self.converters.get(&conv).map(|c| c.color()) == Some(Arrow::White) {
+            self.marked_converters.remove(&conv);
+        }
+        self.ran_converters.insert(conv);
+        if self.halved_converters.contains_key(&conv) {
+            self.locked_service.insert(conv);
+        }
+        self.stats
+            .production
+            .record_run(ProductionSource::Converter(conv), by, &outputs);
+    }
+
+    /// Consumes a colony's inputs and produces its outputs for its owner.
+    /// Colonies the Caylion have doubled produce twice over.
+    pub(crate) fn run_colony(&mut self, id: RecordID, colony: ColonyID, inputs: &BTreeSet<CubeID>) {
+        let (Some(owner), Some(outputs)) = (
+            self.colony_owners.get(&colony).copied(),
+            self.data.colony.get(&colony).map(|c| c.conv.output.clone()),
+        ) else {
+            return;
+        };
+        let times = self.colony_multiplier(colony);
+        let outputs: Vec<Item> = outputs
+            .into_iter()
+            .map(|o| match o {
+                Item::Cubes(typ, qty) => Item::Cubes(typ, qty * times),
+                Item::DonationCubes(typ, qty) => Item::DonationCubes(typ, qty * times),
+                o => o,
+            })
+            .collect();
+        let from = FlowNode::Colony(colony);
+        let consumed = RecordType::DestroyCubes {
+            cubes: inputs.clone(),
+            to: from,
+        };
+        self.apply_record(id, &consumed);
+        for o in outputs.iter() {
+            match o {
+                Item::Cubes(typ, qty) => {
+                    self.apply_record(id, &spawn_record(owner, *typ, *qty, None, from))
+                }
+                Item::DonationCubes(typ, qty) => {
+                    self.apply_record(id, &spawn_record(owner, *typ, *qty, Some(owner), from))
+                }
+                _ => {}
+            }
+        }
+        self.ran_colonies.insert(colony);
+        let by = RunAttribution {
+            owner,
+            operator: owner,
+        };
+        self.stats
+            .production
+            .record_run(ProductionSource::Colony(colony), by, &outputs);
+    }
+}
+
+/// Tries to find a cube for an input, reassigning other inputs' cubes if
+/// needed. `seen` marks cubes already tried for this input.
+fn augment(
+    input: usize,
+    needed: &[CubeType],
+    types: &[CubeType],
+    paying: &mut [Option<usize>],
+    seen: &mut [bool],
+) -> bool {
+    for c in 0..types.len() {
+        if seen[c] || !needed[input].matches(types[c]) {
+            continue;
+        }
+        seen[c] = true;
+        let free = match paying[c] {
+            None => true,
+            Some(other) => augment(other, needed, types, paying, seen),
+        };
+        if free {
+            paying[c] = Some(input);
+            return true;
+        }
+    }
+    false
+}
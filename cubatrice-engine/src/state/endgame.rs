@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::cube::CubeType;
+
+use super::{player::PlayerID, Confluence, GameState, Phase};
+
+/// When a game ends. The game always ends after the economy phase of some
+/// confluence; this decides which one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EndCondition {
+    /// The game ends after the given confluence. Standard games end after
+    /// `Confluence::LAST`, and shorter games may end earlier.
+    AfterConfluence(Confluence),
+    /// The game ends after the confluence in which any player reaches
+    /// `points` victory points, or after `last` if nobody does.
+    SuddenDeath { points: usize, last: Confluence },
+}
+
+impl Default for EndCondition {
+    fn default() -> Self {
+        EndCondition::AfterConfluence(Confluence::LAST)
+    }
+}
+
+impl EndCondition {
+    /// The latest confluence the game can last to.
+    pub fn last_confluence(&self) -> Confluence {
+        match self {
+            EndCondition::AfterConfluence(c) => *c,
+            EndCondition::SuddenDeath { last, .. } => *last,
+        }
+    }
+
+    /// Whether the current confluence is the game's last.
+    pub fn reached(&self, state: &GameState) -> bool {
+        if state.confluence() >= self.last_confluence() {
+            return true;
+        }
+        match self {
+            EndCondition::AfterConfluence(_) => false,
+            EndCondition::SuddenDeath { points, .. } => state
+                .factions
+                .keys()
+                .any(|p| state.victory_points(*p) >= *points as isize),
+        }
+    }
+}
+
+impl GameState {
+    /// Whether this confluence is the last one, according to the game's
+    /// end condition.
+    pub fn final_confluence(&self) -> bool {
+        self.config.end.reached(self)
+    }
+
+    /// The phase that normally follows the current one, ending the game
+    /// after the economy phase once the end condition is reached. None
+    /// once the game is finished.
+    pub fn next_phase(&self) -> Option<Phase> {
+        match self.phase {
+            Phase::Init => Some(Phase::Trade),
+            Phase::Trade => Some(Phase::Economy),
+            Phase::Economy if self.final_confluence() => Some(Phase::Resolution),
+            Phase::Economy => Some(Phase::ColonyBid),
+            Phase::ColonyBid => Some(Phase::TechBid),
+            Phase::TechBid => Some(Phase::ZethSteal),
+            Phase::ZethSteal => Some(Phase::Trade),
+            Phase::Resolution => Some(Phase::Finish),
+            Phase::Finish => None,
+        }
+    }
+
+    /// How many victory points a player holds.
+    pub fn victory_points(&self, player: PlayerID) -> isize {
+        self.get_player_cubes(player)
+            .count_type(CubeType::VictoryPoint)
+    }
+}
@@ -0,0 +1,127 @@
+use crate::entity::{faction::FactionType, Token};
+
+use super::{
+    player::PlayerID,
+    validation::{ensure, ValidationError},
+    GameState,
+};
+
+impl GameState {
+    /// The players a Zeth player may steal from next, in ID order. Safe
+    /// players can't be stolen from, and envoys make a player an easier
+    /// target: only the players holding the most envoys may be picked.
+    pub fn steal_targets_for(&self, zeth: PlayerID) -> Vec<PlayerID> {
+        let open: Vec<PlayerID> = self
+            .seats()
+            .iter()
+            .copied()
+            .filter(|p| *p != zeth && !self.is_zeth_safe(*p))
+            .collect();
+        let most = open.iter().map(|p| self.envoys(*p)).max().unwrap_or(0);
+        open.into_iter()
+            .filter(|p| self.envoys(*p) == most)
+            .collect()
+    }
+
+    /// Checks that a Zeth player can steal from a player.
+    pub(crate) fn can_steal_from(
+        &self,
+        zeth: PlayerID,
+        target: PlayerID,
+    ) -> Result<(), ValidationError> {
+        ensure(
+            self.factions.contains_key(&target),
+            ValidationError::NoSuchPlayer { player: target },
+        )?;
+        ensure(zeth != target, ValidationError::SelfTrade { player: zeth })?;
+        ensure(
+            !self.is_zeth_safe(target),
+            ValidationError::ZethSafe { player: target },
+        )?;
+        let most = self
+            .steal_targets_for(zeth)
+            .first()
+            .map(|p| self.envoys(*p))
+            .unwrap_or(0);
+        ensure(
+            self.envoys(target) == most,
+            ValidationError::NotPreferredTarget {
+                player: target,
+                envoys: most,
+            },
+        )
+    }
+
+    /// Checks that the Zeth can give another player an envoy, either one
+    /// they hold or a new one from the supply. Envoys are given as part of
+    /// deals, so follow the rules for trading colonies.
+    pub(crate) fn can_give_envoy(
+        &self,
+        zeth: PlayerID,
+        to: PlayerID,
+    ) -> Result<(), ValidationError> {
+        let actual = self.factions.get(&zeth).copied();
+        ensure(
+            actual == Some(FactionType::ZethCore),
+            ValidationError::FactionMismatch {
+                player: zeth,
+                expected: FactionType::ZethCore,
+                actual,
+            },
+        )?;
+        ensure(
+            self.factions.contains_key(&to),
+            ValidationError::NoSuchPlayer { player: to },
+        )?;
+        ensure(zeth != to, ValidationError::SelfTrade { player: zeth })?;
+        ensure(
+            self.phase_rules().trade_colonies,
+            ValidationError::TradingClosed { phase: self.phase },
+        )?;
+        ensure(
+            self.envoys(zeth) > 0 || self.supply_of(Token::Envoy).can_supply(1),
+            ValidationError::TokenExhausted {
+                token: Token::Envoy,
+            },
+        )
+    }
+
+    /// Checks that a player holds an envoy to return to the supply.
+    pub(crate) fn can_return_envoy(&self, player: PlayerID) -> Result<(), ValidationError> {
+        ensure(
+            self.phase_rules().trade_colonies,
+            ValidationError::TradingClosed { phase: self.phase },
+        )?;
+        ensure(self.envoys(player) > 0, ValidationError::NoEnvoy { player })
+    }
+
+    pub(crate) fn give_envoy(&mut self, zeth: PlayerID, to: PlayerID) {
+        self.take_envoy(zeth);
+        *self.envoys.entry(to).or_default() += 1;
+    }
+
+    pub(crate) fn return_envoy(&mut self, player: PlayerID) {
+        self.take_envoy(player);
+    }
+
+    /// Marks a player safe once the Zeth have stolen from them. Their
+    /// envoys stay where they are.
+    pub(crate) fn stolen_from(&mut self, target: PlayerID) {
+        self.zeth_safe.insert(target);
+    }
+
+    /// Everyone can be stolen from again in the new confluence.
+    pub(crate) fn reset_zeth_safe(&mut self) {
+        self.zeth_safe.clear();
+    }
+
+    /// Removes one envoy from a player's hand, if they have any.
+    fn take_envoy(&mut self, player: PlayerID) {
+        if let Some(n) = self.envoys.get_mut(&player) {
+            *n = n.saturating_sub(1);
+            if *n == 0 {
+                self.envoys.remove(&player);
+            }
+        }
+    }
+}
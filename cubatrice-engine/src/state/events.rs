@@ -0,0 +1,205 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{
+    colony::ColonyID,
+    converter::ConverterID,
+    faction::{alt_caylion::ProjectID, base_faderan::RelicWorld},
+    technology::TechID,
+};
+
+use super::{player::PlayerID, record::RecordID, Confluence, GameState, Phase};
+
+/// Something worth telling players about, emitted while records are
+/// applied. Unlike records, events only describe what happened, so frontends
+/// and notifications can show them without interpreting the log.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameEvent {
+    ConfluenceStarted {
+        confluence: Confluence,
+    },
+    PhaseStarted {
+        phase: Phase,
+    },
+    /// Every player has bid, so the bids are public.
+    BidsRevealed,
+    /// A player took a colony from the bid track.
+    ColonyWon {
+        player: PlayerID,
+        colony: ColonyID,
+        ships: usize,
+    },
+    /// A player took a research team from the bid track.
+    ResearchTeamWon {
+        player: PlayerID,
+        tech: TechID,
+        ships: usize,
+    },
+    TechInvented {
+        player: PlayerID,
+        tech: TechID,
+        points: usize,
+    },
+    /// A technology was shared with every player.
+    TechShared {
+        tech: TechID,
+    },
+    /// The Yengii licensed one of their technologies to a player.
+    Licensed {
+        player: PlayerID,
+        tech: TechID,
+    },
+    /// The Zeth ran a steal converter, stealing from `target` if one was
+    /// given.
+    Stole {
+        zeth: PlayerID,
+        converter: ConverterID,
+        target: Option<PlayerID>,
+    },
+    /// A player upgraded one of their converters.
+    ConverterUpgraded {
+        player: PlayerID,
+        converter: ConverterID,
+    },
+    /// A colony carrying a cross colonization token was consumed. The token
+    /// went back to the supply and the Charity Syndicate scored a point.
+    CrossTokenReturned {
+        zeth: PlayerID,
+        colony: ColonyID,
+    },
+    /// The Faderan acknowledged a player's help, giving them an
+    /// acknowledgement and a victory point.
+    Acknowledged {
+        player: PlayerID,
+    },
+    /// A player holding an acknowledgement invented a technology, so one
+    /// went back to the Faderan, scoring them a point.
+    AcknowledgementReturned {
+        player: PlayerID,
+        faderan: PlayerID,
+    },
+    /// Two players traded.
+    Traded {
+        a: PlayerID,
+        b: PlayerID,
+    },
+    RelicDrawn {
+        player: PlayerID,
+        relic: RelicWorld,
+    },
+    ProjectActivated {
+        project: ProjectID,
+    },
+    ProjectCompleted {
+        project: ProjectID,
+    },
+    UnityDiceRolled {
+        faces: Vec<u8>,
+    },
+}
+
+impl GameState {
+    /// Events emitted since they were last taken, along with the record
+    /// which caused each one, oldest first.
+    pub fn events(&self) -> &[(RecordID, GameEvent)] {
+        self.events.as_slice()
+    }
+
+    /// Takes every event emitted since the last call, oldest first.
+    pub fn take_events(&mut self) -> Vec<(RecordID, GameEvent)> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub(crate) fn emit(&mut self, rec: RecordID, event: GameEvent) {
+        self.events.push((rec, event));
+    }
+}
+
+impl Display for GameEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ConfluenceStarted { confluence } => {
+                write!(f, "confluence {} has started", confluence.0)
+            }
+            Self::PhaseStarted { phase } => write!(f, "the {:?} phase has started", phase),
+            Self::BidsRevealed => write!(f, "bids are revealed"),
+            Self::ColonyWon {
+                player,
+                colony,
+                ships,
+            } => write!(
+                f,
+                "player {} won colony {} for {} ships",
+                player.0, colony.0, ships
+            ),
+            Self::ResearchTeamWon {
+                player,
+                tech,
+                ships,
+            } => write!(
+                f,
+                "player {} won research team {} for {} ships",
+                player.0, tech.0, ships
+            ),
+            Self::TechInvented {
+                player,
+                tech,
+                points,
+            } => write!(
+                f,
+                "player {} invented technology {} for {} points",
+                player.0, tech.0, points
+            ),
+            Self::TechShared { tech } => {
+                write!(f, "technology {} was shared with everyone", tech.0)
+            }
+            Self::Licensed { player, tech } => write!(
+                f,
+                "the Yengii licensed technology {} to player {}",
+                tech.0, player.0
+            ),
+            Self::Stole {
+                zeth,
+                converter,
+                target: Some(target),
+            } => write!(
+                f,
+                "player {} stole from player {} with converter {}",
+                zeth.0, target.0, converter.0
+            ),
+            Self::Stole {
+                zeth,
+                converter,
+                target: None,
+            } => write!(f, "player {} stole with converter {}", zeth.0, converter.0),
+            Self::ConverterUpgraded { player, converter } => {
+                write!(f, "player {} upgraded converter {}", player.0, converter.0)
+            }
+            Self::CrossTokenReturned { zeth, colony } => write!(
+                f,
+                "colony {} was consumed, returning a cross colonization token and scoring player {} a point",
+                colony.0, zeth.0
+            ),
+            Self::Acknowledged { player } => {
+                write!(f, "the Faderan acknowledged player {}", player.0)
+            }
+            Self::AcknowledgementReturned { player, faderan } => write!(
+                f,
+                "player {} returned an acknowledgement to player {}",
+                player.0, faderan.0
+            ),
+            Self::Traded { a, b } => write!(f, "players {} and {} traded", a.0, b.0),
+            Self::RelicDrawn { player, relic } => {
+                write!(f, "player {} drew the relic world {:?}", player.0, relic)
+            }
+            Self::ProjectActivated { project } => {
+                write!(f, "project {} was activated", project.0)
+            }
+            Self::ProjectCompleted { project } => {
+                write!(f, "project {} was completed", project.0)
+            }
+            Self::UnityDiceRolled { faces } => write!(f, "the Deep Unity rolled {:?}", faces),
+        }
+    }
+}
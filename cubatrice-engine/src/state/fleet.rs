@@ -0,0 +1,67 @@
+use crate::entity::converter::ConverterID;
+
+use super::{player::PlayerID, GameState};
+
+/// Fleet support the Grand Fleet starts with. They can never gain more.
+pub const GRAND_FLEET_SUPPORT: usize = 5;
+
+/// Fleet support the Imdril may raise each economy phase.
+pub const FLEET_SUPPORT_PER_CONFLUENCE: usize = 2;
+
+/// The most fleet support the Imdril can raise their own to.
+pub const MAX_FLEET_SUPPORT: usize = 10;
+
+impl GameState {
+    /// How much fleet support a player has, including any lent to them.
+    pub fn fleet_support(&self, player: PlayerID) -> usize {
+        self.fleet_support.get(&player).copied().unwrap_or_default()
+    }
+
+    /// How much fleet support the converters a player has marked to run
+    /// this economy phase need.
+    pub fn fleet_committed(&self, player: PlayerID) -> usize {
+        self.marked_converters
+            .iter()
+            .filter(|c| self.converter_owners.get(c) == Some(&player))
+            .filter_map(|c| self.converter(*c))
+            .map(|c| c.fleet_support())
+            .sum()
+    }
+
+    /// Fleet support a player has which isn't committed to a marked
+    /// converter.
+    pub fn fleet_free(&self, player: PlayerID) -> usize {
+        self.fleet_support(player)
+            .saturating_sub(self.fleet_committed(player))
+    }
+
+    /// Moves the fleet support a converter needs along with it when it's
+    /// lent out.
+    pub(crate) fn lend_fleet(&mut self, converter: ConverterID, from: PlayerID, to: PlayerID) {
+        let need = self
+            .converter(converter)
+            .map(|c| c.fleet_support())
+            .unwrap_or_default();
+        let has = self.fleet_support.entry(from).or_default();
+        let qty = need.min(*has);
+        if qty == 0 {
+            return;
+        }
+        *has -= qty;
+        *self.fleet_support.entry(to).or_default() += qty;
+        self.lent_fleet.insert(converter, (from, to, qty));
+    }
+
+    /// Gives back the fleet support lent along with a converter. The
+    /// borrower can't end up with less than nothing, even if the support has
+    /// since gone elsewhere.
+    pub(crate) fn return_fleet(&mut self, converter: ConverterID) {
+        let Some((lender, borrower, qty)) = self.lent_fleet.remove(&converter) else {
+            return;
+        };
+        let held = self.fleet_support.entry(borrower).or_default();
+        let qty = qty.min(*held);
+        *held -= qty;
+        *self.fleet_support.entry(lender).or_default() += qty;
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::entity::{
+    colony::ColonyID,
+    converter::ConverterID,
+    cube::{CubeID, CubeRecord, CubeType},
+    technology::TechID,
+    Token,
+};
+
+use super::{player::PlayerID, GameState};
+
+impl GameState {
+    /// Converters a player holds right now, in ID order. Converters they've
+    /// borrowed are included, and converters they've lent out aren't.
+    pub fn converters_of(&self, player: PlayerID) -> Vec<ConverterID> {
+        let mut converters: Vec<ConverterID> = self.owned.converters(player).collect();
+        converters.sort();
+        converters
+    }
+
+    /// Colonies a player owns, in ID order.
+    pub fn colonies_of(&self, player: PlayerID) -> Vec<ColonyID> {
+        let mut colonies: Vec<ColonyID> = self
+            .colony_owners
+            .iter()
+            .filter(|(_, p)| **p == player)
+            .map(|(c, _)| *c)
+            .collect();
+        colonies.sort();
+        colonies
+    }
+
+    /// Technologies a player holds the converter for, whether they invented
+    /// it, were given it or licensed it, in ID order. Research teams they
+    /// haven't invented yet are in `research_teams`.
+    pub fn techs_of(&self, player: PlayerID) -> Vec<TechID> {
+        let mut techs = self.converter_techs(self.converters_of(player).iter());
+        techs.sort();
+        techs.dedup();
+        techs
+    }
+
+    /// Every kind of token a player holds, with how many, in token ID
+    /// order. Service tokens already placed on converters, and tokens on
+    /// other players' colonies, aren't counted.
+    pub fn tokens_of(&self, player: PlayerID) -> Vec<(Token, usize)> {
+        let count = |m: &HashMap<PlayerID, usize>| m.get(&player).copied().unwrap_or_default();
+        let mut tokens = vec![
+            (Token::Acknowledgement, count(&self.acknowledgements)),
+            (Token::Envoy, count(&self.envoys)),
+            (Token::Regret, count(&self.regret)),
+            (Token::Service, count(&self.owned_arrow_tokens)),
+        ];
+        for colony in self.colonies_of(player) {
+            if let Some(typ) = self.factory_colonies.get(&colony) {
+                tokens.push((Token::Factory(*typ), 1));
+            }
+        }
+        if let Some(custom) = self.custom_tokens.get(&player) {
+            tokens.extend(custom.iter().map(|(id, n)| (Token::Custom(*id), *n)));
+        }
+        tokens.retain(|(_, n)| *n > 0);
+        tokens.sort_by_key(|(t, _)| t.id());
+        let mut merged: Vec<(Token, usize)> = Vec::new();
+        for (t, n) in tokens {
+            match merged.last_mut() {
+                Some((last, m)) if *last == t => *m += n,
+                _ => merged.push((t, n)),
+            }
+        }
+        merged
+    }
+
+    /// Totals a set of cubes by type, such as one side of a trade. Cubes
+    /// which don't exist aren't counted.
+    pub fn count_cubes(&self, cubes: &BTreeSet<CubeID>) -> CubeRecord {
+        cubes.iter().filter_map(|c| self.cubes.get(c)).collect()
+    }
+
+    /// How many ships a player has.
+    pub fn ships_of(&self, player: PlayerID) -> usize {
+        self.owned
+            .cubes(player)
+            .filter(|c| self.cubes.get(c).is_some_and(|c| c.typ == CubeType::Ship))
+            .count()
+    }
+}
@@ -0,0 +1,289 @@
+use std::{collections::BTreeSet, fmt::Display};
+
+use crate::entity::{
+    converter::ConverterID,
+    cube::{parse_cube_type, CubeID},
+    technology::TechID,
+};
+
+use super::{
+    player::PlayerID,
+    record::{RecordGroup, RecordType},
+    GameState, Phase,
+};
+
+/// The result of importing a spreadsheet of records. Rows that validated
+/// have been applied to the game, in order, and are listed in `records`.
+/// Rows that didn't are listed in `errors` and were skipped.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    pub records: Vec<RecordGroup>,
+    pub errors: Vec<ImportError>,
+}
+
+/// A row which could not be imported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportError {
+    /// Line number in the source, starting from 1.
+    pub line: usize,
+    /// What the turn column said, if anything.
+    pub turn: String,
+    pub message: String,
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {} (turn {}): {}",
+            self.line, self.turn, self.message
+        )
+    }
+}
+
+/// Imports records from a spreadsheet exported as CSV, applying each valid
+/// row to `state` in order. Players must already exist in the game.
+///
+/// Every row starts with a turn column (only used in error messages) and an
+/// action, followed by the action's fields:
+///
+/// ```text
+/// turn, phase,  <phase>
+/// turn, trade,  <player>, <player>, <cubes given by first>, <cubes given by second>
+/// turn, bid,    <player>, <colony bid>, <tech bid>[, <second colony bid>[, <second tech bid>]]
+/// turn, invent, <player>, <technology>, <cost cube type>
+/// turn, take,   <player>, colony|research, <track slot or 'pass'>
+/// turn, mark,   <player>, <converter ID or 'all'>
+/// ```
+///
+/// Players can be given by ID or by faction name, technologies by ID or
+/// name, and cubes as a list like `2 food; 1 power` (colour names work
+/// too). Track slots count from 0. Blank lines, lines starting with `#` and a header row are ignored.
+pub fn import_csv(state: &mut GameState, src: &str) -> ImportReport {
+    let mut report = ImportReport::default();
+    for (i, line) in src.lines().enumerate() {
+        let fields = split_row(line);
+        if fields.iter().all(|f| f.is_empty()) || fields[0].starts_with('#') {
+            continue;
+        }
+        if i == 0 && fields[0].eq_ignore_ascii_case("turn") {
+            continue;
+        }
+        let error = |message: String| ImportError {
+            line: i + 1,
+            turn: fields[0].clone(),
+            message,
+        };
+        let rec = match parse_row(state, &fields) {
+            Ok(r) => r,
+            Err(e) => {
+                report.errors.push(error(e));
+                continue;
+            }
+        };
+        if let Err(e) = state.validate(&rec) {
+            report.errors.push(error(e.to_string()));
+            continue;
+        }
+        let group = RecordGroup {
+            id: state.next_record_id(),
+            rec: vec![rec],
+        };
+        report.records.push(group.clone());
+        state.apply(group);
+    }
+    report
+}
+
+/// Parses a single record in the same format as an import row, without the
+/// turn column, e.g. `bid, p1, 2, 1`. The record is not validated.
+pub fn parse_command(state: &GameState, line: &str) -> Result<RecordType, String> {
+    let mut fields = split_row(line);
+    fields.insert(0, String::new());
+    parse_row(state, &fields)
+}
+
+/// Splits a CSV row into trimmed fields, allowing double quoted fields.
+fn split_row(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields.iter().map(|f| f.trim().to_string()).collect()
+}
+
+fn parse_row(state: &GameState, fields: &[String]) -> Result<RecordType, String> {
+    let field = |i: usize, name: &str| -> Result<&str, String> {
+        fields
+            .get(i)
+            .map(|f| f.as_str())
+            .filter(|f| !f.is_empty())
+            .ok_or(format!("missing {}", name))
+    };
+    let action = field(1, "action")?.to_lowercase();
+    match action.as_str() {
+        "phase" => Ok(RecordType::ChangePhase {
+            to: parse_phase(field(2, "phase")?)?,
+        }),
+        "trade" => {
+            let a = parse_player(state, field(2, "first player")?)?;
+            let b = parse_player(state, field(3, "second player")?)?;
+            let list = |i: usize| fields.get(i).map(|s| s.as_str()).unwrap_or("");
+            let a_cubes = pick_cubes(state, a, list(4))?;
+            let b_cubes = pick_cubes(state, b, list(5))?;
+            Ok(RecordType::TradeCubes {
+                a,
+                b,
+                a_cubes,
+                b_cubes,
+            })
+        }
+        "bid" => {
+            let optional = |i: usize| -> Result<Option<usize>, String> {
+                match fields.get(i).filter(|f| !f.is_empty()) {
+                    Some(f) => parse_qty(f).map(Some),
+                    None => Ok(None),
+                }
+            };
+            Ok(RecordType::Bid {
+                player: parse_player(state, field(2, "player")?)?,
+                for_colony: parse_qty(field(3, "colony bid")?)?,
+                for_tech: parse_qty(field(4, "tech bid")?)?,
+                for_colony_kjas: optional(5)?,
+                for_tech_faderan: optional(6)?,
+            })
+        }
+        "invent" => Ok(RecordType::InventTech {
+            player: parse_player(state, field(2, "player")?)?,
+            tech: parse_tech(state, field(3, "technology")?)?,
+            cost: parse_cube_type(field(4, "cost")?)?,
+        }),
+        "take" => {
+            let player = parse_player(state, field(2, "player")?)?;
+            let slot = match field(4, "track slot")? {
+                s if s.eq_ignore_ascii_case("pass") => None,
+                s => Some(parse_qty(s)?),
+            };
+            match field(3, "track")?.to_lowercase().as_str() {
+                "colony" => Ok(RecordType::TakeColony {
+                    player,
+                    colony: slot,
+                }),
+                "research" | "tech" => Ok(RecordType::TakeResearch { player, tech: slot }),
+                t => Err(format!("unknown track '{}'", t)),
+            }
+        }
+        "mark" => {
+            let player = parse_player(state, field(2, "player")?)?;
+            match field(3, "converter")? {
+                c if c.eq_ignore_ascii_case("all") => Ok(RecordType::MarkAll {
+                    player,
+                    except: Vec::new(),
+                }),
+                c => Ok(RecordType::MarkConverter {
+                    player,
+                    converter: ConverterID(parse_qty(c)?),
+                }),
+            }
+        }
+        "relic" => Ok(RecordType::DrawRelic {
+            player: parse_player(state, field(2, "player")?)?,
+        }),
+        "roll" => Ok(RecordType::RollUnityDice {
+            player: parse_player(state, field(2, "player")?)?,
+        }),
+        _ => Err(format!("unknown action '{}'", action)),
+    }
+}
+
+fn parse_qty(s: &str) -> Result<usize, String> {
+    s.parse().map_err(|_| format!("'{}' is not a number", s))
+}
+
+pub(crate) fn parse_phase(s: &str) -> Result<Phase, String> {
+    let s = s.to_lowercase().replace([' ', '_', '-'], "");
+    Ok(match s.as_str() {
+        "trade" => Phase::Trade,
+        "economy" => Phase::Economy,
+        "colonybid" => Phase::ColonyBid,
+        "techbid" => Phase::TechBid,
+        "zethsteal" | "steal" => Phase::ZethSteal,
+        "resolution" => Phase::Resolution,
+        "finish" => Phase::Finish,
+        _ => return Err(format!("unknown phase '{}'", s)),
+    })
+}
+
+/// Players can be given as a bare ID, 'p1' / 'player 1', or by the name or
+/// short name of the faction they're playing.
+fn parse_player(state: &GameState, s: &str) -> Result<PlayerID, String> {
+    let lower = s.to_lowercase();
+    let num = lower
+        .trim_start_matches("player")
+        .trim_start_matches('p')
+        .trim();
+    if let Ok(id) = num.parse() {
+        return Ok(PlayerID(id));
+    }
+    state
+        .factions
+        .iter()
+        .find(|(_, f)| {
+            f.name().eq_ignore_ascii_case(s) || f.short_name().eq_ignore_ascii_case(s)
+        })
+        .map(|(p, _)| *p)
+        .ok_or(format!("no player matches '{}'", s))
+}
+
+fn parse_tech(state: &GameState, s: &str) -> Result<TechID, String> {
+    if let Ok(id) = s.parse() {
+        return Ok(TechID(id));
+    }
+    state
+        .data
+        .tech
+        .values()
+        .find(|t| t.name.eq_ignore_ascii_case(s))
+        .map(|t| t.id)
+        .ok_or(format!("no technology named '{}'", s))
+}
+
+/// Parses a cube list like `2 food; 1 power` and picks that many of the
+/// player's cubes, lowest IDs first.
+fn pick_cubes(state: &GameState, player: PlayerID, s: &str) -> Result<BTreeSet<CubeID>, String> {
+    let mut picked = BTreeSet::new();
+    for part in s
+        .split([';', '+'])
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+    {
+        let (qty, typ) = match part.split_once(' ') {
+            Some((q, t)) if q.parse::<usize>().is_ok() => (parse_qty(q)?, t),
+            _ => (1, part),
+        };
+        let typ = parse_cube_type(typ.trim())?;
+        let owned = state.owned_cubes(player, typ);
+        let available: Vec<&CubeID> = owned.iter().filter(|c| !picked.contains(*c)).collect();
+        if available.len() < qty {
+            return Err(format!(
+                "player {} has {} {:?}, needs {}",
+                player.0,
+                available.len(),
+                typ,
+                qty
+            ));
+        }
+        picked.extend(available.into_iter().take(qty));
+    }
+    Ok(picked)
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{
+    faction::FactionType,
+    technology::{ConverterPrototype, TechID},
+};
+
+use super::{player::PlayerID, Confluence, GameState};
+
+/// A license the Yengii granted, giving the licensee their own copy of the
+/// technology's converter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct License {
+    pub tech: TechID,
+    pub licensee: PlayerID,
+    /// When the license was granted.
+    pub confluence: Confluence,
+}
+
+impl GameState {
+    /// The converter a player gets for a technology: their faction's own
+    /// version if it has one, otherwise the generic one.
+    pub(crate) fn tech_converter_for(
+        &self,
+        player: PlayerID,
+        tech: TechID,
+    ) -> Option<ConverterPrototype> {
+        self.factions
+            .get(&player)
+            .and_then(|f| self.data.tech_converter.get(f.short_name()))
+            .and_then(|v| v.iter().find(|p| p.id == tech))
+            .or_else(|| self.data.tech_prototype.get(&tech))
+            .cloned()
+    }
+
+    /// Technologies the Yengii hold the license to, in ID order.
+    pub fn yengii_techs(&self) -> Vec<TechID> {
+        let mut techs: Vec<TechID> = self.yengii_techs.iter().copied().collect();
+        techs.sort();
+        techs
+    }
+
+    /// Every license granted so far, in the order they were granted.
+    pub fn licenses(&self) -> &[License] {
+        &self.licenses
+    }
+
+    /// Players holding a license to a technology.
+    pub fn licensees(&self, tech: TechID) -> Vec<PlayerID> {
+        self.licenses
+            .iter()
+            .filter(|l| l.tech == tech)
+            .map(|l| l.licensee)
+            .collect()
+    }
+
+    /// Points the inventor of a technology scores when it's shared in a
+    /// confluence, from the data's sharing bonus table. The Yengii score
+    /// from their own, reduced column. Zero if the table isn't loaded or
+    /// doesn't cover the confluence.
+    pub fn sharing_bonus(&self, player: PlayerID, confluence: Confluence) -> usize {
+        let yengii = self.factions.get(&player) == Some(&FactionType::YengiiCore);
+        self.data
+            .sharing_bonus(confluence.0)
+            .map(|b| if yengii { b.yengii } else { b.standard })
+            .unwrap_or_default()
+    }
+
+    /// A player's sharing bonus for every confluence the game can last.
+    pub fn sharing_bonus_table(&self, player: PlayerID) -> Vec<(Confluence, usize)> {
+        (1..=self.config.end.last_confluence().0)
+            .map(Confluence)
+            .map(|c| (c, self.sharing_bonus(player, c)))
+            .collect()
+    }
+}
@@ -0,0 +1,1365 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::mpsc::Sender,
+};
+
+use anyhow::{anyhow, Context, Error};
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::{
+        colony::ColonyID,
+        converter::{Arrow, Convert, ConverterID},
+        cube::{Cube, CubeID, CubeRecord, CubeType},
+        faction::{FactionType, alt_caylion::{ProjectID, ProjectState}, alt_unity::AltUnityState},
+        technology::TechID,
+        token::TokenID,
+        Item,
+    },
+    stats::{market::BidKind, provenance::FlowNode, GameStats},
+    Deck,
+};
+
+use self::{
+    config::GameConfig,
+    contracts::Contract,
+    deck::{DeckID, EntityRef, NamedDeck},
+    events::GameEvent,
+    notes::Note,
+    offers::TradeOffer,
+    player::PlayerID,
+    promises::{Promise, PromiseStatus},
+    recurring::{MissedTransfer, RecurringTransfer},
+    record::{RecordID, RecordType, RecordGroup},
+};
+
+pub use crate::game_data::GameData;
+
+/// Which players can see which hidden information.
+pub mod access;
+
+/// Faction specific rules, hooked in at fixed points in the game.
+pub mod behavior;
+
+/// Which players still need to act.
+pub mod awaiting;
+
+/// Configuration used to set up a game, and the canonical ordering of setup
+/// records.
+pub mod config;
+
+/// Jii constraints on which cubes players may use.
+pub mod constraints;
+
+/// Charity Syndicate cross colonization tokens.
+pub mod cross;
+
+/// Zeth envoys, and who the Zeth may steal from.
+pub mod envoys;
+
+/// Agreed future obligations between players.
+pub mod contracts;
+
+/// Promises players make in deals, which the engine records but doesn't
+/// enforce.
+pub mod promises;
+
+/// When the game ends.
+pub mod endgame;
+
+/// Happenings emitted for frontends while records are applied.
+pub mod events;
+
+/// Subscribing to records as they're applied and undone.
+pub mod observers;
+
+/// Moving old history out of memory in long games.
+pub mod compaction;
+
+/// Golden games, replayed to check that rules changes don't alter old
+/// outcomes.
+pub mod corpus;
+
+/// Named decks of cards.
+pub mod deck;
+
+/// Working out what a record would change before applying it.
+pub mod describe;
+
+/// Running converters and colonies.
+pub mod economy;
+
+/// Importing records from spreadsheets of physical games.
+pub mod import;
+
+/// Faction reminders for the current phase.
+pub mod reminders;
+
+/// Society of Falling Light regret, and how it affects bidding and
+/// scoring.
+pub mod regret;
+
+/// Versioned rules interpretations.
+pub mod rules;
+
+/// Token supply and usage.
+pub mod tokens;
+
+/// Creating and destroying cubes through records.
+pub mod pool;
+
+/// Seating order.
+pub mod seats;
+
+/// Retrocontinuity tokens: trading them, and running converters and
+/// colonies early with them.
+mod retro;
+
+/// Base Caylion doubled colonies.
+pub mod doubling;
+
+/// Eni Et service tokens.
+pub mod service;
+
+/// Submitting records from clients which may retry.
+pub mod submission;
+
+/// Sizing and refilling the colony and research bid tracks.
+pub mod tracks;
+
+/// Delayed views for spectators.
+pub mod spectate;
+
+/// Totalling and ranking scores at the end of the game.
+pub mod scoring;
+
+/// The order players take from the bid tracks.
+pub mod ordering;
+
+/// What each player holds.
+pub mod holdings;
+
+/// Taking colonies and research teams from the bid tracks, in bid order.
+pub mod takes;
+
+/// Setting up a new game step by step.
+pub mod wizard;
+
+/// Running scripted converter effects.
+#[cfg(feature = "scripting")]
+pub mod scripting;
+
+/// Checking that records can be applied, and why they can't.
+pub mod validation;
+
+/// Paying for and applying converter upgrades.
+mod upgrades;
+
+/// Players' private notes.
+pub mod notes;
+
+/// Proposed trades waiting for an answer.
+pub mod offers;
+
+/// Trading any mix of items in a single record.
+pub mod trading;
+
+/// Alt Caylion projects.
+pub mod projects;
+
+/// Deep Unity's dice.
+pub mod unity;
+
+/// Imdril fleet support.
+pub mod fleet;
+
+/// Yengii technology licenses and sharing bonuses.
+pub mod licensing;
+
+/// Payments which repeat every confluence.
+pub mod recurring;
+
+/// Stepping through recorded games.
+pub mod replay;
+
+/// Saving games to disk and loading them again.
+pub mod savegame;
+
+/// Per-player views of the game, with hidden information left out.
+pub mod view;
+
+/// Warnings for records which are legal, but probably a mistake.
+pub mod warnings;
+
+/// Deterministic serialization of hash sets.
+mod sorted;
+
+/// Who owns what, indexed by player.
+mod ownership;
+
+/// Game data which is only read from disk when it's first needed.
+pub use cubatrice_data::lazy;
+
+/// Players and how they're shown: `PlayerID`, each player's `Profile` and
+/// `Controller`, and the `Player` summary frontends display. Used by the
+/// CLI, the server and the FFI to refer to players.
+pub mod player;
+
+/// Records are applied to a game state in order to construct it. Records
+/// are atomic and reversable. After applying any record, the game will not be
+/// in an illegal state, and any record can be undone to attain the previous
+/// game state.
+pub mod record;
+
+/// Which phase the game is currently in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Phase {
+    /// Initialization phase before the game has started. GameState is still
+    /// setting up in this phase, creating starting resources, adding players
+    /// etc.
+    #[default]
+    Init,
+    /// During the trade phase, players trade with each other and run purple
+    /// converters.
+    Trade,
+    /// During economy, the engine runs all marked white converters.
+    Economy,
+    /// During colony bid, players decide on which colony to purchase, if
+    /// any.
+    ColonyBid,
+    /// During tech bid, players decide on which tech teams to bid if any.
+    /// If players bid a quantity of ships in colony bid, and later passed
+    /// their bid, they cannot use those ships during this techbid phase.
+    /// They can use those ships on future colony and tech bids.
+    TechBid,
+    /// If the Zeth Anocracy is in the game, they run stealing converters
+    /// during this phase, stealing resources from any players that didn't
+    /// trade with them during the previous phase.
+    ZethSteal,
+    /// Debts are resolved during this phase, including future value and
+    /// recurring debts.
+    Resolution,
+    /// All points are totalled and a winner is decided. In reality, everyone
+    /// wins because a lot of cubes were pushed.
+    Finish,
+}
+
+/// Which round of the game it is. Games start at confluence 1 and end after
+/// `Confluence::LAST`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Confluence(pub usize);
+
+impl Confluence {
+    /// The final confluence of a standard game.
+    pub const LAST: Confluence = Confluence(6);
+}
+
+impl Default for Confluence {
+    fn default() -> Self {
+        Confluence(1)
+    }
+}
+
+// Be warned: Struct definition over 100 lines long.
+
+/// Used to track the state of the game. Modified indirectly and atomically by
+/// applying (and unapplying) records. Unapplying a record that was never
+/// applied is a logic error.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GameState {
+    /// Which phase the game is currently in.
+    phase: Phase,
+    /// Which confluence the game is currently in
+    confluence: Confluence,
+    /// Game data for specific converters, techs, planets, etc. Not saved
+    /// with the game, and must be supplied again when loading.
+    #[serde(skip)]
+    data: GameData,
+
+    /// Techs waiting to be shared at the sharing phase.
+    #[serde(serialize_with = "sorted::set")]
+    to_share: HashSet<TechID>,
+    /// Which techs the yengii hold the license to
+    #[serde(serialize_with = "sorted::set")]
+    yengii_techs: HashSet<TechID>,
+    /// Licenses the yengii have granted, in the order they were granted.
+    licenses: Vec<licensing::License>,
+
+    /// The current deck of technologies, shuffled then sorted by tier.
+    tech_deck: Deck<TechID>,
+    /// The current deck of planets, shuffled.
+    colony_deck: Deck<ColonyID>,
+
+    /// A Map from converter IDs to real converters.
+    #[serde(with = "crate::entity::converter::boxed_map")]
+    converters: HashMap<ConverterID, Box<dyn Convert>>,
+    /// A Map from converters to their current owners.
+    converter_owners: HashMap<ConverterID, PlayerID>,
+    /// If converters are temporarily transferred, their original owners
+    /// are listed here, and will be returned at after the economy phase.
+    original_owners: HashMap<ConverterID, PlayerID>,
+    /// Converters marked to be run during the next economy phase.
+    #[serde(serialize_with = "sorted::set")]
+    marked_converters: HashSet<ConverterID>,
+    /// Converters which have been run this phase. Each converter can only
+    /// be run once per phase.
+    #[serde(serialize_with = "sorted::set")]
+    ran_converters: HashSet<ConverterID>,
+    /// Colonies which have been run this phase.
+    #[serde(serialize_with = "sorted::set")]
+    ran_colonies: HashSet<ColonyID>,
+    /// whether the converter can be traded. If not it will be in this hashset.
+    #[serde(serialize_with = "sorted::set")]
+    untradable_converters: HashSet<ConverterID>,
+    /// Converters which can't be lent out.
+    #[serde(serialize_with = "sorted::set")]
+    unloanable_converters: HashSet<ConverterID>,
+
+    /// Map from cubeID to each cube
+    cubes: HashMap<CubeID, Cube>,
+    /// Map from CubeID to its owner
+    cube_owners: HashMap<CubeID, PlayerID>,
+
+    /// Who owns which tech team, if not yet invented.
+    tech_team_owners: HashMap<TechID, PlayerID>,
+
+    /// Victory points each player has scored from cards, rather than
+    /// holding as victory point cubes.
+    victory_points: HashMap<PlayerID, usize>,
+
+    /// How many ships a player bid for colonies, and an optional second bid.
+    player_colony_bid: HashMap<PlayerID, (usize, Option<usize>)>,
+    /// How many ships a player bid for techs, and an optional second bid.
+    player_tech_bid: HashMap<PlayerID, (usize, Option<usize>)>,
+    /// Which colonies are on the bid track. If colonies are not in the process
+    /// of being doled out, all options will be Some.
+    colony_bid_track: Vec<Option<ColonyID>>,
+    /// The order of players colony bids.
+    colony_bid_order: Vec<PlayerID>,
+    /// The order of players tech bids.
+    tech_bid_order: Vec<PlayerID>,
+    /// Ships players bid for colonies on turns they passed. They stay with
+    /// the player, but can't pay for a research team in the same round.
+    passed_ships: HashMap<PlayerID, usize>,
+    /// Which techs are on the bid track. If techs are not in the process of
+    /// being doles out, all options will be Some.
+    tech_bid_track: Vec<Option<TechID>>,
+
+    /// Who owns which colony, if it exists
+    colony_owners: HashMap<ColonyID, PlayerID>,
+    /// The particular faction a player is.
+    factions: HashMap<PlayerID, FactionType>,
+    /// Players in seating order, clockwise.
+    seats: Vec<PlayerID>,
+    /// Names and other details players have registered.
+    profiles: HashMap<PlayerID, player::Profile>,
+
+    next_cube_id: CubeID,
+    next_converter_id: ConverterID,
+    next_record_id: RecordID,
+
+    /// Base Caylion doubled colonies
+    #[serde(serialize_with = "sorted::set")]
+    doubled_colonies: HashSet<ColonyID>,
+    
+    /// Named decks, such as the Faderan relic deck, Alt Caylion projects and
+    /// homebrew content.
+    decks: HashMap<DeckID, NamedDeck>,
+    /// Cards players have drawn from named decks which aren't owned
+    /// anywhere else.
+    held_cards: HashMap<PlayerID, Vec<EntityRef>>,
+    /// Payments made at the start of every trade phase.
+    recurring_transfers: Vec<RecurringTransfer>,
+    /// Recurring payments which couldn't be afforded.
+    missed_transfers: Vec<MissedTransfer>,
+    /// Trades which have been proposed but not answered, oldest first.
+    pending_offers: Vec<TradeOffer>,
+    /// Contracts players have agreed, in order.
+    contracts: Vec<Contract>,
+    /// Promises players have made, in order.
+    promises: Vec<Promise>,
+    /// Each player's private notes.
+    notes: HashMap<PlayerID, Vec<Note>>,
+    /// How many acknowledgements each player has.
+    acknowledgements: HashMap<PlayerID, usize>,
+    /// How many trades each player has given the Faderan something in,
+    /// which the Faderan haven't acknowledged yet.
+    owed_acknowledgements: HashMap<PlayerID, usize>,
+
+    /// How many fleet support each player has. Typically only imdril players
+    /// will have fleet support, but if they loan converters they must loan
+    /// fleets as well.
+    fleet_support: HashMap<PlayerID, usize>,
+    /// Fleet support lent along with a converter: the lender, the borrower
+    /// and how much. Returned when the converter is.
+    lent_fleet: HashMap<ConverterID, (PlayerID, PlayerID, usize)>,
+    /// The last confluence each player raised fleet support in.
+    fleet_raised: HashMap<PlayerID, Confluence>,
+
+    /// Which planets the kit can still play, along with their cost. (in
+    /// yellow cubes)
+    kit_planets: Vec<(usize, ColonyID)>,
+
+    /// Which players are safe from zeth stealing. Players are marked safe
+    /// after having been stolen from once, until the next confluence.
+    #[serde(serialize_with = "sorted::set")]
+    zeth_safe: HashSet<PlayerID>,
+    /// How many envoy tokens each player holds.
+    envoys: HashMap<PlayerID, usize>,
+
+    /// The status of all current projects.
+    project_status: HashMap<ProjectID, ProjectState>,
+    /// Which converters are associated with each project.
+    #[serde(serialize_with = "sorted::map_of_sets")]
+    project_converters: HashMap<ProjectID, HashSet<ConverterID>>,
+    /// Who has voted for each project, in order.
+    project_votes: HashMap<ProjectID, Vec<PlayerID>>,
+
+    /// Which converters have been halved, who placed the service token, and
+    /// the new cost of the converter. Arrows can only reduce cube costs of
+    /// converters so this is Fine (tm)
+    halved_converters: HashMap<ConverterID, (PlayerID, CubeRecord)>,
+    /// How many service tokens each player owns.
+    owned_arrow_tokens: HashMap<PlayerID, usize>,
+    /// Converters which have run with a service token, which can no longer
+    /// be removed.
+    #[serde(serialize_with = "sorted::set")]
+    locked_service: HashSet<ConverterID>,
+
+    /// Tech which, when traded away from the alt faderan, will add 1 regret
+    /// to the recipient. These have been invented by the alt faderan already.
+    #[serde(serialize_with = "sorted::set")]
+    regret_techs: HashSet<TechID>,
+    /// How many regret each player has.
+    regret: HashMap<PlayerID, usize>,
+    /// How many of each custom token each player holds.
+    custom_tokens: HashMap<PlayerID, HashMap<TokenID, usize>>,
+
+    /// Which colonies have factories on them, and what type of cube they
+    /// produce.
+    factory_colonies: HashMap<ColonyID, CubeType>,
+    
+    /// Who owns whose retrocontinuity token, as they can be traded. the Key
+    /// is the player whose token it is, the value is the player who currently
+    /// owns the token. It will be returned to the original owner.
+    retro_owners: HashMap<PlayerID, PlayerID>,
+    /// Which players' retro tokens have been used.
+    #[serde(serialize_with = "sorted::set")]
+    retro_used: HashSet<PlayerID>,
+    /// Which converters have already had retrocontinuity applied to them.
+    /// These converters cannot be run during the economy phase, 
+    #[serde(serialize_with = "sorted::set")]
+    retro_used_conveter: HashSet<ConverterID>,
+    /// Someone has applied retrocontinuity to a colony, for some reason.
+    /// I could maybe see it on a caylion planet with a factory.
+    #[serde(serialize_with = "sorted::set")]
+    retro_used_colony: HashSet<ColonyID>,
+    /// How much a player's colony support has increased from base.
+    increased_colony_support: HashMap<PlayerID, usize>,
+    
+    /// The current state of alt unity's dice, as well as their dice change
+    /// cards.
+    alt_unity_state: AltUnityState,
+
+    /// Which color constraints a player has. Players may not invent using
+    /// cubes of this type, or run converters using cubes of this type.
+    #[serde(serialize_with = "sorted::map_of_sets")]
+    constraints: HashMap<PlayerID, HashSet<CubeType>>,
+
+    /// How many undesirables the Alt Zeth player may run.
+    undesirable_limit: usize,
+    /// Which converters are the undesirables.
+    #[serde(serialize_with = "sorted::set")]
+    undesirable_converters: HashSet<ConverterID>,
+    /// Which colonies Zeth's cross colonization tokens are on. There can be
+    /// at most 3. These tokens are returned to the common pool whenever their
+    /// associated colony is destroyed, and the Zeth earn a point.
+    #[serde(serialize_with = "sorted::set")]
+    cross_tokens: HashSet<ColonyID>,
+
+    /// Statistics collected over the course of the game.
+    stats: GameStats,
+
+    /// The config this game was set up with.
+    config: GameConfig,
+    /// Every record group applied to this game, in order, unless the game
+    /// is being compacted, in which case only the most recent.
+    log: Vec<RecordGroup>,
+    /// Each owner a converter has had, and the record that caused the
+    /// transfer. Used to answer "whose converter is this?" from the log.
+    converter_history: HashMap<ConverterID, Vec<(PlayerID, RecordID)>>,
+    /// Nonces of the most recently applied submissions, oldest first, with
+    /// who submitted them and the groups they were applied as.
+    nonces: VecDeque<(PlayerID, submission::Nonce, RecordID)>,
+    /// Events emitted since they were last taken. These are only for
+    /// whoever is watching the game, so aren't saved.
+    #[serde(skip)]
+    events: Vec<(RecordID, GameEvent)>,
+    /// `cube_owners` and `converter_owners` indexed by player. Rebuilt on
+    /// load rather than saved.
+    #[serde(skip)]
+    owned: ownership::OwnershipIndex,
+    /// Whoever is subscribed to the game. Not saved.
+    #[serde(skip)]
+    subscribers: Vec<Sender<observers::Notification>>,
+    /// Where record groups moved out of `log` went, if the game is being
+    /// compacted. Not saved.
+    #[serde(skip)]
+    history: Option<compaction::History>,
+}
+
+impl GameState {
+    /// Constructs a new empty game state with a given game data. Data will be
+    /// used as the source of truth for game cards (converters, colonies,
+    /// starting resources, etc.)
+    pub fn new(data: GameData) -> Self {
+        Self {
+            data,
+            ..Self::default()
+        }
+    }
+
+    /// Sets up a new game from a config. Setup records are validated and
+    /// applied in the canonical order given by `GameConfig::setup_records`,
+    /// so equal configs always produce equal game states and logs.
+    pub fn from_config(data: GameData, config: GameConfig) -> Result<Self, Error> {
+        let mut state = Self::new(data);
+        let setup = config.setup_records();
+        state.config = config;
+        for group in setup {
+            for r in group.rec.iter() {
+                if let Err(e) = state.validate(r) {
+                    return Err(anyhow!("invalid setup record {:?}: {}", r, e));
+                }
+            }
+            state.apply(group);
+        }
+        Ok(state)
+    }
+
+    /// Serializes the whole game state, including the log, to JSON. Game data
+    /// isn't included.
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string(self).context("Failed to serialize game state")
+    }
+
+    /// Restores a game state serialized with `to_json`, using `data` as the
+    /// game data.
+    pub fn from_json(data: GameData, json: &str) -> Result<Self, Error> {
+        let mut state: Self =
+            serde_json::from_str(json).context("Failed to deserialize game state")?;
+        state.data = data;
+        state.owned =
+            ownership::OwnershipIndex::build(&state.cube_owners, &state.converter_owners);
+        Ok(state)
+    }
+
+    /// A copy of the game to try records out on, leaving this one
+    /// untouched. Events waiting to be taken and subscribers aren't copied.
+    pub(crate) fn fork(&self) -> Result<Self, Error> {
+        Self::from_json(self.data.clone(), &self.to_json()?)
+    }
+
+    /// Sets the game data for a given game.
+    pub fn set_game_data(&mut self, data: GameData) {
+        self.data = data;
+    }
+
+    /// Applies a group of records to the game, and appends it to the log.
+    /// Records should be validated before they are applied.
+    pub fn apply(&mut self, rec: RecordGroup) {
+        let first_event = self.events.len();
+        for r in rec.rec.iter() {
+            self.apply_record(rec.id, r);
+            self.record_applied_behaviors(rec.id, r);
+        }
+        self.next_record_id = RecordID(rec.id.0 + 1);
+        if !self.subscribers.is_empty() {
+            let events = self.events[first_event..]
+                .iter()
+                .map(|(_, e)| e.clone())
+                .collect();
+            self.notify(observers::Notification::Applied {
+                group: rec.clone(),
+                events,
+            });
+        }
+        self.log.push(rec);
+        self.compact_if_due();
+    }
+
+    /// Undoes the most recent record group, returning it, by replaying the
+    /// log without it. Events waiting to be taken are dropped, since they
+    /// may describe the group being undone.
+    pub fn undo(&mut self) -> Option<RecordGroup> {
+        self.unspill().ok()?;
+        let mut state = self.base().ok()?;
+        let mut log = std::mem::take(&mut self.log);
+        let group = log.pop();
+        for g in log {
+            state.apply(g);
+        }
+        state.events.clear();
+        state.nonces = std::mem::take(&mut self.nonces);
+        state.subscribers = std::mem::take(&mut self.subscribers);
+        state.history = self.history.take();
+        *self = state;
+        let group = group?;
+        self.nonces.retain(|(_, _, id)| *id != group.id);
+        self.notify(observers::Notification::Undone {
+            group: group.clone(),
+        });
+        Some(group)
+    }
+
+    fn apply_record(&mut self, id: RecordID, rec: &RecordType) {
+        match rec {
+            RecordType::CreatePlayer { player, faction } => {
+                self.factions.insert(*player, *faction);
+                self.seats.push(*player);
+                self.give_retro_token(*player);
+                behavior::behavior(*faction).on_setup(self, *player);
+                let items = self
+                    .data
+                    .start_resources
+                    .get(faction)
+                    .cloned()
+                    .unwrap_or_default();
+                // starting resources are created in data order, so cube IDs
+                // are the same every time a game is set up.
+                for i in items {
+                    match i {
+                        Item::Cubes(typ, qty) => {
+                            let r = pool::spawn_record(*player, typ, qty, None, FlowNode::Supply);
+                            self.apply_record(id, &r);
+                        }
+                        Item::DonationCubes(typ, qty) => {
+                            let donation = Some(*player);
+                            let r =
+                                pool::spawn_record(*player, typ, qty, donation, FlowNode::Supply);
+                            self.apply_record(id, &r);
+                        }
+                        Item::SpecificColony(c) => {
+                            self.colony_owners.insert(c, *player);
+                        }
+                        // TODO: starting tokens, and colonies that aren't
+                        // specified by ID.
+                        _ => {}
+                    }
+                }
+                let bonus = self.config.start_points(*player);
+                if bonus > 0 {
+                    self.spawn_cubes(*player, CubeType::VictoryPoint, bonus, None);
+                }
+            }
+            RecordType::ChangePhase { to } => {
+                if *to == Phase::Trade && self.phase == Phase::ZethSteal {
+                    self.confluence = Confluence(self.confluence.0 + 1);
+                    self.reset_retrocontinuity();
+                    self.reset_zeth_safe();
+                    let confluence = self.confluence;
+                    self.emit(id, GameEvent::ConfluenceStarted { confluence });
+                }
+                if self.phase == Phase::Economy {
+                    self.marked_converters.clear();
+                    // converters lent for the economy phase go back home
+                    let mut loans: Vec<(ConverterID, PlayerID)> =
+                        self.original_owners.drain().collect();
+                    loans.sort();
+                    for (c, p) in loans {
+                        self.transfer_converter(id, c, p);
+                        self.return_fleet(c);
+                    }
+                }
+                // colonies and research teams taken in a bid round are
+                // replaced once it's over
+                match self.phase {
+                    Phase::ColonyBid => self.finish_takes(BidKind::Colony),
+                    Phase::TechBid => self.finish_takes(BidKind::Tech),
+                    _ => {}
+                }
+                self.phase = *to;
+                self.ran_converters.clear();
+                self.ran_colonies.clear();
+                if *to == Phase::Trade {
+                    self.pay_recurring_transfers();
+                }
+                if *to == Phase::Resolution {
+                    self.enforce_contracts();
+                }
+                self.phase_started_behaviors();
+                self.emit(id, GameEvent::PhaseStarted { phase: *to });
+            }
+            RecordType::TradeCubes {
+                a,
+                b,
+                a_cubes,
+                b_cubes,
+            } => {
+                for c in a_cubes.iter() {
+                    self.move_cube(*c, *b);
+                }
+                for c in b_cubes.iter() {
+                    self.move_cube(*c, *a);
+                }
+                self.emit(id, GameEvent::Traded { a: *a, b: *b });
+            }
+            RecordType::TradeColony {
+                a,
+                b,
+                a_colony,
+                b_colony,
+            } => {
+                for c in a_colony.iter() {
+                    self.colony_owners.insert(*c, *b);
+                }
+                for c in b_colony.iter() {
+                    self.colony_owners.insert(*c, *a);
+                }
+                self.emit(id, GameEvent::Traded { a: *a, b: *b });
+            }
+            RecordType::TradeConverter {
+                a,
+                b,
+                a_converter,
+                b_converter,
+                permanent,
+            } => {
+                for (from, to, convs) in [(a, b, a_converter), (b, a, b_converter)] {
+                    for c in convs.iter() {
+                        if !permanent && !self.original_owners.contains_key(c) {
+                            self.original_owners.insert(*c, *from);
+                            self.lend_fleet(*c, *from, *to);
+                        }
+                        self.transfer_converter(id, *c, *to);
+                    }
+                }
+                self.emit(id, GameEvent::Traded { a: *a, b: *b });
+            }
+            RecordType::TradeConverterPermanently {
+                a,
+                b,
+                a_converter,
+                b_converter,
+            } => {
+                for c in a_converter.iter() {
+                    self.transfer_converter(id, *c, *b);
+                }
+                for c in b_converter.iter() {
+                    self.transfer_converter(id, *c, *a);
+                }
+                let a_techs = self.converter_techs(a_converter);
+                let b_techs = self.converter_techs(b_converter);
+                self.attach_regret(*a, *b, &a_techs);
+                self.attach_regret(*b, *a, &b_techs);
+                self.emit(id, GameEvent::Traded { a: *a, b: *b });
+            }
+            RecordType::Bid {
+                player,
+                for_colony,
+                for_colony_kjas,
+                for_tech,
+                for_tech_faderan,
+            } => {
+                self.player_colony_bid
+                    .insert(*player, (*for_colony, *for_colony_kjas));
+                self.player_tech_bid
+                    .insert(*player, (*for_tech, *for_tech_faderan));
+                let bids = [
+                    (BidKind::Colony, Some(*for_colony)),
+                    (BidKind::Colony, *for_colony_kjas),
+                    (BidKind::Tech, Some(*for_tech)),
+                    (BidKind::Tech, *for_tech_faderan),
+                ];
+                for (kind, ships) in bids {
+                    if let Some(ships) = ships {
+                        self.stats
+                            .market
+                            .record_bid(self.confluence, *player, kind, ships);
+                    }
+                }
+                if self.bids_revealed() {
+                    self.start_takes();
+                    self.emit(id, GameEvent::BidsRevealed);
+                }
+            }
+            RecordType::TakeColony { colony, .. } => self.take(id, BidKind::Colony, *colony),
+            RecordType::TakeResearch { tech, .. } => self.take(id, BidKind::Tech, *tech),
+            RecordType::InventTech { player, tech, cost } => {
+                let (qty, reward) = self
+                    .data
+                    .tech
+                    .get(tech)
+                    .map(|t| {
+                        let qty = t.cost.iter().find(|c| c.typ == *cost).map(|c| c.qty);
+                        (qty.unwrap_or(0), t.invent_reward)
+                    })
+                    .unwrap_or((0, 0));
+                for c in self.owned_cubes(*player, *cost).into_iter().take(qty) {
+                    self.destroy_cube(c, FlowNode::Supply);
+                }
+                self.tech_team_owners.remove(tech);
+                // factions which don't share their inventions take them back
+                // out once the record is applied
+                self.to_share.insert(*tech);
+                self.spawn_cubes(*player, CubeType::VictoryPoint, reward, None);
+                if let Some(conv) = self.tech_converter_for(*player, *tech) {
+                    self.add_converter(id, *player, Box::new(conv));
+                }
+                self.emit(
+                    id,
+                    GameEvent::TechInvented {
+                        player: *player,
+                        tech: *tech,
+                        points: reward,
+                    },
+                );
+            }
+            RecordType::MarkAll { player, except } => {
+                for r in self.expand_mark_all(*player, except) {
+                    self.apply_record(id, &r);
+                }
+            }
+            RecordType::MarkConverter { converter, .. } => {
+                self.marked_converters.insert(*converter);
+            }
+            RecordType::UnmarkConverter { converter, .. } => {
+                self.marked_converters.remove(converter);
+            }
+            RecordType::CreateDeck { deck, name, cards } => {
+                let named = NamedDeck {
+                    name: name.clone(),
+                    cards: Deck::new(cards.clone()),
+                };
+                self.decks.insert(*deck, named);
+            }
+            RecordType::DrawFromDeck { deck, player } => {
+                let card = self.decks.get_mut(deck).and_then(|d| d.cards.draw_next());
+                if let Some(card) = card {
+                    self.give_card(id, *player, card);
+                }
+            }
+            RecordType::AssignSeats { seats } => self.seats = seats.clone(),
+            RecordType::SwapSeats { a, b } => {
+                if let (Some(i), Some(j)) = (self.seat(*a), self.seat(*b)) {
+                    self.seats.swap(i, j);
+                }
+            }
+            RecordType::VoteForProject { player, project } => {
+                self.vote_for_project(*player, *project)
+            }
+            RecordType::ActivateProject { project, .. } => {
+                self.activate_project(id, *project);
+                self.emit(id, GameEvent::ProjectActivated { project: *project });
+            }
+            RecordType::CompleteProject { project, .. } => {
+                self.complete_project(*project);
+                self.emit(id, GameEvent::ProjectCompleted { project: *project });
+            }
+            RecordType::DrawRelic { player } => {
+                let card = self
+                    .decks
+                    .get_mut(&DeckID::RELICS)
+                    .and_then(|d| d.cards.draw_next());
+                if let Some(card) = card {
+                    self.give_card(id, *player, card);
+                    if let EntityRef::Relic(relic) = card {
+                        let player = *player;
+                        self.emit(id, GameEvent::RelicDrawn { player, relic });
+                    }
+                }
+            }
+            RecordType::RollUnityDice { .. } => {
+                self.roll_unity_dice(id);
+                let faces = self.alt_unity_state.faces.clone();
+                self.emit(id, GameEvent::UnityDiceRolled { faces });
+            }
+            RecordType::ChangeUnityDie { die, face, .. } => {
+                self.alt_unity_state.faces[*die] = *face;
+                self.alt_unity_state.change_cards -= 1;
+            }
+            RecordType::GainDiceChangeCards { count, .. } => {
+                self.alt_unity_state.change_cards += *count
+            }
+            RecordType::GainFleetSupport { player, qty } => {
+                *self.fleet_support.entry(*player).or_default() += *qty;
+                self.fleet_raised.insert(*player, self.confluence);
+            }
+            RecordType::PlaceServiceToken { player, converter } => {
+                self.place_service_token(*player, *converter)
+            }
+            RecordType::RemoveServiceToken { converter } => {
+                self.remove_service_token(*converter)
+            }
+            RecordType::RunConverter {
+                converter,
+                inputs,
+                target,
+            } => {
+                let stealer = self
+                    .converter_owners
+                    .get(converter)
+                    .copied()
+                    .filter(|_| self.converter(*converter).map(|c| c.color()) == Some(Arrow::Red));
+                self.run_converter(id, *converter, inputs);
+                if let Some(zeth) = stealer {
+                    if let Some(t) = target {
+                        self.stolen_from(*t);
+                    }
+                    self.emit(
+                        id,
+                        GameEvent::Stole {
+                            zeth,
+                            converter: *converter,
+                            target: *target,
+                        },
+                    );
+                }
+            }
+            RecordType::RecurringTransfer {
+                from,
+                to,
+                typ,
+                qty,
+                until,
+            } => self.recurring_transfers.push(RecurringTransfer {
+                id,
+                from: *from,
+                to: *to,
+                typ: *typ,
+                qty: *qty,
+                until: *until,
+            }),
+            // peeking is only recorded so that the log shows who knows what.
+            RecordType::PeekDeck { .. } => {}
+            RecordType::ProposeTrade {
+                from,
+                to,
+                give,
+                take,
+                permanent,
+                counters,
+            } => {
+                if let Some(c) = counters {
+                    self.pending_offers.retain(|o| o.id != *c);
+                }
+                self.pending_offers.push(TradeOffer {
+                    id,
+                    from: *from,
+                    to: *to,
+                    give: give.clone(),
+                    take: take.clone(),
+                    permanent: *permanent,
+                });
+            }
+            RecordType::AcceptTrade { offer } => {
+                let Some(i) = self.pending_offers.iter().position(|o| o.id == *offer) else {
+                    return;
+                };
+                let offer = self.pending_offers.remove(i);
+                for r in offer.records() {
+                    self.apply_record(id, &r);
+                    self.record_applied_behaviors(id, &r);
+                }
+            }
+            RecordType::RejectTrade { offer } => {
+                self.pending_offers.retain(|o| o.id != *offer);
+            }
+            RecordType::Contract {
+                debtor,
+                creditor,
+                typ,
+                qty,
+                due,
+                binding,
+            } => self.contracts.push(Contract {
+                id,
+                debtor: *debtor,
+                creditor: *creditor,
+                typ: *typ,
+                qty: *qty,
+                due: *due,
+                binding: *binding,
+                paid: 0,
+            }),
+            RecordType::FulfillContract { contract, cubes } => {
+                let Some(i) = self.contracts.iter().position(|c| c.id == *contract) else {
+                    return;
+                };
+                let creditor = self.contracts[i].creditor;
+                for c in cubes.iter() {
+                    self.move_cube(*c, creditor);
+                }
+                self.contracts[i].paid += cubes.len();
+            }
+            RecordType::Promise { from, to, text } => self.promises.push(Promise {
+                id,
+                from: *from,
+                to: *to,
+                text: text.clone(),
+                made: self.confluence,
+                status: PromiseStatus::Outstanding,
+            }),
+            RecordType::SettlePromise { promise, kept, .. } => self.settle_promise(*promise, *kept),
+            RecordType::AddNote {
+                player,
+                target,
+                text,
+            } => self.notes.entry(*player).or_default().push(Note {
+                id,
+                target: *target,
+                text: text.clone(),
+            }),
+            RecordType::RemoveNote { player, note } => {
+                if let Some(notes) = self.notes.get_mut(player) {
+                    notes.retain(|n| n.id != *note);
+                }
+            }
+            RecordType::ShuffleDeck { deck } => {
+                // seeded from the record ID so replaying the log reproduces
+                // the same order.
+                let mut rng = StdRng::seed_from_u64(self.config.seed.wrapping_add(id.0 as u64));
+                if let Some(d) = self.decks.get_mut(deck) {
+                    d.cards.shuffle_with_rng(&mut rng);
+                }
+            }
+            RecordType::CreateTechDeck => {
+                let mut rng = StdRng::seed_from_u64(self.config.seed.wrapping_add(id.0 as u64));
+                self.tech_deck = self.build_tech_deck(&mut rng);
+                self.refill_tech_track();
+            }
+            RecordType::SetProfile { player, profile } => {
+                self.profiles.insert(*player, profile.clone());
+            }
+            RecordType::CreateColonyDeck => {
+                let mut rng = StdRng::seed_from_u64(self.config.seed.wrapping_add(id.0 as u64));
+                self.colony_deck = self.build_colony_deck(&mut rng);
+                self.refill_colony_track();
+            }
+            RecordType::Retrocontinuity { converter, inputs } => {
+                self.retrocontinue_converter(id, *converter, inputs);
+            }
+            RecordType::PlaceConstraint { on, typ, .. } => self.place_constraint(*on, *typ),
+            RecordType::RemoveConstraint { from, typ, .. } => self.remove_constraint(*from, *typ),
+            RecordType::GiveEnvoy { player, to } => self.give_envoy(*player, *to),
+            RecordType::ReturnEnvoy { player } => self.return_envoy(*player),
+            RecordType::PlaceCrossToken { colony, .. } => self.place_cross_token(*colony),
+            RecordType::SpawnCubes {
+                player,
+                typ,
+                qty,
+                donation,
+                from,
+            } => self.spawn_cubes_from(*player, *typ, *qty, *donation, *from),
+            RecordType::DestroyCubes { cubes, to } => {
+                for c in cubes.iter() {
+                    self.destroy_cube(*c, *to);
+                }
+            }
+            RecordType::RunColony { colony, inputs } => self.run_colony(id, *colony, inputs),
+            RecordType::DoubleColony { colony, .. } => self.double_colony(*colony),
+            RecordType::UndoubleColony { colony, .. } => self.undouble_colony(*colony),
+            RecordType::ColonyRetrocontinuity { colony, inputs } => {
+                self.retrocontinue_colony(id, *colony, inputs);
+            }
+            RecordType::TradeRetroTokens {
+                a,
+                b,
+                a_tokens,
+                b_tokens,
+            } => self.trade_retro_tokens(id, *a, *b, a_tokens, b_tokens),
+            RecordType::TradeItems {
+                a,
+                b,
+                a_items,
+                b_items,
+            } => self.trade_items(id, *a, *b, a_items, b_items),
+            RecordType::License { player, tech } => {
+                self.licenses.push(licensing::License {
+                    tech: *tech,
+                    licensee: *player,
+                    confluence: self.confluence,
+                });
+                if let Some(conv) = self.tech_converter_for(*player, *tech) {
+                    self.add_converter(id, *player, Box::new(conv));
+                }
+                self.emit(
+                    id,
+                    GameEvent::Licensed {
+                        player: *player,
+                        tech: *tech,
+                    },
+                );
+            }
+            RecordType::GiveAcknowledgement { player } => {
+                if let Some(owed) = self.owed_acknowledgements.get_mut(player) {
+                    *owed = owed.saturating_sub(1);
+                }
+                *self.acknowledgements.entry(*player).or_default() += 1;
+                self.spawn_cubes(*player, CubeType::VictoryPoint, 1, None);
+                self.emit(id, GameEvent::Acknowledged { player: *player });
+            }
+            RecordType::UpgradeConverter {
+                conv,
+                opt,
+                colonies,
+            } => self.upgrade_converter(id, *conv, *opt, colonies),
+        }
+    }
+
+    /// Cubes of exactly a given type owned by a player, in ID order.
+    fn owned_cubes(&self, player: PlayerID, typ: CubeType) -> Vec<CubeID> {
+        let mut cubes: Vec<CubeID> = self
+            .owned
+            .cubes(player)
+            .filter(|c| self.cubes.get(c).is_some_and(|c| c.typ == typ))
+            .collect();
+        cubes.sort();
+        cubes
+    }
+
+    /// Transfers ownership of a cube to a player.
+    fn move_cube(&mut self, cube: CubeID, to: PlayerID) {
+        let Some(typ) = self.cubes.get(&cube).map(|c| c.typ) else {
+            return;
+        };
+        let from = self.cube_owners.insert(cube, to);
+        self.owned.move_cube(cube, from, Some(to));
+        if let Some(from) = from {
+            self.stats.provenance.record(
+                cube,
+                typ,
+                FlowNode::Player(from),
+                FlowNode::Player(to),
+                self.confluence,
+            );
+        }
+    }
+
+    /// Removes a cube from the game, recording where it went.
+    fn destroy_cube(&mut self, cube: CubeID, to: FlowNode) {
+        let Some(c) = self.cubes.remove(&cube) else {
+            return;
+        };
+        let from = self.cube_owners.remove(&cube);
+        self.owned.move_cube(cube, from, None);
+        if let Some(from) = from {
+            self.stats
+                .provenance
+                .record(cube, c.typ, FlowNode::Player(from), to, self.confluence);
+        }
+    }
+
+    /// Puts a new converter into play, owned by a player.
+    fn add_converter(
+        &mut self,
+        rec: RecordID,
+        player: PlayerID,
+        conv: Box<dyn Convert>,
+    ) -> ConverterID {
+        let id = self.next_converter_id;
+        self.next_converter_id = ConverterID(id.0 + 1);
+        if !conv.tradable() {
+            self.untradable_converters.insert(id);
+        }
+        if !conv.loanable() {
+            self.unloanable_converters.insert(id);
+        }
+        self.converters.insert(id, conv);
+        self.transfer_converter(rec, id, player);
+        id
+    }
+
+    /// Takes a converter out of play.
+    fn remove_converter(&mut self, conv: ConverterID) {
+        self.converters.remove(&conv);
+        let owner = self.converter_owners.remove(&conv);
+        self.owned.move_converter(conv, owner, None);
+        self.original_owners.remove(&conv);
+        self.marked_converters.remove(&conv);
+        self.ran_converters.remove(&conv);
+        self.untradable_converters.remove(&conv);
+        self.unloanable_converters.remove(&conv);
+        self.halved_converters.remove(&conv);
+        self.locked_service.remove(&conv);
+    }
+
+    /// Changes a converter's owner, remembering which record caused it.
+    fn transfer_converter(&mut self, rec: RecordID, conv: ConverterID, to: PlayerID) {
+        let from = self.converter_owners.insert(conv, to);
+        self.owned.move_converter(conv, from, Some(to));
+        self.converter_history
+            .entry(conv)
+            .or_default()
+            .push((to, rec));
+    }
+
+    /// Every owner a converter has had, oldest first, along with the record
+    /// group that gave it to them. The first entry is the record that put
+    /// the converter into play.
+    pub fn ownership_history(&self, conv: ConverterID) -> Vec<(PlayerID, &RecordGroup)> {
+        self.converter_history
+            .get(&conv)
+            .map(|h| {
+                h.iter()
+                    .filter_map(|(p, r)| self.record(*r).map(|g| (*p, g)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Looks up an applied record group by ID. Groups moved out of memory
+    /// by compaction aren't found.
+    pub fn record(&self, id: RecordID) -> Option<&RecordGroup> {
+        self.log.iter().find(|g| g.id == id)
+    }
+
+    /// Creates new cubes owned by a player, numbered sequentially from
+    /// `next_cube_id`.
+    fn spawn_cubes(
+        &mut self,
+        player: PlayerID,
+        typ: CubeType,
+        qty: usize,
+        donation: Option<PlayerID>,
+    ) {
+        self.spawn_cubes_from(player, typ, qty, donation, FlowNode::Supply)
+    }
+
+    /// Like `spawn_cubes`, but recording that the cubes came from somewhere
+    /// other than the supply (such as a converter).
+    fn spawn_cubes_from(
+        &mut self,
+        player: PlayerID,
+        typ: CubeType,
+        qty: usize,
+        donation: Option<PlayerID>,
+        from: FlowNode,
+    ) {
+        for _ in 0..qty {
+            let id = self.next_cube_id;
+            self.next_cube_id = CubeID(id.0 + 1);
+            self.cubes.insert(id, Cube::new(typ, donation));
+            self.cube_owners.insert(id, player);
+            self.owned.move_cube(id, None, Some(player));
+            self.stats
+                .provenance
+                .record(id, typ, from, FlowNode::Player(player), self.confluence);
+        }
+    }
+
+    /// The ID the next record group applied to this game should have.
+    pub fn next_record_id(&self) -> RecordID {
+        self.next_record_id
+    }
+
+    /// The config this game was set up with.
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Every record group applied to this game so far, in order. Once the
+    /// game is being compacted this is only the most recent groups, see
+    /// `full_log`.
+    pub fn log(&self) -> &[RecordGroup] {
+        self.log.as_slice()
+    }
+
+    /// The current confluence.
+    pub fn confluence(&self) -> Confluence {
+        self.confluence
+    }
+
+    /// The current phase.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// The game data this game is using.
+    pub fn data(&self) -> &GameData {
+        &self.data
+    }
+
+    /// Which faction a player is playing, if they're in the game.
+    pub fn faction(&self, player: PlayerID) -> Option<FactionType> {
+        self.factions.get(&player).copied()
+    }
+
+    /// Whether a player is safe from Zeth stealing.
+    pub fn is_zeth_safe(&self, player: PlayerID) -> bool {
+        self.zeth_safe.contains(&player)
+    }
+
+    /// A converter in play.
+    pub fn converter(&self, id: ConverterID) -> Option<&dyn Convert> {
+        self.converters.get(&id).map(|c| c.as_ref())
+    }
+
+    /// The converters a player currently owns, in ID order.
+    pub fn owned_converters(&self, player: PlayerID) -> Vec<ConverterID> {
+        let mut owned: Vec<ConverterID> = self.owned.converters(player).collect();
+        owned.sort();
+        owned
+    }
+
+    /// The research teams a player owns which haven't been invented yet,
+    /// in ID order.
+    pub fn research_teams(&self, player: PlayerID) -> Vec<TechID> {
+        let mut teams: Vec<TechID> = self
+            .tech_team_owners
+            .iter()
+            .filter(|(_, p)| **p == player)
+            .map(|(t, _)| *t)
+            .collect();
+        teams.sort();
+        teams
+    }
+
+    /// A forecast of the cubes a player gains each confluence, assuming they
+    /// run every white converter and colony they own, with doubled colonies
+    /// taking and producing twice. Inputs count against the income, so this
+    /// may be negative for some cube types.
+    pub fn income(&self, player: PlayerID) -> CubeRecord {
+        let mut income = CubeRecord::default();
+        let converters = self
+            .owned
+            .converters(player)
+            .filter_map(|c| self.converters.get(&c))
+            .map(|c| (c.as_ref() as &dyn Convert, 1));
+        let colonies = self
+            .colony_owners
+            .iter()
+            .filter(|(_, p)| **p == player)
+            .filter_map(|(c, _)| Some((self.data.colony.get(c)?, self.colony_multiplier(*c))))
+            .map(|(c, times)| (c as &dyn Convert, times));
+        for (c, times) in converters
+            .chain(colonies)
+            .filter(|(c, _)| c.color() == Arrow::White)
+        {
+            for i in c.output() {
+                if let Item::Cubes(typ, qty) | Item::DonationCubes(typ, qty) = i {
+                    income.add_type(*typ, (*qty * times) as isize);
+                }
+            }
+            for i in c.input() {
+                if let Item::Cubes(typ, qty) | Item::DonationCubes(typ, qty) = i {
+                    income.add_type(*typ, -((*qty * times) as isize));
+                }
+            }
+        }
+        income
+    }
+
+    /// Statistics collected so far this game.
+    pub fn stats(&self) -> &GameStats {
+        &self.stats
+    }
+
+    pub fn get_player_cubes(&self, id: PlayerID) -> CubeRecord {
+        self.owned
+            .cubes(id)
+            .filter_map(|c| self.cubes.get(&c))
+            .collect()
+    }
+}
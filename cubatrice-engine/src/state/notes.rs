@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{colony::ColonyID, converter::ConverterID};
+
+use super::{player::PlayerID, record::RecordID, GameState};
+
+/// A private note a player has made, for keeping track of informal deals the
+/// engine doesn't enforce. Notes are only ever shown to the player who made
+/// them.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Note {
+    /// The record which added the note, used to refer to it later.
+    pub id: RecordID,
+    /// What the note is attached to.
+    pub target: NoteTarget,
+    pub text: String,
+}
+
+/// Something a note can be attached to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NoteTarget {
+    /// Not attached to anything in particular.
+    Game,
+    Player(PlayerID),
+    Converter(ConverterID),
+    Colony(ColonyID),
+}
+
+impl GameState {
+    /// A player's notes, oldest first.
+    pub fn notes(&self, player: PlayerID) -> &[Note] {
+        self.notes
+            .get(&player)
+            .map(|n| n.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// A player's notes attached to a particular target.
+    pub fn notes_on(&self, player: PlayerID, target: NoteTarget) -> Vec<&Note> {
+        self.notes(player)
+            .iter()
+            .filter(|n| n.target == target)
+            .collect()
+    }
+}
@@ -0,0 +1,37 @@
+use std::sync::mpsc::{channel, Receiver};
+
+use serde::{Deserialize, Serialize};
+
+use super::{events::GameEvent, record::RecordGroup, GameState};
+
+/// What subscribers to a game are told as it changes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Notification {
+    /// A record group was applied, emitting these events.
+    Applied {
+        group: RecordGroup,
+        events: Vec<GameEvent>,
+    },
+    /// The most recent record group was undone.
+    Undone { group: RecordGroup },
+}
+
+impl GameState {
+    /// Subscribes to the game, so UIs, loggers and bots can react to it
+    /// changing without polling the whole state. Every record group applied
+    /// or undone from now on is sent to the receiver. Dropping the receiver
+    /// unsubscribes.
+    ///
+    /// Subscriptions aren't saved, and aren't carried over to forks.
+    pub fn subscribe(&mut self) -> Receiver<Notification> {
+        let (tx, rx) = channel();
+        self.subscribers.push(tx);
+        rx
+    }
+
+    /// Sends a notification to every subscriber, forgetting any who have
+    /// gone away.
+    pub(crate) fn notify(&mut self, note: Notification) {
+        self.subscribers.retain(|s| s.send(note.clone()).is_ok());
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{colony::ColonyID, converter::ConverterID, cube::CubeID};
+
+use super::{
+    player::PlayerID,
+    record::{RecordID, RecordType},
+    GameState,
+};
+
+/// One side of a proposed trade.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TradeTerms {
+    pub cubes: BTreeSet<CubeID>,
+    pub colonies: BTreeSet<ColonyID>,
+    pub converters: BTreeSet<ConverterID>,
+    /// Retrocontinuity tokens, named by the player each belongs to.
+    #[serde(default)]
+    pub retro_tokens: BTreeSet<PlayerID>,
+}
+
+impl TradeTerms {
+    pub fn is_empty(&self) -> bool {
+        self.cubes.is_empty()
+            && self.colonies.is_empty()
+            && self.converters.is_empty()
+            && self.retro_tokens.is_empty()
+    }
+}
+
+/// A trade one player has proposed to another, which hasn't been accepted
+/// or rejected yet.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TradeOffer {
+    /// The record which proposed the trade, used to refer to it.
+    pub id: RecordID,
+    /// The player proposing the trade.
+    pub from: PlayerID,
+    /// The player the trade is proposed to.
+    pub to: PlayerID,
+    /// What `from` gives.
+    pub give: TradeTerms,
+    /// What `from` gets in return.
+    pub take: TradeTerms,
+    /// Whether converters change hands permanently, or are only lent for
+    /// the economy phase.
+    pub permanent: bool,
+}
+
+impl TradeOffer {
+    /// The records which carry out the trade once it's accepted.
+    pub fn records(&self) -> Vec<RecordType> {
+        let mut out = Vec::new();
+        if !self.give.cubes.is_empty() || !self.take.cubes.is_empty() {
+            out.push(RecordType::TradeCubes {
+                a: self.from,
+                b: self.to,
+                a_cubes: self.give.cubes.clone(),
+                b_cubes: self.take.cubes.clone(),
+            });
+        }
+        if !self.give.colonies.is_empty() || !self.take.colonies.is_empty() {
+            out.push(RecordType::TradeColony {
+                a: self.from,
+                b: self.to,
+                a_colony: self.give.colonies.clone(),
+                b_colony: self.take.colonies.clone(),
+            });
+        }
+        if !self.give.converters.is_empty() || !self.take.converters.is_empty() {
+            out.push(RecordType::TradeConverter {
+                a: self.from,
+                b: self.to,
+                a_converter: self.give.converters.clone(),
+                b_converter: self.take.converters.clone(),
+                permanent: self.permanent,
+            });
+        }
+        if !self.give.retro_tokens.is_empty() || !self.take.retro_tokens.is_empty() {
+            out.push(RecordType::TradeRetroTokens {
+                a: self.from,
+                b: self.to,
+                a_tokens: self.give.retro_tokens.clone(),
+                b_tokens: self.take.retro_tokens.clone(),
+            });
+        }
+        out
+    }
+}
+
+impl GameState {
+    /// Every trade which has been proposed and not yet accepted or rejected,
+    /// oldest first.
+    pub fn pending_offers(&self) -> &[TradeOffer] {
+        &self.pending_offers
+    }
+
+    /// A pending offer, by the ID of the record which proposed it.
+    pub fn offer(&self, id: RecordID) -> Option<&TradeOffer> {
+        self.pending_offers.iter().find(|o| o.id == id)
+    }
+
+    /// Pending offers a player has made or received.
+    pub fn offers_involving(&self, player: PlayerID) -> Vec<&TradeOffer> {
+        self.pending_offers
+            .iter()
+            .filter(|o| o.from == player || o.to == player)
+            .collect()
+    }
+}
@@ -0,0 +1,142 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use crate::entity::faction::FactionType;
+
+use super::player::PlayerID;
+
+/// The order players take from a bid track once bids are revealed.
+///
+/// `bids` holds each player's bid and optional second bid, in ships.
+/// `factions` lists every player with their faction, in seating order, and
+/// `regret` how many regret each player holds.
+///
+/// - The highest bid goes first, and ties go to the earlier seat.
+/// - Players holding any regret go after everyone who doesn't, whatever
+///   they bid.
+/// - Second bids only count for factions that can split a bid (the Kjas
+///   Directorate for colonies, the Society of Falling Light for research
+///   teams), and give the player a second entry, ordered like any other
+///   bid.
+/// - Players who didn't bid, or aren't in `factions`, are left out.
+pub fn bid_order(
+    bids: &HashMap<PlayerID, (usize, Option<usize>)>,
+    factions: &[(PlayerID, FactionType)],
+    regret: &HashMap<PlayerID, usize>,
+) -> Vec<PlayerID> {
+    let mut entries: Vec<(bool, Reverse<usize>, usize, PlayerID)> = Vec::new();
+    for (seat, (player, faction)) in factions.iter().enumerate() {
+        let Some((first, second)) = bids.get(player) else {
+            continue;
+        };
+        let regretful = regret.get(player).is_some_and(|r| *r > 0);
+        entries.push((regretful, Reverse(*first), seat, *player));
+        if let Some(s) = second.filter(|_| splits_bids(*faction)) {
+            entries.push((regretful, Reverse(s), seat, *player));
+        }
+    }
+    entries.sort();
+    entries.into_iter().map(|(_, _, _, p)| p).collect()
+}
+
+/// Whether a faction may split a bid in two.
+fn splits_bids(faction: FactionType) -> bool {
+    matches!(faction, FactionType::KjasCore | FactionType::FaderanAlt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use FactionType::{
+        CaylionCore as Caylion, FaderanAlt as Faderan, KjasCore as Kjas, ZethCore as Zeth,
+    };
+
+    /// A seat's faction, bid and regret.
+    type Seat = (FactionType, (usize, Option<usize>), usize);
+
+    /// Players are numbered from 1 in seating order.
+    fn order(seats: &[Seat]) -> Vec<usize> {
+        let player = |i: usize| PlayerID(i + 1);
+        let factions: Vec<_> = seats
+            .iter()
+            .enumerate()
+            .map(|(i, (f, _, _))| (player(i), *f))
+            .collect();
+        let bids = seats
+            .iter()
+            .enumerate()
+            .map(|(i, (_, b, _))| (player(i), *b))
+            .collect();
+        let regret = seats
+            .iter()
+            .enumerate()
+            .map(|(i, (_, _, r))| (player(i), *r))
+            .collect();
+        bid_order(&bids, &factions, &regret)
+            .into_iter()
+            .map(|p| p.0)
+            .collect()
+    }
+
+    #[test]
+    fn orders_bids() {
+        #[rustfmt::skip]
+        let cases: &[(&str, &[Seat], &[usize])] = &[
+            ("highest bid first",
+             &[(Caylion, (1, None), 0), (Zeth, (3, None), 0)],
+             &[2, 1]),
+            ("ties go to the earlier seat",
+             &[(Zeth, (2, None), 0), (Caylion, (2, None), 0)],
+             &[1, 2]),
+            ("nobody bids",
+             &[(Zeth, (0, None), 0), (Caylion, (0, None), 0)],
+             &[1, 2]),
+            ("regret goes last",
+             &[(Zeth, (5, None), 1), (Caylion, (1, None), 0)],
+             &[2, 1]),
+            ("regret goes last on a tie",
+             &[(Zeth, (2, None), 2), (Caylion, (2, None), 0)],
+             &[2, 1]),
+            ("regret orders by bid",
+             &[(Zeth, (1, None), 1), (Caylion, (4, None), 3)],
+             &[2, 1]),
+            ("kjas split around another bid",
+             &[(Kjas, (5, Some(2)), 0), (Zeth, (3, None), 0)],
+             &[1, 2, 1]),
+            ("kjas split ties an earlier seat",
+             &[(Zeth, (3, None), 0), (Kjas, (3, Some(3)), 0)],
+             &[1, 2, 2]),
+            ("kjas split ties a later seat",
+             &[(Kjas, (3, Some(3)), 0), (Zeth, (3, None), 0)],
+             &[1, 1, 2]),
+            ("kjas second bid above the first",
+             &[(Kjas, (1, Some(4)), 0), (Zeth, (2, None), 0)],
+             &[1, 2, 1]),
+            ("kjas zero second bid",
+             &[(Kjas, (2, Some(0)), 0), (Zeth, (1, None), 0)],
+             &[1, 2, 1]),
+            ("faderan split",
+             &[(Zeth, (2, None), 0), (Faderan, (4, Some(1)), 0)],
+             &[2, 1, 2]),
+            ("split with regret goes last",
+             &[(Faderan, (6, Some(5)), 1), (Zeth, (1, None), 0)],
+             &[2, 1, 1]),
+            ("regret splits around other regret",
+             &[(Kjas, (3, Some(1)), 1), (Zeth, (2, None), 1), (Caylion, (0, None), 0)],
+             &[3, 1, 2, 1]),
+            ("only kjas and faderan split",
+             &[(Caylion, (1, Some(5)), 0), (Zeth, (2, None), 0)],
+             &[2, 1]),
+        ];
+        for (name, seats, expected) in cases {
+            assert_eq!(order(seats), *expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn leaves_out_players_who_didnt_bid() {
+        let factions = [(PlayerID(1), Zeth), (PlayerID(2), Kjas)];
+        let bids = HashMap::from([(PlayerID(2), (1, Some(1))), (PlayerID(3), (9, None))]);
+        let order = bid_order(&bids, &factions, &HashMap::new());
+        assert_eq!(order, vec![PlayerID(2), PlayerID(2)]);
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::entity::{converter::ConverterID, cube::CubeID};
+
+use super::player::PlayerID;
+
+/// What each player owns, indexed by player. This mirrors `cube_owners` and
+/// `converter_owners`, so that asking what one player holds doesn't mean
+/// scanning everything in the game. It isn't saved, and is rebuilt from
+/// those maps on load.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct OwnershipIndex {
+    cubes: HashMap<PlayerID, HashSet<CubeID>>,
+    converters: HashMap<PlayerID, HashSet<ConverterID>>,
+}
+
+impl OwnershipIndex {
+    /// Builds the index from scratch.
+    pub(crate) fn build(
+        cube_owners: &HashMap<CubeID, PlayerID>,
+        converter_owners: &HashMap<ConverterID, PlayerID>,
+    ) -> Self {
+        let mut index = Self::default();
+        for (cube, player) in cube_owners {
+            index.move_cube(*cube, None, Some(*player));
+        }
+        for (conv, player) in converter_owners {
+            index.move_converter(*conv, None, Some(*player));
+        }
+        index
+    }
+
+    /// Records a cube changing hands. None means the cube is coming into or
+    /// going out of the game.
+    pub(crate) fn move_cube(&mut self, cube: CubeID, from: Option<PlayerID>, to: Option<PlayerID>) {
+        move_between(&mut self.cubes, cube, from, to);
+    }
+
+    /// Records a converter changing hands. None means the converter is
+    /// coming into or going out of play.
+    pub(crate) fn move_converter(
+        &mut self,
+        conv: ConverterID,
+        from: Option<PlayerID>,
+        to: Option<PlayerID>,
+    ) {
+        move_between(&mut self.converters, conv, from, to);
+    }
+
+    /// Cubes a player owns, in no particular order.
+    pub(crate) fn cubes(&self, player: PlayerID) -> impl Iterator<Item = CubeID> + '_ {
+        self.cubes.get(&player).into_iter().flatten().copied()
+    }
+
+    /// Converters a player owns, in no particular order.
+    pub(crate) fn converters(&self, player: PlayerID) -> impl Iterator<Item = ConverterID> + '_ {
+        self.converters.get(&player).into_iter().flatten().copied()
+    }
+}
+
+fn move_between<T: Copy + Eq + std::hash::Hash>(
+    index: &mut HashMap<PlayerID, HashSet<T>>,
+    item: T,
+    from: Option<PlayerID>,
+    to: Option<PlayerID>,
+) {
+    if let Some(from) = from {
+        if let Some(set) = index.get_mut(&from) {
+            set.remove(&item);
+            if set.is_empty() {
+                index.remove(&from);
+            }
+        }
+    }
+    if let Some(to) = to {
+        index.entry(to).or_default().insert(item);
+    }
+}
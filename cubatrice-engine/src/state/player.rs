@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::faction::FactionType;
+
+pub use crate::entity::player::PlayerID;
+
+use super::GameState;
+
+/// Who makes a player's decisions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Controller {
+    #[default]
+    Human,
+    Ai,
+}
+
+/// How a player is shown to other players. Kept in the game, so frontends
+/// don't need to store it themselves.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Profile {
+    /// Display name. Players without a name are shown as "Player <id>".
+    pub name: Option<String>,
+    pub controller: Controller,
+    /// Whether the player's client is connected. Always false for players
+    /// who've never connected, such as in a hotseat game.
+    pub connected: bool,
+}
+
+/// Everything a frontend needs to show a player, from their profile and
+/// their place in the game.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Player {
+    pub id: PlayerID,
+    pub name: String,
+    pub faction: FactionType,
+    /// Which seat the player is in, counting clockwise from 0.
+    pub seat: Option<usize>,
+    pub controller: Controller,
+    pub connected: bool,
+}
+
+impl GameState {
+    /// A player's profile. Players who haven't set one have the default.
+    pub fn profile(&self, player: PlayerID) -> Profile {
+        self.profiles.get(&player).cloned().unwrap_or_default()
+    }
+
+    /// The name a player is shown as.
+    pub fn player_name(&self, player: PlayerID) -> String {
+        self.profile(player)
+            .name
+            .unwrap_or_else(|| format!("Player {}", player.0))
+    }
+
+    /// A player in the game, if they exist.
+    pub fn player(&self, player: PlayerID) -> Option<Player> {
+        let faction = *self.factions.get(&player)?;
+        let profile = self.profile(player);
+        Some(Player {
+            id: player,
+            name: self.player_name(player),
+            faction,
+            seat: self.seat(player),
+            controller: profile.controller,
+            connected: profile.connected,
+        })
+    }
+
+    /// Every player in the game, in ascending ID order.
+    pub fn players(&self) -> Vec<Player> {
+        let mut ids: Vec<PlayerID> = self.factions.keys().copied().collect();
+        ids.sort();
+        ids.into_iter().filter_map(|p| self.player(p)).collect()
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    entity::cube::{CubeID, CubeType},
+    stats::provenance::FlowNode,
+};
+
+use super::{
+    player::PlayerID,
+    record::RecordType,
+    validation::{ensure, ValidationError},
+    GameState,
+};
+
+impl GameState {
+    /// The ID the next cube created will have.
+    pub fn next_cube_id(&self) -> CubeID {
+        self.next_cube_id
+    }
+
+    /// Checks that new cubes can be created for a player. Only physical
+    /// cubes can exist, and donation cubes must come from a player in the
+    /// game.
+    pub(crate) fn can_spawn_cubes(
+        &self,
+        player: PlayerID,
+        typ: CubeType,
+        donation: Option<PlayerID>,
+    ) -> Result<(), ValidationError> {
+        for p in std::iter::once(player).chain(donation) {
+            ensure(
+                self.factions.contains_key(&p),
+                ValidationError::NoSuchPlayer { player: p },
+            )?;
+        }
+        ensure(!typ.is_virtual(), ValidationError::VirtualCube { typ })
+    }
+
+    /// Checks that every cube being destroyed exists.
+    pub(crate) fn can_destroy_cubes(
+        &self,
+        cubes: &BTreeSet<CubeID>,
+    ) -> Result<(), ValidationError> {
+        for c in cubes.iter() {
+            ensure(
+                self.cubes.contains_key(c),
+                ValidationError::NoSuchCube { cube: *c },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The record creating cubes a converter or colony produces for a player. A
+/// choice of cube is produced as the wild cube of that size.
+pub(crate) fn spawn_record(
+    player: PlayerID,
+    typ: CubeType,
+    qty: usize,
+    donation: Option<PlayerID>,
+    from: FlowNode,
+) -> RecordType {
+    let typ = match typ {
+        CubeType::AnySmall | CubeType::AnySmallNonUnity => CubeType::UnitySmall,
+        CubeType::AnyLarge | CubeType::AnyLargeNonUnity => CubeType::UnityLarge,
+        t => t,
+    };
+    RecordType::SpawnCubes {
+        player,
+        typ,
+        qty,
+        donation,
+        from,
+    }
+}
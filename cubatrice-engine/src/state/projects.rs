@@ -0,0 +1,110 @@
+use crate::entity::{
+    converter::ConverterID,
+    faction::alt_caylion::{ProjectConverter, ProjectID, ProjectState},
+};
+
+use super::{player::PlayerID, record::RecordID, GameState};
+
+impl GameState {
+    /// Every project in the game and its state, in ID order.
+    pub fn projects(&self) -> Vec<(ProjectID, ProjectState)> {
+        let mut projects: Vec<(ProjectID, ProjectState)> =
+            self.project_status.iter().map(|(p, s)| (*p, *s)).collect();
+        projects.sort();
+        projects
+    }
+
+    /// The state of a project, if it's in the game.
+    pub fn project_state(&self, project: ProjectID) -> Option<ProjectState> {
+        self.project_status.get(&project).copied()
+    }
+
+    /// Players who have voted for a project, in the order they voted.
+    pub fn project_votes(&self, project: ProjectID) -> &[PlayerID] {
+        self.project_votes
+            .get(&project)
+            .map(|v| v.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Converters handed out by an active project, in ID order.
+    pub fn project_converters(&self, project: ProjectID) -> Vec<ConverterID> {
+        let mut convs: Vec<ConverterID> = self
+            .project_converters
+            .get(&project)
+            .map(|c| c.iter().copied().collect())
+            .unwrap_or_default();
+        convs.sort();
+        convs
+    }
+
+    /// Puts every project in the game data into play. The front of each card
+    /// can be voted on, and its reverse is unavailable. Of two sides which
+    /// both list each other, the lower ID is the front.
+    pub(crate) fn setup_projects(&mut self) {
+        let mut ids: Vec<ProjectID> = self.data.projects.keys().copied().collect();
+        ids.sort();
+        for id in ids {
+            if self.project_status.contains_key(&id) {
+                continue;
+            }
+            self.project_status.insert(id, ProjectState::Idle);
+            if let Some(r) = self.data.projects[&id].reverse {
+                self.project_status.insert(r, ProjectState::Unavailable);
+            }
+        }
+    }
+
+    pub(crate) fn vote_for_project(&mut self, player: PlayerID, project: ProjectID) {
+        let votes = self.project_votes.entry(project).or_default();
+        votes.push(player);
+        let needed = self
+            .data
+            .projects
+            .get(&project)
+            .map(|p| p.votes_needed)
+            .unwrap_or_default();
+        if votes.len() >= needed {
+            self.project_status.insert(project, ProjectState::Pending);
+        }
+    }
+
+    /// Activates a project, giving every voter a copy of each of its
+    /// converters.
+    pub(crate) fn activate_project(&mut self, rec: RecordID, project: ProjectID) {
+        let Some(p) = self.data.projects.get(&project).cloned() else {
+            return;
+        };
+        for voter in self.project_votes(project).to_vec() {
+            for conv in p.converters.iter() {
+                let c = ProjectConverter {
+                    project,
+                    name: p.name.clone(),
+                    conv: conv.clone(),
+                };
+                let id = self.add_converter(rec, voter, Box::new(c));
+                self.project_converters
+                    .entry(project)
+                    .or_default()
+                    .insert(id);
+            }
+        }
+        self.project_status.insert(project, ProjectState::Active);
+    }
+
+    /// Completes a project: its converters leave play, its votes are
+    /// cleared, and the card is flipped so the reverse side can be voted on.
+    pub(crate) fn complete_project(&mut self, project: ProjectID) {
+        for c in self.project_converters(project) {
+            self.remove_converter(c);
+        }
+        self.project_converters.remove(&project);
+        self.project_votes.remove(&project);
+        self.project_status
+            .insert(project, ProjectState::Unavailable);
+        let reverse = self.data.projects.get(&project).and_then(|p| p.reverse);
+        if let Some(r) = reverse {
+            self.project_status.insert(r, ProjectState::Idle);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    player::PlayerID,
+    record::RecordID,
+    validation::{ensure, ValidationError},
+    Confluence, GameState,
+};
+
+/// A promise one player has made to another as part of a deal, such as "I'll
+/// vote for your project". Promises are only recorded, never enforced:
+/// either player may mark one kept or broken.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Promise {
+    /// The record which made the promise, used to refer to it.
+    pub id: RecordID,
+    /// The player who made the promise.
+    pub from: PlayerID,
+    /// The player the promise was made to.
+    pub to: PlayerID,
+    pub text: String,
+    /// The confluence the promise was made in.
+    pub made: Confluence,
+    pub status: PromiseStatus,
+}
+
+/// Whether a promise has been kept.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PromiseStatus {
+    #[default]
+    Outstanding,
+    Kept,
+    Broken,
+}
+
+impl GameState {
+    /// Every promise made this game, in the order they were made.
+    pub fn promises(&self) -> &[Promise] {
+        &self.promises
+    }
+
+    /// A promise, by the ID of the record which made it.
+    pub fn promise(&self, id: RecordID) -> Option<&Promise> {
+        self.promises.iter().find(|p| p.id == id)
+    }
+
+    /// Promises which haven't been marked kept or broken yet.
+    pub fn outstanding_promises(&self) -> Vec<&Promise> {
+        self.promises
+            .iter()
+            .filter(|p| p.status == PromiseStatus::Outstanding)
+            .collect()
+    }
+
+    /// How many promises a player has kept and broken.
+    pub fn promise_record(&self, player: PlayerID) -> (usize, usize) {
+        let count = |status| {
+            self.promises
+                .iter()
+                .filter(|p| p.from == player && p.status == status)
+                .count()
+        };
+        (count(PromiseStatus::Kept), count(PromiseStatus::Broken))
+    }
+
+    /// Checks that a promise can be marked kept or broken. Only the two
+    /// players involved may settle it, and only once.
+    pub(crate) fn can_settle_promise(
+        &self,
+        player: PlayerID,
+        promise: RecordID,
+    ) -> Result<(), ValidationError> {
+        let p = self
+            .promise(promise)
+            .ok_or(ValidationError::NoSuchPromise { promise })?;
+        ensure(
+            player == p.from || player == p.to,
+            ValidationError::NotPromiseParty { player, promise },
+        )?;
+        ensure(
+            p.status == PromiseStatus::Outstanding,
+            ValidationError::PromiseSettled { promise },
+        )
+    }
+
+    pub(crate) fn settle_promise(&mut self, promise: RecordID, kept: bool) {
+        if let Some(p) = self.promises.iter_mut().find(|p| p.id == promise) {
+            p.status = if kept {
+                PromiseStatus::Kept
+            } else {
+                PromiseStatus::Broken
+            };
+        }
+    }
+}
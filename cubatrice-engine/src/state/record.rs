@@ -0,0 +1,466 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::{
+        colony::ColonyID,
+        converter::ConverterID,
+        cube::{CubeID, CubeType},
+        faction::{alt_caylion::ProjectID, FactionType},
+        technology::TechID,
+    },
+    stats::provenance::FlowNode,
+};
+
+use super::{
+    deck::{DeckID, EntityRef},
+    notes::NoteTarget,
+    offers::TradeTerms,
+    player::{PlayerID, Profile},
+    trading::TradeItem,
+    Confluence, Phase,
+};
+
+/// Transparent type for referring to records
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RecordID(pub usize);
+
+/// A record of a GameEvent used to modify gamestate
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RecordType {
+    // TODO
+    // I know I'm going to need more record types later. There's no way to
+    // represent debts currently, or recurring payments, or other things of
+    // that nature.
+    /// Cube portion of a trade. Transfers cube ownership between players
+    TradeCubes {
+        /// Player 'A' in the trade
+        a: PlayerID,
+        /// Player 'B' in the trade
+        b: PlayerID,
+        /// Cubes (currently) owned by A, transfered to B.
+        a_cubes: BTreeSet<CubeID>,
+        /// cubes (currently) owned by B, transfered to A.
+        b_cubes: BTreeSet<CubeID>,
+    },
+    /// Colony portion of a trade. Transfers colony ownership between players
+    TradeColony {
+        /// Player 'A' in the trade
+        a: PlayerID,
+        /// Player 'B' in the trade
+        b: PlayerID,
+        /// Colonies (currently) owned by A, transferred to B.
+        a_colony: BTreeSet<ColonyID>,
+        /// Colonies (currently) owned by B, transferred to A.
+        b_colony: BTreeSet<ColonyID>,
+    },
+    /// Converter portion of a trade. Transfers converter ownership between
+    /// players
+    TradeConverter {
+        /// Player 'A' in the trade
+        a: PlayerID,
+        /// Player 'B' in the trade
+        b: PlayerID,
+        /// Converters (currently) owned by A, transferred to A temporarily
+        a_converter: BTreeSet<ConverterID>,
+        /// Converters (currently) owned by B, transferred to B temporarily
+        b_converter: BTreeSet<ConverterID>,
+        /// Whether the trade is permanent or temporary.
+        permanent: bool,
+    },
+    /// Converter portion of a trade. Transfers converter ownership between
+    /// players permentantly.
+    TradeConverterPermanently {
+        a: PlayerID,
+        b: PlayerID,
+        a_converter: BTreeSet<ConverterID>,
+        b_converter: BTreeSet<ConverterID>,
+    },
+    /// Creates a player with a given faction, adding them and all of their
+    /// resources to the game.
+    CreatePlayer {
+        player: PlayerID,
+        faction: FactionType,
+    },
+    /// Changes the current game phase to the specified phase. Must be the
+    /// phase immediately following the current phase.
+    ChangePhase {
+        to: Phase,
+    },
+    /// Represents a player's bid for colonies and tech teams. Bids must be
+    /// made at the same time. Base Kjas may optionally bid for two colonies,
+    /// and Alt Faderan may optionally bid for two research teams.
+    Bid {
+        player: PlayerID,
+        for_colony: usize,
+        for_colony_kjas: Option<usize>,
+        for_tech: usize,
+        for_tech_faderan: Option<usize>,
+    },
+    /// A Player taking a colony after bidding
+    TakeColony {
+        player: PlayerID,
+        /// Colony is None if the player passed on taking a colony.
+        /// If some, it is an index into colony_bid_track
+        colony: Option<usize>,
+    },
+    /// A Player taking a research team after bidding
+    TakeResearch {
+        player: PlayerID,
+        /// Tech is None if the player passed on taking a tech.
+        /// If some, it is an index into tech_bid_track
+        tech: Option<usize>,
+    },
+    /// A Player inventing a technology
+    InventTech {
+        player: PlayerID,
+        tech: TechID,
+        cost: CubeType,
+    },
+    /// Upgrading a converter with one of its upgrade options, paying its
+    /// cost. Converter cards the upgrade consumes are found automatically.
+    UpgradeConverter {
+        conv: ConverterID,
+        opt: usize,
+        /// Colonies consumed by the upgrade, chosen by its owner. Empty if
+        /// the upgrade doesn't consume any.
+        #[serde(default)]
+        colonies: BTreeSet<ColonyID>,
+    },
+    /// The Faderan acknowledge a player's help, for one of the trades in
+    /// which the player gave them something.
+    GiveAcknowledgement {
+        player: PlayerID,
+    },
+
+    /// The Yengii player licensing a technology to another player
+    License {
+        player: PlayerID,
+        tech: TechID,
+    },
+    /// Applies a retrocontinuity token to a converter, producing its outputs
+    /// during the trade phase instead of the economy phase. The converter
+    /// is run straight away, paying `inputs`, and can't be run again in the
+    /// economy phase this confluence.
+    Retrocontinuity {
+        converter: ConverterID,
+        #[serde(default)]
+        inputs: BTreeSet<CubeID>,
+    },
+    /// Applies a retrocontinuity token to a colony, running it during the
+    /// trade phase with `inputs` from its owner.
+    ColonyRetrocontinuity {
+        colony: ColonyID,
+        #[serde(default)]
+        inputs: BTreeSet<CubeID>,
+    },
+    /// Retrocontinuity token portion of a trade. Tokens are named by the
+    /// player they belong to, and go back to them when the confluence ends.
+    TradeRetroTokens {
+        a: PlayerID,
+        b: PlayerID,
+        /// Tokens held by A, transferred to B.
+        a_tokens: BTreeSet<PlayerID>,
+        /// Tokens held by B, transferred to A.
+        b_tokens: BTreeSet<PlayerID>,
+    },
+    /// A trade of any mix of cubes, colonies, converters, tokens, research
+    /// teams and retrocontinuity tokens. Each item follows the same rules
+    /// as trading it on its own.
+    TradeItems {
+        a: PlayerID,
+        b: PlayerID,
+        /// Items held by A, transferred to B.
+        a_items: Vec<TradeItem>,
+        /// Items held by B, transferred to A.
+        b_items: Vec<TradeItem>,
+    },
+    /// Marks a white converter to be run during the economy phase. Marks are
+    /// cleared when the economy phase ends.
+    MarkConverter {
+        player: PlayerID,
+        converter: ConverterID,
+    },
+    /// Marks every white converter a player owns, except those listed, that
+    /// the player can afford to run alongside the others. Applied as the
+    /// individual marks given by `GameState::expand_mark_all`.
+    MarkAll {
+        player: PlayerID,
+        except: Vec<ConverterID>,
+    },
+    /// Removes the mark from a converter, so it won't be run during the
+    /// economy phase.
+    UnmarkConverter {
+        player: PlayerID,
+        converter: ConverterID,
+    },
+    /// Runs a converter once, consuming `inputs` from its owner and producing
+    /// its outputs as new cubes. White converters must be marked and run
+    /// during the economy phase, purple converters run during the trade
+    /// phase and red converters during the Zeth steal phase.
+    RunConverter {
+        converter: ConverterID,
+        inputs: BTreeSet<CubeID>,
+        /// The player stolen from, for red converters only.
+        #[serde(default)]
+        target: Option<PlayerID>,
+    },
+    /// Runs a white colony once during the economy phase, consuming
+    /// `inputs` from its owner and producing its outputs as new cubes.
+    RunColony {
+        colony: ColonyID,
+        inputs: BTreeSet<CubeID>,
+    },
+    /// The Caylion doubles one of their colonies, so it produces twice its
+    /// outputs whenever it's run.
+    DoubleColony {
+        player: PlayerID,
+        colony: ColonyID,
+    },
+    /// The Caylion stops doubling a colony, freeing up room to double
+    /// another.
+    UndoubleColony {
+        player: PlayerID,
+        colony: ColonyID,
+    },
+    /// The Jii place a constraint on another player, usually as their side
+    /// of a deal. The player can't use cubes of that colour to run
+    /// converters or invent technologies until it's removed.
+    PlaceConstraint {
+        player: PlayerID,
+        on: PlayerID,
+        typ: CubeType,
+    },
+    /// The Jii lift a constraint they placed on a player.
+    RemoveConstraint {
+        player: PlayerID,
+        from: PlayerID,
+        typ: CubeType,
+    },
+    /// The Zeth give another player an envoy, from their own hand if they
+    /// hold one and otherwise from the supply.
+    GiveEnvoy {
+        player: PlayerID,
+        to: PlayerID,
+    },
+    /// A player returns one of their envoys to the supply.
+    ReturnEnvoy {
+        player: PlayerID,
+    },
+    /// The Charity Syndicate put a cross colonization token on a colony up
+    /// for bids. When the colony is later consumed the token comes back and
+    /// they score a point.
+    PlaceCrossToken {
+        player: PlayerID,
+        colony: ColonyID,
+    },
+    /// Creates new cubes for a player, numbered on from the last cube
+    /// created. Running converters and colonies, and setting up a player's
+    /// starting resources, apply these for the cubes they produce. Only the
+    /// engine makes these.
+    SpawnCubes {
+        player: PlayerID,
+        typ: CubeType,
+        qty: usize,
+        /// The player who produced donation cubes, which must be traded
+        /// away.
+        #[serde(default)]
+        donation: Option<PlayerID>,
+        /// Where the cubes came from, for stats.
+        #[serde(default)]
+        from: FlowNode,
+    },
+    /// Removes cubes from the game. Running converters and colonies apply
+    /// these for the cubes they consume. Only the engine makes these.
+    DestroyCubes {
+        cubes: BTreeSet<CubeID>,
+        /// Where the cubes went, for stats.
+        #[serde(default)]
+        to: FlowNode,
+    },
+    /// Creates a named deck, with cards listed from the top down.
+    CreateDeck {
+        deck: DeckID,
+        name: String,
+        cards: Vec<EntityRef>,
+    },
+    /// A player draws the top card of a named deck.
+    DrawFromDeck {
+        deck: DeckID,
+        player: PlayerID,
+    },
+    /// A player votes for an Alt Caylion project. Once it has enough votes
+    /// it's pending, waiting for the Caylion to activate it.
+    VoteForProject {
+        player: PlayerID,
+        project: ProjectID,
+    },
+    /// The Alt Caylion player activates a pending project, giving each voter
+    /// the project's converters.
+    ActivateProject {
+        player: PlayerID,
+        project: ProjectID,
+    },
+    /// The Alt Caylion player completes an active project. Its converters
+    /// leave play, and the reverse side of the card becomes available.
+    CompleteProject {
+        player: PlayerID,
+        project: ProjectID,
+    },
+    /// Seats every player, clockwise from the first seat.
+    AssignSeats {
+        seats: Vec<PlayerID>,
+    },
+    /// Two players swap seats.
+    SwapSeats {
+        a: PlayerID,
+        b: PlayerID,
+    },
+    /// The Faderan player draws the top relic world from the relic deck and
+    /// puts it into play.
+    DrawRelic {
+        player: PlayerID,
+    },
+    /// The Deep Unity player rolls their dice for the confluence, once,
+    /// during the trade phase. The faces come from the game's seed, so
+    /// replaying the log rolls the same.
+    RollUnityDice {
+        player: PlayerID,
+    },
+    /// The Deep Unity player spends a dice change card to set one die to any
+    /// face.
+    ChangeUnityDie {
+        player: PlayerID,
+        /// Index of the die to change
+        die: usize,
+        face: u8,
+    },
+    /// The Deep Unity player gains dice change cards. They're dealt at the
+    /// start of every confluence, so this can't be submitted, and is only
+    /// kept so older logs still replay.
+    GainDiceChangeCards {
+        player: PlayerID,
+        count: usize,
+    },
+    /// The Imdril player raises fleet support, once each economy phase and
+    /// up to `fleet::FLEET_SUPPORT_PER_CONFLUENCE` at a time.
+    GainFleetSupport {
+        player: PlayerID,
+        qty: usize,
+    },
+    /// A player puts one of their service tokens on a white converter,
+    /// halving its cost. The converter can belong to anyone, as the Eni Et
+    /// place tokens on other players' converters as part of a deal.
+    PlaceServiceToken {
+        player: PlayerID,
+        converter: ConverterID,
+    },
+    /// Takes a service token off a converter that hasn't run with it yet,
+    /// giving the token to the converter's owner.
+    RemoveServiceToken {
+        converter: ConverterID,
+    },
+    /// A player looks at the top cards of a named deck without drawing them.
+    PeekDeck {
+        deck: DeckID,
+        player: PlayerID,
+        count: usize,
+    },
+    /// Sets up a payment from one player to another, made automatically at
+    /// the start of every trade phase, until (and including) a given
+    /// confluence or for the rest of the game.
+    RecurringTransfer {
+        from: PlayerID,
+        to: PlayerID,
+        typ: CubeType,
+        qty: usize,
+        until: Option<Confluence>,
+    },
+    /// Two players agree that `debtor` will give `creditor` cubes by a later
+    /// confluence. If `binding`, which both players must agree to, the
+    /// debt is collected in the resolution phase.
+    Contract {
+        debtor: PlayerID,
+        creditor: PlayerID,
+        typ: CubeType,
+        qty: usize,
+        due: Confluence,
+        binding: bool,
+    },
+    /// The debtor pays some or all of what they owe on a contract, referred
+    /// to by the ID of the record which registered it.
+    FulfillContract {
+        contract: RecordID,
+        cubes: BTreeSet<CubeID>,
+    },
+    /// `from` promises `to` something the engine can't enforce, such as a
+    /// vote. The promise is only recorded.
+    Promise {
+        from: PlayerID,
+        to: PlayerID,
+        text: String,
+    },
+    /// One of the players involved marks a promise, referred to by the ID
+    /// of the record which made it, as kept or broken.
+    SettlePromise {
+        player: PlayerID,
+        promise: RecordID,
+        kept: bool,
+    },
+    /// Proposes a trade. Nothing changes hands until the other player accepts.
+    /// If `counters` is given, this is a counter-offer, and the offer it
+    /// counters is withdrawn.
+    ProposeTrade {
+        from: PlayerID,
+        to: PlayerID,
+        give: TradeTerms,
+        take: TradeTerms,
+        permanent: bool,
+        counters: Option<RecordID>,
+    },
+    /// The player a trade was proposed to accepts it, carrying out the trade.
+    AcceptTrade {
+        offer: RecordID,
+    },
+    /// A proposed trade is rejected (or withdrawn by the proposer).
+    RejectTrade {
+        offer: RecordID,
+    },
+    /// A player makes a private note, optionally attached to another player,
+    /// a converter or a colony.
+    AddNote {
+        player: PlayerID,
+        target: NoteTarget,
+        text: String,
+    },
+    /// A player removes one of their notes, referred to by the ID of the
+    /// record which added it.
+    RemoveNote {
+        player: PlayerID,
+        note: RecordID,
+    },
+    /// Shuffles a named deck.
+    ShuffleDeck {
+        deck: DeckID,
+    },
+    /// Builds the research team deck from the game data, for the players
+    /// in the game, and fills the research track from it. See
+    /// `deck::teams_per_tier`.
+    CreateTechDeck,
+    /// Builds the colony deck from the game data, and fills the colony
+    /// track from it.
+    CreateColonyDeck,
+    /// Registers a player's profile, or replaces it.
+    SetProfile {
+        player: PlayerID,
+        profile: Profile,
+    },
+}
+
+/// A Record along with its ID.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RecordGroup {
+    pub id: RecordID,
+    pub rec: Vec<RecordType>,
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::cube::CubeType;
+
+use super::{player::PlayerID, record::RecordID, Confluence, GameState};
+
+/// A standing agreement to pay cubes at the start of every trade phase, such
+/// as "2 green per confluence for the rest of the game".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RecurringTransfer {
+    /// The record which set up the agreement.
+    pub id: RecordID,
+    pub from: PlayerID,
+    pub to: PlayerID,
+    pub typ: CubeType,
+    pub qty: usize,
+    /// The last confluence a payment is due, or None for the rest of the
+    /// game.
+    pub until: Option<Confluence>,
+}
+
+impl RecurringTransfer {
+    /// Whether a payment is due at the start of a confluence's trade phase.
+    pub fn due_in(&self, confluence: Confluence) -> bool {
+        self.until.is_none_or(|u| confluence <= u)
+    }
+}
+
+/// A payment which wasn't made because the payer couldn't afford it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MissedTransfer {
+    pub transfer: RecurringTransfer,
+    pub confluence: Confluence,
+}
+
+impl GameState {
+    /// Every recurring transfer agreed this game, in the order they were
+    /// agreed.
+    pub fn recurring_transfers(&self) -> &[RecurringTransfer] {
+        &self.recurring_transfers
+    }
+
+    /// Payments which were skipped because the payer couldn't afford them.
+    pub fn missed_transfers(&self) -> &[MissedTransfer] {
+        &self.missed_transfers
+    }
+
+    /// Recurring transfers which will be paid at the start of the current
+    /// confluence's trade phase, and which the payer can't currently afford.
+    pub(crate) fn unaffordable_transfers(&self, confluence: Confluence) -> Vec<RecurringTransfer> {
+        self.recurring_transfers
+            .iter()
+            .filter(|t| t.due_in(confluence))
+            .filter(|t| self.owned_cubes(t.from, t.typ).len() < t.qty)
+            .copied()
+            .collect()
+    }
+
+    /// Makes every payment due this confluence, in the order they were
+    /// agreed. Payments the payer can't afford in full are skipped and
+    /// recorded as missed.
+    pub(crate) fn pay_recurring_transfers(&mut self) {
+        let due: Vec<RecurringTransfer> = self
+            .recurring_transfers
+            .iter()
+            .filter(|t| t.due_in(self.confluence))
+            .copied()
+            .collect();
+        for t in due {
+            let cubes = self.owned_cubes(t.from, t.typ);
+            if cubes.len() < t.qty {
+                self.missed_transfers.push(MissedTransfer {
+                    transfer: t,
+                    confluence: self.confluence,
+                });
+                continue;
+            }
+            for c in cubes.into_iter().take(t.qty) {
+                self.move_cube(c, t.to);
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{
+    entity::{
+        converter::{ConverterID, ConverterKind},
+        faction::FactionType,
+        technology::TechID,
+        Token,
+    },
+    stats::market::BidKind,
+};
+
+use super::{ordering, player::PlayerID, GameState};
+
+impl GameState {
+    /// How many regret a player holds.
+    pub fn regret(&self, player: PlayerID) -> usize {
+        self.regret.get(&player).copied().unwrap_or_default()
+    }
+
+    /// Technologies invented by the Society of Falling Light, which give
+    /// regret to whoever they're sold to, in ID order.
+    pub fn regret_techs(&self) -> Vec<TechID> {
+        let mut techs: Vec<TechID> = self.regret_techs.iter().copied().collect();
+        techs.sort();
+        techs
+    }
+
+    /// Gives a player a regret, if any are left in the supply.
+    pub(crate) fn grant_regret(&mut self, player: PlayerID) {
+        if self.supply_of(Token::Regret).can_supply(1) {
+            *self.regret.entry(player).or_default() += 1;
+        }
+    }
+
+    /// Gives `to` a regret for each of the Society's inventions among the
+    /// technologies `from` gave them, if `from` is the Society.
+    pub(crate) fn attach_regret(&mut self, from: PlayerID, to: PlayerID, given: &[TechID]) {
+        if self.factions.get(&from) != Some(&FactionType::FaderanAlt) {
+            return;
+        }
+        for tech in given {
+            if self.regret_techs.contains(tech) {
+                self.grant_regret(to);
+            }
+        }
+    }
+
+    /// Gives `to` a regret for each research team `from` gave them, if
+    /// `from` is the Society. Every team the Society sells carries regret,
+    /// whether or not it's been invented.
+    pub(crate) fn attach_team_regret(&mut self, from: PlayerID, to: PlayerID, teams: usize) {
+        if self.factions.get(&from) != Some(&FactionType::FaderanAlt) {
+            return;
+        }
+        for _ in 0..teams {
+            self.grant_regret(to);
+        }
+    }
+
+    /// The technologies a set of converters were invented from, if any.
+    pub(crate) fn converter_techs<'a, I>(&self, converters: I) -> Vec<TechID>
+    where
+        I: IntoIterator<Item = &'a ConverterID>,
+    {
+        converters
+            .into_iter()
+            .filter_map(|c| self.converter(*c))
+            .filter_map(|c| match c.kind() {
+                ConverterKind::Prototype(p) => Some(p.id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The order players take from a bid track once bids are revealed. See
+    /// `ordering::bid_order`.
+    pub fn bid_order(&self, kind: BidKind) -> Vec<PlayerID> {
+        let bids = match kind {
+            BidKind::Colony => &self.player_colony_bid,
+            BidKind::Tech => &self.player_tech_bid,
+        };
+        let factions: Vec<(PlayerID, FactionType)> = self
+            .seats
+            .iter()
+            .filter_map(|p| self.factions.get(p).map(|f| (*p, *f)))
+            .collect();
+        ordering::bid_order(bids, &factions, &self.regret)
+    }
+
+    /// A player's score: their victory points, less one for each regret.
+    pub fn score(&self, player: PlayerID) -> isize {
+        self.victory_points(player) - self.regret(player) as isize
+    }
+}
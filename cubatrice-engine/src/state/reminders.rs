@@ -0,0 +1,31 @@
+use crate::entity::reminder::Reminder;
+
+use super::{import::parse_phase, player::PlayerID, GameState};
+
+impl GameState {
+    /// Reminders for every faction in play which apply to the current
+    /// phase, for frontends to show as a checklist. Players are in ID
+    /// order, and each player's reminders are in the order the data lists
+    /// them.
+    pub fn reminders(&self) -> Vec<(PlayerID, &Reminder)> {
+        let mut players: Vec<PlayerID> = self.factions.keys().copied().collect();
+        players.sort();
+        players
+            .into_iter()
+            .flat_map(|p| self.reminders_for(p).into_iter().map(move |r| (p, r)))
+            .collect()
+    }
+
+    /// Reminders for one player's faction which apply to the current phase.
+    pub fn reminders_for(&self, player: PlayerID) -> Vec<&Reminder> {
+        let Some(faction) = self.factions.get(&player) else {
+            return Vec::new();
+        };
+        self.data
+            .reminders
+            .iter()
+            .filter(|r| r.faction == *faction)
+            .filter(|r| parse_phase(&r.phase) == Ok(self.phase))
+            .collect()
+    }
+}
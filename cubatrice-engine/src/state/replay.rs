@@ -0,0 +1,108 @@
+use anyhow::{anyhow, Error};
+
+use super::{config::GameConfig, record::RecordGroup, savegame::SaveFile, GameData, GameState};
+
+/// Steps through a recorded game one record group at a time, for reviewing
+/// a game after it's been played. Since records fully determine the state,
+/// any point in the game can be reconstructed from the setup config and the
+/// log.
+///
+/// Positions count record groups applied after setup: position 0 is the game
+/// just after setup, and position `len()` is the end of the log.
+#[derive(Debug)]
+pub struct Replay {
+    data: GameData,
+    config: GameConfig,
+    log: Vec<RecordGroup>,
+    state: GameState,
+    position: usize,
+}
+
+impl Replay {
+    /// Sets up a replay of `log`, the record groups applied after setting up
+    /// a game with `config`. The replay starts at position 0.
+    pub fn new(data: GameData, config: GameConfig, log: Vec<RecordGroup>) -> Result<Self, Error> {
+        let state = GameState::from_config(data.clone(), config.clone())?;
+        Ok(Self {
+            data,
+            config,
+            log,
+            state,
+            position: 0,
+        })
+    }
+
+    /// Sets up a replay of a saved game.
+    pub fn from_save(data: GameData, save: SaveFile) -> Result<Self, Error> {
+        Self::new(data, save.config, save.log)
+    }
+
+    /// The game state at the current position.
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// How many record groups have been applied since setup.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// How many record groups the log has after setup.
+    pub fn len(&self) -> usize {
+        self.log.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.log.is_empty()
+    }
+
+    /// The whole log being replayed.
+    pub fn log(&self) -> &[RecordGroup] {
+        &self.log
+    }
+
+    /// The record group which will be applied by the next `step`, if any.
+    pub fn next_group(&self) -> Option<&RecordGroup> {
+        self.log.get(self.position)
+    }
+
+    /// Applies the next record group. Returns false if already at the end.
+    pub fn step(&mut self) -> Result<bool, Error> {
+        let Some(group) = self.log.get(self.position).cloned() else {
+            return Ok(false);
+        };
+        for r in group.rec.iter() {
+            if let Err(e) = self.state.validate(r) {
+                return Err(anyhow!(
+                    "invalid record {:?} at position {}: {}",
+                    r,
+                    self.position,
+                    e
+                ));
+            }
+        }
+        self.state.apply(group);
+        self.position += 1;
+        Ok(true)
+    }
+
+    /// Moves to a position, replaying the game from setup if it's earlier
+    /// than the current one.
+    pub fn seek(&mut self, position: usize) -> Result<(), Error> {
+        if position > self.log.len() {
+            return Err(anyhow!(
+                "position {} is past the end of the log ({})",
+                position,
+                self.log.len()
+            ));
+        }
+        if position < self.position {
+            self.state = GameState::from_config(self.data.clone(), self.config.clone())?;
+            self.position = 0;
+        }
+        while self.position < position {
+            self.step()?;
+        }
+        Ok(())
+    }
+}
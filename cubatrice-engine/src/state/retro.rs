@@ -0,0 +1,137 @@
+use std::collections::BTreeSet;
+
+use crate::entity::{colony::ColonyID, converter::ConverterID, cube::CubeID};
+
+use super::{
+    events::GameEvent,
+    player::PlayerID,
+    record::RecordID,
+    validation::{ensure, ValidationError},
+    GameState, Phase,
+};
+
+impl GameState {
+    /// Whose retrocontinuity tokens a player holds and hasn't used this
+    /// confluence, in ID order.
+    pub fn retro_tokens(&self, player: PlayerID) -> Vec<PlayerID> {
+        let mut out: Vec<PlayerID> = self
+            .retro_owners
+            .iter()
+            .filter(|(t, o)| **o == player && !self.retro_used.contains(t))
+            .map(|(t, _)| *t)
+            .collect();
+        out.sort();
+        out
+    }
+
+    /// Whether retrocontinuity has been applied to a converter this
+    /// confluence.
+    pub fn converter_retrocontinued(&self, conv: ConverterID) -> bool {
+        self.retro_used_conveter.contains(&conv)
+    }
+
+    /// Whether retrocontinuity has been applied to a colony this
+    /// confluence.
+    pub fn colony_retrocontinued(&self, colony: ColonyID) -> bool {
+        self.retro_used_colony.contains(&colony)
+    }
+
+    /// Checks that it's the trade phase, and the player has a token to use.
+    pub(crate) fn can_retrocontinue(&self, player: PlayerID) -> Result<(), ValidationError> {
+        ensure(
+            self.phase == Phase::Trade,
+            ValidationError::WrongPhase { phase: self.phase },
+        )?;
+        ensure(
+            !self.retro_tokens(player).is_empty(),
+            ValidationError::NoRetroToken { player },
+        )
+    }
+
+    /// Checks that a player holds every one of the tokens they're trading.
+    pub(crate) fn holds_retro_tokens(
+        &self,
+        player: PlayerID,
+        tokens: &BTreeSet<PlayerID>,
+    ) -> Result<(), ValidationError> {
+        for t in tokens.iter() {
+            ensure(
+                self.retro_owners.get(t) == Some(&player),
+                ValidationError::RetroTokenNotHeld { player, token: *t },
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Gives a player joining the game their own token.
+    pub(crate) fn give_retro_token(&mut self, player: PlayerID) {
+        self.retro_owners.insert(player, player);
+    }
+
+    pub(crate) fn trade_retro_tokens(
+        &mut self,
+        id: RecordID,
+        a: PlayerID,
+        b: PlayerID,
+        a_tokens: &BTreeSet<PlayerID>,
+        b_tokens: &BTreeSet<PlayerID>,
+    ) {
+        for t in a_tokens.iter() {
+            self.retro_owners.insert(*t, b);
+        }
+        for t in b_tokens.iter() {
+            self.retro_owners.insert(*t, a);
+        }
+        self.emit(id, GameEvent::Traded { a, b });
+    }
+
+    /// Uses a token on a converter and runs it straight away. It won't run
+    /// again in the economy phase.
+    pub(crate) fn retrocontinue_converter(
+        &mut self,
+        id: RecordID,
+        conv: ConverterID,
+        inputs: &BTreeSet<CubeID>,
+    ) {
+        let Some(player) = self.converter_owners.get(&conv).copied() else {
+            return;
+        };
+        self.use_retro_token(player);
+        self.retro_used_conveter.insert(conv);
+        self.run_converter(id, conv, inputs);
+    }
+
+    /// Uses a token on a colony and runs it, producing its outputs for its
+    /// owner.
+    pub(crate) fn retrocontinue_colony(
+        &mut self,
+        id: RecordID,
+        colony: ColonyID,
+        inputs: &BTreeSet<CubeID>,
+    ) {
+        let Some(owner) = self.colony_owners.get(&colony).copied() else {
+            return;
+        };
+        self.use_retro_token(owner);
+        self.retro_used_colony.insert(colony);
+        self.run_colony(id, colony, inputs);
+    }
+
+    /// Tokens go back to the players they belong to, ready to be used again
+    /// in the new confluence.
+    pub(crate) fn reset_retrocontinuity(&mut self) {
+        for (t, o) in self.retro_owners.iter_mut() {
+            *o = *t;
+        }
+        self.retro_used.clear();
+        self.retro_used_conveter.clear();
+        self.retro_used_colony.clear();
+    }
+
+    /// Marks the lowest numbered token a player holds as used.
+    fn use_retro_token(&mut self, player: PlayerID) {
+        if let Some(t) = self.retro_tokens(player).first() {
+            self.retro_used.insert(*t);
+        }
+    }
+}
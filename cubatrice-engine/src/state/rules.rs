@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+use super::{GameState, Phase};
+
+/// Which interpretation of the rules a game is played under. Rules
+/// interpretations get fixed over time, but old games must still validate
+/// the way they did when they were played, so each game records the version
+/// it was set up with and validation asks the version how to behave for any
+/// rule that has changed.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum RulesVersion {
+    /// The original rules. This is the default so that games recorded
+    /// before versions existed load with the rules they were played under.
+    #[default]
+    V1,
+    /// The game ends after the economy phase of the last confluence: there
+    /// is no colony or tech bid in the last confluence, and resolution can't
+    /// happen before it.
+    V2,
+    /// Cubes and colonies can only change hands during the trade phase, as
+    /// set out in `RulesVersion::phase_rules`.
+    V3,
+}
+
+/// What players may do during a phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PhaseRules {
+    /// Whether players may trade cubes.
+    pub trade_cubes: bool,
+    /// Whether players may trade colonies.
+    pub trade_colonies: bool,
+}
+
+impl RulesVersion {
+    /// The version new games are set up with.
+    pub const CURRENT: RulesVersion = RulesVersion::V3;
+
+    /// Whether the last confluence must go straight from the economy phase to
+    /// resolution. Before V2 either transition was allowed at any time.
+    pub fn strict_game_end(&self) -> bool {
+        *self >= RulesVersion::V2
+    }
+
+    /// What players may do during a phase. Before V3 anything could be
+    /// traded at any time. From V3:
+    /// - Anything may be traded during the trade phase.
+    /// - Cubes may be traded during the economy phase if
+    ///   `economy_deliveries` is set, so deals struck during the trade
+    ///   phase can be delivered once converters have produced what was
+    ///   promised.
+    /// - Nothing may be traded during any other phase.
+    pub fn phase_rules(&self, phase: Phase, economy_deliveries: bool) -> PhaseRules {
+        if *self < RulesVersion::V3 {
+            return PhaseRules {
+                trade_cubes: true,
+                trade_colonies: true,
+            };
+        }
+        match phase {
+            Phase::Trade => PhaseRules {
+                trade_cubes: true,
+                trade_colonies: true,
+            },
+            Phase::Economy => PhaseRules {
+                trade_cubes: economy_deliveries,
+                trade_colonies: false,
+            },
+            _ => PhaseRules {
+                trade_cubes: false,
+                trade_colonies: false,
+            },
+        }
+    }
+}
+
+impl GameState {
+    /// What players may do during the current phase, under this game's
+    /// rules and house rules.
+    pub fn phase_rules(&self) -> PhaseRules {
+        self.config
+            .rules
+            .phase_rules(self.phase, self.config.economy_deliveries)
+    }
+}
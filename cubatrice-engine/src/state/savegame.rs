@@ -0,0 +1,98 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context, Error};
+use serde::{Deserialize, Serialize};
+
+use super::{config::GameConfig, record::RecordGroup, GameData, GameState};
+
+/// The first word of every save file.
+pub const SAVE_MAGIC: &str = "cubatrice-save";
+
+/// The save format written by this version of the crate. Bump this whenever
+/// `SaveFile` changes, and teach `SaveFile::parse` to migrate the old format.
+pub const SAVE_VERSION: u32 = 1;
+
+/// The contents of a save file. Only the config and the records played after
+/// setup are saved; everything else is rebuilt by replaying them, so saves
+/// stay valid as long as the records they contain do.
+///
+/// On disk a save is a header line, `cubatrice-save <version>`, followed by
+/// the file serialized as JSON.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaveFile {
+    pub config: GameConfig,
+    /// Every record group applied after setup, in order.
+    pub log: Vec<RecordGroup>,
+}
+
+impl SaveFile {
+    /// Reads the header and body of a save file, migrating older formats.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let (header, body) = s.split_once('\n').unwrap_or((s, ""));
+        let version = match header.trim().split_once(' ') {
+            Some((SAVE_MAGIC, v)) => v
+                .trim()
+                .parse::<u32>()
+                .with_context(|| format!("bad save version {:?}", v))?,
+            _ => return Err(anyhow!("not a save file (header was {:?})", header)),
+        };
+        match version {
+            1 => serde_json::from_str(body).context("parsing save file"),
+            v => Err(anyhow!(
+                "save format version {} is newer than this version supports ({})",
+                v,
+                SAVE_VERSION
+            )),
+        }
+    }
+
+    /// The save file as written to disk, header included.
+    pub fn to_string(&self) -> Result<String, Error> {
+        let body = serde_json::to_string(self).context("serializing save file")?;
+        Ok(format!("{} {}\n{}", SAVE_MAGIC, SAVE_VERSION, body))
+    }
+
+    /// Sets up the saved game and replays its log. Every record is validated
+    /// before it's applied, so a corrupted save fails to load instead of
+    /// producing an illegal game state.
+    pub fn restore(self, data: GameData) -> Result<GameState, Error> {
+        let mut state = GameState::from_config(data, self.config)?;
+        for group in self.log {
+            for r in group.rec.iter() {
+                if let Err(e) = state.validate(r) {
+                    return Err(anyhow!("invalid record {:?} in save: {}", r, e));
+                }
+            }
+            state.apply(group);
+        }
+        Ok(state)
+    }
+}
+
+impl GameState {
+    /// The save file for this game. Fails if history moved out of memory
+    /// by compaction can't be read back.
+    pub fn save_file(&self) -> Result<SaveFile, Error> {
+        let setup = self.config.setup_records().len();
+        Ok(SaveFile {
+            config: self.config.clone(),
+            log: self.full_log()?.into_iter().skip(setup).collect(),
+        })
+    }
+
+    /// Saves the game's config and record log to `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        fs::write(path, self.save_file()?.to_string()?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// Loads a game saved with `save`, using `data` as the game data.
+    pub fn load<P: AsRef<Path>>(data: GameData, path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let s = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        SaveFile::parse(&s)
+            .and_then(|f| f.restore(data))
+            .with_context(|| format!("loading {}", path.display()))
+    }
+}
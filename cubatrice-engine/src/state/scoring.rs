@@ -0,0 +1,131 @@
+use std::cmp::Reverse;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{entity::cube::CubeRecord, Fraction};
+
+use super::{player::PlayerID, GameState};
+
+/// What leftover ships and tokens are worth at the end of the game, in
+/// victory points. None of them are worth anything in a standard game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EndgameValues {
+    pub ship: Fraction,
+    pub envoy: Fraction,
+    /// An unplaced Eni Et service token. Tokens on converters belong to
+    /// the converter, and aren't counted.
+    pub service_token: Fraction,
+}
+
+impl Default for EndgameValues {
+    fn default() -> Self {
+        Self {
+            ship: Fraction::new(0, 1),
+            envoy: Fraction::new(0, 1),
+            service_token: Fraction::new(0, 1),
+        }
+    }
+}
+
+/// How one player's final score is made up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FinalScore {
+    pub player: PlayerID,
+    /// 1 for the winner. Players still tied after the tiebreaker share a
+    /// place, and the place after them is skipped.
+    pub place: usize,
+    /// Victory point tokens held.
+    pub tokens: isize,
+    /// Points scored from converter cards, rather than held as tokens.
+    pub cards: isize,
+    /// What the player's remaining cubes are worth, using
+    /// `CubeRecord::vp_value`.
+    pub cubes: Fraction,
+    /// What the player's remaining ships, envoys and service tokens are
+    /// worth, using the game's `EndgameValues`.
+    pub leftovers: Fraction,
+    /// One point lost for each regret held.
+    pub regret: isize,
+    /// Promises the player kept and broke. They don't affect the score.
+    #[serde(default)]
+    pub promises_kept: usize,
+    #[serde(default)]
+    pub promises_broken: usize,
+    pub total: Fraction,
+    /// The raw value of the player's remaining cubes, using
+    /// `CubeRecord::value`. Only used to break ties.
+    pub tiebreak: Fraction,
+}
+
+/// Every player's final score, best first.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FinalScores {
+    pub ranking: Vec<FinalScore>,
+}
+
+impl FinalScores {
+    /// Everyone in first place.
+    pub fn winners(&self) -> Vec<PlayerID> {
+        self.ranking
+            .iter()
+            .filter(|s| s.place == 1)
+            .map(|s| s.player)
+            .collect()
+    }
+
+    /// A player's score, if they're in the game.
+    pub fn of(&self, player: PlayerID) -> Option<&FinalScore> {
+        self.ranking.iter().find(|s| s.player == player)
+    }
+}
+
+impl GameState {
+    /// Totals every player's score: victory point tokens, points from
+    /// cards, what their remaining cubes, ships and tokens are worth, less a
+    /// point for each regret. Players are ranked by total, and ties go to whoever has the
+    /// most valuable cubes left over. Can be called at any time, but is only
+    /// final once the game reaches `Phase::Finish`.
+    pub fn final_scores(&self) -> FinalScores {
+        let mut ranking: Vec<FinalScore> = self
+            .factions
+            .keys()
+            .map(|p| {
+                let held = self.get_player_cubes(*p);
+                let leftover = CubeRecord { points: 0, ..held };
+                let tokens = held.points;
+                let cards = self.victory_points.get(p).copied().unwrap_or_default() as isize;
+                let cubes = leftover.vp_value();
+                let values = self.config.scoring;
+                let leftovers = values.ship * held.ships
+                    + values.envoy * self.envoys(*p) as isize
+                    + values.service_token * self.service_tokens(*p) as isize;
+                let regret = self.regret(*p) as isize;
+                let (promises_kept, promises_broken) = self.promise_record(*p);
+                FinalScore {
+                    player: *p,
+                    place: 0,
+                    tokens,
+                    cards,
+                    cubes,
+                    leftovers,
+                    regret,
+                    promises_kept,
+                    promises_broken,
+                    total: cubes + leftovers + (tokens + cards - regret),
+                    tiebreak: leftover.value(),
+                }
+            })
+            .collect();
+        ranking.sort_by_key(|s| (Reverse(s.total), Reverse(s.tiebreak), s.player));
+        let mut prev = None;
+        for (i, s) in ranking.iter_mut().enumerate() {
+            let key = (s.total, s.tiebreak);
+            s.place = match prev {
+                Some((k, place)) if k == key => place,
+                _ => i + 1,
+            };
+            prev = Some((key, s.place));
+        }
+        FinalScores { ranking }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{
+    script::{Effect, Script, ScriptDeck},
+    stats::provenance::FlowNode,
+};
+
+use super::{deck::DeckID, player::PlayerID, pool::spawn_record, record::RecordID, GameState};
+
+impl GameState {
+    /// Runs each effect of a script for `player`, usually the owner of the
+    /// converter the script is attached to. `rec` is the record which ran
+    /// the converter, and granted cubes are spawned by it, coming `from` the
+    /// converter. Draws from an empty deck do nothing.
+    pub fn run_script(&mut self, rec: RecordID, player: PlayerID, from: FlowNode, script: &Script) {
+        for effect in script.effects() {
+            match *effect {
+                Effect::Grant(typ, qty) => {
+                    self.apply_record(rec, &spawn_record(player, typ, qty, None, from))
+                }
+                Effect::Draw(ScriptDeck::Tech) => {
+                    if let Some(t) = self.tech_deck.draw_next() {
+                        self.tech_team_owners.insert(t, player);
+                    }
+                }
+                Effect::Draw(ScriptDeck::Colony) => {
+                    if let Some(c) = self.colony_deck.draw_next() {
+                        self.colony_owners.insert(c, player);
+                    }
+                }
+                Effect::Draw(ScriptDeck::Relic) => {
+                    let card = self
+                        .decks
+                        .get_mut(&DeckID::RELICS)
+                        .and_then(|d| d.cards.draw_next());
+                    if let Some(card) = card {
+                        self.give_card(rec, player, card);
+                    }
+                }
+                Effect::Support(delta) => {
+                    let support = self.increased_colony_support.entry(player).or_default();
+                    *support = support.saturating_add_signed(delta);
+                }
+            }
+        }
+    }
+}
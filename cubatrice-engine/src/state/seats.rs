@@ -0,0 +1,37 @@
+use super::{player::PlayerID, GameState};
+
+impl GameState {
+    /// Every player in seating order, clockwise from the first seat. Players
+    /// are seated in the order they join until seats are assigned.
+    pub fn seats(&self) -> &[PlayerID] {
+        &self.seats
+    }
+
+    /// Which seat a player is in, counting clockwise from 0.
+    pub fn seat(&self, player: PlayerID) -> Option<usize> {
+        self.seats.iter().position(|p| *p == player)
+    }
+
+    /// Every player in clockwise order, starting with `player`. Empty if the
+    /// player isn't seated.
+    pub fn clockwise_from(&self, player: PlayerID) -> Vec<PlayerID> {
+        let Some(seat) = self.seat(player) else {
+            return Vec::new();
+        };
+        self.seats[seat..]
+            .iter()
+            .chain(self.seats[..seat].iter())
+            .copied()
+            .collect()
+    }
+
+    /// The player seated to the left (clockwise) of `player`.
+    pub fn left_of(&self, player: PlayerID) -> Option<PlayerID> {
+        self.clockwise_from(player).get(1).copied()
+    }
+
+    /// The player seated to the right (counter-clockwise) of `player`.
+    pub fn right_of(&self, player: PlayerID) -> Option<PlayerID> {
+        self.clockwise_from(player).last().copied()
+    }
+}
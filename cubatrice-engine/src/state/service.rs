@@ -0,0 +1,67 @@
+use crate::entity::{converter::ConverterID, cube::CubeRecord, Item, Token};
+
+use super::{player::PlayerID, GameState};
+
+impl GameState {
+    /// How many unplaced service tokens a player holds.
+    pub fn service_tokens(&self, player: PlayerID) -> usize {
+        self.owned_arrow_tokens
+            .get(&player)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Whether a converter has a service token on it.
+    pub fn serviced(&self, conv: ConverterID) -> bool {
+        self.halved_converters.contains_key(&conv)
+    }
+
+    /// Whether a converter's service token can no longer be removed, which
+    /// happens once the converter has run with it.
+    pub fn service_locked(&self, conv: ConverterID) -> bool {
+        self.locked_service.contains(&conv)
+    }
+
+    /// A converter's input cost with a service token on it: each type of
+    /// cube is halved, rounding up. Non-cube inputs aren't affected.
+    pub fn halved_cost(&self, conv: ConverterID) -> Option<CubeRecord> {
+        let conv = self.converters.get(&conv)?;
+        let mut full = CubeRecord::default();
+        for i in conv.input() {
+            if let Item::Cubes(typ, qty) | Item::DonationCubes(typ, qty) = i {
+                full.add_type(*typ, *qty as isize);
+            }
+        }
+        let mut halved = CubeRecord::default();
+        for (typ, qty) in full.entries() {
+            halved.add_type(typ, (qty + 1) / 2);
+        }
+        Some(halved)
+    }
+
+    /// Gives a player a service token, if any are left in the supply.
+    pub(crate) fn gain_service_token(&mut self, player: PlayerID) {
+        if self.supply_of(Token::Service).can_supply(1) {
+            *self.owned_arrow_tokens.entry(player).or_default() += 1;
+        }
+    }
+
+    /// Puts one of a player's service tokens on a converter.
+    pub(crate) fn place_service_token(&mut self, player: PlayerID, conv: ConverterID) {
+        let Some(halved) = self.halved_cost(conv) else {
+            return;
+        };
+        if let Some(n) = self.owned_arrow_tokens.get_mut(&player) {
+            *n = n.saturating_sub(1);
+        }
+        self.halved_converters.insert(conv, (player, halved));
+    }
+
+    /// Takes the service token off a converter, giving it back to whoever
+    /// placed it.
+    pub(crate) fn remove_service_token(&mut self, conv: ConverterID) {
+        if let Some((placer, _)) = self.halved_converters.remove(&conv) {
+            *self.owned_arrow_tokens.entry(placer).or_default() += 1;
+        }
+    }
+}
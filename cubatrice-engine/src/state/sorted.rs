@@ -0,0 +1,26 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use serde::{Serialize, Serializer};
+
+/// Serializes a hash set in sorted order, so equal game states always
+/// serialize the same way. Use with `#[serde(serialize_with = "...")]`.
+pub fn set<T, S>(set: &HashSet<T>, s: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize + Ord,
+    S: Serializer,
+{
+    set.iter().collect::<BTreeSet<_>>().serialize(s)
+}
+
+/// Serializes a map of hash sets with both the keys and the sets sorted.
+pub fn map_of_sets<K, T, S>(map: &HashMap<K, HashSet<T>>, s: S) -> Result<S::Ok, S::Error>
+where
+    K: Serialize + Ord,
+    T: Serialize + Ord,
+    S: Serializer,
+{
+    map.iter()
+        .map(|(k, v)| (k, v.iter().collect::<BTreeSet<_>>()))
+        .collect::<BTreeMap<_, _>>()
+        .serialize(s)
+}
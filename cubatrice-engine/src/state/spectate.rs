@@ -0,0 +1,72 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use super::{view::GameStateView, GameState};
+
+/// Spectator views held back for a while before they're shown, so nobody
+/// watching a competitive game can coach a player in real time. A host
+/// pushes a view whenever the game changes, and broadcasts whatever has
+/// become due.
+///
+/// Times are passed in rather than read from the clock, so a host decides
+/// what "now" is.
+#[derive(Clone, Debug)]
+pub struct SpectatorFeed {
+    delay: Duration,
+    /// Views not yet due, oldest first, with when they were taken.
+    pending: VecDeque<(Instant, GameStateView)>,
+    /// The most recent view which has become due.
+    current: Option<GameStateView>,
+}
+
+impl SpectatorFeed {
+    /// A feed which holds views back for `delay`. A zero delay shows views
+    /// as soon as they're pushed.
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: VecDeque::new(),
+            current: None,
+        }
+    }
+
+    /// A feed delayed by a number of minutes.
+    pub fn delayed_minutes(minutes: u64) -> Self {
+        Self::new(Duration::from_secs(minutes * 60))
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Takes a spectator view of the game as it is at `now`.
+    pub fn push(&mut self, now: Instant, state: &GameState) {
+        self.pending.push_back((now, state.spectator_view()));
+    }
+
+    /// Views which have become due by `now`, oldest first. These should be
+    /// broadcast to every spectator.
+    pub fn due(&mut self, now: Instant) -> Vec<GameStateView> {
+        let mut due = Vec::new();
+        while let Some((taken, _)) = self.pending.front() {
+            if now.saturating_duration_since(*taken) < self.delay {
+                break;
+            }
+            if let Some((_, view)) = self.pending.pop_front() {
+                due.push(view);
+            }
+        }
+        if let Some(last) = due.last() {
+            self.current = Some(last.clone());
+        }
+        due
+    }
+
+    /// What a spectator joining now should be shown: the latest view which
+    /// has been broadcast. None until the first view is due.
+    pub fn current(&self) -> Option<&GameStateView> {
+        self.current.as_ref()
+    }
+}
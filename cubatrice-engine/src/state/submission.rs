@@ -0,0 +1,84 @@
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    access::Viewer,
+    player::PlayerID,
+    record::{RecordGroup, RecordID, RecordType},
+    validation::ValidationError,
+    GameState,
+};
+
+/// How many recent nonces a game remembers, across all players. A retry
+/// arriving after this many other submissions is no longer recognised as a
+/// duplicate.
+pub const NONCE_WINDOW: usize = 256;
+
+/// A value chosen by the client for each submission. Retrying a submission
+/// reuses its nonce, so the game can tell a retry from a new submission.
+/// Nonces only need to be unique for each player, so every client can
+/// count from 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Nonce(pub u64);
+
+/// Records sent by a client, which haven't been given an ID yet.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Submission {
+    pub nonce: Nonce,
+    pub rec: Vec<RecordType>,
+}
+
+/// What happened to a submission.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SubmitOutcome {
+    /// The records were applied as the group with this ID.
+    Applied(RecordID),
+    /// A submission by the same player with the same nonce was already
+    /// applied as the group with this ID, and nothing was changed.
+    Duplicate(RecordID),
+    /// One of the records isn't valid, given the records before it, and
+    /// nothing was changed.
+    Rejected(ValidationError),
+}
+
+impl GameState {
+    /// Validates and applies a submission made by `player`'s client, unless
+    /// they recently submitted one with the same nonce. Every record has to
+    /// be one `player` may submit, see `may_submit`, and the records are
+    /// validated together, as `validate_group` does. Rejected submissions
+    /// don't use up their nonce, so a corrected retry can reuse it. Fails if
+    /// the game can't be copied to validate the records against.
+    pub fn submit(&mut self, player: PlayerID, sub: Submission) -> Result<SubmitOutcome, Error> {
+        if let Some(id) = self.submitted(player, sub.nonce) {
+            return Ok(SubmitOutcome::Duplicate(id));
+        }
+        if !sub
+            .rec
+            .iter()
+            .all(|r| self.may_submit(Viewer::Player(player), r))
+        {
+            return Ok(SubmitOutcome::Rejected(ValidationError::NotPlayers {
+                player,
+            }));
+        }
+        if let Err(e) = self.validate_group(&sub.rec)? {
+            return Ok(SubmitOutcome::Rejected(e));
+        }
+        let id = self.next_record_id();
+        self.apply(RecordGroup { id, rec: sub.rec });
+        self.nonces.push_back((player, sub.nonce, id));
+        if self.nonces.len() > NONCE_WINDOW {
+            self.nonces.pop_front();
+        }
+        Ok(SubmitOutcome::Applied(id))
+    }
+
+    /// The group a recent submission by `player` with this nonce was
+    /// applied as.
+    pub fn submitted(&self, player: PlayerID, nonce: Nonce) -> Option<RecordID> {
+        self.nonces
+            .iter()
+            .find(|(p, n, _)| *p == player && *n == nonce)
+            .map(|(_, _, id)| *id)
+    }
+}
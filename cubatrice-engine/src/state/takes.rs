@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::cube::CubeType,
+    stats::{market::BidKind, provenance::FlowNode},
+};
+
+use super::{events::GameEvent, player::PlayerID, record::RecordID, GameState};
+
+/// What happens to colonies and research teams nobody took by the end of a
+/// bid round.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Untaken {
+    /// They stay on the track, and only the empty slots are refilled.
+    #[default]
+    Stay,
+    /// They're discarded, and the whole track is refilled.
+    Discard,
+}
+
+impl GameState {
+    /// Players still to take from a bid track, in order. The first player
+    /// takes or passes next. Each of a split bidder's bids is a separate
+    /// turn.
+    pub fn take_order(&self, kind: BidKind) -> &[PlayerID] {
+        match kind {
+            BidKind::Colony => &self.colony_bid_order,
+            BidKind::Tech => &self.tech_bid_order,
+        }
+    }
+
+    /// How many ships the player whose turn it is pays if they take from a
+    /// bid track. A split bidder pays their higher bid on their first turn
+    /// and their lower bid on the second.
+    pub fn take_cost(&self, kind: BidKind) -> Option<usize> {
+        let order = self.take_order(kind);
+        let player = order.first()?;
+        let bids = match kind {
+            BidKind::Colony => &self.player_colony_bid,
+            BidKind::Tech => &self.player_tech_bid,
+        };
+        let (first, second) = *bids.get(player)?;
+        let turns_left = order.iter().filter(|p| *p == player).count();
+        Some(match second {
+            Some(second) if turns_left < 2 => first.min(second),
+            Some(second) => first.max(second),
+            None => first,
+        })
+    }
+
+    /// Ships a player can pay to take from a bid track. Ships bid on a
+    /// colony and passed go back to the player, but can't be spent on a
+    /// research team in the same round.
+    pub fn ships_available(&self, player: PlayerID, kind: BidKind) -> usize {
+        let ships = self.ships_of(player);
+        match kind {
+            BidKind::Colony => ships,
+            BidKind::Tech => {
+                ships.saturating_sub(self.passed_ships.get(&player).copied().unwrap_or_default())
+            }
+        }
+    }
+
+    /// Works out who takes from each track, once every player has bid.
+    pub(crate) fn start_takes(&mut self) {
+        self.colony_bid_order = self.bid_order(BidKind::Colony);
+        self.tech_bid_order = self.bid_order(BidKind::Tech);
+    }
+
+    /// The player whose turn it is takes the item in a slot of a bid track,
+    /// paying the ships they bid, or passes if there's no slot. Passing
+    /// players keep their ships, but don't get another turn, and a split
+    /// bidder passing one bid still has their turn for the other. Once the
+    /// track is empty, everyone left passes.
+    pub(crate) fn take(&mut self, id: RecordID, kind: BidKind, slot: Option<usize>) {
+        let cost = self.take_cost(kind).unwrap_or_default();
+        let order = match kind {
+            BidKind::Colony => &mut self.colony_bid_order,
+            BidKind::Tech => &mut self.tech_bid_order,
+        };
+        if order.is_empty() {
+            return;
+        }
+        let player = order.remove(0);
+        if slot.is_none() && kind == BidKind::Colony {
+            *self.passed_ships.entry(player).or_default() += cost;
+        }
+        if let Some(slot) = slot {
+            for ship in self
+                .owned_cubes(player, CubeType::Ship)
+                .into_iter()
+                .take(cost)
+            {
+                self.destroy_cube(ship, FlowNode::Supply);
+            }
+            match kind {
+                BidKind::Colony => {
+                    if let Some(colony) = self.colony_bid_track.get_mut(slot).and_then(|s| s.take())
+                    {
+                        self.colony_owners.insert(colony, player);
+                        self.emit(
+                            id,
+                            GameEvent::ColonyWon {
+                                player,
+                                colony,
+                                ships: cost,
+                            },
+                        );
+                    }
+                }
+                BidKind::Tech => {
+                    if let Some(tech) = self.tech_bid_track.get_mut(slot).and_then(|s| s.take()) {
+                        self.tech_team_owners.insert(tech, player);
+                        self.emit(
+                            id,
+                            GameEvent::ResearchTeamWon {
+                                player,
+                                tech,
+                                ships: cost,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+        let track_empty = match kind {
+            BidKind::Colony => self.colony_bid_track.iter().all(|s| s.is_none()),
+            BidKind::Tech => self.tech_bid_track.iter().all(|s| s.is_none()),
+        };
+        if track_empty {
+            match kind {
+                BidKind::Colony => self.colony_bid_order.clear(),
+                BidKind::Tech => self.tech_bid_order.clear(),
+            }
+        }
+    }
+
+    /// Moves what's left on a bid track at the end of its round to its deck's
+    /// discard pile if the config says untaken cards are discarded, then
+    /// refills it.
+    pub(crate) fn finish_takes(&mut self, kind: BidKind) {
+        let discard = self.config.untaken == Untaken::Discard;
+        match kind {
+            BidKind::Colony => {
+                if discard {
+                    for c in self.colony_bid_track.iter_mut().filter_map(|s| s.take()) {
+                        self.colony_deck.discard(c);
+                    }
+                    self.drop_stray_cross_tokens();
+                }
+                self.refill_colony_track();
+            }
+            BidKind::Tech => {
+                if discard {
+                    for t in self.tech_bid_track.iter_mut().filter_map(|s| s.take()) {
+                        self.tech_deck.discard(t);
+                    }
+                }
+                self.refill_tech_track();
+                // both bids were made together, and are spent by now
+                self.player_colony_bid.clear();
+                self.player_tech_bid.clear();
+                self.passed_ships.clear();
+            }
+        }
+    }
+}
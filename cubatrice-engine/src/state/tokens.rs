@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{token::TokenID, Token};
+
+use super::{player::PlayerID, GameState};
+
+/// How many of a token are in use, compared to how many exist.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TokenSupply {
+    pub token: Token,
+    /// Tokens currently held by players or placed on cards.
+    pub in_play: usize,
+    /// How many of the token exist, from the token's definition. None if the
+    /// supply is unlimited.
+    pub limit: Option<usize>,
+}
+
+impl TokenSupply {
+    /// How many more tokens can be put into play, or None if unlimited.
+    pub fn remaining(&self) -> Option<usize> {
+        self.limit.map(|l| l.saturating_sub(self.in_play))
+    }
+
+    /// Whether `qty` more tokens can be put into play.
+    pub fn can_supply(&self, qty: usize) -> bool {
+        self.remaining().is_none_or(|r| r >= qty)
+    }
+}
+
+impl GameState {
+    /// Usage of every token type, built in or defined in data, including
+    /// each colour of factory separately.
+    pub fn token_supply(&self) -> Vec<TokenSupply> {
+        self.data
+            .all_tokens()
+            .into_iter()
+            .map(|d| self.supply_of(Token::from_id(d.id)))
+            .collect()
+    }
+
+    /// How many envoy tokens a player holds.
+    pub fn envoys(&self, player: PlayerID) -> usize {
+        self.envoys.get(&player).copied().unwrap_or_default()
+    }
+
+    /// How many of the Faderan's acknowledgements a player holds.
+    pub fn acknowledgements(&self, player: PlayerID) -> usize {
+        self.acknowledgements.get(&player).copied().unwrap_or_default()
+    }
+
+    /// How many trades a player has given the Faderan something in, which
+    /// the Faderan haven't acknowledged yet.
+    pub fn acknowledgements_owed(&self, player: PlayerID) -> usize {
+        self.owed_acknowledgements.get(&player).copied().unwrap_or_default()
+    }
+
+    /// How many of a custom token a player holds.
+    pub fn custom_tokens(&self, player: PlayerID, token: TokenID) -> usize {
+        self.custom_tokens
+            .get(&player)
+            .and_then(|t| t.get(&token))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Usage of a single token type.
+    pub fn supply_of(&self, token: Token) -> TokenSupply {
+        let in_play = match token {
+            Token::Acknowledgement => self.acknowledgements.values().sum(),
+            Token::Envoy => self.envoys.values().sum(),
+            Token::Regret => self.regret.values().sum(),
+            // service tokens are either held, or placed on a converter
+            Token::Service => {
+                self.owned_arrow_tokens.values().sum::<usize>() + self.halved_converters.len()
+            }
+            Token::CrossColonization => self.cross_tokens.len(),
+            Token::Factory(typ) => self
+                .factory_colonies
+                .values()
+                .filter(|t| **t == typ)
+                .count(),
+            Token::Custom(id) => self
+                .custom_tokens
+                .values()
+                .filter_map(|t| t.get(&id))
+                .sum(),
+        };
+        TokenSupply {
+            token,
+            in_play,
+            limit: self.data.token(token.id()).and_then(|d| d.supply),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{entity::faction::FactionType, Deck};
+
+use super::GameState;
+
+impl GameState {
+    /// How many colonies are put up for bids each confluence: one for each
+    /// player, and one more if the Kjas Directorate is playing, since they
+    /// can bid for two.
+    pub fn colony_track_size(&self) -> usize {
+        let kjas = self.factions.values().any(|f| *f == FactionType::KjasCore);
+        self.factions.len() + usize::from(kjas)
+    }
+
+    /// How many research teams are put up for bids each confluence: one for
+    /// each player.
+    pub fn tech_track_size(&self) -> usize {
+        self.factions.len()
+    }
+
+    /// Fills the empty slots on the colony track from the colony deck,
+    /// resizing the track for the players in the game first. Slots stay
+    /// empty once the deck runs out.
+    pub(crate) fn refill_colony_track(&mut self) {
+        let size = self.colony_track_size();
+        refill(&mut self.colony_bid_track, size, &mut self.colony_deck);
+    }
+
+    /// Fills the empty slots on the research track from the research team
+    /// deck, like `refill_colony_track`.
+    pub(crate) fn refill_tech_track(&mut self) {
+        let size = self.tech_track_size();
+        refill(&mut self.tech_bid_track, size, &mut self.tech_deck);
+    }
+}
+
+fn refill<T>(track: &mut Vec<Option<T>>, size: usize, deck: &mut Deck<T>) {
+    track.resize_with(size, || None);
+    for slot in track.iter_mut().filter(|s| s.is_none()) {
+        *slot = deck.draw_next();
+    }
+}
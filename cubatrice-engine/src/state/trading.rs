@@ -0,0 +1,199 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{
+    colony::ColonyID, converter::ConverterID, cube::CubeID, technology::TechID, Token,
+};
+
+use super::{
+    events::GameEvent,
+    player::PlayerID,
+    record::RecordID,
+    validation::{ensure, ValidationError},
+    GameState,
+};
+
+/// Anything which can change hands in a `TradeItems` record.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TradeItem {
+    Cube(CubeID),
+    Colony(ColonyID),
+    /// A converter, either given for good or lent for the economy phase
+    /// along with the fleet support it needs.
+    Converter {
+        converter: ConverterID,
+        permanent: bool,
+    },
+    /// Some of a token the player holds. Only tokens their definition
+    /// allows to be traded can be given.
+    Token {
+        token: Token,
+        qty: usize,
+    },
+    /// A research team which hasn't been invented yet.
+    TechTeam(TechID),
+    /// A retrocontinuity token, named by the player it belongs to.
+    RetroToken(PlayerID),
+}
+
+impl GameState {
+    /// How many of a token a player holds in hand. Tokens placed on cards
+    /// or colonies aren't counted.
+    pub fn held_tokens(&self, player: PlayerID, token: Token) -> usize {
+        match token {
+            Token::Acknowledgement => self.acknowledgements(player),
+            Token::Envoy => self.envoys(player),
+            Token::Regret => self.regret(player),
+            Token::Service => self.service_tokens(player),
+            Token::Custom(id) => self.custom_tokens(player, id),
+            Token::CrossColonization | Token::Factory(_) => 0,
+        }
+    }
+
+    /// Checks that a player can give away everything on their side of a
+    /// trade. Cubes follow the phase rules for cubes, converters can be
+    /// traded whenever, and everything else follows the rules for colonies.
+    pub(crate) fn can_give_items(
+        &self,
+        player: PlayerID,
+        items: &[TradeItem],
+    ) -> Result<(), ValidationError> {
+        let rules = self.phase_rules();
+        let mut loaned = BTreeSet::new();
+        let mut tokens: HashMap<Token, usize> = HashMap::new();
+        for item in items {
+            let open = match item {
+                TradeItem::Cube(_) => rules.trade_cubes,
+                TradeItem::Converter { .. } => true,
+                _ => rules.trade_colonies,
+            };
+            ensure(open, ValidationError::TradingClosed { phase: self.phase })?;
+            match item {
+                TradeItem::Cube(c) => self.owns_cubes(player, &BTreeSet::from([*c]))?,
+                TradeItem::Colony(c) => ensure(
+                    self.colony_owners.get(c) == Some(&player),
+                    ValidationError::ColonyNotOwned { player, colony: *c },
+                )?,
+                TradeItem::Converter {
+                    converter,
+                    permanent,
+                } => {
+                    self.can_trade_converters(player, &BTreeSet::from([*converter]), *permanent)?;
+                    if !permanent {
+                        loaned.insert(*converter);
+                    }
+                }
+                TradeItem::Token { token, qty } => {
+                    ensure(
+                        self.data.token(token.id()).is_some_and(|d| d.tradable),
+                        ValidationError::TokenUntradable { token: *token },
+                    )?;
+                    *tokens.entry(*token).or_default() += qty;
+                }
+                TradeItem::TechTeam(tech) => ensure(
+                    self.tech_team_owners.get(tech) == Some(&player),
+                    ValidationError::TeamNotOwned {
+                        player,
+                        tech: *tech,
+                    },
+                )?,
+                TradeItem::RetroToken(t) => {
+                    self.holds_retro_tokens(player, &BTreeSet::from([*t]))?
+                }
+            }
+        }
+        for (token, qty) in tokens {
+            let held = self.held_tokens(player, token);
+            ensure(
+                qty <= held,
+                ValidationError::NotEnoughTokens {
+                    player,
+                    token,
+                    held,
+                },
+            )?;
+        }
+        self.can_lend_fleet(player, &loaned)
+    }
+
+    pub(crate) fn trade_items(
+        &mut self,
+        id: RecordID,
+        a: PlayerID,
+        b: PlayerID,
+        a_items: &[TradeItem],
+        b_items: &[TradeItem],
+    ) {
+        for (from, to, items) in [(a, b, a_items), (b, a, b_items)] {
+            let mut sold = Vec::new();
+            let mut teams = 0;
+            for item in items {
+                match item {
+                    TradeItem::Cube(c) => self.move_cube(*c, to),
+                    TradeItem::Colony(c) => {
+                        self.colony_owners.insert(*c, to);
+                    }
+                    TradeItem::Converter {
+                        converter,
+                        permanent,
+                    } => {
+                        if *permanent {
+                            sold.push(*converter);
+                        } else if !self.original_owners.contains_key(converter) {
+                            self.original_owners.insert(*converter, from);
+                            self.lend_fleet(*converter, from, to);
+                        }
+                        self.transfer_converter(id, *converter, to);
+                    }
+                    TradeItem::Token { token, qty } => self.move_tokens(*token, *qty, from, to),
+                    TradeItem::TechTeam(tech) => {
+                        self.tech_team_owners.insert(*tech, to);
+                        teams += 1;
+                    }
+                    TradeItem::RetroToken(t) => {
+                        self.retro_owners.insert(*t, to);
+                    }
+                }
+            }
+            // converters sold for good and research teams carry the
+            // Society's regret with them
+            let techs = self.converter_techs(sold.iter());
+            self.attach_regret(from, to, &techs);
+            self.attach_team_regret(from, to, teams);
+        }
+        self.emit(id, GameEvent::Traded { a, b });
+    }
+
+    /// Moves tokens held in hand from one player to another.
+    fn move_tokens(&mut self, token: Token, qty: usize, from: PlayerID, to: PlayerID) {
+        let held = match token {
+            Token::Acknowledgement => &mut self.acknowledgements,
+            Token::Envoy => &mut self.envoys,
+            Token::Regret => &mut self.regret,
+            Token::Service => &mut self.owned_arrow_tokens,
+            Token::Custom(id) => {
+                let n = self
+                    .custom_tokens
+                    .get_mut(&from)
+                    .and_then(|t| t.get_mut(&id))
+                    .map(|n| std::mem::replace(n, n.saturating_sub(qty)))
+                    .unwrap_or_default();
+                *self
+                    .custom_tokens
+                    .entry(to)
+                    .or_default()
+                    .entry(id)
+                    .or_default() += qty.min(n);
+                return;
+            }
+            Token::CrossColonization | Token::Factory(_) => return,
+        };
+        let n = held
+            .get_mut(&from)
+            .map(|n| std::mem::replace(n, n.saturating_sub(qty)))
+            .unwrap_or_default();
+        *held.entry(to).or_default() += qty.min(n);
+        held.retain(|_, n| *n > 0);
+    }
+}
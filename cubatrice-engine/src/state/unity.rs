@@ -0,0 +1,58 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::entity::{
+    converter::{ConverterID, ConverterKind},
+    faction::{alt_unity::AltUnityState, FactionType},
+};
+
+use super::{player::PlayerID, record::RecordID, GameState};
+
+impl GameState {
+    /// Deep Unity's dice and dice change cards.
+    pub fn unity_dice(&self) -> &AltUnityState {
+        &self.alt_unity_state
+    }
+
+    /// The die face a converter needs showing before it can run, if it's a
+    /// Deep Unity starting converter owned by the Deep Unity player.
+    pub fn unity_die_needed(&self, converter: ConverterID) -> Option<u8> {
+        let owner = self.converter_owners.get(&converter)?;
+        if self.factions.get(owner) != Some(&FactionType::UnityAlt) {
+            return None;
+        }
+        match self.converters.get(&converter)?.kind() {
+            ConverterKind::Starting(s) => s.die,
+            _ => None,
+        }
+    }
+
+    /// Deep Unity starting converters a player owns whose die face is
+    /// showing this confluence, in ID order.
+    pub fn runnable_unity_converters(&self, player: PlayerID) -> Vec<ConverterID> {
+        self.owned_converters(player)
+            .into_iter()
+            .filter(|c| {
+                self.unity_die_needed(*c)
+                    .is_some_and(|f| self.alt_unity_state.shows(f))
+            })
+            .collect()
+    }
+
+    /// Rolls every die, for the rest of the confluence. Seeded from the
+    /// record ID, like deck shuffles, so replaying the log reproduces the
+    /// same faces.
+    pub(crate) fn roll_unity_dice(&mut self, rec: RecordID) {
+        let mut rng = StdRng::seed_from_u64(self.config.seed.wrapping_add(rec.0 as u64));
+        self.alt_unity_state.faces = (0..AltUnityState::DICE)
+            .map(|_| rng.gen_range(1..=AltUnityState::SIDES))
+            .collect();
+        self.alt_unity_state.rolled = true;
+    }
+
+    /// Lets Deep Unity roll again, and deals them this confluence's dice
+    /// change cards.
+    pub(crate) fn start_unity_confluence(&mut self) {
+        self.alt_unity_state.rolled = false;
+        self.alt_unity_state.change_cards += AltUnityState::CHANGE_CARDS_PER_CONFLUENCE;
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    entity::{
+        colony::{ColonyID, ColonyType},
+        converter::{ConverterID, ConverterKind},
+        cube::{CubeRecord, CubeType},
+        technology::TechID,
+        Upgrade,
+    },
+    stats::provenance::FlowNode,
+};
+
+use super::{
+    events::GameEvent,
+    player::PlayerID,
+    record::RecordID,
+    validation::{ensure, ValidationError},
+    GameState,
+};
+
+impl GameState {
+    /// Checks that a player can pay for an upgrade of one of their
+    /// converters, with the colonies they've chosen to consume.
+    pub(crate) fn can_pay_upgrade(
+        &self,
+        player: PlayerID,
+        conv: ConverterID,
+        opt: usize,
+        cost: &Upgrade,
+        colonies: &BTreeSet<ColonyID>,
+    ) -> Result<(), ValidationError> {
+        let wrong_colonies = ValidationError::WrongUpgradeColonies {
+            converter: conv,
+            opt,
+        };
+        let not_triggered = ValidationError::UpgradeNotTriggered {
+            converter: conv,
+            opt,
+        };
+        match cost {
+            Upgrade::Cubes(typ, qty) => {
+                ensure(colonies.is_empty(), wrong_colonies)?;
+                self.has_exact_cubes(player, *typ, *qty)
+            }
+            Upgrade::ColonyAndCubes {
+                colony_type,
+                in_cubes,
+                ..
+            } => {
+                self.can_consume_colonies(player, colonies, &[*colony_type], wrong_colonies)?;
+                self.has_cube_record(player, in_cubes)
+            }
+            Upgrade::ColoniesAndCubes {
+                colonies: types,
+                in_cubes,
+                ..
+            } => {
+                self.can_consume_colonies(player, colonies, types, wrong_colonies)?;
+                self.has_cube_record(player, in_cubes)
+            }
+            Upgrade::ConverterCard(tech) => {
+                ensure(colonies.is_empty(), wrong_colonies)?;
+                self.converter_card(player, *tech, conv).map(|_| ()).ok_or(
+                    ValidationError::NoConverterCard {
+                        player,
+                        tech: *tech,
+                    },
+                )
+            }
+            Upgrade::ConverterCardOtherPlayer(tech) => {
+                ensure(colonies.is_empty(), wrong_colonies)?;
+                let held = self
+                    .converter_owners
+                    .iter()
+                    .filter(|(_, p)| **p != player)
+                    .any(|(c, _)| self.converter_tech(*c) == Some(*tech));
+                ensure(
+                    held,
+                    ValidationError::NoOtherConverterCard {
+                        player,
+                        tech: *tech,
+                    },
+                )
+            }
+            Upgrade::KitTechShared => {
+                ensure(colonies.is_empty(), wrong_colonies)?;
+                ensure(!self.to_share.is_empty(), not_triggered)
+            }
+            Upgrade::CrossColonizedPlanetBought => {
+                ensure(colonies.is_empty(), wrong_colonies)?;
+                ensure(self.cross_colonized_planet_bought(), not_triggered)
+            }
+        }
+    }
+
+    /// Pays for an upgrade and upgrades the converter. Colonies and
+    /// converter cards the upgrade consumes leave the game.
+    pub(crate) fn upgrade_converter(
+        &mut self,
+        id: RecordID,
+        conv: ConverterID,
+        opt: usize,
+        colonies: &BTreeSet<ColonyID>,
+    ) {
+        let (Some(player), Some(cost)) = (
+            self.converter_owners.get(&conv).copied(),
+            self.converters.get(&conv).and_then(|c| c.upgrade_cost(opt)),
+        ) else {
+            return;
+        };
+        match cost {
+            Upgrade::Cubes(typ, qty) => self.pay_exact_cubes(player, typ, qty, conv),
+            Upgrade::ColonyAndCubes {
+                in_cubes,
+                out_cubes,
+                ..
+            }
+            | Upgrade::ColoniesAndCubes {
+                in_cubes,
+                out_cubes,
+                ..
+            } => {
+                for c in colonies.iter() {
+                    self.consume_colony(id, *c);
+                }
+                for (typ, qty) in in_cubes.entries() {
+                    self.pay_exact_cubes(player, typ, qty.max(0) as usize, conv);
+                }
+                for (typ, qty) in out_cubes.entries() {
+                    let from = FlowNode::Converter(conv);
+                    self.spawn_cubes_from(player, typ, qty.max(0) as usize, None, from);
+                }
+            }
+            Upgrade::ConverterCard(tech) => {
+                if let Some(card) = self.converter_card(player, tech, conv) {
+                    self.remove_converter(card);
+                }
+            }
+            // nothing changes hands for these, they only need to be
+            // available
+            Upgrade::ConverterCardOtherPlayer(_)
+            | Upgrade::KitTechShared
+            | Upgrade::CrossColonizedPlanetBought => {}
+        }
+        if let Some(c) = self.converters.get_mut(&conv) {
+            c.upgrade(&self.data, opt);
+        }
+        self.emit(
+            id,
+            GameEvent::ConverterUpgraded {
+                player,
+                converter: conv,
+            },
+        );
+    }
+
+    /// The technology a converter was invented from, if it's a technology
+    /// converter.
+    fn converter_tech(&self, conv: ConverterID) -> Option<TechID> {
+        match self.converter(conv)?.kind() {
+            ConverterKind::Prototype(p) => Some(p.id),
+            _ => None,
+        }
+    }
+
+    /// The lowest numbered converter card for a technology a player owns,
+    /// other than the converter being upgraded.
+    fn converter_card(
+        &self,
+        player: PlayerID,
+        tech: TechID,
+        except: ConverterID,
+    ) -> Option<ConverterID> {
+        self.owned_converters(player)
+            .into_iter()
+            .find(|c| *c != except && self.converter_tech(*c) == Some(tech))
+    }
+
+    fn has_exact_cubes(
+        &self,
+        player: PlayerID,
+        typ: CubeType,
+        needed: usize,
+    ) -> Result<(), ValidationError> {
+        let has = self.owned_cubes(player, typ).len();
+        ensure(
+            has >= needed,
+            ValidationError::InsufficientCubes {
+                player,
+                typ,
+                needed,
+                has,
+            },
+        )
+    }
+
+    fn has_cube_record(&self, player: PlayerID, cubes: &CubeRecord) -> Result<(), ValidationError> {
+        for (typ, qty) in cubes.entries() {
+            self.has_exact_cubes(player, typ, qty.max(0) as usize)?;
+        }
+        Ok(())
+    }
+
+    fn pay_exact_cubes(&mut self, player: PlayerID, typ: CubeType, qty: usize, conv: ConverterID) {
+        for c in self.owned_cubes(player, typ).into_iter().take(qty) {
+            self.destroy_cube(c, FlowNode::Converter(conv));
+        }
+    }
+
+    /// Checks that the colonies are owned by the player, and are exactly
+    /// one of each of the colony types the upgrade consumes.
+    fn can_consume_colonies(
+        &self,
+        player: PlayerID,
+        colonies: &BTreeSet<ColonyID>,
+        types: &[ColonyType],
+        wrong: ValidationError,
+    ) -> Result<(), ValidationError> {
+        for c in colonies.iter() {
+            ensure(
+                self.colony_owners.get(c) == Some(&player),
+                ValidationError::ColonyNotOwned { player, colony: *c },
+            )?;
+        }
+        ensure(colonies.len() == types.len(), wrong)?;
+        // specific types claim their colonies first, so a colony which
+        // would do for either isn't used up by an `Any`
+        let mut left: Vec<ColonyType> = colonies
+            .iter()
+            .filter_map(|c| self.data.colony.get(c).map(|c| c.typ))
+            .collect();
+        ensure(left.len() == types.len(), wrong)?;
+        let mut wanted: Vec<&ColonyType> = types.iter().collect();
+        wanted.sort_by_key(|t| **t == ColonyType::Any);
+        for t in wanted {
+            let i = left
+                .iter()
+                .position(|c| *t == ColonyType::Any || c == t)
+                .ok_or(wrong)?;
+            left.swap_remove(i);
+        }
+        Ok(())
+    }
+
+    /// Removes a colony from play, onto the colony deck's discard pile. If
+    /// the Charity Syndicate had a cross colonization token on it, the token
+    /// goes back to the supply and they score a point.
+    fn consume_colony(&mut self, id: RecordID, colony: ColonyID) {
+        self.colony_owners.remove(&colony);
+        self.colony_deck.discard(colony);
+        self.return_cross_token(id, colony);
+    }
+}
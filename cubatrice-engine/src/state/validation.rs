@@ -0,0 +1,1692 @@
+use std::{collections::BTreeSet, fmt::Display};
+
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::{
+        colony::ColonyID,
+        converter::{Arrow, ConverterID},
+        cube::{CubeID, CubeType},
+        faction::{
+            alt_caylion::{ProjectID, ProjectState},
+            alt_unity::AltUnityState,
+            FactionType,
+        },
+        technology::TechID,
+        Token,
+    },
+    stats::market::BidKind,
+};
+
+use super::{
+    deck::DeckID,
+    fleet::{FLEET_SUPPORT_PER_CONFLUENCE, MAX_FLEET_SUPPORT},
+    offers::TradeOffer,
+    player::PlayerID,
+    record::{RecordGroup, RecordID, RecordType},
+    GameState, Phase,
+};
+
+/// Why a record can't be applied to the current game state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ValidationError {
+    /// The record can't be applied during the current phase.
+    WrongPhase { phase: Phase },
+    /// Phases must be advanced one at a time, in order.
+    PhaseOrder { from: Phase, to: Phase },
+    /// This can't be traded during the current phase.
+    TradingClosed { phase: Phase },
+    /// A player with this ID is already in the game.
+    PlayerExists { player: PlayerID },
+    /// This faction, or the other side of it, is already being played.
+    FactionTaken { faction: FactionType },
+    /// Both sides of a trade are the same player.
+    SelfTrade { player: PlayerID },
+    /// There's no player with this ID in the game.
+    NoSuchPlayer { player: PlayerID },
+    /// No faction which can do this is in the game.
+    NoSuchFaction { faction: FactionType },
+    /// The player doesn't own a cube they're trying to use.
+    CubeNotOwned { player: PlayerID, cube: CubeID },
+    /// The player doesn't own a colony they're trying to use.
+    ColonyNotOwned { player: PlayerID, colony: ColonyID },
+    /// The player doesn't own a converter they're trying to use.
+    ConverterNotOwned {
+        player: PlayerID,
+        converter: ConverterID,
+    },
+    /// There's no converter with this ID in the game.
+    NoSuchConverter { converter: ConverterID },
+    /// The converter can't be traded.
+    ConverterUntradable { converter: ConverterID },
+    /// The converter can't be lent out.
+    ConverterUnloanable { converter: ConverterID },
+    /// Only white converters can be marked to run in the economy phase.
+    WrongArrow {
+        converter: ConverterID,
+        color: Arrow,
+    },
+    /// The converter can't be upgraded, or doesn't have this upgrade option.
+    NoSuchUpgrade { converter: ConverterID, opt: usize },
+    /// The colonies given for an upgrade aren't the ones it consumes.
+    WrongUpgradeColonies { converter: ConverterID, opt: usize },
+    /// The player doesn't own a converter card the upgrade consumes.
+    NoConverterCard { player: PlayerID, tech: TechID },
+    /// The upgrade needs another player to hold a converter card, and
+    /// nobody else does.
+    NoOtherConverterCard { player: PlayerID, tech: TechID },
+    /// The upgrade only becomes available once something has happened in
+    /// the game, and it hasn't yet.
+    UpgradeNotTriggered { converter: ConverterID, opt: usize },
+    /// Retrocontinuity has already been applied to the converter.
+    AlreadyRetrocontinued { converter: ConverterID },
+    /// The player doesn't hold an unused retrocontinuity token.
+    NoRetroToken { player: PlayerID },
+    /// The player doesn't hold the retrocontinuity token belonging to
+    /// `token`.
+    RetroTokenNotHeld { player: PlayerID, token: PlayerID },
+    /// Retrocontinuity has already been applied to the colony.
+    ColonyAlreadyRetrocontinued { colony: ColonyID },
+    /// Nobody owns the colony.
+    UnownedColony { colony: ColonyID },
+    /// The colony has already been run this phase.
+    ColonyAlreadyRun { colony: ColonyID },
+    /// The colony's arrow isn't white, so it isn't run in the economy phase.
+    WrongColonyArrow { colony: ColonyID, color: Arrow },
+    /// The Caylion have already doubled the colony.
+    AlreadyDoubled { colony: ColonyID },
+    /// The colony isn't doubled.
+    NotDoubled { colony: ColonyID },
+    /// The player already has as many colonies doubled as they can.
+    DoublingLimit { player: PlayerID, limit: usize },
+    /// The cubes given don't pay for exactly one run of the colony.
+    ColonyInputsDontMatch { colony: ColonyID },
+    /// The player doesn't hold an unplaced service token.
+    NoServiceToken { player: PlayerID },
+    /// The converter already has a service token on it.
+    AlreadyServiced { converter: ConverterID },
+    /// The converter doesn't have a service token on it.
+    NotServiced { converter: ConverterID },
+    /// The converter has run with its service token, so the token can't be
+    /// removed.
+    ServiceLocked { converter: ConverterID },
+    /// Every token of this kind is already in play.
+    TokenExhausted { token: Token },
+    /// A deck with this ID already exists.
+    DeckExists { deck: DeckID },
+    /// There's no deck with this ID.
+    NoSuchDeck { deck: DeckID },
+    /// The deck doesn't have enough cards left.
+    NotEnoughCards {
+        deck: DeckID,
+        needed: usize,
+        has: usize,
+    },
+    /// There's no pending trade offer with this ID.
+    NoSuchOffer { offer: RecordID },
+    /// A counter-offer must go back to the player who made the original
+    /// offer.
+    NotACounter { offer: RecordID },
+    /// There's no contract with this ID.
+    NoSuchContract { contract: RecordID },
+    /// More cubes were given than are still owed on a contract.
+    ContractOverpaid {
+        contract: RecordID,
+        remaining: usize,
+    },
+    /// There's no promise made by this record.
+    NoSuchPromise { promise: RecordID },
+    /// Only the players involved in a promise can settle it.
+    NotPromiseParty { player: PlayerID, promise: RecordID },
+    /// The promise has already been marked kept or broken.
+    PromiseSettled { promise: RecordID },
+    /// A cube of a different type was expected.
+    WrongCubeType { cube: CubeID, expected: CubeType },
+    /// The player has no note with this ID.
+    NoSuchNote { player: PlayerID, note: RecordID },
+    /// The Yengii don't hold the license to this technology.
+    NotLicensable { tech: TechID },
+    /// The player already holds a license to the technology.
+    AlreadyLicensed { player: PlayerID, tech: TechID },
+    /// The converter has already been run this phase.
+    AlreadyRun { converter: ConverterID },
+    /// The cubes given don't pay for exactly one run of the converter.
+    InputsDontMatch { converter: ConverterID },
+    /// The player is constrained from using cubes of this type.
+    ConstrainedCube { player: PlayerID, typ: CubeType },
+    /// The Jii can't constrain players from using this type of cube.
+    NotConstrainable { typ: CubeType },
+    /// The player is already constrained from using cubes of this type.
+    AlreadyConstrained { player: PlayerID, typ: CubeType },
+    /// The player isn't constrained from using cubes of this type.
+    NotConstrained { player: PlayerID, typ: CubeType },
+    /// The player doesn't hold an envoy.
+    NoEnvoy { player: PlayerID },
+    /// The player has already been stolen from this confluence.
+    ZethSafe { player: PlayerID },
+    /// Another player holds more envoys, so must be stolen from first.
+    NotPreferredTarget { player: PlayerID, envoys: usize },
+    /// Red converters must be given a player to steal from, and other
+    /// converters mustn't.
+    StealTargetMismatch { converter: ConverterID },
+    /// Only physical cubes can be created.
+    VirtualCube { typ: CubeType },
+    /// There's no cube with this ID.
+    NoSuchCube { cube: CubeID },
+    /// The colony isn't up for bids on the colony track.
+    ColonyNotOnTrack { colony: ColonyID },
+    /// The colony already carries a cross colonization token.
+    AlreadyCrossColonized { colony: ColonyID },
+    /// The token's definition doesn't allow it to be traded.
+    TokenUntradable { token: Token },
+    /// The player doesn't hold enough of the token.
+    NotEnoughTokens {
+        player: PlayerID,
+        token: Token,
+        held: usize,
+    },
+    /// The converter is already marked to run.
+    AlreadyMarked { converter: ConverterID },
+    /// The converter isn't marked to run.
+    NotMarked { converter: ConverterID },
+    /// The player has already bid this confluence.
+    AlreadyBid { player: PlayerID },
+    /// The record is only allowed for a particular faction, and the player
+    /// isn't playing it (e.g. only Base Kjas may split a colony bid).
+    FactionMismatch {
+        player: PlayerID,
+        expected: FactionType,
+        actual: Option<FactionType>,
+    },
+    /// A split bid must be split as evenly as possible.
+    UnevenSplitBid { player: PlayerID },
+    /// The player doesn't have enough ships for their bid.
+    InsufficientShips {
+        player: PlayerID,
+        bid: usize,
+        ships: usize,
+    },
+    /// The player doesn't have enough cubes of a type.
+    InsufficientCubes {
+        player: PlayerID,
+        typ: CubeType,
+        needed: usize,
+        has: usize,
+    },
+    /// It isn't this player's turn to take from a bid track.
+    NotPlayersTurn { player: PlayerID },
+    /// There's no such position on the bid track.
+    NoSuchTrackSlot { index: usize },
+    /// The slot on the bid track has already been taken.
+    EmptyTrackSlot { index: usize },
+    /// Players still have to take from the bid track, or pass, before the
+    /// phase can end.
+    TakesPending { phase: Phase },
+    /// The player doesn't own this research team.
+    TeamNotOwned { player: PlayerID, tech: TechID },
+    /// The technology can't be paid for with this cube type.
+    NoSuchCost { tech: TechID, typ: CubeType },
+    /// Seats must list every player exactly once.
+    BadSeating,
+    /// A player's name can't be blank.
+    BlankName { player: PlayerID },
+    /// The project isn't in the game.
+    NoSuchProject { project: ProjectID },
+    /// The project isn't in the right state for this (e.g. only idle
+    /// projects can be voted on).
+    WrongProjectState {
+        project: ProjectID,
+        state: ProjectState,
+    },
+    /// The player has already voted for this project.
+    AlreadyVoted {
+        player: PlayerID,
+        project: ProjectID,
+    },
+    /// Deep Unity doesn't have this die, or hasn't rolled yet.
+    NoSuchDie { die: usize },
+    /// Dice faces are numbered from 1 to `AltUnityState::SIDES`.
+    BadDieFace { face: u8 },
+    /// The player has no dice change cards left.
+    NoDiceChangeCards { player: PlayerID },
+    /// Deep Unity have already rolled their dice this confluence.
+    AlreadyRolled { player: PlayerID },
+    /// None of Deep Unity's dice show the face this converter needs.
+    DieNotShowing { converter: ConverterID, face: u8 },
+    /// The player doesn't have enough fleet support for the converters
+    /// they'd be running or lending.
+    InsufficientFleetSupport {
+        player: PlayerID,
+        needed: usize,
+        has: usize,
+    },
+    /// The Imdril player has already raised fleet support this confluence.
+    FleetAlreadyRaised { player: PlayerID },
+    /// The Imdril player can't raise this much fleet support, at once or
+    /// in all.
+    TooMuchFleetSupport { player: PlayerID, qty: usize },
+    /// Only the engine makes this record, as part of applying another.
+    EngineOnly,
+    /// The player hasn't given the Faderan anything in a trade since they
+    /// were last acknowledged.
+    NothingToAcknowledge { player: PlayerID },
+    /// The player can't submit this record on their own: it acts for
+    /// someone else, or is left to the host.
+    NotPlayers { player: PlayerID },
+}
+
+/// Shorthand for failing validation unless a condition holds.
+pub(crate) fn ensure(cond: bool, err: ValidationError) -> Result<(), ValidationError> {
+    if cond {
+        Ok(())
+    } else {
+        Err(err)
+    }
+}
+
+impl GameState {
+    /// Checks whether a record can be applied to the current state, and if
+    /// not, why not.
+    pub fn validate(&self, rec: &RecordType) -> Result<(), ValidationError> {
+        self.validate_rules(rec)?;
+        self.validate_behaviors(rec)
+    }
+
+    /// The checks every record gets, whichever factions are playing. See
+    /// `behavior::FactionBehavior::validate_record` for the rest.
+    fn validate_rules(&self, rec: &RecordType) -> Result<(), ValidationError> {
+        match rec {
+            RecordType::CreatePlayer { player, faction } => {
+                // check that this player ID doesn't exist, and that
+                // nobody has selected this faction yet.
+                ensure(
+                    !self.factions.contains_key(player),
+                    ValidationError::PlayerExists { player: *player },
+                )?;
+                ensure(
+                    !self
+                        .factions
+                        .values()
+                        .any(|f| f == faction || *f == faction.bifurcate()),
+                    ValidationError::FactionTaken { faction: *faction },
+                )
+            }
+            RecordType::ChangePhase { to } => {
+                // check that we don't skip a phase. There has to be a more
+                // idiomatic way to do this.
+                let ok = match to {
+                    Phase::Init => false,
+                    Phase::Trade => self.phase == Phase::Init || self.phase == Phase::ZethSteal,
+                    Phase::Economy => self.phase == Phase::Trade,
+                    Phase::ColonyBid => {
+                        self.phase == Phase::Economy
+                            && !(self.config.rules.strict_game_end() && self.final_confluence())
+                    }
+                    Phase::TechBid => self.phase == Phase::ColonyBid,
+                    Phase::ZethSteal => self.phase == Phase::TechBid,
+                    Phase::Resolution => {
+                        self.phase == Phase::Economy
+                            && (!self.config.rules.strict_game_end() || self.final_confluence())
+                    }
+                    Phase::Finish => self.phase == Phase::Resolution,
+                };
+                ensure(
+                    ok,
+                    ValidationError::PhaseOrder {
+                        from: self.phase,
+                        to: *to,
+                    },
+                )?;
+                // everyone has to take or pass before a bid round ends
+                let taken = match self.phase {
+                    Phase::ColonyBid => self.colony_bid_order.is_empty(),
+                    Phase::TechBid => self.tech_bid_order.is_empty(),
+                    _ => true,
+                };
+                ensure(taken, ValidationError::TakesPending { phase: self.phase })
+            }
+            RecordType::TradeCubes {
+                a,
+                b,
+                a_cubes,
+                b_cubes,
+            } => {
+                ensure(
+                    self.phase_rules().trade_cubes,
+                    ValidationError::TradingClosed { phase: self.phase },
+                )?;
+                // check that each player owns all cubes involved.
+                ensure(a != b, ValidationError::SelfTrade { player: *a })?;
+                self.owns_cubes(*a, a_cubes)?;
+                self.owns_cubes(*b, b_cubes)
+            }
+            RecordType::TradeColony {
+                a,
+                b,
+                a_colony,
+                b_colony,
+            } => {
+                ensure(
+                    self.phase_rules().trade_colonies,
+                    ValidationError::TradingClosed { phase: self.phase },
+                )?;
+                ensure(a != b, ValidationError::SelfTrade { player: *a })?;
+                for (player, colonies) in [(a, a_colony), (b, b_colony)] {
+                    for c in colonies.iter() {
+                        ensure(
+                            self.colony_owners.get(c) == Some(player),
+                            ValidationError::ColonyNotOwned {
+                                player: *player,
+                                colony: *c,
+                            },
+                        )?;
+                    }
+                }
+                Ok(())
+            }
+            RecordType::TradeConverter {
+                a,
+                b,
+                a_converter,
+                b_converter,
+                permanent,
+            } => {
+                ensure(a != b, ValidationError::SelfTrade { player: *a })?;
+                self.can_trade_converters(*a, a_converter, *permanent)?;
+                self.can_trade_converters(*b, b_converter, *permanent)?;
+                // fleet support goes along with loaned converters, so the
+                // lender needs enough to send.
+                if !permanent {
+                    self.can_lend_fleet(*a, a_converter)?;
+                    self.can_lend_fleet(*b, b_converter)?;
+                }
+                Ok(())
+            }
+            RecordType::TradeConverterPermanently {
+                a,
+                b,
+                a_converter,
+                b_converter,
+            } => {
+                ensure(a != b, ValidationError::SelfTrade { player: *a })?;
+                self.can_trade_converters(*a, a_converter, true)?;
+                self.can_trade_converters(*b, b_converter, true)
+            }
+            RecordType::Bid {
+                player,
+                for_colony,
+                for_colony_kjas,
+                for_tech,
+                for_tech_faderan,
+            } => {
+                // bids for both tracks are made at once, before the colony
+                // round
+                ensure(
+                    self.phase == Phase::ColonyBid,
+                    ValidationError::WrongPhase { phase: self.phase },
+                )?;
+                let ships = self.ships_of(*player);
+                // player has not bid for colonies or techs yet
+                ensure(
+                    !self.player_colony_bid.contains_key(player)
+                        && !self.player_tech_bid.contains_key(player),
+                    ValidationError::AlreadyBid { player: *player },
+                )?;
+                // check that the player if the player bid twice, that they
+                // are kjas (or alt faderan for techs) and their bid is split
+                // evenly.
+                for (first, second, faction) in [
+                    (for_colony, for_colony_kjas, FactionType::KjasCore),
+                    (for_tech, for_tech_faderan, FactionType::FaderanAlt),
+                ] {
+                    if let Some(b) = second {
+                        let actual = self.factions.get(player).copied();
+                        ensure(
+                            actual == Some(faction),
+                            ValidationError::FactionMismatch {
+                                player: *player,
+                                expected: faction,
+                                actual,
+                            },
+                        )?;
+                        ensure(
+                            b.max(first) - b.min(first) <= 1,
+                            ValidationError::UnevenSplitBid { player: *player },
+                        )?;
+                    }
+                }
+                // check that the player can afford the bid.
+                let bid = for_colony
+                    + for_colony_kjas.unwrap_or(0)
+                    + for_tech
+                    + for_tech_faderan.unwrap_or(0);
+                ensure(
+                    ships >= bid,
+                    ValidationError::InsufficientShips {
+                        player: *player,
+                        bid,
+                        ships,
+                    },
+                )
+            }
+            RecordType::TakeColony { player, colony } => {
+                self.can_take(BidKind::Colony, *player, *colony)
+            }
+            RecordType::TakeResearch { player, tech } => {
+                self.can_take(BidKind::Tech, *player, *tech)
+            }
+            RecordType::InventTech { player, tech, cost } => {
+                ensure(
+                    self.phase == Phase::Trade,
+                    ValidationError::WrongPhase { phase: self.phase },
+                )?;
+                ensure(
+                    self.tech_team_owners.get(tech) == Some(player),
+                    ValidationError::TeamNotOwned {
+                        player: *player,
+                        tech: *tech,
+                    },
+                )?;
+                let needed = self
+                    .data
+                    .tech
+                    .get(tech)
+                    .and_then(|t| t.cost.iter().find(|t| t.typ == *cost))
+                    .map(|c| c.qty)
+                    .ok_or(ValidationError::NoSuchCost {
+                        tech: *tech,
+                        typ: *cost,
+                    })?;
+                ensure(
+                    !self.is_constrained(*player, *cost),
+                    ValidationError::ConstrainedCube {
+                        player: *player,
+                        typ: *cost,
+                    },
+                )?;
+                let has = self.get_player_cubes(*player).count_type(*cost).max(0) as usize;
+                ensure(
+                    has >= needed,
+                    ValidationError::InsufficientCubes {
+                        player: *player,
+                        typ: *cost,
+                        needed,
+                        has,
+                    },
+                )
+            }
+            RecordType::MarkConverter { player, converter } => {
+                ensure(
+                    self.phase == Phase::Trade,
+                    ValidationError::WrongPhase { phase: self.phase },
+                )?;
+                ensure(
+                    !self.marked_converters.contains(converter),
+                    ValidationError::AlreadyMarked {
+                        converter: *converter,
+                    },
+                )?;
+                self.owns_converter(*player, *converter)?;
+                let fleet = self.converter(*converter).map(|c| c.fleet_support());
+                let needed = self.fleet_committed(*player) + fleet.unwrap_or_default();
+                let has = self.fleet_support(*player);
+                ensure(
+                    needed <= has,
+                    ValidationError::InsufficientFleetSupport {
+                        player: *player,
+                        needed,
+                        has,
+                    },
+                )?;
+                match self.converters.get(converter).map(|c| c.color()) {
+                    Some(Arrow::White) => Ok(()),
+                    Some(color) => Err(ValidationError::WrongArrow {
+                        converter: *converter,
+                        color,
+                    }),
+                    None => Err(ValidationError::ConverterNotOwned {
+                        player: *player,
+                        converter: *converter,
+                    }),
+                }
+            }
+            RecordType::MarkAll { player, .. } => {
+                ensure(
+                    self.phase == Phase::Trade,
+                    ValidationError::WrongPhase { phase: self.phase },
+                )?;
+                self.player_exists(*player)
+            }
+            RecordType::UnmarkConverter { player, converter } => {
+                ensure(
+                    self.marked_converters.contains(converter),
+                    ValidationError::NotMarked {
+                        converter: *converter,
+                    },
+                )?;
+                self.owns_converter(*player, *converter)
+            }
+            RecordType::UpgradeConverter {
+                conv,
+                opt,
+                colonies,
+            } => {
+                ensure(
+                    self.phase == Phase::Trade,
+                    ValidationError::WrongPhase { phase: self.phase },
+                )?;
+                let (Some(converter), Some(player)) =
+                    (self.converters.get(conv), self.converter_owners.get(conv))
+                else {
+                    return Err(ValidationError::NoSuchConverter { converter: *conv });
+                };
+                let cost = converter
+                    .upgrade_opts()
+                    .filter(|n| converter.upgradable() && *opt < *n)
+                    .and_then(|_| converter.upgrade_cost(*opt))
+                    .ok_or(ValidationError::NoSuchUpgrade {
+                        converter: *conv,
+                        opt: *opt,
+                    })?;
+                self.can_pay_upgrade(*player, *conv, *opt, &cost, colonies)
+            }
+            RecordType::GiveAcknowledgement { player } => {
+                // acknowledgements are given by the Faderan to other players
+                let faderan = self
+                    .factions
+                    .iter()
+                    .find(|(_, f)| **f == FactionType::FaderanCore)
+                    .map(|(p, _)| *p)
+                    .ok_or(ValidationError::NoSuchFaction {
+                        faction: FactionType::FaderanCore,
+                    })?;
+                self.player_exists(*player)?;
+                ensure(
+                    faderan != *player,
+                    ValidationError::SelfTrade { player: *player },
+                )?;
+                // one for each trade in which the player gave them something
+                ensure(
+                    self.acknowledgements_owed(*player) > 0,
+                    ValidationError::NothingToAcknowledge { player: *player },
+                )?;
+                ensure(
+                    self.supply_of(Token::Acknowledgement).can_supply(1),
+                    ValidationError::TokenExhausted {
+                        token: Token::Acknowledgement,
+                    },
+                )
+            }
+            RecordType::License { player, tech } => {
+                // what the yengii may license is up to their behavior
+                ensure(
+                    self.factions
+                        .values()
+                        .any(|f| *f == FactionType::YengiiCore),
+                    ValidationError::NoSuchFaction {
+                        faction: FactionType::YengiiCore,
+                    },
+                )?;
+                self.player_exists(*player)?;
+                ensure(
+                    !self.licensees(*tech).contains(player),
+                    ValidationError::AlreadyLicensed {
+                        player: *player,
+                        tech: *tech,
+                    },
+                )
+            }
+            RecordType::Retrocontinuity { converter, inputs } => {
+                let (Some(conv), Some(player)) = (
+                    self.converters.get(converter),
+                    self.converter_owners.get(converter),
+                ) else {
+                    return Err(ValidationError::NoSuchConverter {
+                        converter: *converter,
+                    });
+                };
+                self.can_retrocontinue(*player)?;
+                // only converters which would run in the economy phase can
+                // have their outputs brought forward.
+                ensure(
+                    conv.color() == Arrow::White,
+                    ValidationError::WrongArrow {
+                        converter: *converter,
+                        color: conv.color(),
+                    },
+                )?;
+                ensure(
+                    !self.retro_used_conveter.contains(converter),
+                    ValidationError::AlreadyRetrocontinued {
+                        converter: *converter,
+                    },
+                )?;
+                if let Some(face) = self.unity_die_needed(*converter) {
+                    ensure(
+                        self.alt_unity_state.shows(face),
+                        ValidationError::DieNotShowing {
+                            converter: *converter,
+                            face,
+                        },
+                    )?;
+                }
+                self.can_pay_inputs(*player, inputs)?;
+                ensure(
+                    self.inputs_match(*converter, inputs),
+                    ValidationError::InputsDontMatch {
+                        converter: *converter,
+                    },
+                )
+            }
+            RecordType::ColonyRetrocontinuity { colony, inputs } => {
+                let player = self
+                    .colony_owners
+                    .get(colony)
+                    .ok_or(ValidationError::UnownedColony { colony: *colony })?;
+                self.can_retrocontinue(*player)?;
+                ensure(
+                    !self.retro_used_colony.contains(colony),
+                    ValidationError::ColonyAlreadyRetrocontinued { colony: *colony },
+                )?;
+                self.can_pay_inputs(*player, inputs)?;
+                ensure(
+                    self.cubes_match(&self.colony_inputs(*colony), inputs),
+                    ValidationError::ColonyInputsDontMatch { colony: *colony },
+                )
+            }
+            RecordType::TradeRetroTokens {
+                a,
+                b,
+                a_tokens,
+                b_tokens,
+            } => {
+                // tokens are only any use in the trade phase, so they
+                // follow the rules for colonies rather than cubes
+                ensure(
+                    self.phase_rules().trade_colonies,
+                    ValidationError::TradingClosed { phase: self.phase },
+                )?;
+                ensure(a != b, ValidationError::SelfTrade { player: *a })?;
+                self.holds_retro_tokens(*a, a_tokens)?;
+                self.holds_retro_tokens(*b, b_tokens)
+            }
+            RecordType::TradeItems {
+                a,
+                b,
+                a_items,
+                b_items,
+            } => {
+                ensure(a != b, ValidationError::SelfTrade { player: *a })?;
+                self.can_give_items(*a, a_items)?;
+                self.can_give_items(*b, b_items)
+            }
+            RecordType::RunConverter {
+                converter,
+                inputs,
+                target,
+            } => {
+                let (Some(conv), Some(player)) = (
+                    self.converters.get(converter),
+                    self.converter_owners.get(converter),
+                ) else {
+                    return Err(ValidationError::NoSuchConverter {
+                        converter: *converter,
+                    });
+                };
+                let phase = match conv.color() {
+                    Arrow::White => Phase::Economy,
+                    Arrow::Purple => Phase::Trade,
+                    Arrow::Red => Phase::ZethSteal,
+                };
+                ensure(
+                    self.phase == phase,
+                    ValidationError::WrongPhase { phase: self.phase },
+                )?;
+                if conv.color() == Arrow::White {
+                    ensure(
+                        self.marked_converters.contains(converter),
+                        ValidationError::NotMarked {
+                            converter: *converter,
+                        },
+                    )?;
+                    ensure(
+                        !self.retro_used_conveter.contains(converter),
+                        ValidationError::AlreadyRetrocontinued {
+                            converter: *converter,
+                        },
+                    )?;
+                }
+                ensure(
+                    !self.ran_converters.contains(converter),
+                    ValidationError::AlreadyRun {
+                        converter: *converter,
+                    },
+                )?;
+                if let Some(face) = self.unity_die_needed(*converter) {
+                    ensure(
+                        self.alt_unity_state.shows(face),
+                        ValidationError::DieNotShowing {
+                            converter: *converter,
+                            face,
+                        },
+                    )?;
+                }
+                ensure(
+                    target.is_some() == (conv.color() == Arrow::Red),
+                    ValidationError::StealTargetMismatch {
+                        converter: *converter,
+                    },
+                )?;
+                if let Some(t) = target {
+                    self.can_steal_from(*player, *t)?;
+                }
+                self.can_pay_inputs(*player, inputs)?;
+                ensure(
+                    self.inputs_match(*converter, inputs),
+                    ValidationError::InputsDontMatch {
+                        converter: *converter,
+                    },
+                )
+            }
+            RecordType::RunColony { colony, inputs } => {
+                ensure(
+                    self.phase == Phase::Economy,
+                    ValidationError::WrongPhase { phase: self.phase },
+                )?;
+                let (Some(data), Some(player)) =
+                    (self.data.colony.get(colony), self.colony_owners.get(colony))
+                else {
+                    return Err(ValidationError::UnownedColony { colony: *colony });
+                };
+                ensure(
+                    data.conv.color == Arrow::White,
+                    ValidationError::WrongColonyArrow {
+                        colony: *colony,
+                        color: data.conv.color,
+                    },
+                )?;
+                ensure(
+                    !self.ran_colonies.contains(colony),
+                    ValidationError::ColonyAlreadyRun { colony: *colony },
+                )?;
+                ensure(
+                    !self.retro_used_colony.contains(colony),
+                    ValidationError::ColonyAlreadyRetrocontinued { colony: *colony },
+                )?;
+                self.can_pay_inputs(*player, inputs)?;
+                ensure(
+                    self.cubes_match(&self.colony_inputs(*colony), inputs),
+                    ValidationError::ColonyInputsDontMatch { colony: *colony },
+                )
+            }
+            RecordType::DoubleColony { player, colony } => {
+                self.is_faction(*player, FactionType::CaylionCore)?;
+                self.can_double_colony(*player, *colony)
+            }
+            RecordType::UndoubleColony { player, colony } => {
+                self.is_faction(*player, FactionType::CaylionCore)?;
+                ensure(
+                    self.doubled_colonies.contains(colony),
+                    ValidationError::NotDoubled { colony: *colony },
+                )
+            }
+            RecordType::PlaceConstraint { player, on, typ } => {
+                self.can_place_constraint(*player, *on, *typ)
+            }
+            RecordType::RemoveConstraint { player, from, typ } => {
+                self.can_remove_constraint(*player, *from, *typ)
+            }
+            RecordType::GiveEnvoy { player, to } => self.can_give_envoy(*player, *to),
+            RecordType::ReturnEnvoy { player } => self.can_return_envoy(*player),
+            RecordType::PlaceCrossToken { player, colony } => {
+                self.can_place_cross_token(*player, *colony)
+            }
+            RecordType::SpawnCubes {
+                player,
+                typ,
+                donation,
+                ..
+            } => self.can_spawn_cubes(*player, *typ, *donation),
+            RecordType::DestroyCubes { cubes, .. } => self.can_destroy_cubes(cubes),
+            RecordType::CreateDeck { deck, .. } => ensure(
+                !self.decks.contains_key(deck),
+                ValidationError::DeckExists { deck: *deck },
+            ),
+            RecordType::DrawFromDeck { deck, player } => {
+                self.player_exists(*player)?;
+                self.deck_has(*deck, 1)
+            }
+            RecordType::VoteForProject { player, project } => {
+                self.player_exists(*player)?;
+                self.project_is(*project, ProjectState::Idle)?;
+                ensure(
+                    !self.project_votes(*project).contains(player),
+                    ValidationError::AlreadyVoted {
+                        player: *player,
+                        project: *project,
+                    },
+                )
+            }
+            RecordType::ActivateProject { player, project } => {
+                self.is_faction(*player, FactionType::CaylionAlt)?;
+                self.project_is(*project, ProjectState::Pending)
+            }
+            RecordType::CompleteProject { player, project } => {
+                self.is_faction(*player, FactionType::CaylionAlt)?;
+                self.project_is(*project, ProjectState::Active)
+            }
+            RecordType::AssignSeats { seats } => {
+                let mut sorted = seats.clone();
+                sorted.sort();
+                sorted.dedup();
+                ensure(
+                    sorted.len() == seats.len()
+                        && seats.len() == self.factions.len()
+                        && seats.iter().all(|p| self.factions.contains_key(p)),
+                    ValidationError::BadSeating,
+                )
+            }
+            RecordType::SwapSeats { a, b } => {
+                self.player_exists(*a)?;
+                self.player_exists(*b)
+            }
+            RecordType::DrawRelic { player } => {
+                self.is_faction(*player, FactionType::FaderanCore)?;
+                self.deck_has(DeckID::RELICS, 1)
+            }
+            // the dice are rolled once a confluence, before the economy phase
+            RecordType::RollUnityDice { player } => {
+                self.is_faction(*player, FactionType::UnityAlt)?;
+                ensure(
+                    self.phase == Phase::Trade,
+                    ValidationError::WrongPhase { phase: self.phase },
+                )?;
+                ensure(
+                    !self.alt_unity_state.rolled,
+                    ValidationError::AlreadyRolled { player: *player },
+                )
+            }
+            RecordType::GainDiceChangeCards { player, .. } => {
+                self.is_faction(*player, FactionType::UnityAlt)
+            }
+            // the Grand Fleet can never gain more fleet support, and the
+            // Imdril raise theirs a little each economy phase
+            RecordType::GainFleetSupport { player, qty } => {
+                self.is_faction(*player, FactionType::ImdrilCore)?;
+                ensure(
+                    self.phase == Phase::Economy,
+                    ValidationError::WrongPhase { phase: self.phase },
+                )?;
+                ensure(
+                    self.fleet_raised.get(player) != Some(&self.confluence),
+                    ValidationError::FleetAlreadyRaised { player: *player },
+                )?;
+                let has = self.fleet_support(*player);
+                ensure(
+                    *qty <= FLEET_SUPPORT_PER_CONFLUENCE && has + *qty <= MAX_FLEET_SUPPORT,
+                    ValidationError::TooMuchFleetSupport {
+                        player: *player,
+                        qty: *qty,
+                    },
+                )
+            }
+            RecordType::PlaceServiceToken { player, converter } => {
+                self.player_exists(*player)?;
+                ensure(
+                    self.service_tokens(*player) > 0,
+                    ValidationError::NoServiceToken { player: *player },
+                )?;
+                let conv = self
+                    .converters
+                    .get(converter)
+                    .filter(|_| self.converter_owners.contains_key(converter))
+                    .ok_or(ValidationError::NoSuchConverter {
+                        converter: *converter,
+                    })?;
+                ensure(
+                    conv.color() == Arrow::White,
+                    ValidationError::WrongArrow {
+                        converter: *converter,
+                        color: conv.color(),
+                    },
+                )?;
+                ensure(
+                    !self.serviced(*converter),
+                    ValidationError::AlreadyServiced {
+                        converter: *converter,
+                    },
+                )
+            }
+            RecordType::RemoveServiceToken { converter } => {
+                ensure(
+                    self.serviced(*converter),
+                    ValidationError::NotServiced {
+                        converter: *converter,
+                    },
+                )?;
+                ensure(
+                    !self.service_locked(*converter),
+                    ValidationError::ServiceLocked {
+                        converter: *converter,
+                    },
+                )
+            }
+            RecordType::ChangeUnityDie { player, die, face } => {
+                self.is_faction(*player, FactionType::UnityAlt)?;
+                ensure(
+                    *die < self.alt_unity_state.faces.len(),
+                    ValidationError::NoSuchDie { die: *die },
+                )?;
+                ensure(
+                    (1..=AltUnityState::SIDES).contains(face),
+                    ValidationError::BadDieFace { face: *face },
+                )?;
+                ensure(
+                    self.alt_unity_state.change_cards > 0,
+                    ValidationError::NoDiceChangeCards { player: *player },
+                )
+            }
+            RecordType::PeekDeck {
+                deck,
+                player,
+                count,
+            } => {
+                self.player_exists(*player)?;
+                self.deck_has(*deck, *count)
+            }
+            RecordType::RecurringTransfer { from, to, .. } => {
+                ensure(from != to, ValidationError::SelfTrade { player: *from })?;
+                self.player_exists(*from)?;
+                self.player_exists(*to)
+            }
+            RecordType::ProposeTrade {
+                from,
+                to,
+                give,
+                take,
+                permanent,
+                counters,
+            } => {
+                self.player_exists(*from)?;
+                self.player_exists(*to)?;
+                if let Some(c) = counters {
+                    let old = self
+                        .offer(*c)
+                        .ok_or(ValidationError::NoSuchOffer { offer: *c })?;
+                    ensure(
+                        old.from == *to && old.to == *from,
+                        ValidationError::NotACounter { offer: *c },
+                    )?;
+                }
+                let offer = TradeOffer {
+                    id: self.next_record_id,
+                    from: *from,
+                    to: *to,
+                    give: give.clone(),
+                    take: take.clone(),
+                    permanent: *permanent,
+                };
+                ensure(from != to, ValidationError::SelfTrade { player: *from })?;
+                offer.records().iter().try_for_each(|r| self.validate(r))
+            }
+            RecordType::AcceptTrade { offer } => {
+                // everything has to still be owned by the same players
+                self.offer(*offer)
+                    .ok_or(ValidationError::NoSuchOffer { offer: *offer })?
+                    .records()
+                    .iter()
+                    .try_for_each(|r| self.validate(r))
+            }
+            RecordType::RejectTrade { offer } => {
+                self.offer(*offer)
+                    .ok_or(ValidationError::NoSuchOffer { offer: *offer })?;
+                Ok(())
+            }
+            RecordType::Contract {
+                debtor, creditor, ..
+            } => {
+                ensure(
+                    debtor != creditor,
+                    ValidationError::SelfTrade { player: *debtor },
+                )?;
+                self.player_exists(*debtor)?;
+                self.player_exists(*creditor)
+            }
+            RecordType::Promise { from, to, .. } => {
+                ensure(from != to, ValidationError::SelfTrade { player: *from })?;
+                self.player_exists(*from)?;
+                self.player_exists(*to)
+            }
+            RecordType::SettlePromise {
+                player, promise, ..
+            } => self.can_settle_promise(*player, *promise),
+            RecordType::FulfillContract { contract, cubes } => {
+                let c = self
+                    .contract(*contract)
+                    .ok_or(ValidationError::NoSuchContract {
+                        contract: *contract,
+                    })?;
+                ensure(
+                    cubes.len() <= c.remaining(),
+                    ValidationError::ContractOverpaid {
+                        contract: *contract,
+                        remaining: c.remaining(),
+                    },
+                )?;
+                self.owns_cubes(c.debtor, cubes)?;
+                for cube in cubes.iter() {
+                    ensure(
+                        self.cubes.get(cube).is_some_and(|x| x.typ == c.typ),
+                        ValidationError::WrongCubeType {
+                            cube: *cube,
+                            expected: c.typ,
+                        },
+                    )?;
+                }
+                Ok(())
+            }
+            RecordType::AddNote { player, .. } => self.player_exists(*player),
+            RecordType::RemoveNote { player, note } => ensure(
+                self.notes(*player).iter().any(|n| n.id == *note),
+                ValidationError::NoSuchNote {
+                    player: *player,
+                    note: *note,
+                },
+            ),
+            RecordType::ShuffleDeck { deck } => self.deck_has(*deck, 0),
+            RecordType::SetProfile { player, profile } => {
+                self.player_exists(*player)?;
+                ensure(
+                    profile.name.as_ref().is_none_or(|n| !n.trim().is_empty()),
+                    ValidationError::BlankName { player: *player },
+                )
+            }
+            RecordType::CreateTechDeck | RecordType::CreateColonyDeck => ensure(
+                self.phase == Phase::Init,
+                ValidationError::WrongPhase { phase: self.phase },
+            ),
+        }
+    }
+
+    /// Validates each record as if the ones before it had been applied,
+    /// without changing the game. Invalid records are left out of the
+    /// state later records are checked against, so one mistake doesn't
+    /// make everything after it fail too. Useful for feedback while a
+    /// multi-part trade is being put together. Fails if the game can't be
+    /// copied to check the records against.
+    pub fn validate_partial(
+        &self,
+        records: &[RecordType],
+    ) -> Result<Vec<Result<(), ValidationError>>, Error> {
+        let mut fork = self.fork()?;
+        Ok(records
+            .iter()
+            .map(|r| {
+                fork.validate(r)?;
+                // each record gets its own ID, as it would if submitted, so
+                // offers and contracts it makes don't collide
+                fork.apply(RecordGroup {
+                    id: fork.next_record_id(),
+                    rec: vec![r.clone()],
+                });
+                Ok(())
+            })
+            .collect())
+    }
+
+    /// Validates a group of records from outside the engine the way it
+    /// would be applied: each record as if the ones before it had been,
+    /// without changing the game. The first invalid record fails the
+    /// group, as does any record only the engine may make. Fails outright
+    /// if the game can't be copied to check the records against.
+    pub fn validate_group(
+        &self,
+        records: &[RecordType],
+    ) -> Result<Result<(), ValidationError>, Error> {
+        if records.iter().any(|r| self.engine_only(r)) {
+            return Ok(Err(ValidationError::EngineOnly));
+        }
+        // a lone record has nothing before it to be checked after
+        if let [rec] = records {
+            return Ok(self.validate(rec));
+        }
+        let mut fork = self.fork()?;
+        let id = fork.next_record_id();
+        for r in records {
+            if let Err(e) = fork.validate(r) {
+                return Ok(Err(e));
+            }
+            fork.apply_record(id, r);
+            fork.record_applied_behaviors(id, r);
+        }
+        Ok(Ok(()))
+    }
+
+    /// Checks that it's a player's turn to take from a bid track, and that
+    /// the slot they're taking, if any, is filled and they can pay their
+    /// bid for it.
+    fn can_take(
+        &self,
+        kind: BidKind,
+        player: PlayerID,
+        slot: Option<usize>,
+    ) -> Result<(), ValidationError> {
+        let phase = match kind {
+            BidKind::Colony => Phase::ColonyBid,
+            BidKind::Tech => Phase::TechBid,
+        };
+        ensure(
+            self.phase == phase,
+            ValidationError::WrongPhase { phase: self.phase },
+        )?;
+        ensure(
+            self.take_order(kind).first() == Some(&player),
+            ValidationError::NotPlayersTurn { player },
+        )?;
+        let Some(index) = slot else {
+            return Ok(());
+        };
+        let filled = match kind {
+            BidKind::Colony => self.colony_bid_track.get(index).map(|s| s.is_some()),
+            BidKind::Tech => self.tech_bid_track.get(index).map(|s| s.is_some()),
+        };
+        match filled {
+            None => return Err(ValidationError::NoSuchTrackSlot { index }),
+            Some(false) => return Err(ValidationError::EmptyTrackSlot { index }),
+            Some(true) => {}
+        }
+        let bid = self.take_cost(kind).unwrap_or_default();
+        let ships = self.ships_available(player, kind);
+        ensure(
+            ships >= bid,
+            ValidationError::InsufficientShips { player, bid, ships },
+        )
+    }
+
+    /// Checks that a player is playing a particular faction.
+    fn is_faction(&self, player: PlayerID, faction: FactionType) -> Result<(), ValidationError> {
+        let actual = self.factions.get(&player).copied();
+        ensure(
+            actual == Some(faction),
+            ValidationError::FactionMismatch {
+                player,
+                expected: faction,
+                actual,
+            },
+        )
+    }
+
+    /// Checks that a project is in the game, and in a given state.
+    fn project_is(&self, project: ProjectID, state: ProjectState) -> Result<(), ValidationError> {
+        let actual = self
+            .project_state(project)
+            .ok_or(ValidationError::NoSuchProject { project })?;
+        ensure(
+            actual == state,
+            ValidationError::WrongProjectState {
+                project,
+                state: actual,
+            },
+        )
+    }
+
+    /// Checks that a deck exists, and has at least `needed` cards.
+    fn deck_has(&self, deck: DeckID, needed: usize) -> Result<(), ValidationError> {
+        let has = self
+            .decks
+            .get(&deck)
+            .ok_or(ValidationError::NoSuchDeck { deck })?
+            .cards
+            .len();
+        ensure(
+            has >= needed,
+            ValidationError::NotEnoughCards { deck, needed, has },
+        )
+    }
+
+    fn player_exists(&self, player: PlayerID) -> Result<(), ValidationError> {
+        ensure(
+            self.factions.contains_key(&player),
+            ValidationError::NoSuchPlayer { player },
+        )
+    }
+
+    /// Checks that a player owns cubes they're paying as inputs, and isn't
+    /// constrained from using any of them.
+    pub(crate) fn can_pay_inputs(
+        &self,
+        player: PlayerID,
+        inputs: &BTreeSet<CubeID>,
+    ) -> Result<(), ValidationError> {
+        self.owns_cubes(player, inputs)?;
+        let constrained = self.constraints.get(&player);
+        for c in inputs.iter().filter_map(|c| self.cubes.get(c)) {
+            ensure(
+                constrained.is_none_or(|s| !s.contains(&c.typ)),
+                ValidationError::ConstrainedCube { player, typ: c.typ },
+            )?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn owns_cubes(
+        &self,
+        player: PlayerID,
+        cubes: &BTreeSet<CubeID>,
+    ) -> Result<(), ValidationError> {
+        for c in cubes.iter() {
+            ensure(
+                self.cube_owners.get(c) == Some(&player),
+                ValidationError::CubeNotOwned { player, cube: *c },
+            )?;
+        }
+        Ok(())
+    }
+
+    fn owns_converter(
+        &self,
+        player: PlayerID,
+        converter: ConverterID,
+    ) -> Result<(), ValidationError> {
+        ensure(
+            self.converter_owners.get(&converter) == Some(&player),
+            ValidationError::ConverterNotOwned { player, converter },
+        )
+    }
+
+    pub(crate) fn can_lend_fleet(
+        &self,
+        player: PlayerID,
+        converters: &BTreeSet<ConverterID>,
+    ) -> Result<(), ValidationError> {
+        let fleet = |c: &ConverterID| self.converter(*c).map(|c| c.fleet_support());
+        let needed = converters.iter().filter_map(fleet).sum();
+        // support committed to a lent converter goes along with it
+        let has = self.fleet_free(player)
+            + converters
+                .iter()
+                .filter(|c| self.marked_converters.contains(c))
+                .filter_map(fleet)
+                .sum::<usize>();
+        ensure(
+            needed <= has,
+            ValidationError::InsufficientFleetSupport {
+                player,
+                needed,
+                has,
+            },
+        )
+    }
+
+    /// Checks that a player owns converters and may trade them away, either
+    /// permanently or as a loan.
+    pub(crate) fn can_trade_converters(
+        &self,
+        player: PlayerID,
+        converters: &BTreeSet<ConverterID>,
+        permanent: bool,
+    ) -> Result<(), ValidationError> {
+        for c in converters.iter() {
+            if permanent {
+                ensure(
+                    !self.untradable_converters.contains(c),
+                    ValidationError::ConverterUntradable { converter: *c },
+                )?;
+            } else {
+                ensure(
+                    !self.unloanable_converters.contains(c),
+                    ValidationError::ConverterUnloanable { converter: *c },
+                )?;
+            }
+            self.owns_converter(player, *c)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::WrongPhase { phase } => write!(f, "can't do that during the {:?} phase", phase),
+            Self::PhaseOrder { from, to } => write!(f, "can't go from {:?} to {:?}", from, to),
+            Self::TradingClosed { phase } => {
+                write!(f, "that can't be traded during the {:?} phase", phase)
+            }
+            Self::PlayerExists { player } => write!(f, "player {} already exists", player.0),
+            Self::FactionTaken { faction } => {
+                write!(f, "{} (or its other side) is already taken", faction.name())
+            }
+            Self::SelfTrade { player } => {
+                write!(f, "player {} can't trade with themselves", player.0)
+            }
+            Self::NoSuchPlayer { player } => write!(f, "there is no player {}", player.0),
+            Self::NoSuchFaction { faction } => {
+                write!(f, "the {} aren't in the game", faction.name())
+            }
+            Self::CubeNotOwned { player, cube } => {
+                write!(f, "player {} doesn't own cube {}", player.0, cube.0)
+            }
+            Self::ColonyNotOwned { player, colony } => {
+                write!(f, "player {} doesn't own colony {}", player.0, colony.0)
+            }
+            Self::ConverterNotOwned { player, converter } => {
+                write!(
+                    f,
+                    "player {} doesn't own converter {}",
+                    player.0, converter.0
+                )
+            }
+            Self::NoSuchConverter { converter } => {
+                write!(f, "there is no converter {}", converter.0)
+            }
+            Self::ConverterUntradable { converter } => {
+                write!(f, "converter {} can't be traded", converter.0)
+            }
+            Self::ConverterUnloanable { converter } => {
+                write!(f, "converter {} can't be lent out", converter.0)
+            }
+            Self::WrongArrow { converter, color } => {
+                write!(f, "converter {} has a {:?} arrow", converter.0, color)
+            }
+            Self::NoSuchUpgrade { converter, opt } => {
+                write!(f, "converter {} has no upgrade option {}", converter.0, opt)
+            }
+            Self::WrongUpgradeColonies { converter, opt } => write!(
+                f,
+                "those aren't the colonies upgrade option {} of converter {} consumes",
+                opt, converter.0
+            ),
+            Self::NoConverterCard { player, tech } => write!(
+                f,
+                "player {} doesn't have a converter card for technology {}",
+                player.0, tech.0
+            ),
+            Self::NoOtherConverterCard { player, tech } => write!(
+                f,
+                "nobody but player {} has a converter card for technology {}",
+                player.0, tech.0
+            ),
+            Self::UpgradeNotTriggered { converter, opt } => write!(
+                f,
+                "upgrade option {} of converter {} isn't available yet",
+                opt, converter.0
+            ),
+            Self::AlreadyRetrocontinued { converter } => write!(
+                f,
+                "retrocontinuity has already been applied to converter {}",
+                converter.0
+            ),
+            Self::NoRetroToken { player } => {
+                write!(f, "player {} has no unused retrocontinuity token", player.0)
+            }
+            Self::RetroTokenNotHeld { player, token } => write!(
+                f,
+                "player {} doesn't hold player {}'s retrocontinuity token",
+                player.0, token.0
+            ),
+            Self::ColonyAlreadyRetrocontinued { colony } => write!(
+                f,
+                "retrocontinuity has already been applied to colony {}",
+                colony.0
+            ),
+            Self::UnownedColony { colony } => write!(f, "nobody owns colony {}", colony.0),
+            Self::ColonyAlreadyRun { colony } => {
+                write!(f, "colony {} has already been run this phase", colony.0)
+            }
+            Self::WrongColonyArrow { colony, color } => {
+                write!(f, "colony {} has a {:?} arrow", colony.0, color)
+            }
+            Self::AlreadyDoubled { colony } => {
+                write!(f, "colony {} is already doubled", colony.0)
+            }
+            Self::NotDoubled { colony } => write!(f, "colony {} isn't doubled", colony.0),
+            Self::DoublingLimit { player, limit } => write!(
+                f,
+                "player {} already has {} colonies doubled",
+                player.0, limit
+            ),
+            Self::ColonyInputsDontMatch { colony } => write!(
+                f,
+                "those cubes don't pay for exactly one run of colony {}",
+                colony.0
+            ),
+            Self::NoServiceToken { player } => {
+                write!(f, "player {} has no service token to place", player.0)
+            }
+            Self::AlreadyServiced { converter } => {
+                write!(f, "converter {} already has a service token", converter.0)
+            }
+            Self::NotServiced { converter } => {
+                write!(f, "converter {} has no service token", converter.0)
+            }
+            Self::ServiceLocked { converter } => write!(
+                f,
+                "converter {} has run with its service token, which can't be removed",
+                converter.0
+            ),
+            Self::TokenExhausted { token } => write!(f, "there are no {:?} tokens left", token),
+            Self::DeckExists { deck } => write!(f, "deck {} already exists", deck.0),
+            Self::NoSuchDeck { deck } => write!(f, "there is no deck {}", deck.0),
+            Self::NotEnoughCards { deck, needed, has } => {
+                write!(f, "deck {} has {} card(s), needs {}", deck.0, has, needed)
+            }
+            Self::NoSuchOffer { offer } => write!(f, "there is no pending offer {}", offer.0),
+            Self::NotACounter { offer } => write!(
+                f,
+                "a counter to offer {} must go back to the player who made it",
+                offer.0
+            ),
+            Self::NoSuchContract { contract } => write!(f, "there is no contract {}", contract.0),
+            Self::ContractOverpaid {
+                contract,
+                remaining,
+            } => write!(
+                f,
+                "only {} cube(s) are still owed on contract {}",
+                remaining, contract.0
+            ),
+            Self::NoSuchPromise { promise } => write!(f, "there is no promise {}", promise.0),
+            Self::NotPromiseParty { player, promise } => write!(
+                f,
+                "player {} wasn't part of promise {}",
+                player.0, promise.0
+            ),
+            Self::PromiseSettled { promise } => {
+                write!(f, "promise {} has already been settled", promise.0)
+            }
+            Self::WrongCubeType { cube, expected } => {
+                write!(f, "cube {} isn't {:?}", cube.0, expected)
+            }
+            Self::NoSuchNote { player, note } => {
+                write!(f, "player {} has no note {}", player.0, note.0)
+            }
+            Self::NotLicensable { tech } => {
+                write!(
+                    f,
+                    "the Yengii don't hold the license to technology {}",
+                    tech.0
+                )
+            }
+            Self::AlreadyLicensed { player, tech } => {
+                write!(
+                    f,
+                    "player {} already holds a license to technology {}",
+                    player.0, tech.0
+                )
+            }
+            Self::AlreadyRun { converter } => {
+                write!(
+                    f,
+                    "converter {} has already been run this phase",
+                    converter.0
+                )
+            }
+            Self::InputsDontMatch { converter } => write!(
+                f,
+                "those cubes don't pay for exactly one run of converter {}",
+                converter.0
+            ),
+            Self::ConstrainedCube { player, typ } => {
+                write!(f, "player {} can't use {:?} cubes", player.0, typ)
+            }
+            Self::NotConstrainable { typ } => {
+                write!(f, "players can't be constrained from using {:?} cubes", typ)
+            }
+            Self::AlreadyConstrained { player, typ } => write!(
+                f,
+                "player {} is already constrained from using {:?} cubes",
+                player.0, typ
+            ),
+            Self::NotConstrained { player, typ } => write!(
+                f,
+                "player {} isn't constrained from using {:?} cubes",
+                player.0, typ
+            ),
+            Self::NoEnvoy { player } => write!(f, "player {} has no envoys", player.0),
+            Self::ZethSafe { player } => write!(
+                f,
+                "player {} has already been stolen from this confluence",
+                player.0
+            ),
+            Self::NotPreferredTarget { player, envoys } => write!(
+                f,
+                "player {} can't be stolen from while another player holds {} envoys",
+                player.0, envoys
+            ),
+            Self::VirtualCube { typ } => write!(f, "{:?} cubes can't be created", typ),
+            Self::NoSuchCube { cube } => write!(f, "there is no cube {}", cube.0),
+            Self::ColonyNotOnTrack { colony } => {
+                write!(f, "colony {} isn't on the colony track", colony.0)
+            }
+            Self::AlreadyCrossColonized { colony } => write!(
+                f,
+                "colony {} already has a cross colonization token",
+                colony.0
+            ),
+            Self::TokenUntradable { token } => write!(f, "{:?} tokens can't be traded", token),
+            Self::NotEnoughTokens {
+                player,
+                token,
+                held,
+            } => write!(
+                f,
+                "player {} only holds {} {:?} tokens",
+                player.0, held, token
+            ),
+            Self::StealTargetMismatch { converter } => write!(
+                f,
+                "a player to steal from must be given for converter {} if and only if it's red",
+                converter.0
+            ),
+            Self::AlreadyMarked { converter } => {
+                write!(f, "converter {} is already marked", converter.0)
+            }
+            Self::NotMarked { converter } => write!(f, "converter {} isn't marked", converter.0),
+            Self::AlreadyBid { player } => write!(f, "player {} has already bid", player.0),
+            Self::FactionMismatch {
+                player,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "only the {} can do that, player {} is {}",
+                expected.name(),
+                player.0,
+                actual.map(|a| a.name()).unwrap_or("not in the game")
+            ),
+            Self::UnevenSplitBid { player } => {
+                write!(f, "player {}'s split bid isn't split evenly", player.0)
+            }
+            Self::InsufficientShips { player, bid, ships } => write!(
+                f,
+                "player {} bid {} ships but only has {}",
+                player.0, bid, ships
+            ),
+            Self::InsufficientCubes {
+                player,
+                typ,
+                needed,
+                has,
+            } => write!(
+                f,
+                "player {} needs {} {:?} but only has {}",
+                player.0, needed, typ, has
+            ),
+            Self::NotPlayersTurn { player } => {
+                write!(f, "it isn't player {}'s turn to take", player.0)
+            }
+            Self::NoSuchTrackSlot { index } => write!(f, "there is no slot {} on the track", index),
+            Self::EmptyTrackSlot { index } => {
+                write!(f, "slot {} on the track has already been taken", index)
+            }
+            Self::TakesPending { phase } => write!(
+                f,
+                "players still have to take or pass before the {:?} phase ends",
+                phase
+            ),
+            Self::TeamNotOwned { player, tech } => {
+                write!(
+                    f,
+                    "player {} doesn't own research team {}",
+                    player.0, tech.0
+                )
+            }
+            Self::NoSuchCost { tech, typ } => {
+                write!(f, "technology {} can't be invented with {:?}", tech.0, typ)
+            }
+            Self::BadSeating => write!(f, "seats must list every player exactly once"),
+            Self::BlankName { player } => write!(f, "player {}'s name can't be blank", player.0),
+            Self::NoSuchProject { project } => write!(f, "there is no project {}", project.0),
+            Self::WrongProjectState { project, state } => {
+                write!(f, "project {} is {:?}", project.0, state)
+            }
+            Self::AlreadyVoted { player, project } => write!(
+                f,
+                "player {} has already voted for project {}",
+                player.0, project.0
+            ),
+            Self::NoSuchDie { die } => write!(f, "there is no die {}", die),
+            Self::BadDieFace { face } => write!(f, "dice don't have a face {}", face),
+            Self::NoDiceChangeCards { player } => {
+                write!(f, "player {} has no dice change cards", player.0)
+            }
+            Self::AlreadyRolled { player } => {
+                write!(f, "player {} has already rolled this confluence", player.0)
+            }
+            Self::DieNotShowing { converter, face } => {
+                write!(f, "converter {} needs a die showing {}", converter.0, face)
+            }
+            Self::InsufficientFleetSupport {
+                player,
+                needed,
+                has,
+            } => write!(
+                f,
+                "player {} needs {} fleet support but only has {}",
+                player.0, needed, has
+            ),
+            Self::FleetAlreadyRaised { player } => write!(
+                f,
+                "player {} has already raised fleet support this confluence",
+                player.0
+            ),
+            Self::TooMuchFleetSupport { player, qty } => write!(
+                f,
+                "player {} can't raise {} fleet support (at most {} at a time, {} in all)",
+                player.0, qty, FLEET_SUPPORT_PER_CONFLUENCE, MAX_FLEET_SUPPORT
+            ),
+            Self::EngineOnly => write!(f, "only the engine can do that"),
+            Self::NothingToAcknowledge { player } => write!(
+                f,
+                "player {} hasn't given the Faderan anything to acknowledge",
+                player.0
+            ),
+            Self::NotPlayers { player } => {
+                write!(f, "player {} can't do that on their own", player.0)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
@@ -0,0 +1,341 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{
+    colony::ColonyID,
+    converter::ConverterID,
+    cube::{CubeID, CubeRecord},
+    faction::FactionType,
+    technology::TechID,
+};
+
+use super::{
+    access::{Viewer, Zone},
+    awaiting::PendingAction,
+    deck::{DeckID, EntityRef},
+    notes::Note,
+    offers::TradeOffer,
+    player::PlayerID,
+    promises::Promise,
+    record::{RecordID, RecordType},
+    Confluence, GameState, Phase,
+};
+
+/// The game as one player is allowed to see it. Anything the player
+/// shouldn't know (other players' sealed bids, which converters they plan to
+/// run, cards in hand and in decks) is left out, as decided by
+/// `GameState::can_see`, so a view is safe to show on a shared screen or
+/// send to that player's client.
+///
+/// A spectator's view leaves out everything that belongs to any particular
+/// player, including sealed bids until they're revealed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameStateView {
+    /// Who this view is for.
+    pub viewer: Viewer,
+    pub phase: Phase,
+    pub confluence: Confluence,
+    /// Every player in the game, in ascending ID order.
+    pub players: Vec<PlayerView>,
+    /// Players in seating order, clockwise.
+    pub seats: Vec<PlayerID>,
+    /// The viewer's own notes. Spectators have none.
+    pub notes: Vec<Note>,
+    /// Pending trade offers the viewer has made or received. Spectators
+    /// see none.
+    pub offers: Vec<TradeOffer>,
+    /// Every promise made this game, kept, broken or outstanding. Promises
+    /// are made openly, so everyone sees them.
+    #[serde(default)]
+    pub promises: Vec<Promise>,
+    /// Colonies up for bidding, in track order. None for slots already
+    /// taken.
+    pub colony_track: Vec<Option<ColonyID>>,
+    /// Research teams up for bidding, in track order. None for slots
+    /// already taken.
+    pub tech_track: Vec<Option<TechID>>,
+    /// Every named deck, in ID order.
+    pub decks: Vec<DeckView>,
+}
+
+/// What a viewer can see of a named deck.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckView {
+    pub id: DeckID,
+    pub name: String,
+    /// How many cards are left.
+    pub size: usize,
+    /// The cards, top first, if the viewer may see them.
+    pub cards: Option<Vec<EntityRef>>,
+}
+
+/// What a viewer can see of one player.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerView {
+    pub player: PlayerID,
+    pub faction: FactionType,
+    pub cubes: CubeRecord,
+    pub converters: Vec<ConverterID>,
+    /// Converters the player owns which are lent to someone else. These
+    /// aren't in `converters` until they're returned.
+    pub loaned_out: Vec<ConverterID>,
+    pub colonies: Vec<ColonyID>,
+    pub research_teams: Vec<TechID>,
+    /// Whether the player has bid this confluence. Always visible, so
+    /// everyone knows who they're waiting on.
+    pub has_bid: bool,
+    /// The player's bid. Bids are sealed: only the bidder can see theirs
+    /// until every player has bid.
+    pub bid: Option<BidView>,
+    /// Converters the player has marked to run. Only visible to the player
+    /// themselves.
+    pub marked: Option<Vec<ConverterID>>,
+    /// How many cards the player holds. Always visible.
+    pub held_count: usize,
+    /// The cards the player holds. Only visible to the player themselves.
+    pub held_cards: Option<Vec<EntityRef>>,
+    /// What the player can do next. Only visible to the player themselves.
+    #[serde(default)]
+    pub turn: Option<TurnView>,
+}
+
+/// Everything a player needs to decide what to do next, without seeing
+/// anything else they shouldn't: what the game is waiting on them for, the
+/// cubes they'd pay with and the offers made to them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnView {
+    pub phase: Phase,
+    pub confluence: Confluence,
+    /// The ID the player's next group of records gets, unless someone
+    /// else's is applied first.
+    pub next_record: RecordID,
+    /// What the player has to do, or may do, before the phase ends.
+    pub awaiting: Vec<PendingAction>,
+    /// The converters a `MarkAll` would mark now.
+    pub markable: Vec<ConverterID>,
+    /// The player's marked converters which haven't run, in ID order, with
+    /// the cubes picked to run each. No cube is picked twice. None for
+    /// converters the player can't pay for.
+    pub converter_inputs: Vec<(ConverterID, Option<BTreeSet<CubeID>>)>,
+    /// The player's colonies which can run now, with the cubes picked to
+    /// run each, none of them picked for a converter or an earlier colony.
+    pub colony_inputs: Vec<(ColonyID, BTreeSet<CubeID>)>,
+    /// Colony bid track slots the player can take now.
+    pub colony_slots: Vec<usize>,
+    /// Research bid track slots the player can take now.
+    pub tech_slots: Vec<usize>,
+    /// Trade offers made to the player which are still open.
+    pub offers: Vec<OfferView>,
+}
+
+/// A trade offer as the player it's made to sees it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfferView {
+    pub offer: TradeOffer,
+    /// The cubes in `offer.give`, counted by type.
+    pub give_cubes: CubeRecord,
+    /// The cubes in `offer.take`, counted by type.
+    pub take_cubes: CubeRecord,
+    /// Whether the offer can be accepted as things stand.
+    pub acceptable: bool,
+}
+
+/// A player's colony and research team bids, in ships.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BidView {
+    pub colony: (usize, Option<usize>),
+    pub tech: (usize, Option<usize>),
+}
+
+impl GameState {
+    /// The game as `player` is allowed to see it.
+    pub fn view(&self, player: PlayerID) -> GameStateView {
+        self.view_as(Viewer::Player(player))
+    }
+
+    /// The game as someone watching, but not playing, is allowed to see it.
+    pub fn spectator_view(&self) -> GameStateView {
+        self.view_as(Viewer::Spectator)
+    }
+
+    /// The game as any viewer is allowed to see it.
+    pub fn view_as(&self, viewer: Viewer) -> GameStateView {
+        let mut players: Vec<PlayerID> = self.factions.keys().copied().collect();
+        players.sort();
+        let players = players
+            .into_iter()
+            .map(|p| {
+                let see = |zone| self.can_see(viewer, zone, Some(p));
+                let bid = match (self.player_colony_bid.get(&p), self.player_tech_bid.get(&p)) {
+                    (Some(colony), Some(tech)) if see(Zone::SealedBids) => Some(BidView {
+                        colony: *colony,
+                        tech: *tech,
+                    }),
+                    _ => None,
+                };
+                let converters = self.converters_of(p);
+                let marked = see(Zone::MarkedConverters).then(|| {
+                    converters
+                        .iter()
+                        .filter(|c| self.marked_converters.contains(c))
+                        .copied()
+                        .collect()
+                });
+                PlayerView {
+                    player: p,
+                    faction: self.factions[&p],
+                    cubes: self.get_player_cubes(p),
+                    converters,
+                    loaned_out: self.loaned_out(p),
+                    colonies: self.colonies_of(p),
+                    research_teams: self.research_teams(p),
+                    has_bid: self.player_colony_bid.contains_key(&p),
+                    bid,
+                    marked,
+                    held_count: self.held_cards(p).len(),
+                    held_cards: see(Zone::HeldCards).then(|| self.held_cards(p).to_vec()),
+                    turn: (viewer.player() == Some(p)).then(|| self.turn_view(p)),
+                }
+            })
+            .collect();
+        GameStateView {
+            viewer,
+            phase: self.phase,
+            confluence: self.confluence,
+            players,
+            seats: self.seats.clone(),
+            notes: viewer
+                .player()
+                .map(|p| self.notes(p).to_vec())
+                .unwrap_or_default(),
+            offers: viewer
+                .player()
+                .map(|p| self.offers_involving(p).into_iter().cloned().collect())
+                .unwrap_or_default(),
+            promises: self.promises.clone(),
+            colony_track: self.visible_track(viewer, &self.colony_bid_track),
+            tech_track: self.visible_track(viewer, &self.tech_bid_track),
+            decks: self.deck_views(viewer),
+        }
+    }
+
+    fn turn_view(&self, player: PlayerID) -> TurnView {
+        let awaiting: Vec<PendingAction> = self
+            .awaiting()
+            .into_iter()
+            .filter(|(p, _)| *p == player)
+            .map(|(_, a)| a)
+            .collect();
+        let markable = self
+            .expand_mark_all(player, &[])
+            .into_iter()
+            .filter_map(|rec| match rec {
+                RecordType::MarkConverter { converter, .. } => Some(converter),
+                _ => None,
+            })
+            .collect();
+        let mut reserved = BTreeSet::new();
+        let mut converter_inputs = Vec::new();
+        for action in awaiting.iter() {
+            if let PendingAction::RunConverter { converter } = action {
+                let inputs = self.pick_inputs_excluding(*converter, &reserved);
+                reserved.extend(inputs.iter().flatten().copied());
+                converter_inputs.push((*converter, inputs));
+            }
+        }
+        let mut colony_inputs = Vec::new();
+        for colony in self.colonies_of(player) {
+            let Some(inputs) = self.pick_colony_inputs_excluding(colony, &reserved) else {
+                continue;
+            };
+            let rec = RecordType::RunColony {
+                colony,
+                inputs: inputs.clone(),
+            };
+            if self.validate(&rec).is_ok() {
+                reserved.extend(inputs.iter().copied());
+                colony_inputs.push((colony, inputs));
+            }
+        }
+        let colony_slots = (0..self.colony_track_size())
+            .filter(|i| {
+                let rec = RecordType::TakeColony {
+                    player,
+                    colony: Some(*i),
+                };
+                self.validate(&rec).is_ok()
+            })
+            .collect();
+        let tech_slots = (0..self.tech_track_size())
+            .filter(|i| {
+                let rec = RecordType::TakeResearch {
+                    player,
+                    tech: Some(*i),
+                };
+                self.validate(&rec).is_ok()
+            })
+            .collect();
+        let offers = self
+            .pending_offers
+            .iter()
+            .filter(|o| o.to == player)
+            .map(|o| OfferView {
+                offer: o.clone(),
+                give_cubes: self.count_cubes(&o.give.cubes),
+                take_cubes: self.count_cubes(&o.take.cubes),
+                acceptable: self
+                    .validate(&RecordType::AcceptTrade { offer: o.id })
+                    .is_ok(),
+            })
+            .collect();
+        TurnView {
+            phase: self.phase,
+            confluence: self.confluence,
+            next_record: self.next_record_id(),
+            awaiting,
+            markable,
+            converter_inputs,
+            colony_inputs,
+            colony_slots,
+            tech_slots,
+            offers,
+        }
+    }
+
+    fn visible_track<T: Copy>(&self, viewer: Viewer, track: &[Option<T>]) -> Vec<Option<T>> {
+        if self.can_see(viewer, Zone::BidTracks, None) {
+            track.to_vec()
+        } else {
+            vec![None; track.len()]
+        }
+    }
+
+    fn deck_views(&self, viewer: Viewer) -> Vec<DeckView> {
+        let mut ids: Vec<DeckID> = self.decks.keys().copied().collect();
+        ids.sort();
+        ids.into_iter()
+            .map(|id| {
+                let deck = &self.decks[&id];
+                DeckView {
+                    id,
+                    name: deck.name.clone(),
+                    size: deck.cards.len(),
+                    cards: self
+                        .can_see(viewer, Zone::DeckContents, None)
+                        .then(|| deck.cards.iter().copied().collect()),
+                }
+            })
+            .collect()
+    }
+
+    /// Whether sealed bids can be shown to everyone, which happens once
+    /// every player has bid.
+    pub fn bids_revealed(&self) -> bool {
+        !self.factions.is_empty()
+            && self
+                .factions
+                .keys()
+                .all(|p| self.player_colony_bid.contains_key(p))
+    }
+}
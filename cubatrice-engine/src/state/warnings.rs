@@ -0,0 +1,234 @@
+use std::{collections::BTreeSet, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{
+    converter::ConverterID,
+    cube::{CubeID, CubeRecord, CubeType},
+};
+
+use super::{
+    player::PlayerID,
+    record::{RecordID, RecordType},
+    trading::TradeItem,
+    Confluence, GameState, Phase,
+};
+
+/// Something about a record that is legal, but is probably a mistake.
+/// Warnings never stop a record from being applied; clients should ask the
+/// player to confirm instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Warning {
+    /// A trade leaves a player unable to pay for the converters they've
+    /// marked to run this economy phase.
+    StarvesMarkedConverters { player: PlayerID },
+    /// A player is bidding every ship they have.
+    BidsAllShips { player: PlayerID, ships: usize },
+    /// Inventing would spend donation cubes, which are meant to be traded
+    /// away this trade phase.
+    InventsWithDonations { player: PlayerID, donations: usize },
+    /// A converter which is marked to run is being upgraded, which changes
+    /// what it consumes and produces.
+    UpgradesMarkedConverter { converter: ConverterID },
+    /// A contract is still unfulfilled at the resolution phase. Binding
+    /// contracts are collected as far as the debtor can pay; others are
+    /// only reported.
+    UnfulfilledContract {
+        contract: RecordID,
+        debtor: PlayerID,
+        creditor: PlayerID,
+        remaining: usize,
+    },
+    /// Starting the trade phase will skip a recurring payment, because the
+    /// payer can't afford it.
+    MissesRecurringTransfer { from: PlayerID, to: PlayerID },
+}
+
+impl GameState {
+    /// Checks a record for likely mistakes. The record should be valid; the
+    /// result for an invalid record is meaningless.
+    pub fn check_warnings(&self, rec: &RecordType) -> Vec<Warning> {
+        let mut out = Vec::new();
+        match rec {
+            RecordType::TradeCubes {
+                a,
+                b,
+                a_cubes,
+                b_cubes,
+            } => self.check_cube_trade(*a, *b, a_cubes, b_cubes, &mut out),
+            RecordType::TradeItems {
+                a,
+                b,
+                a_items,
+                b_items,
+            } => {
+                let cubes = |items: &[TradeItem]| -> BTreeSet<CubeID> {
+                    items
+                        .iter()
+                        .filter_map(|i| match i {
+                            TradeItem::Cube(c) => Some(*c),
+                            _ => None,
+                        })
+                        .collect()
+                };
+                self.check_cube_trade(*a, *b, &cubes(a_items), &cubes(b_items), &mut out)
+            }
+            RecordType::Bid {
+                player,
+                for_colony,
+                for_colony_kjas,
+                for_tech,
+                for_tech_faderan,
+            } => {
+                let ships = self.get_player_cubes(*player).count_type(CubeType::Ship) as usize;
+                let bid = for_colony
+                    + for_colony_kjas.unwrap_or(0)
+                    + for_tech
+                    + for_tech_faderan.unwrap_or(0);
+                if ships > 0 && bid >= ships {
+                    out.push(Warning::BidsAllShips {
+                        player: *player,
+                        ships,
+                    });
+                }
+            }
+            RecordType::InventTech { player, tech, cost } => {
+                let qty = self
+                    .data
+                    .tech
+                    .get(tech)
+                    .and_then(|t| t.cost.iter().find(|c| c.typ == *cost))
+                    .map(|c| c.qty)
+                    .unwrap_or(0);
+                // the same cubes that applying the record would spend
+                let donations = self
+                    .owned_cubes(*player, *cost)
+                    .into_iter()
+                    .take(qty)
+                    .filter(|c| self.cubes.get(c).is_some_and(|c| c.donation.is_some()))
+                    .count();
+                if donations > 0 {
+                    out.push(Warning::InventsWithDonations {
+                        player: *player,
+                        donations,
+                    });
+                }
+            }
+            RecordType::ChangePhase {
+                to: Phase::Resolution,
+            } => {
+                for c in self.unfulfilled_contracts() {
+                    out.push(Warning::UnfulfilledContract {
+                        contract: c.id,
+                        debtor: c.debtor,
+                        creditor: c.creditor,
+                        remaining: c.remaining(),
+                    });
+                }
+            }
+            RecordType::ChangePhase { to: Phase::Trade } => {
+                let confluence = if self.phase == Phase::ZethSteal {
+                    Confluence(self.confluence.0 + 1)
+                } else {
+                    self.confluence
+                };
+                for t in self.unaffordable_transfers(confluence) {
+                    out.push(Warning::MissesRecurringTransfer {
+                        from: t.from,
+                        to: t.to,
+                    });
+                }
+            }
+            RecordType::UpgradeConverter { conv, .. } if self.marked_converters.contains(conv) => {
+                out.push(Warning::UpgradesMarkedConverter { converter: *conv });
+            }
+            _ => {}
+        }
+        out
+    }
+
+    /// Whether `cubes` covers the inputs of every converter a player has
+    /// marked.
+    /// Warns each side of a trade whose cubes would no longer cover their
+    /// marked converters.
+    fn check_cube_trade(
+        &self,
+        a: PlayerID,
+        b: PlayerID,
+        a_cubes: &BTreeSet<CubeID>,
+        b_cubes: &BTreeSet<CubeID>,
+        out: &mut Vec<Warning>,
+    ) {
+        for (player, given, received) in [(a, a_cubes, b_cubes), (b, b_cubes, a_cubes)] {
+            let before = self.get_player_cubes(player);
+            let mut after = before;
+            for (c, sign) in given
+                .iter()
+                .map(|c| (c, -1))
+                .chain(received.iter().map(|c| (c, 1)))
+            {
+                if let Some(cube) = self.cubes.get(c) {
+                    after.add_type(cube.typ, sign);
+                }
+            }
+            if self.can_run_marked(player, &before) && !self.can_run_marked(player, &after) {
+                out.push(Warning::StarvesMarkedConverters { player });
+            }
+        }
+    }
+
+    fn can_run_marked(&self, player: PlayerID, cubes: &CubeRecord) -> bool {
+        let needed: CubeRecord = self
+            .marked_converters
+            .iter()
+            .filter(|c| self.converter_owners.get(c) == Some(&player))
+            .filter_map(|c| self.converters.get(c))
+            .map(|c| CubeRecord::from(c.input()))
+            .sum();
+        cubes.satisfies(&needed)
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::StarvesMarkedConverters { player } => write!(
+                f,
+                "player {} won't have the cubes to run their marked converters",
+                player.0
+            ),
+            Self::BidsAllShips { player, ships } => {
+                write!(
+                    f,
+                    "player {} is bidding all {} of their ships",
+                    player.0, ships
+                )
+            }
+            Self::InventsWithDonations { player, donations } => write!(
+                f,
+                "player {} would spend {} donation cube(s) inventing",
+                player.0, donations
+            ),
+            Self::UpgradesMarkedConverter { converter } => write!(
+                f,
+                "converter {} is marked to run, and upgrading will change it",
+                converter.0
+            ),
+            Self::UnfulfilledContract {
+                contract,
+                debtor,
+                creditor,
+                remaining,
+            } => write!(
+                f,
+                "player {} still owes player {} {} cube(s) on contract {}",
+                debtor.0, creditor.0, remaining, contract.0
+            ),
+            Self::MissesRecurringTransfer { from, to } => write!(
+                f,
+                "player {} can't afford their recurring payment to player {}",
+                from.0, to.0
+            ),
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::ops::RangeInclusive;
+
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+
+use crate::{entity::faction::FactionType, game_data::GameData};
+
+use super::{
+    config::GameConfig, endgame::EndCondition, player::PlayerID, scoring::EndgameValues,
+    takes::Untaken, GameState,
+};
+
+/// How many players the wizard will set a game up for.
+pub const PLAYER_COUNTS: RangeInclusive<usize> = 4..=9;
+
+/// Where a `Wizard` is in setting up a game. Steps are always visited in
+/// this order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WizardStep {
+    PlayerCount,
+    /// Choosing the faction for a seat, counting from 0. There's one of
+    /// these steps per player.
+    Faction {
+        seat: usize,
+    },
+    HouseRules,
+    Seed,
+    /// The config is ready, see `Wizard::finish`.
+    Done,
+}
+
+/// Everything in a config which changes the rules from a standard game.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HouseRules {
+    pub end: EndCondition,
+    pub scoring: EndgameValues,
+    pub untaken: Untaken,
+    pub economy_deliveries: bool,
+}
+
+/// An answer to the wizard's current step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WizardInput {
+    PlayerCount(usize),
+    Faction(FactionType),
+    HouseRules(HouseRules),
+    /// A seed for the game, or none to pick one at random.
+    Seed(Option<u64>),
+}
+
+/// Walks through setting up a new game one step at a time, checking each
+/// answer as it's given, so that frontends only have to ask the questions.
+///
+/// Players are numbered from `PlayerID(1)` and seated in the order their
+/// factions were chosen, as with `GameConfig::preset`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Wizard {
+    step: WizardStep,
+    players: usize,
+    factions: Vec<FactionType>,
+    house_rules: HouseRules,
+    seed: u64,
+}
+
+impl Default for Wizard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Wizard {
+    /// Starts setting up a game, at the player count step.
+    pub fn new() -> Self {
+        Self {
+            step: WizardStep::PlayerCount,
+            players: 0,
+            factions: Vec::new(),
+            house_rules: HouseRules::default(),
+            seed: 0,
+        }
+    }
+
+    /// The step waiting for an answer.
+    pub fn step(&self) -> WizardStep {
+        self.step
+    }
+
+    /// The factions chosen so far, in seating order.
+    pub fn factions(&self) -> &[FactionType] {
+        &self.factions
+    }
+
+    /// Answers the current step, moving on to the next one if the answer is
+    /// acceptable. On error the wizard stays where it was.
+    pub fn answer(&mut self, input: WizardInput) -> Result<WizardStep, Error> {
+        match (self.step, input) {
+            (WizardStep::PlayerCount, WizardInput::PlayerCount(n)) => {
+                if !PLAYER_COUNTS.contains(&n) {
+                    return Err(anyhow!(
+                        "games are for {} to {} players",
+                        PLAYER_COUNTS.start(),
+                        PLAYER_COUNTS.end()
+                    ));
+                }
+                self.players = n;
+                self.factions.clear();
+                self.step = WizardStep::Faction { seat: 0 };
+            }
+            (WizardStep::Faction { seat }, WizardInput::Faction(faction)) => {
+                if let Some(taken) = self
+                    .factions
+                    .iter()
+                    .find(|f| **f == faction || **f == faction.bifurcate())
+                {
+                    return Err(anyhow!(
+                        "{} can't be played alongside {}",
+                        faction.name(),
+                        taken.name()
+                    ));
+                }
+                self.factions.push(faction);
+                self.step = if seat + 1 < self.players {
+                    WizardStep::Faction { seat: seat + 1 }
+                } else {
+                    WizardStep::HouseRules
+                };
+            }
+            (WizardStep::HouseRules, WizardInput::HouseRules(rules)) => {
+                self.house_rules = rules;
+                self.step = WizardStep::Seed;
+            }
+            (WizardStep::Seed, WizardInput::Seed(seed)) => {
+                self.seed = seed.unwrap_or_else(rand::random);
+                self.step = WizardStep::Done;
+            }
+            (step, input) => {
+                return Err(anyhow!("{:?} doesn't answer {:?}", input, step));
+            }
+        }
+        Ok(self.step)
+    }
+
+    /// Goes back a step, forgetting the faction chosen for the seat being
+    /// returned to. Answers to other steps are kept until they're replaced.
+    pub fn back(&mut self) -> WizardStep {
+        self.step = match self.step {
+            WizardStep::PlayerCount | WizardStep::Faction { seat: 0 } => WizardStep::PlayerCount,
+            WizardStep::Faction { seat } => WizardStep::Faction { seat: seat - 1 },
+            WizardStep::HouseRules => WizardStep::Faction {
+                seat: self.players - 1,
+            },
+            WizardStep::Seed => WizardStep::HouseRules,
+            WizardStep::Done => WizardStep::Seed,
+        };
+        if let WizardStep::Faction { seat } = self.step {
+            self.factions.truncate(seat);
+        }
+        self.step
+    }
+
+    /// Factions which can still be chosen, easiest to play first. Factions
+    /// which affect other players less come first among equally difficult
+    /// ones, so new tables aren't dominated by one player.
+    pub fn recommendations(&self) -> Vec<FactionType> {
+        let mut factions: Vec<FactionType> = FactionType::core()
+            .into_iter()
+            .chain(FactionType::bifurcation())
+            .filter(|f| !self.factions.iter().any(|t| t == f || *t == f.bifurcate()))
+            .collect();
+        factions.sort_by_key(|f| (f.difficulty(), f.impact()));
+        factions
+    }
+
+    /// The finished config, checked by setting a game up with it.
+    pub fn finish(&self, data: &GameData) -> Result<GameConfig, Error> {
+        if self.step != WizardStep::Done {
+            return Err(anyhow!("setup isn't finished, still at {:?}", self.step));
+        }
+        let config = self
+            .factions
+            .iter()
+            .enumerate()
+            .fold(GameConfig::new(self.seed), |cfg, (i, f)| {
+                cfg.with_player(PlayerID(i + 1), *f)
+            })
+            .with_end(self.house_rules.end)
+            .with_scoring(self.house_rules.scoring)
+            .with_untaken(self.house_rules.untaken)
+            .with_economy_deliveries(self.house_rules.economy_deliveries);
+        GameState::from_config(data.clone(), config.clone())?;
+        Ok(config)
+    }
+}
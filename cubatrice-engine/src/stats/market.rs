@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    state::{player::PlayerID, Confluence},
+    Fraction,
+};
+
+/// What a bid was for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum BidKind {
+    Colony,
+    Tech,
+}
+
+/// A single bid of ships. Split bids (Kjas colonies, Faderan research
+/// teams) are recorded as two bids.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BidEntry {
+    pub confluence: Confluence,
+    pub player: PlayerID,
+    pub kind: BidKind,
+    pub ships: usize,
+}
+
+/// Every bid made over the course of a game, so the going rate for colonies
+/// and research teams at a particular table can be worked out.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarketStats {
+    bids: Vec<BidEntry>,
+}
+
+impl MarketStats {
+    /// Records a bid.
+    pub fn record_bid(
+        &mut self,
+        confluence: Confluence,
+        player: PlayerID,
+        kind: BidKind,
+        ships: usize,
+    ) {
+        self.bids.push(BidEntry {
+            confluence,
+            player,
+            kind,
+            ships,
+        });
+    }
+
+    /// Every bid recorded so far, oldest first.
+    pub fn bids(&self) -> &[BidEntry] {
+        self.bids.as_slice()
+    }
+
+    /// The largest bid of a kind made in each confluence.
+    pub fn winning_bids(&self, kind: BidKind) -> BTreeMap<Confluence, usize> {
+        let mut out = BTreeMap::new();
+        for b in self.bids.iter().filter(|b| b.kind == kind) {
+            let best = out.entry(b.confluence).or_insert(0);
+            *best = b.ships.max(*best);
+        }
+        out
+    }
+
+    /// The average winning bid of a kind in each confluence, across any
+    /// number of games. Confluences with no bids are left out.
+    pub fn average_winning_bids<'a, I>(games: I, kind: BidKind) -> BTreeMap<Confluence, Fraction>
+    where
+        I: IntoIterator<Item = &'a MarketStats>,
+    {
+        let mut totals: BTreeMap<Confluence, (isize, isize)> = BTreeMap::new();
+        for g in games {
+            for (c, ships) in g.winning_bids(kind) {
+                let t = totals.entry(c).or_insert((0, 0));
+                t.0 += ships as isize;
+                t.1 += 1;
+            }
+        }
+        totals
+            .into_iter()
+            .map(|(c, (sum, n))| (c, Fraction::new(sum, n)))
+            .collect()
+    }
+}
@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::{colony::ColonyID, converter::ConverterID, cube::CubeRecord, Item},
+    state::player::PlayerID,
+    Fraction,
+};
+
+use self::{market::MarketStats, provenance::ProvenanceLedger};
+
+/// Bids made for colonies and research teams.
+pub mod market;
+/// Tracing of individual cubes through the economy.
+pub mod provenance;
+
+/// Everything tracked about a game for post-game review. This is the bundle
+/// that gets exported once a game is finished.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameStats {
+    /// What each converter and colony produced over the course of the game.
+    pub production: ProductionStats,
+    /// Every movement of every cube over the course of the game.
+    pub provenance: ProvenanceLedger,
+    /// Every bid made over the course of the game.
+    pub market: MarketStats,
+}
+
+/// Where a set of outputs came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProductionSource {
+    /// A converter card (starting converter, tech converter, relic, etc.)
+    Converter(ConverterID),
+    /// A colony producing during the economy phase.
+    Colony(ColonyID),
+}
+
+/// Who a card's run counts for. The owner is whoever the card belongs to,
+/// and the operator is whoever ran it and received the outputs. They only
+/// differ while a converter is on loan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RunAttribution {
+    pub owner: PlayerID,
+    pub operator: PlayerID,
+}
+
+impl RunAttribution {
+    /// Whether the card was run by someone borrowing it.
+    pub fn on_loan(&self) -> bool {
+        self.owner != self.operator
+    }
+}
+
+/// Production attributed to a single card while it was owned and run by a
+/// single pair of players. A card that changes hands permanently, or is lent
+/// out, will have one entry per owner and operator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardStats {
+    /// The card which produced the outputs
+    pub source: ProductionSource,
+    /// The player who received the outputs
+    pub player: PlayerID,
+    /// The player the card belonged to. Differs from `player` for runs of a
+    /// borrowed converter.
+    pub owner: PlayerID,
+    /// How many times the card was run
+    pub runs: usize,
+    /// Total cubes produced by the card
+    pub produced: CubeRecord,
+    /// Total value of cubes produced by the card, using `CubeType::value`
+    pub value: Fraction,
+}
+
+/// A card's position in a "most valuable card" ranking.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardRank {
+    pub source: ProductionSource,
+    pub runs: usize,
+    pub value: Fraction,
+}
+
+/// Per-card production over a whole game. Stored as a flat list rather than
+/// a map so that it can be exported as JSON directly.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProductionStats {
+    cards: Vec<CardStats>,
+}
+
+impl ProductionStats {
+    /// Records a single run of a card, producing `outputs` for the operator.
+    /// Non-cube outputs (tokens, colonies) are counted as a run but add no
+    /// value.
+    pub fn record_run(&mut self, source: ProductionSource, by: RunAttribution, outputs: &[Item]) {
+        let idx = match self
+            .cards
+            .iter()
+            .position(|c| c.source == source && c.player == by.operator && c.owner == by.owner)
+        {
+            Some(i) => i,
+            None => {
+                self.cards.push(CardStats {
+                    source,
+                    player: by.operator,
+                    owner: by.owner,
+                    runs: 0,
+                    produced: CubeRecord::default(),
+                    value: Fraction::new(0, 1),
+                });
+                self.cards.len() - 1
+            }
+        };
+        let card = &mut self.cards[idx];
+        card.runs += 1;
+        for i in outputs {
+            if let Item::Cubes(typ, qty) | Item::DonationCubes(typ, qty) = i {
+                card.produced.add_type(*typ, *qty as isize);
+                card.value += typ.value() * (*qty as isize);
+            }
+        }
+    }
+
+    /// All per-card production entries, in the order they were first run.
+    pub fn cards(&self) -> &[CardStats] {
+        self.cards.as_slice()
+    }
+
+    /// Ranks a player's cards by the total value they produced, most
+    /// valuable first. Runs of cards the player lent out count for them,
+    /// even though the borrower received the outputs.
+    pub fn ranking_for(&self, player: PlayerID) -> Vec<CardRank> {
+        Self::rank(self.cards.iter().filter(|c| c.owner == player))
+    }
+
+    /// Production of a player's cards while they were lent to someone else.
+    pub fn loaned_out(&self, player: PlayerID) -> impl Iterator<Item = &CardStats> {
+        self.cards
+            .iter()
+            .filter(move |c| c.owner == player && c.player != player)
+    }
+
+    /// Ranks cards by the total value they produced across every player that
+    /// owned them, most valuable first.
+    pub fn ranking(&self) -> Vec<CardRank> {
+        Self::rank(self.cards.iter())
+    }
+
+    fn rank<'a, I>(cards: I) -> Vec<CardRank>
+    where
+        I: Iterator<Item = &'a CardStats>,
+    {
+        let mut out: Vec<CardRank> = Vec::new();
+        for c in cards {
+            match out.iter_mut().find(|r| r.source == c.source) {
+                Some(r) => {
+                    r.runs += c.runs;
+                    r.value += c.value;
+                }
+                None => out.push(CardRank {
+                    source: c.source,
+                    runs: c.runs,
+                    value: c.value,
+                }),
+            }
+        }
+        out.sort_by(|a, b| b.value.cmp(&a.value).then(b.runs.cmp(&a.runs)));
+        out
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::{
+        colony::ColonyID,
+        converter::ConverterID,
+        cube::{CubeID, CubeType},
+    },
+    state::{player::PlayerID, Confluence},
+    Fraction,
+};
+
+/// Somewhere a cube can be. Cubes are created by converters and colonies,
+/// held by players, and consumed by converters again.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FlowNode {
+    /// The general supply. Starting resources come from here, and cubes that
+    /// leave the game without being consumed by a converter go back here.
+    #[default]
+    Supply,
+    /// A player holding the cube
+    Player(PlayerID),
+    /// A converter producing or consuming the cube
+    Converter(ConverterID),
+    /// A colony producing or consuming the cube
+    Colony(ColonyID),
+}
+
+/// A single movement of a single cube.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FlowEntry {
+    pub cube: CubeID,
+    pub typ: CubeType,
+    pub from: FlowNode,
+    pub to: FlowNode,
+    /// The confluence the cube moved in.
+    pub confluence: Confluence,
+}
+
+/// Ordered list of every cube movement in the game, so that any cube can be
+/// traced from creation to consumption.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenanceLedger {
+    entries: Vec<FlowEntry>,
+}
+
+impl ProvenanceLedger {
+    /// Records that a cube moved from one node to another during a
+    /// confluence.
+    pub fn record(
+        &mut self,
+        cube: CubeID,
+        typ: CubeType,
+        from: FlowNode,
+        to: FlowNode,
+        confluence: Confluence,
+    ) {
+        self.entries.push(FlowEntry {
+            cube,
+            typ,
+            from,
+            to,
+            confluence,
+        });
+    }
+
+    /// Every movement recorded so far, oldest first.
+    pub fn entries(&self) -> &[FlowEntry] {
+        self.entries.as_slice()
+    }
+
+    /// Every movement of a specific cube, oldest first.
+    pub fn history(&self, cube: CubeID) -> impl Iterator<Item = &FlowEntry> {
+        self.entries.iter().filter(move |e| e.cube == cube)
+    }
+
+    /// The confluence a cube was created in.
+    pub fn created_in(&self, cube: CubeID) -> Option<Confluence> {
+        self.history(cube).next().map(|e| e.confluence)
+    }
+
+    /// How many confluences each cube a player fed into a converter or
+    /// colony had been in the game for, in the order they were consumed.
+    pub fn consumption_ages(&self, player: PlayerID) -> Vec<usize> {
+        let mut created: HashMap<CubeID, Confluence> = HashMap::new();
+        let mut ages = Vec::new();
+        for e in self.entries.iter() {
+            let born = *created.entry(e.cube).or_insert(e.confluence);
+            if e.from == FlowNode::Player(player) && e.consumed() {
+                ages.push(e.confluence.0.saturating_sub(born.0));
+            }
+        }
+        ages
+    }
+
+    /// The average age, in confluences, of the cubes a player consumed.
+    /// Players who sit on their resources score high, and players who keep
+    /// them moving score low. None if the player hasn't consumed anything.
+    pub fn average_consumption_age(&self, player: PlayerID) -> Option<Fraction> {
+        let ages = self.consumption_ages(player);
+        if ages.is_empty() {
+            return None;
+        }
+        let total: usize = ages.iter().sum();
+        Some(Fraction::new(total as isize, ages.len() as isize))
+    }
+
+    /// `average_consumption_age` for every player who consumed anything.
+    pub fn average_consumption_ages(&self) -> BTreeMap<PlayerID, Fraction> {
+        let mut players: Vec<PlayerID> = self
+            .entries
+            .iter()
+            .filter(|e| e.consumed())
+            .filter_map(|e| match e.from {
+                FlowNode::Player(p) => Some(p),
+                _ => None,
+            })
+            .collect();
+        players.sort();
+        players.dedup();
+        players
+            .into_iter()
+            .filter_map(|p| self.average_consumption_age(p).map(|a| (p, a)))
+            .collect()
+    }
+
+    /// Aggregates the ledger into nodes and value-weighted links, in the
+    /// shape expected by most sankey diagram libraries.
+    pub fn sankey(&self) -> Sankey {
+        let mut nodes: Vec<FlowNode> = Vec::new();
+        let mut links: Vec<(usize, usize, Fraction)> = Vec::new();
+        let mut index = |n: FlowNode| match nodes.iter().position(|m| *m == n) {
+            Some(i) => i,
+            None => {
+                nodes.push(n);
+                nodes.len() - 1
+            }
+        };
+        for e in self.entries.iter() {
+            let (source, target) = (index(e.from), index(e.to));
+            match links.iter_mut().find(|l| l.0 == source && l.1 == target) {
+                Some(l) => l.2 += e.typ.value(),
+                None => links.push((source, target, e.typ.value())),
+            }
+        }
+        Sankey {
+            nodes: nodes
+                .into_iter()
+                .map(|n| SankeyNode {
+                    name: n.to_string(),
+                    node: n,
+                })
+                .collect(),
+            links: links
+                .into_iter()
+                .map(|(source, target, value)| SankeyLink {
+                    source,
+                    target,
+                    value: value.value(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Sankey diagram of cube value moving through the economy. Links refer to
+/// nodes by index.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Sankey {
+    pub nodes: Vec<SankeyNode>,
+    pub links: Vec<SankeyLink>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SankeyNode {
+    /// Display name of the node
+    pub name: String,
+    pub node: FlowNode,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SankeyLink {
+    /// Index of the node the value flowed out of
+    pub source: usize,
+    /// Index of the node the value flowed into
+    pub target: usize,
+    /// Total value of cubes moved, using `CubeType::value`
+    pub value: f64,
+}
+
+impl FlowEntry {
+    /// Whether the cube was used up by a converter or colony.
+    pub fn consumed(&self) -> bool {
+        matches!(self.to, FlowNode::Converter(_) | FlowNode::Colony(_))
+    }
+}
+
+impl Display for FlowNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Supply => write!(f, "Supply"),
+            Self::Player(p) => write!(f, "Player {}", p.0),
+            Self::Converter(c) => write!(f, "Converter {}", c.0),
+            Self::Colony(c) => write!(f, "Colony {}", c.0),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use anyhow::{anyhow, Context, Error};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bot::{play_to_end, Bot, GreedyBot},
+    entity::{colony::ColonyID, cube::CubeType, faction::FactionType, player::PlayerID, Item},
+    game_data::GameData,
+    state::{config::GameConfig, GameState},
+    Fraction,
+};
+
+/// Something a sweep varies between batches of games.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Parameter {
+    /// How many cubes of a type a colony makes each time it runs.
+    ColonyOutput { colony: ColonyID, cube: CubeType },
+    /// How many cubes of a type a faction starts with.
+    StartingCubes {
+        faction: FactionType,
+        cube: CubeType,
+    },
+    /// The share of their ships one seat's bot bids each confluence, see
+    /// `GreedyBot::bid_share`.
+    BidShare { player: PlayerID },
+}
+
+impl Display for Parameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Parameter::ColonyOutput { colony, cube } => {
+                write!(f, "colony {}'s {} output", colony.0, cube.name())
+            }
+            Parameter::StartingCubes { faction, cube } => {
+                write!(f, "{}'s starting {}", faction.short_name(), cube.name())
+            }
+            Parameter::BidShare { player } => write!(f, "player {}'s bid share", player.0),
+        }
+    }
+}
+
+/// A parameter, the values to try it at, and how many games to play at
+/// each. Every value is played with the same seeds, counting up from the
+/// config's, so differences in the results come from the parameter rather
+/// than the shuffles.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Sweep {
+    pub parameter: Parameter,
+    pub values: Vec<Fraction>,
+    pub games: usize,
+}
+
+/// How the games at one value of a sweep went.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SweepResult {
+    pub value: Fraction,
+    pub games: usize,
+    /// Games won by each seat. Players sharing first place share the win.
+    pub wins: BTreeMap<PlayerID, Fraction>,
+}
+
+impl SweepResult {
+    /// The share of games a seat won.
+    pub fn win_rate(&self, player: PlayerID) -> Fraction {
+        let wins = self
+            .wins
+            .get(&player)
+            .copied()
+            .unwrap_or(Fraction::new(0, 1));
+        if self.games == 0 {
+            wins
+        } else {
+            wins / self.games as isize
+        }
+    }
+}
+
+impl Sweep {
+    /// Plays every game of the sweep with a `GreedyBot` in every seat, and
+    /// tallies who won at each value.
+    pub fn run(&self, data: &GameData, config: &GameConfig) -> Result<Vec<SweepResult>, Error> {
+        self.values
+            .iter()
+            .map(|value| self.run_value(*value, data, config))
+            .collect()
+    }
+
+    fn run_value(
+        &self,
+        value: Fraction,
+        data: &GameData,
+        config: &GameConfig,
+    ) -> Result<SweepResult, Error> {
+        let mut data = data.clone();
+        let mut bots: BTreeMap<PlayerID, GreedyBot> = config
+            .players
+            .iter()
+            .map(|(p, _)| (*p, GreedyBot::default()))
+            .collect();
+        self.parameter
+            .set(value, &mut data, config, &mut bots)
+            .with_context(|| format!("setting {} to {}", self.parameter, value))?;
+        let mut result = SweepResult {
+            value,
+            games: self.games,
+            wins: bots.keys().map(|p| (*p, Fraction::new(0, 1))).collect(),
+        };
+        for i in 0..self.games {
+            let seed = config.seed.wrapping_add(i as u64);
+            let config = GameConfig {
+                seed,
+                ..config.clone()
+            };
+            let mut state = GameState::from_config(data.clone(), config)?;
+            let mut seats: BTreeMap<PlayerID, Box<dyn Bot>> = bots
+                .iter()
+                .map(|(p, b)| (*p, Box::new(*b) as Box<dyn Bot>))
+                .collect();
+            play_to_end(&mut state, &mut seats)
+                .with_context(|| format!("playing seed {} at {}", seed, value))?;
+            let winners = state.final_scores().winners();
+            for w in winners.iter() {
+                *result.wins.entry(*w).or_insert(Fraction::new(0, 1)) +=
+                    Fraction::new(1, winners.len() as isize);
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Parameter {
+    /// Sets the parameter to `value` in the games' data, or in their bots.
+    fn set(
+        &self,
+        value: Fraction,
+        data: &mut GameData,
+        config: &GameConfig,
+        bots: &mut BTreeMap<PlayerID, GreedyBot>,
+    ) -> Result<(), Error> {
+        match *self {
+            Parameter::ColonyOutput { colony, cube } => {
+                let c = data
+                    .colony
+                    .get_mut(&colony)
+                    .ok_or_else(|| anyhow!("there's no colony {}", colony.0))?;
+                set_cubes(&mut c.conv.output, cube, whole(value)?);
+            }
+            Parameter::StartingCubes { faction, cube } => {
+                if !config.players.iter().any(|(_, f)| *f == faction) {
+                    return Err(anyhow!("nobody is playing {}", faction.name()));
+                }
+                let items = data.start_resources.entry(faction).or_default();
+                set_cubes(items, cube, whole(value)?);
+            }
+            Parameter::BidShare { player } => {
+                let bot = bots
+                    .get_mut(&player)
+                    .ok_or_else(|| anyhow!("player {} isn't in the game", player.0))?;
+                if value < Fraction::new(0, 1) || value > Fraction::new(1, 1) {
+                    return Err(anyhow!("a bid share is between 0 and 1"));
+                }
+                bot.bid_share = value;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A value as a number of cubes.
+fn whole(value: Fraction) -> Result<usize, Error> {
+    if value.remainder() != 0 || value.integer() < 0 {
+        return Err(anyhow!("{} isn't a whole number of cubes", value));
+    }
+    Ok(value.integer() as usize)
+}
+
+/// Makes a list of items hold exactly `qty` cubes of a type.
+fn set_cubes(items: &mut Vec<Item>, cube: CubeType, qty: usize) {
+    items.retain(|i| !matches!(i, Item::Cubes(t, _) if *t == cube));
+    if qty > 0 {
+        items.push(Item::Cubes(cube, qty));
+    }
+}
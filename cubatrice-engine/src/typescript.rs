@@ -0,0 +1,895 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::{
+    entity::{
+        colony::ColonyID,
+        converter::ConverterID,
+        cube::{CubeID, CubeRecord, CubeType},
+        faction::{alt_caylion::ProjectID, base_faderan::RelicWorld, FactionType},
+        technology::TechID,
+        token::TokenID,
+        Token,
+    },
+    state::{
+        access::Viewer,
+        awaiting::PendingAction,
+        deck::{DeckID, EntityRef},
+        events::GameEvent,
+        notes::{Note, NoteTarget},
+        observers::Notification,
+        offers::{TradeOffer, TradeTerms},
+        player::{Controller, PlayerID, Profile},
+        promises::{Promise, PromiseStatus},
+        record::{RecordGroup, RecordID, RecordType},
+        submission::{Nonce, Submission},
+        trading::TradeItem,
+        view::{BidView, DeckView, GameStateView, OfferView, PlayerView, TurnView},
+        Confluence, Phase,
+    },
+    stats::provenance::FlowNode,
+};
+
+/// A type which crosses the wire to a frontend, with a TypeScript
+/// equivalent matching its serde JSON form.
+///
+/// Implementations are written with the macros in this module, which also
+/// destructure the Rust type field by field and variant by variant, so
+/// adding, removing or retyping a field without updating its TypeScript
+/// fails to compile.
+pub trait TypeScript {
+    /// How the type is written where another type refers to it.
+    fn ts_name() -> String;
+
+    /// Declares the type, and every type it refers to, unless they're
+    /// already declared. Primitives and containers have nothing to declare.
+    fn declare(_decls: &mut Declarations) {}
+}
+
+/// TypeScript declarations, each type declared once, after the types it
+/// refers to.
+#[derive(Debug, Default)]
+pub struct Declarations {
+    started: BTreeSet<&'static str>,
+    out: Vec<String>,
+}
+
+impl Declarations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares a type and everything it refers to.
+    pub fn add<T: TypeScript>(&mut self) -> &mut Self {
+        T::declare(self);
+        self
+    }
+
+    /// Whether a type still needs declaring. Marks it as declared, so
+    /// types which refer back to it don't declare it again.
+    fn begin(&mut self, name: &'static str) -> bool {
+        self.started.insert(name)
+    }
+
+    fn push(&mut self, decl: String) {
+        self.out.push(decl);
+    }
+
+    /// The declarations as the contents of a `.d.ts` file.
+    pub fn render(&self) -> String {
+        let mut s = String::from("// Generated by cubatrice. Do not edit.\n");
+        for d in self.out.iter() {
+            s.push('\n');
+            s.push_str(d);
+            s.push('\n');
+        }
+        s
+    }
+}
+
+/// Declarations for everything a frontend exchanges with the engine:
+/// views of the game, notifications of changes to it, and the records and
+/// submissions which change it.
+pub fn definitions() -> String {
+    Declarations::new()
+        .add::<GameStateView>()
+        .add::<Notification>()
+        .add::<RecordGroup>()
+        .add::<Submission>()
+        .render()
+}
+
+macro_rules! primitive {
+    ($ts:literal: $($ty:ty),*) => {$(
+        impl TypeScript for $ty {
+            fn ts_name() -> String {
+                String::from($ts)
+            }
+        }
+    )*};
+}
+
+// u64s past 2^53 lose precision as JSON numbers, which only matters for
+// nonces chosen carelessly.
+primitive!("number": u8, u64, usize, isize);
+primitive!("boolean": bool);
+primitive!("string": String);
+
+/// Wraps a union in parentheses, so it can be followed by `[]`.
+fn group(name: String) -> String {
+    if name.contains(' ') {
+        format!("({})", name)
+    } else {
+        name
+    }
+}
+
+impl<T: TypeScript> TypeScript for Option<T> {
+    fn ts_name() -> String {
+        format!("{} | null", T::ts_name())
+    }
+
+    fn declare(decls: &mut Declarations) {
+        T::declare(decls);
+    }
+}
+
+impl<T: TypeScript> TypeScript for Vec<T> {
+    fn ts_name() -> String {
+        format!("{}[]", group(T::ts_name()))
+    }
+
+    fn declare(decls: &mut Declarations) {
+        T::declare(decls);
+    }
+}
+
+impl<T: TypeScript> TypeScript for BTreeSet<T> {
+    fn ts_name() -> String {
+        Vec::<T>::ts_name()
+    }
+
+    fn declare(decls: &mut Declarations) {
+        T::declare(decls);
+    }
+}
+
+// JSON object keys are always strings, whatever the Rust key type.
+impl<K: TypeScript, V: TypeScript> TypeScript for HashMap<K, V> {
+    fn ts_name() -> String {
+        format!("Record<string, {}>", V::ts_name())
+    }
+
+    fn declare(decls: &mut Declarations) {
+        K::declare(decls);
+        V::declare(decls);
+    }
+}
+
+impl<K: TypeScript, V: TypeScript> TypeScript for BTreeMap<K, V> {
+    fn ts_name() -> String {
+        HashMap::<K, V>::ts_name()
+    }
+
+    fn declare(decls: &mut Declarations) {
+        K::declare(decls);
+        V::declare(decls);
+    }
+}
+
+impl<A: TypeScript, B: TypeScript> TypeScript for (A, B) {
+    fn ts_name() -> String {
+        format!("[{}, {}]", A::ts_name(), B::ts_name())
+    }
+
+    fn declare(decls: &mut Declarations) {
+        A::declare(decls);
+        B::declare(decls);
+    }
+}
+
+/// Newtypes serialize as what they wrap, so they're declared as aliases.
+macro_rules! newtype {
+    ($($ty:ident($inner:ty)),* $(,)?) => {$(
+        impl TypeScript for $ty {
+            fn ts_name() -> String {
+                String::from(stringify!($ty))
+            }
+
+            fn declare(decls: &mut Declarations) {
+                if decls.begin(stringify!($ty)) {
+                    <$inner>::declare(decls);
+                    decls.push(format!(
+                        "export type {} = {};",
+                        stringify!($ty),
+                        <$inner>::ts_name()
+                    ));
+                }
+            }
+        }
+
+        const _: fn(&$ty) = |v| {
+            let $ty(inner) = v;
+            let _: &$inner = inner;
+        };
+    )*};
+}
+
+/// Structs with named fields are declared as interfaces.
+macro_rules! interface {
+    ($ty:ident { $($field:ident: $fty:ty),* $(,)? }) => {
+        impl TypeScript for $ty {
+            fn ts_name() -> String {
+                String::from(stringify!($ty))
+            }
+
+            fn declare(decls: &mut Declarations) {
+                if decls.begin(stringify!($ty)) {
+                    $( <$fty>::declare(decls); )*
+                    let fields: Vec<String> = vec![$(
+                        format!("  {}: {};", stringify!($field), <$fty>::ts_name())
+                    ),*];
+                    decls.push(format!(
+                        "export interface {} {{\n{}\n}}",
+                        stringify!($ty),
+                        fields.join("\n")
+                    ));
+                }
+            }
+        }
+
+        const _: fn(&$ty) = |v| {
+            let $ty { $($field),* } = v;
+            $( let _: &$fty = $field; )*
+        };
+    };
+}
+
+/// One variant of an externally tagged enum: unit variants are their name
+/// as a string, and others are an object keyed by their name.
+macro_rules! variant {
+    ($decls:ident, $var:ident) => {
+        format!("\"{}\"", stringify!($var))
+    };
+    ($decls:ident, $var:ident { $($f:ident: $fty:ty),* }) => {{
+        $( <$fty>::declare($decls); )*
+        let fields: Vec<String> = vec![$(
+            format!("{}: {}", stringify!($f), <$fty>::ts_name())
+        ),*];
+        format!("{{ {}: {{ {} }} }}", stringify!($var), fields.join("; "))
+    }};
+    ($decls:ident, $var:ident ($nty:ty)) => {{
+        <$nty>::declare($decls);
+        format!("{{ {}: {} }}", stringify!($var), <$nty>::ts_name())
+    }};
+}
+
+/// Enums are declared as unions of their variants, as serde tags them by
+/// default. Newtype variants name their field, so it can be checked.
+macro_rules! union {
+    ($ty:ident {
+        $( $var:ident
+            $( { $($f:ident: $fty:ty),* $(,)? } )?
+            $( ($n:ident: $nty:ty) )?
+        ),* $(,)?
+    }) => {
+        impl TypeScript for $ty {
+            fn ts_name() -> String {
+                String::from(stringify!($ty))
+            }
+
+            fn declare(decls: &mut Declarations) {
+                if decls.begin(stringify!($ty)) {
+                    let variants: Vec<String> = vec![$(
+                        variant!(decls, $var $( { $($f: $fty),* } )? $( ($nty) )?)
+                    ),*];
+                    decls.push(format!(
+                        "export type {} =\n  | {};",
+                        stringify!($ty),
+                        variants.join("\n  | ")
+                    ));
+                }
+            }
+        }
+
+        const _: fn(&$ty) = |v| match v {
+            $( $ty::$var $( { $($f),* } )? $( ($n) )? => {
+                $( $( let _: &$fty = $f; )* )?
+                $( let _: &$nty = $n; )?
+            } )*
+        };
+    };
+}
+
+newtype!(
+    PlayerID(usize),
+    RecordID(usize),
+    CubeID(usize),
+    ColonyID(usize),
+    ConverterID(usize),
+    TechID(usize),
+    ProjectID(usize),
+    DeckID(usize),
+    TokenID(usize),
+    Confluence(usize),
+    Nonce(u64),
+);
+
+union!(Phase {
+    Init,
+    Trade,
+    Economy,
+    ColonyBid,
+    TechBid,
+    ZethSteal,
+    Resolution,
+    Finish,
+});
+
+union!(FactionType {
+    CaylionCore,
+    EniEtCore,
+    FaderanCore,
+    ImdrilCore,
+    KitCore,
+    KjasCore,
+    UnityCore,
+    YengiiCore,
+    ZethCore,
+    CaylionAlt,
+    EniEtAlt,
+    FaderanAlt,
+    ImdrilAlt,
+    KitAlt,
+    KjasAlt,
+    UnityAlt,
+    YengiiAlt,
+    ZethAlt,
+});
+
+union!(CubeType {
+    Ship,
+    Culture,
+    Food,
+    Industry,
+    UnitySmall,
+    AnySmall,
+    AnySmallNonUnity,
+    Power,
+    Biotech,
+    Information,
+    UnityLarge,
+    AnyLarge,
+    AnyLargeNonUnity,
+    Ultratech,
+    VictoryPoint,
+});
+
+union!(Token {
+    Acknowledgement,
+    Envoy,
+    Regret,
+    Service,
+    CrossColonization,
+    Factory(typ: CubeType),
+    Custom(id: TokenID),
+});
+
+union!(TradeItem {
+    Cube(cube: CubeID),
+    Colony(colony: ColonyID),
+    Converter {
+        converter: ConverterID,
+        permanent: bool,
+    },
+    Token { token: Token, qty: usize },
+    TechTeam(tech: TechID),
+    RetroToken(token: PlayerID),
+});
+
+union!(RelicWorld {
+    GiftOfTheDuruntai,
+    ContextualIntegratorCache,
+    AutomatedTransportNetwork,
+    RelicDetector,
+    LibraryOfEntelechy,
+    TransmutiveDecomposer,
+    NalgorianGrindstone,
+    StarsRuin,
+    ParadiseConverter,
+    BarianTradeArmada,
+    ThilsDemiring,
+    TheGrandArmilla,
+});
+
+union!(Controller { Human, Ai });
+
+union!(Viewer {
+    Player(player: PlayerID),
+    Spectator,
+});
+
+union!(EntityRef {
+    Tech(tech: TechID),
+    Colony(colony: ColonyID),
+    Converter(converter: ConverterID),
+    Project(project: ProjectID),
+    Relic(relic: RelicWorld),
+    Custom(id: usize),
+});
+
+union!(NoteTarget {
+    Game,
+    Player(player: PlayerID),
+    Converter(converter: ConverterID),
+    Colony(colony: ColonyID),
+});
+
+union!(FlowNode {
+    Supply,
+    Player(player: PlayerID),
+    Converter(converter: ConverterID),
+    Colony(colony: ColonyID),
+});
+
+interface!(CubeRecord {
+    food: isize,
+    culture: isize,
+    industry: isize,
+    small_wild: isize,
+    biotech: isize,
+    power: isize,
+    information: isize,
+    large_wild: isize,
+    ultratech: isize,
+    ships: isize,
+    points: isize,
+});
+
+interface!(Note {
+    id: RecordID,
+    target: NoteTarget,
+    text: String,
+});
+
+union!(PromiseStatus {
+    Outstanding,
+    Kept,
+    Broken,
+});
+
+interface!(Promise {
+    id: RecordID,
+    from: PlayerID,
+    to: PlayerID,
+    text: String,
+    made: Confluence,
+    status: PromiseStatus,
+});
+
+interface!(TradeTerms {
+    cubes: BTreeSet<CubeID>,
+    colonies: BTreeSet<ColonyID>,
+    converters: BTreeSet<ConverterID>,
+    retro_tokens: BTreeSet<PlayerID>,
+});
+
+interface!(TradeOffer {
+    id: RecordID,
+    from: PlayerID,
+    to: PlayerID,
+    give: TradeTerms,
+    take: TradeTerms,
+    permanent: bool,
+});
+
+interface!(Profile {
+    name: Option<String>,
+    controller: Controller,
+    connected: bool,
+});
+
+interface!(BidView {
+    colony: (usize, Option<usize>),
+    tech: (usize, Option<usize>),
+});
+
+interface!(DeckView {
+    id: DeckID,
+    name: String,
+    size: usize,
+    cards: Option<Vec<EntityRef>>,
+});
+
+union!(PendingAction {
+    Bid,
+    TakeColony,
+    TakeResearch,
+    Invent { tech: TechID },
+    RunConverter { converter: ConverterID },
+    RespondToOffer { offer: RecordID },
+});
+
+interface!(OfferView {
+    offer: TradeOffer,
+    give_cubes: CubeRecord,
+    take_cubes: CubeRecord,
+    acceptable: bool,
+});
+
+interface!(TurnView {
+    phase: Phase,
+    confluence: Confluence,
+    next_record: RecordID,
+    awaiting: Vec<PendingAction>,
+    markable: Vec<ConverterID>,
+    converter_inputs: Vec<(ConverterID, Option<BTreeSet<CubeID>>)>,
+    colony_inputs: Vec<(ColonyID, BTreeSet<CubeID>)>,
+    colony_slots: Vec<usize>,
+    tech_slots: Vec<usize>,
+    offers: Vec<OfferView>,
+});
+
+interface!(PlayerView {
+    player: PlayerID,
+    faction: FactionType,
+    cubes: CubeRecord,
+    converters: Vec<ConverterID>,
+    loaned_out: Vec<ConverterID>,
+    colonies: Vec<ColonyID>,
+    research_teams: Vec<TechID>,
+    has_bid: bool,
+    bid: Option<BidView>,
+    marked: Option<Vec<ConverterID>>,
+    held_count: usize,
+    held_cards: Option<Vec<EntityRef>>,
+    turn: Option<TurnView>,
+});
+
+interface!(GameStateView {
+    viewer: Viewer,
+    phase: Phase,
+    confluence: Confluence,
+    players: Vec<PlayerView>,
+    seats: Vec<PlayerID>,
+    notes: Vec<Note>,
+    offers: Vec<TradeOffer>,
+    promises: Vec<Promise>,
+    colony_track: Vec<Option<ColonyID>>,
+    tech_track: Vec<Option<TechID>>,
+    decks: Vec<DeckView>,
+});
+
+union!(GameEvent {
+    ConfluenceStarted { confluence: Confluence },
+    PhaseStarted { phase: Phase },
+    BidsRevealed,
+    ColonyWon {
+        player: PlayerID,
+        colony: ColonyID,
+        ships: usize,
+    },
+    ResearchTeamWon {
+        player: PlayerID,
+        tech: TechID,
+        ships: usize,
+    },
+    TechInvented {
+        player: PlayerID,
+        tech: TechID,
+        points: usize,
+    },
+    TechShared { tech: TechID },
+    Licensed { player: PlayerID, tech: TechID },
+    Stole {
+        zeth: PlayerID,
+        converter: ConverterID,
+        target: Option<PlayerID>,
+    },
+    ConverterUpgraded {
+        player: PlayerID,
+        converter: ConverterID,
+    },
+    Acknowledged { player: PlayerID },
+    CrossTokenReturned {
+        zeth: PlayerID,
+        colony: ColonyID,
+    },
+    AcknowledgementReturned {
+        player: PlayerID,
+        faderan: PlayerID,
+    },
+    Traded { a: PlayerID, b: PlayerID },
+    RelicDrawn {
+        player: PlayerID,
+        relic: RelicWorld,
+    },
+    ProjectActivated { project: ProjectID },
+    ProjectCompleted { project: ProjectID },
+    UnityDiceRolled { faces: Vec<u8> },
+});
+
+union!(Notification {
+    Applied {
+        group: RecordGroup,
+        events: Vec<GameEvent>,
+    },
+    Undone { group: RecordGroup },
+});
+
+interface!(RecordGroup {
+    id: RecordID,
+    rec: Vec<RecordType>,
+});
+
+interface!(Submission {
+    nonce: Nonce,
+    rec: Vec<RecordType>,
+});
+
+union!(RecordType {
+    TradeCubes {
+        a: PlayerID,
+        b: PlayerID,
+        a_cubes: BTreeSet<CubeID>,
+        b_cubes: BTreeSet<CubeID>,
+    },
+    TradeColony {
+        a: PlayerID,
+        b: PlayerID,
+        a_colony: BTreeSet<ColonyID>,
+        b_colony: BTreeSet<ColonyID>,
+    },
+    TradeConverter {
+        a: PlayerID,
+        b: PlayerID,
+        a_converter: BTreeSet<ConverterID>,
+        b_converter: BTreeSet<ConverterID>,
+        permanent: bool,
+    },
+    TradeConverterPermanently {
+        a: PlayerID,
+        b: PlayerID,
+        a_converter: BTreeSet<ConverterID>,
+        b_converter: BTreeSet<ConverterID>,
+    },
+    CreatePlayer {
+        player: PlayerID,
+        faction: FactionType,
+    },
+    ChangePhase {
+        to: Phase,
+    },
+    Bid {
+        player: PlayerID,
+        for_colony: usize,
+        for_colony_kjas: Option<usize>,
+        for_tech: usize,
+        for_tech_faderan: Option<usize>,
+    },
+    TakeColony {
+        player: PlayerID,
+        colony: Option<usize>,
+    },
+    TakeResearch {
+        player: PlayerID,
+        tech: Option<usize>,
+    },
+    InventTech {
+        player: PlayerID,
+        tech: TechID,
+        cost: CubeType,
+    },
+    UpgradeConverter {
+        conv: ConverterID,
+        opt: usize,
+        colonies: BTreeSet<ColonyID>,
+    },
+    GiveAcknowledgement {
+        player: PlayerID,
+    },
+    License {
+        player: PlayerID,
+        tech: TechID,
+    },
+    Retrocontinuity {
+        converter: ConverterID,
+        inputs: BTreeSet<CubeID>,
+    },
+    ColonyRetrocontinuity {
+        colony: ColonyID,
+        inputs: BTreeSet<CubeID>,
+    },
+    TradeRetroTokens {
+        a: PlayerID,
+        b: PlayerID,
+        a_tokens: BTreeSet<PlayerID>,
+        b_tokens: BTreeSet<PlayerID>,
+    },
+    TradeItems {
+        a: PlayerID,
+        b: PlayerID,
+        a_items: Vec<TradeItem>,
+        b_items: Vec<TradeItem>,
+    },
+    MarkConverter {
+        player: PlayerID,
+        converter: ConverterID,
+    },
+    MarkAll {
+        player: PlayerID,
+        except: Vec<ConverterID>,
+    },
+    UnmarkConverter {
+        player: PlayerID,
+        converter: ConverterID,
+    },
+    RunConverter {
+        converter: ConverterID,
+        inputs: BTreeSet<CubeID>,
+        target: Option<PlayerID>,
+    },
+    RunColony {
+        colony: ColonyID,
+        inputs: BTreeSet<CubeID>,
+    },
+    DoubleColony {
+        player: PlayerID,
+        colony: ColonyID,
+    },
+    UndoubleColony {
+        player: PlayerID,
+        colony: ColonyID,
+    },
+    PlaceConstraint {
+        player: PlayerID,
+        on: PlayerID,
+        typ: CubeType,
+    },
+    RemoveConstraint {
+        player: PlayerID,
+        from: PlayerID,
+        typ: CubeType,
+    },
+    GiveEnvoy {
+        player: PlayerID,
+        to: PlayerID,
+    },
+    ReturnEnvoy {
+        player: PlayerID,
+    },
+    PlaceCrossToken {
+        player: PlayerID,
+        colony: ColonyID,
+    },
+    SpawnCubes {
+        player: PlayerID,
+        typ: CubeType,
+        qty: usize,
+        donation: Option<PlayerID>,
+        from: FlowNode,
+    },
+    DestroyCubes {
+        cubes: BTreeSet<CubeID>,
+        to: FlowNode,
+    },
+    CreateDeck {
+        deck: DeckID,
+        name: String,
+        cards: Vec<EntityRef>,
+    },
+    DrawFromDeck {
+        deck: DeckID,
+        player: PlayerID,
+    },
+    VoteForProject {
+        player: PlayerID,
+        project: ProjectID,
+    },
+    ActivateProject {
+        player: PlayerID,
+        project: ProjectID,
+    },
+    CompleteProject {
+        player: PlayerID,
+        project: ProjectID,
+    },
+    AssignSeats {
+        seats: Vec<PlayerID>,
+    },
+    SwapSeats {
+        a: PlayerID,
+        b: PlayerID,
+    },
+    DrawRelic {
+        player: PlayerID,
+    },
+    RollUnityDice {
+        player: PlayerID,
+    },
+    ChangeUnityDie {
+        player: PlayerID,
+        die: usize,
+        face: u8,
+    },
+    GainDiceChangeCards {
+        player: PlayerID,
+        count: usize,
+    },
+    GainFleetSupport {
+        player: PlayerID,
+        qty: usize,
+    },
+    PlaceServiceToken {
+        player: PlayerID,
+        converter: ConverterID,
+    },
+    RemoveServiceToken {
+        converter: ConverterID,
+    },
+    PeekDeck {
+        deck: DeckID,
+        player: PlayerID,
+        count: usize,
+    },
+    RecurringTransfer {
+        from: PlayerID,
+        to: PlayerID,
+        typ: CubeType,
+        qty: usize,
+        until: Option<Confluence>,
+    },
+    Contract {
+        debtor: PlayerID,
+        creditor: PlayerID,
+        typ: CubeType,
+        qty: usize,
+        due: Confluence,
+        binding: bool,
+    },
+    FulfillContract {
+        contract: RecordID,
+        cubes: BTreeSet<CubeID>,
+    },
+    Promise {
+        from: PlayerID,
+        to: PlayerID,
+        text: String,
+    },
+    SettlePromise {
+        player: PlayerID,
+        promise: RecordID,
+        kept: bool,
+    },
+    ProposeTrade {
+        from: PlayerID,
+        to: PlayerID,
+        give: TradeTerms,
+        take: TradeTerms,
+        permanent: bool,
+        counters: Option<RecordID>,
+    },
+    AcceptTrade {
+        offer: RecordID,
+    },
+    RejectTrade {
+        offer: RecordID,
+    },
+    AddNote {
+        player: PlayerID,
+        target: NoteTarget,
+        text: String,
+    },
+    RemoveNote {
+        player: PlayerID,
+        note: RecordID,
+    },
+    ShuffleDeck {
+        deck: DeckID,
+    },
+    CreateTechDeck,
+    CreateColonyDeck,
+    SetProfile {
+        player: PlayerID,
+        profile: Profile,
+    },
+});
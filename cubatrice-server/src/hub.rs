@@ -0,0 +1,507 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    net::{TcpListener, TcpStream},
+    sync::{
+        mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Error};
+use rand::RngCore;
+
+#[cfg(feature = "metrics")]
+use cubatrice_engine::{metrics::EngineMetrics, state::Phase};
+
+use cubatrice_engine::state::{
+    access::Viewer,
+    config::GameConfig,
+    player::PlayerID,
+    record::{RecordGroup, RecordID, RecordType},
+    spectate::SpectatorFeed,
+    submission::{Submission, SubmitOutcome},
+    GameData, GameState,
+};
+
+use crate::{
+    protocol::{ClientMessage, ServerMessage},
+    websocket::{self, Message},
+};
+
+/// How long spectators wait to see the game, so nobody watching can coach a
+/// player in real time.
+const SPECTATOR_DELAY_MINUTES: u64 = 2;
+
+/// How often the hub checks for spectator views which have become due, when
+/// no messages are coming in.
+const TICK: Duration = Duration::from_secs(1);
+
+/// Identifies a connection for as long as it's open.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct ConnID(usize);
+
+/// The sending half of a connection, shared between the hub and the
+/// connection's own thread, which answers pings.
+type Outgoing = Arc<Mutex<TcpStream>>;
+
+/// What connection threads tell the hub.
+enum Event {
+    Connected { conn: ConnID, out: Outgoing },
+    Message { conn: ConnID, text: String },
+    Closed { conn: ConnID },
+}
+
+/// Accepts WebSocket connections on `addr` and hosts games for them until
+/// the listener fails. Every lobby's game is set up with `data`.
+///
+/// Each connection gets a thread which reads its messages, but every game
+/// lives on a single hub thread, so messages are handled one at a time in
+/// the order they arrive.
+pub fn serve(addr: &str, data: GameData) -> Result<(), Error> {
+    listen(addr, move || Hub::new(data))
+}
+
+/// Like `serve`, but keeps `metrics` up to date as games are played.
+#[cfg(feature = "metrics")]
+pub fn serve_with_metrics(
+    addr: &str,
+    data: GameData,
+    metrics: Arc<EngineMetrics>,
+) -> Result<(), Error> {
+    listen(addr, move || {
+        let mut hub = Hub::new(data);
+        hub.metrics = Some(metrics);
+        hub
+    })
+}
+
+/// Starts the hub thread, with the hub `make` sets up, and hands it every
+/// connection to `addr`. Games can't be moved between threads, so the hub
+/// is set up on its own.
+fn listen(addr: &str, make: impl FnOnce() -> Hub + Send + 'static) -> Result<(), Error> {
+    let listener = TcpListener::bind(addr)?;
+    let (tx, rx) = channel();
+    thread::spawn(move || make().run(rx));
+    for (i, stream) in listener.incoming().enumerate() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let tx = tx.clone();
+        thread::spawn(move || connection(ConnID(i), stream, tx));
+    }
+    Ok(())
+}
+
+/// Upgrades a connection to a WebSocket, then passes its messages to the
+/// hub until it closes.
+fn connection(conn: ConnID, mut stream: TcpStream, hub: Sender<Event>) {
+    if websocket::handshake(&mut stream).is_err() {
+        return;
+    }
+    let Ok(out) = stream.try_clone() else {
+        return;
+    };
+    let out = Arc::new(Mutex::new(out));
+    if hub
+        .send(Event::Connected {
+            conn,
+            out: out.clone(),
+        })
+        .is_err()
+    {
+        return;
+    }
+    loop {
+        let pong = |payload: &[u8]| match out.lock() {
+            Ok(mut s) => websocket::write_pong(&mut *s, payload),
+            Err(_) => Err(anyhow!("connection poisoned")),
+        };
+        match websocket::read_message(&mut stream, pong) {
+            Ok(Message::Text(text)) => {
+                if hub.send(Event::Message { conn, text }).is_err() {
+                    break;
+                }
+            }
+            Ok(Message::Close) | Err(_) => break,
+        }
+    }
+    if let Ok(mut s) = out.lock() {
+        let _ = websocket::write_close(&mut *s);
+    }
+    let _ = hub.send(Event::Closed { conn });
+}
+
+struct Client {
+    out: Outgoing,
+    /// The lobby the client is in, and who they are in it.
+    joined: Option<(String, Viewer)>,
+}
+
+struct Lobby {
+    state: GameState,
+    members: BTreeSet<ConnID>,
+    /// The secret each player joins their seat with, handed out by
+    /// whoever created the lobby.
+    tokens: BTreeMap<PlayerID, String>,
+    /// The secret the lobby's creator moves the game on with.
+    host: String,
+    spectators: SpectatorFeed,
+    /// When the game entered its current phase.
+    #[cfg(feature = "metrics")]
+    phase_since: Instant,
+}
+
+/// Every lobby and connected client.
+struct Hub {
+    data: GameData,
+    lobbies: HashMap<String, Lobby>,
+    clients: HashMap<ConnID, Client>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<EngineMetrics>>,
+}
+
+impl Hub {
+    fn new(data: GameData) -> Self {
+        Self {
+            data,
+            lobbies: HashMap::new(),
+            clients: HashMap::new(),
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+
+    fn run(mut self, events: Receiver<Event>) {
+        loop {
+            match events.recv_timeout(TICK) {
+                Ok(Event::Connected { conn, out }) => {
+                    self.clients.insert(conn, Client { out, joined: None });
+                }
+                Ok(Event::Message { conn, text }) => self.handle(conn, &text),
+                Ok(Event::Closed { conn }) => {
+                    self.leave(conn);
+                    self.clients.remove(&conn);
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            self.release_spectator_views();
+        }
+    }
+
+    fn handle(&mut self, conn: ConnID, text: &str) {
+        let result = match serde_json::from_str(text) {
+            Ok(ClientMessage::Create { lobby, config }) => self.create(conn, lobby, config),
+            Ok(ClientMessage::Join {
+                lobby,
+                viewer,
+                token,
+            }) => self.join(conn, lobby, viewer, token),
+            Ok(ClientMessage::Submit(sub)) => self.submit(conn, sub),
+            Ok(ClientMessage::Advance { lobby, token }) => self.advance(lobby, token),
+            Err(e) => Err(anyhow!("couldn't read message: {}", e)),
+        };
+        if let Err(e) = result {
+            self.send(
+                conn,
+                &ServerMessage::Error {
+                    message: format!("{:#}", e),
+                },
+            );
+        }
+    }
+
+    fn create(&mut self, conn: ConnID, lobby: String, mut config: GameConfig) -> Result<(), Error> {
+        if self.lobbies.contains_key(&lobby) {
+            return Err(anyhow!("lobby {} already exists", lobby));
+        }
+        // every shuffle and roll follows from the seed, so the creator
+        // mustn't get to pick it
+        config.seed = rand::thread_rng().next_u64();
+        let tokens: BTreeMap<PlayerID, String> = config
+            .players
+            .iter()
+            .map(|(p, _)| (*p, new_token()))
+            .collect();
+        let host = new_token();
+        let state = GameState::from_config(self.data.clone(), config)?;
+        let mut spectators = SpectatorFeed::delayed_minutes(SPECTATOR_DELAY_MINUTES);
+        spectators.push(Instant::now(), &state);
+        self.lobbies.insert(
+            lobby.clone(),
+            Lobby {
+                state,
+                members: BTreeSet::new(),
+                tokens: tokens.clone(),
+                host: host.clone(),
+                spectators,
+                #[cfg(feature = "metrics")]
+                phase_since: Instant::now(),
+            },
+        );
+        #[cfg(feature = "metrics")]
+        if let Some(m) = &self.metrics {
+            m.game_started();
+        }
+        self.send(
+            conn,
+            &ServerMessage::Created {
+                lobby,
+                tokens,
+                host,
+            },
+        );
+        Ok(())
+    }
+
+    fn join(
+        &mut self,
+        conn: ConnID,
+        lobby: String,
+        viewer: Viewer,
+        token: Option<String>,
+    ) -> Result<(), Error> {
+        let game = self
+            .lobbies
+            .get(&lobby)
+            .ok_or_else(|| anyhow!("no lobby named {}", lobby))?;
+        if let Some(p) = viewer.player() {
+            let Some(seat) = game.tokens.get(&p) else {
+                return Err(anyhow!("there's no player {} in {}", p.0, lobby));
+            };
+            if !token.is_some_and(|t| same_token(&t, seat)) {
+                return Err(anyhow!("wrong token for player {} in {}", p.0, lobby));
+            }
+        }
+        self.leave(conn);
+        let Some(game) = self.lobbies.get_mut(&lobby) else {
+            return Ok(());
+        };
+        game.members.insert(conn);
+        let view = match viewer {
+            Viewer::Player(_) => Some(game.state.view_as(viewer)),
+            Viewer::Spectator => game.spectators.current().cloned(),
+        };
+        if let Some(client) = self.clients.get_mut(&conn) {
+            client.joined = Some((lobby.clone(), viewer));
+        }
+        self.send(
+            conn,
+            &ServerMessage::Joined {
+                lobby: lobby.clone(),
+                view,
+            },
+        );
+        self.update_connected(&lobby, viewer);
+        Ok(())
+    }
+
+    /// Takes a client out of their lobby, if they're in one.
+    fn leave(&mut self, conn: ConnID) {
+        let Some((lobby, viewer)) = self.clients.get_mut(&conn).and_then(|c| c.joined.take())
+        else {
+            return;
+        };
+        if let Some(game) = self.lobbies.get_mut(&lobby) {
+            game.members.remove(&conn);
+        }
+        self.update_connected(&lobby, viewer);
+    }
+
+    fn submit(&mut self, conn: ConnID, sub: Submission) -> Result<(), Error> {
+        let (lobby, player) = match self.clients.get(&conn).and_then(|c| c.joined.clone()) {
+            Some((lobby, Viewer::Player(p))) => (lobby, p),
+            Some((_, Viewer::Spectator)) => return Err(anyhow!("spectators can't submit")),
+            None => return Err(anyhow!("join a lobby first")),
+        };
+        let game = self
+            .lobbies
+            .get_mut(&lobby)
+            .ok_or_else(|| anyhow!("no lobby named {}", lobby))?;
+        #[cfg(feature = "metrics")]
+        let phase = game.state.phase();
+        let outcome = game.state.submit(player, sub)?;
+        self.send(conn, &ServerMessage::Outcome(outcome));
+        match outcome {
+            SubmitOutcome::Applied(id) => {
+                #[cfg(feature = "metrics")]
+                self.count_applied(&lobby, phase);
+                self.applied(&lobby, id);
+            }
+            #[cfg(feature = "metrics")]
+            SubmitOutcome::Rejected(e) => {
+                if let Some(m) = &self.metrics {
+                    m.validation_failed(&e);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn advance(&mut self, lobby: String, token: String) -> Result<(), Error> {
+        let game = self
+            .lobbies
+            .get_mut(&lobby)
+            .ok_or_else(|| anyhow!("no lobby named {}", lobby))?;
+        if !same_token(&token, &game.host) {
+            return Err(anyhow!("wrong host token for {}", lobby));
+        }
+        let to = game
+            .state
+            .next_phase()
+            .ok_or_else(|| anyhow!("the game in {} is over", lobby))?;
+        let rec = RecordType::ChangePhase { to };
+        if let Err(e) = game.state.validate(&rec) {
+            #[cfg(feature = "metrics")]
+            if let Some(m) = &self.metrics {
+                m.validation_failed(&e);
+            }
+            return Err(anyhow!("can't move on to {:?}: {}", to, e));
+        }
+        #[cfg(feature = "metrics")]
+        let phase = game.state.phase();
+        let id = game.state.next_record_id();
+        game.state.apply(RecordGroup { id, rec: vec![rec] });
+        #[cfg(feature = "metrics")]
+        self.count_applied(&lobby, phase);
+        self.applied(&lobby, id);
+        Ok(())
+    }
+
+    /// Marks a player as connected if any client has joined as them, and
+    /// disconnected otherwise, telling the lobby if that changes anything.
+    fn update_connected(&mut self, lobby: &str, viewer: Viewer) {
+        let Some(player) = viewer.player() else {
+            return;
+        };
+        let connected = self.clients.values().any(|c| {
+            c.joined
+                .as_ref()
+                .is_some_and(|(l, v)| l == lobby && *v == viewer)
+        });
+        let Some(game) = self.lobbies.get_mut(lobby) else {
+            return;
+        };
+        let mut profile = game.state.profile(player);
+        if profile.connected == connected {
+            return;
+        }
+        profile.connected = connected;
+        let rec = RecordType::SetProfile { player, profile };
+        if game.state.validate(&rec).is_err() {
+            return;
+        }
+        #[cfg(feature = "metrics")]
+        let phase = game.state.phase();
+        let id = game.state.next_record_id();
+        game.state.apply(RecordGroup { id, rec: vec![rec] });
+        #[cfg(feature = "metrics")]
+        self.count_applied(lobby, phase);
+        self.applied(lobby, id);
+    }
+
+    /// Counts a record group applied to a lobby's game, which was in
+    /// `phase` beforehand, timing the phase if the group ended it.
+    #[cfg(feature = "metrics")]
+    fn count_applied(&mut self, lobby: &str, phase: Phase) {
+        let (Some(m), Some(game)) = (&self.metrics, self.lobbies.get_mut(lobby)) else {
+            return;
+        };
+        m.record_applied();
+        if game.state.phase() != phase {
+            let now = Instant::now();
+            m.phase_finished(phase, now.duration_since(game.phase_since));
+            game.phase_since = now;
+        }
+    }
+
+    /// Tells every player in a lobby that a record group was applied, and
+    /// sends them their new view of the game. Spectators get the new view
+    /// once it's due. Events the group emitted are dropped, since clients
+    /// work out what changed from the views.
+    fn applied(&mut self, lobby: &str, id: RecordID) {
+        let Some(game) = self.lobbies.get_mut(lobby) else {
+            return;
+        };
+        game.state.take_events();
+        game.spectators.push(Instant::now(), &game.state);
+        let game = &self.lobbies[lobby];
+        let group = game.state.log().iter().rev().find(|g| g.id == id);
+        for conn in game.members.iter() {
+            let Some((_, viewer)) = self.clients.get(conn).and_then(|c| c.joined.clone()) else {
+                continue;
+            };
+            if viewer == Viewer::Spectator {
+                continue;
+            }
+            let visible =
+                group.filter(|g| g.rec.iter().all(|r| game.state.can_see_record(viewer, r)));
+            self.send(
+                *conn,
+                &ServerMessage::Applied {
+                    id,
+                    group: visible.cloned(),
+                },
+            );
+            self.send(*conn, &ServerMessage::View(game.state.view_as(viewer)));
+        }
+    }
+
+    /// Sends spectators every view of their game which has become due.
+    fn release_spectator_views(&mut self) {
+        let now = Instant::now();
+        for game in self.lobbies.values_mut() {
+            let due = game.spectators.due(now);
+            if due.is_empty() {
+                continue;
+            }
+            for conn in game.members.iter() {
+                let spectating = self.clients.get(conn).is_some_and(|c| {
+                    c.joined
+                        .as_ref()
+                        .is_some_and(|(_, v)| *v == Viewer::Spectator)
+                });
+                if spectating {
+                    for view in due.iter() {
+                        send(&self.clients, *conn, &ServerMessage::View(view.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sends a message to a client. Failures are ignored, since the
+    /// client's thread will notice the connection has gone.
+    fn send(&self, conn: ConnID, msg: &ServerMessage) {
+        send(&self.clients, conn, msg);
+    }
+}
+
+/// `Hub::send`, for when the rest of the hub is borrowed.
+fn send(clients: &HashMap<ConnID, Client>, conn: ConnID, msg: &ServerMessage) {
+    let Some(client) = clients.get(&conn) else {
+        return;
+    };
+    let Ok(text) = serde_json::to_string(msg) else {
+        return;
+    };
+    if let Ok(mut out) = client.out.lock() {
+        let _ = websocket::write_text(&mut *out, &text);
+    }
+}
+
+/// A new seat token: 128 random bits, in hex.
+fn new_token() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Compares tokens in time that doesn't depend on where they differ, so
+/// they can't be guessed a character at a time.
+fn same_token(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |d, (x, y)| d | (x ^ y)) == 0
+}
@@ -0,0 +1,26 @@
+//! An online host for Cubatrice games. Clients connect over WebSockets, set
+//! up or join a lobby, and submit records, which the server validates and
+//! applies before telling everyone in the lobby. Messages are JSON, see
+//! `protocol`.
+//!
+//! Creating a lobby issues a token for each seat, which the creator hands
+//! out, and a host token the creator keeps. Joining as a player takes that
+//! seat's token, and players can only submit records acting for themselves.
+//! Only the host moves the game from phase to phase, and the server picks
+//! the game's seed. Spectators don't need a token, and see the game a
+//! couple of minutes late so they can't coach anyone.
+
+/// Messages exchanged with clients.
+pub mod protocol;
+/// Just enough of RFC 6455 to talk to browsers.
+pub mod websocket;
+
+mod hub;
+#[cfg(feature = "metrics")]
+mod metrics;
+
+pub use hub::serve;
+#[cfg(feature = "metrics")]
+pub use hub::serve_with_metrics;
+#[cfg(feature = "metrics")]
+pub use metrics::serve_metrics;
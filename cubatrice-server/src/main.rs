@@ -0,0 +1,46 @@
+use std::{env, process::ExitCode};
+
+use cubatrice_engine::state::GameData;
+
+/// Where the server listens unless told otherwise.
+const DEFAULT_ADDR: &str = "127.0.0.1:7878";
+
+/// Usage: `cubatrice-server [addr]`, or with the `metrics` feature
+/// `cubatrice-server [addr] [metrics addr]` to also serve metrics to
+/// scrapers.
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().collect();
+    let addr = args.get(1).map(|s| s.as_str()).unwrap_or(DEFAULT_ADDR);
+    let data = match GameData::preloaded() {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("failed to load game data: {:#}", e);
+            eprintln!("run `cubatrice-core doctor` for details.");
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("listening on {}", addr);
+    #[cfg(feature = "metrics")]
+    if let Some(metrics_addr) = args.get(2).cloned() {
+        let metrics = std::sync::Arc::new(cubatrice_engine::metrics::EngineMetrics::new());
+        let served = metrics.clone();
+        eprintln!("serving metrics on {}/metrics", metrics_addr);
+        std::thread::spawn(move || {
+            if let Err(e) = cubatrice_server::serve_metrics(&metrics_addr, served) {
+                eprintln!("metrics: {:#}", e);
+            }
+        });
+        return match cubatrice_server::serve_with_metrics(addr, data, metrics) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{:#}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    if let Err(e) = cubatrice_server::serve(addr, data) {
+        eprintln!("{:#}", e);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
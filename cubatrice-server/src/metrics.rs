@@ -0,0 +1,62 @@
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::Arc,
+    thread,
+};
+
+use anyhow::{anyhow, Error};
+
+use cubatrice_engine::metrics::EngineMetrics;
+
+/// The largest request a scraper may send.
+const MAX_REQUEST: usize = 8 << 10;
+
+/// Serves `metrics` to Prometheus-style scrapers at `GET /metrics` on
+/// `addr`, until the listener fails. Anything else gets a 404.
+pub fn serve_metrics(addr: &str, metrics: Arc<EngineMetrics>) -> Result<(), Error> {
+    let listener = TcpListener::bind(addr)?;
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let metrics = metrics.clone();
+        thread::spawn(move || {
+            let _ = scrape(stream, &metrics);
+        });
+    }
+    Ok(())
+}
+
+/// Answers one HTTP request.
+fn scrape(mut stream: TcpStream, metrics: &EngineMetrics) -> Result<(), Error> {
+    let mut request = Vec::new();
+    while !request.ends_with(b"\r\n\r\n") {
+        if request.len() >= MAX_REQUEST {
+            return Err(anyhow!("request is larger than {} bytes", MAX_REQUEST));
+        }
+        let mut byte = [0u8; 1];
+        if stream.read(&mut byte)? == 0 {
+            return Err(anyhow!("connection closed during request"));
+        }
+        request.push(byte[0]);
+    }
+    let line = request.split(|b| *b == b'\r').next().unwrap_or_default();
+    let (status, body) = if line.starts_with(b"GET /metrics ") {
+        ("200 OK", metrics.render())
+    } else {
+        ("404 Not Found", String::from("not found\n"))
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\n\
+         Content-Type: text/plain; version=0.0.4\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use cubatrice_engine::state::{
+    access::Viewer,
+    config::GameConfig,
+    player::PlayerID,
+    record::{RecordGroup, RecordID},
+    submission::{Submission, SubmitOutcome},
+    view::GameStateView,
+};
+
+/// Messages clients send, one per WebSocket text message, as JSON.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// Sets up a new game in a lobby, which must not exist yet. The client
+    /// still has to join it.
+    Create { lobby: String, config: GameConfig },
+    /// Joins a lobby as one of its players, with the seat's token, or as a
+    /// spectator, who doesn't need one. A client can only be in one lobby
+    /// at a time.
+    Join {
+        lobby: String,
+        viewer: Viewer,
+        #[serde(default)]
+        token: Option<String>,
+    },
+    /// Records to validate and apply to the game in the client's lobby.
+    /// Every record must act only for the player the client joined as.
+    Submit(Submission),
+    /// Moves a lobby's game on to its next phase, with the host token the
+    /// lobby's creator was given. Players can't change the phase
+    /// themselves.
+    Advance { lobby: String, token: String },
+}
+
+/// Messages the server sends, one per WebSocket text message, as JSON.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// A lobby was created. Only the creator is told the token for each
+    /// seat, and hands them out to the players, along with the host token
+    /// which moves the game from phase to phase.
+    Created {
+        lobby: String,
+        tokens: BTreeMap<PlayerID, String>,
+        host: String,
+    },
+    /// The client joined a lobby, and this is the game as they see it.
+    /// Spectators see the game as it was a while ago, and get no view
+    /// until the first one is due.
+    Joined {
+        lobby: String,
+        view: Option<GameStateView>,
+    },
+    /// What happened to the client's submission. Only sent to the client
+    /// who submitted it.
+    Outcome(SubmitOutcome),
+    /// A record group was applied to the game, sent to every player in the
+    /// lobby. The group is left out for players who aren't allowed to see
+    /// it, such as other players' sealed bids. Spectators aren't told.
+    Applied {
+        id: RecordID,
+        group: Option<RecordGroup>,
+    },
+    /// The game as the client sees it, sent to players after every change
+    /// and to spectators once a delayed view is due.
+    View(GameStateView),
+    /// The client's last message couldn't be handled.
+    Error { message: String },
+}
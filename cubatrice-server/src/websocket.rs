@@ -0,0 +1,222 @@
+use std::io::{Read, Write};
+
+use anyhow::{anyhow, Context, Error};
+
+/// Appended to a client's key to prove the server understood the handshake,
+/// as given by RFC 6455.
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// The largest message a client may send. Records are small, so anything
+/// bigger is a mistake or an attack.
+pub const MAX_MESSAGE: usize = 1 << 20;
+
+/// The largest handshake request a client may send.
+const MAX_HANDSHAKE: usize = 8 << 10;
+
+/// A message received from a client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Message {
+    Text(String),
+    /// The client wants to close the connection.
+    Close,
+}
+
+/// Reads the client's opening HTTP request and answers it, upgrading the
+/// connection to a WebSocket.
+pub fn handshake<S: Read + Write>(stream: &mut S) -> Result<(), Error> {
+    // read a byte at a time, so nothing the client sends after the request
+    // is lost to a buffer
+    let mut request = Vec::new();
+    while !request.ends_with(b"\r\n\r\n") {
+        if request.len() >= MAX_HANDSHAKE {
+            return Err(anyhow!("handshake is larger than {} bytes", MAX_HANDSHAKE));
+        }
+        let mut byte = [0u8; 1];
+        if stream.read(&mut byte)? == 0 {
+            return Err(anyhow!("connection closed during handshake"));
+        }
+        request.push(byte[0]);
+    }
+    let request = String::from_utf8(request).context("handshake isn't UTF-8")?;
+    let key = request.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("sec-websocket-key")
+            .then(|| String::from(value.trim()))
+    });
+    let key = key.ok_or_else(|| anyhow!("not a WebSocket request"))?;
+    let accept = base64(&sha1(format!("{}{}", key, ACCEPT_GUID).as_bytes()));
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Reads the next message from a client, putting fragmented messages back
+/// together. Pings are answered with `pong`, which writes to the same
+/// connection.
+pub fn read_message<R: Read>(
+    stream: &mut R,
+    mut pong: impl FnMut(&[u8]) -> Result<(), Error>,
+) -> Result<Message, Error> {
+    let mut message: Vec<u8> = Vec::new();
+    loop {
+        let mut head = [0u8; 2];
+        stream.read_exact(&mut head).context("reading frame")?;
+        let fin = head[0] & 0x80 != 0;
+        let opcode = head[0] & 0x0f;
+        let masked = head[1] & 0x80 != 0;
+        let len = match head[1] & 0x7f {
+            126 => {
+                let mut ext = [0u8; 2];
+                stream.read_exact(&mut ext)?;
+                u16::from_be_bytes(ext) as usize
+            }
+            127 => {
+                let mut ext = [0u8; 8];
+                stream.read_exact(&mut ext)?;
+                usize::try_from(u64::from_be_bytes(ext)).unwrap_or(usize::MAX)
+            }
+            n => n as usize,
+        };
+        if message.len().saturating_add(len) > MAX_MESSAGE {
+            return Err(anyhow!("message is larger than {} bytes", MAX_MESSAGE));
+        }
+        // clients must mask everything they send
+        if !masked {
+            return Err(anyhow!("unmasked frame from client"));
+        }
+        let mut mask = [0u8; 4];
+        stream.read_exact(&mut mask)?;
+        let mut payload = vec![0u8; len];
+        stream.read_exact(&mut payload)?;
+        for (i, b) in payload.iter_mut().enumerate() {
+            *b ^= mask[i % 4];
+        }
+        match opcode {
+            0x0 | 0x1 => message.extend(payload),
+            0x2 => return Err(anyhow!("binary messages aren't supported")),
+            0x8 => return Ok(Message::Close),
+            0x9 => {
+                pong(&payload)?;
+                continue;
+            }
+            0xa => continue,
+            op => return Err(anyhow!("unknown opcode {:#x}", op)),
+        }
+        if fin {
+            return String::from_utf8(message)
+                .map(Message::Text)
+                .context("message isn't UTF-8");
+        }
+    }
+}
+
+/// Writes a text message.
+pub fn write_text<W: Write>(stream: &mut W, text: &str) -> Result<(), Error> {
+    write_frame(stream, 0x1, text.as_bytes())
+}
+
+/// Answers a ping.
+pub fn write_pong<W: Write>(stream: &mut W, payload: &[u8]) -> Result<(), Error> {
+    write_frame(stream, 0xa, payload)
+}
+
+/// Tells the client the connection is closing.
+pub fn write_close<W: Write>(stream: &mut W) -> Result<(), Error> {
+    write_frame(stream, 0x8, &[])
+}
+
+/// Writes a single unfragmented, unmasked frame.
+fn write_frame<W: Write>(stream: &mut W, opcode: u8, payload: &[u8]) -> Result<(), Error> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        n if n < 126 => frame.push(n as u8),
+        n if n <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend((n as u16).to_be_bytes());
+        }
+        n => {
+            frame.push(127);
+            frame.extend((n as u64).to_be_bytes());
+        }
+    }
+    frame.extend(payload);
+    stream.write_all(&frame)?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// SHA-1, which the handshake needs and nothing else does.
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut msg = data.to_vec();
+    msg.push(0x80);
+    while msg.len() % 64 != 56 {
+        msg.push(0);
+    }
+    msg.extend(((data.len() as u64) * 8).to_be_bytes());
+    for chunk in msg.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in chunk.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, wi) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let t = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*wi);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = t;
+        }
+        for (x, y) in h.iter_mut().zip([a, b, c, d, e]) {
+            *x = x.wrapping_add(y);
+        }
+    }
+    let mut out = [0u8; 20];
+    for (i, x) in h.iter().enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&x.to_be_bytes());
+    }
+    out
+}
+
+/// Standard base64, with padding.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}